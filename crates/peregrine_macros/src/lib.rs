@@ -2,15 +2,17 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, ItemFn, FnArg, Pat};
 
-/// Automatically profile a function when `perf_stats` feature is enabled.
+/// Automatically profile a function when `perf_stats` and/or `trace_spans` is enabled.
 /// 
 /// This macro wraps the function body with timing code that logs
-/// execution time on function exit. Compiles to nothing when the
-/// `perf_stats` feature is disabled.
+/// execution time on function exit. Compiles to nothing when both
+/// features are disabled.
 /// 
 /// # Features
 /// - Auto-detects `tick: Res<SimTick>` parameter for tick-based logging
-/// - Logs when duration > 1ms OR every 100 ticks (if tick available)
+/// - `perf_stats`: logs when duration > 1ms OR every 100 ticks (if tick available)
+/// - `trace_spans`: enters an `info_span!` named after the function for the whole body
+///   (with a `tick` field if available), for tracy / tracing-chrome style consumers
 /// - Uses Bevy's `info!` logging instead of println
 /// - Zero-cost abstraction when feature is disabled
 /// 
@@ -103,9 +105,19 @@ pub fn profile(attr: TokenStream, item: TokenStream) -> TokenStream {
         }
     };
     
+    let span_def = if has_tick_param {
+        quote! { bevy::log::info_span!(#fn_name_str, tick = tick.0) }
+    } else {
+        quote! { bevy::log::info_span!(#fn_name_str) }
+    };
+    
+    // The span is entered before the timer so the timer's log line lands inside it
     let output = quote! {
         #(#attrs)*
         #vis #sig {
+            #[cfg(feature = "trace_spans")]
+            let _profile_span = #span_def.entered();
+            
             #[cfg(feature = "perf_stats")]
             let _profile_timer = {
                 #profile_guard_def
@@ -0,0 +1,48 @@
+//! Headless scenario runner for CI.
+//!
+//! Usage: `cargo run --release --bin peregrine_headless -- <scenario.ron> [ticks]`
+//!
+//! Prints the final checksum. Exits with status 1 if the scenario has an
+//! `expected_checksum` and the run doesn't match it, 2 on usage or load errors.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use peregrine::game::headless::{run_scenario, Scenario};
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let Some(path) = args.next().map(PathBuf::from) else {
+        eprintln!("Usage: peregrine_headless <scenario.ron> [ticks]");
+        return ExitCode::from(2);
+    };
+
+    let mut scenario = match Scenario::load(&path) {
+        Ok(scenario) => scenario,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    if let Some(ticks) = args.next() {
+        match ticks.parse() {
+            Ok(ticks) => scenario.ticks = ticks,
+            Err(_) => {
+                eprintln!("Invalid tick count: {}", ticks);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let result = run_scenario(&scenario);
+    println!("ticks: {}", result.ticks);
+    println!("units: {}", result.unit_count);
+    println!("checksum: {:#018x}", result.checksum);
+
+    match scenario.expected_checksum {
+        Some(expected) if expected != result.checksum => {
+            eprintln!("Checksum mismatch: expected {:#018x}, got {:#018x}", expected, result.checksum);
+            ExitCode::from(1)
+        }
+        _ => ExitCode::SUCCESS,
+    }
+}
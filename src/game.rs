@@ -13,8 +13,11 @@ pub mod map;
 mod menu;
 mod hud;
 pub mod loading;  // Made public for test access to LoadingProgress
+pub mod launch;  // Command-line options, used by main.rs
 mod editor;
 pub mod profiling;  // Made public for profiling helpers
+pub mod logging;  // Log file retention, used by main.rs
+pub mod headless;  // Scripted headless runs for CI regression checks
 pub mod collections;  // Generic high-performance data structures
 
 use camera::RtsCameraPlugin;
@@ -67,16 +70,33 @@ impl Plugin for GamePlugin {
         // Common setup (UI Camera)
         app.add_systems(Startup, setup_common);
 
+        // Last thing on the way out, after anything that logs about shutting down
+        app.add_systems(Last, logging::flush_logs_on_exit);
+
         // Game setup (Map, Lights)
         app.add_systems(OnEnter(GameState::InGame), setup_game);
         app.add_systems(OnEnter(GameState::Editor), setup_game); // Reuse for now
         
-        // Cleanup
-        app.add_systems(OnExit(GameState::InGame), cleanup_game);
-        app.add_systems(OnExit(GameState::Editor), cleanup_game);
+        // Cleanup (pausing keeps the world; leaving the pause menu for anything else doesn't)
+        app.add_systems(OnExit(GameState::InGame), cleanup_game.run_if(not(is_pause_transition)));
+        app.add_systems(OnExit(GameState::Editor), cleanup_game.run_if(not(is_pause_transition)));
+        app.add_systems(OnExit(GameState::Paused), cleanup_game.run_if(not(is_pause_transition)));
     }
 }
 
+/// Run condition: the current transition enters or leaves the pause overlay
+/// (InGame/Editor → Paused or Paused → InGame/Editor).
+///
+/// These transitions don't change the world, so OnExit teardown (units, HUD, spatial hash)
+/// must be skipped for them; OnEnter setup is guarded against running twice instead.
+pub(crate) fn is_pause_transition(mut transitions: MessageReader<StateTransitionEvent<GameState>>) -> bool {
+    transitions.read().last().is_some_and(|transition| matches!(
+        (transition.exited, transition.entered),
+        (Some(GameState::InGame | GameState::Editor), Some(GameState::Paused))
+            | (Some(GameState::Paused), Some(GameState::InGame | GameState::Editor))
+    ))
+}
+
 #[derive(Component)]
 pub struct GameEntity;
 
@@ -99,7 +119,12 @@ fn setup_game(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     sim_config: Res<simulation::SimConfig>,
+    q_ground: Query<(), With<GroundPlane>>,
 ) {
+    // Resuming from pause: the world is still there
+    if !q_ground.is_empty() {
+        return;
+    }
     info!("Game setup started");
 
     // Ground Plane - sized to match map dimensions
@@ -139,3 +164,65 @@ fn cleanup_game(
     // Clear spatial hash to reset incremental mode state
     spatial_hash.clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+    use crate::game::unit::{Unit, Selected};
+
+    /// World setup/teardown and the selection ring batch, without rendering
+    fn setup_transition_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin));
+        app.init_state::<GameState>();
+        app.init_resource::<Assets<Mesh>>();
+        app.init_resource::<Assets<StandardMaterial>>();
+        app.init_resource::<simulation::SimConfig>();
+        app.insert_resource(spatial_hash::SpatialHash::new(
+            fixed_math::FixedNum::from_num(100.0), fixed_math::FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.0,
+        ));
+        app.init_resource::<unit::SelectionRings>();
+        app.add_systems(OnEnter(GameState::InGame), setup_game);
+        app.add_systems(Update, unit::update_selection_rings.run_if(in_state(GameState::InGame)));
+        app.add_systems(OnExit(GameState::InGame), cleanup_game.run_if(not(is_pause_transition)));
+        app.add_systems(OnExit(GameState::Paused), cleanup_game.run_if(not(is_pause_transition)));
+        app
+    }
+
+    fn enter(app: &mut App, state: GameState) {
+        app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+        app.update();
+    }
+
+    fn spawn_selected_unit(app: &mut App) -> Entity {
+        app.world_mut().spawn((Unit, GameEntity, Selected, Transform::default())).id()
+    }
+
+    #[test]
+    fn test_selection_persists_through_pause_and_resume() {
+        let mut app = setup_transition_app();
+        enter(&mut app, GameState::InGame);
+        let unit = spawn_selected_unit(&mut app);
+
+        enter(&mut app, GameState::Paused);
+        assert!(app.world().get_entity(unit).is_ok(), "Pausing should not despawn units");
+        enter(&mut app, GameState::InGame);
+
+        assert!(app.world().entity(unit).contains::<Selected>(), "Selection should survive pause/resume");
+        assert_eq!(app.world().resource::<unit::SelectionRings>().rings.len(), 1, "Ring should match the selection");
+        let mut grounds = app.world_mut().query_filtered::<(), With<GroundPlane>>();
+        assert_eq!(grounds.iter(app.world()).count(), 1, "Resuming should not rebuild the map");
+    }
+
+    #[test]
+    fn test_leaving_pause_for_main_menu_tears_down_world() {
+        let mut app = setup_transition_app();
+        enter(&mut app, GameState::InGame);
+        let unit = spawn_selected_unit(&mut app);
+
+        enter(&mut app, GameState::Paused);
+        enter(&mut app, GameState::MainMenu);
+        assert!(app.world().get_entity(unit).is_err());
+    }
+}
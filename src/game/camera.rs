@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy::input::mouse::MouseWheel;
+use bevy::window::PrimaryWindow;
 use crate::game::config::{GameConfig, GameConfigHandle};
+use crate::game::simulation::SimConfig;
 use crate::game::GameState;
 
 pub struct RtsCameraPlugin;
@@ -33,16 +35,20 @@ fn spawn_camera(mut commands: Commands, query: Query<Entity, With<RtsCamera>>) {
 }
 
 fn move_camera(
-    mut query: Query<&mut Transform, With<RtsCamera>>,
+    mut query: Query<(&mut Transform, &Camera, &GlobalTransform), With<RtsCamera>>,
     keys: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
     mut scroll_evr: MessageReader<MouseWheel>,
+    mut last_drag_cursor: Local<Option<Vec2>>,
     time: Res<Time>,
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
+    sim_config: Option<Res<SimConfig>>,
 ) {
-    let Some(mut transform) = query.iter_mut().next() else { return };
+    let Some((mut transform, camera, camera_transform)) = query.iter_mut().next() else { return };
     let Some(config) = game_configs.get(&config_handle.0) else { return };
-    
+
     let mut velocity = Vec3::ZERO;
     let speed = config.camera_speed;
     let zoom_speed = config.camera_zoom_speed;
@@ -79,4 +85,133 @@ fn move_camera(
         let forward = transform.forward();
         transform.translation += forward * zoom * zoom_speed * time.delta_secs();
     }
+
+    // Middle-mouse drag: pan (grab the ground) or, with the modifier held, orbit yaw
+    let cursor = q_window.iter().next().and_then(|window| window.cursor_position());
+    match (mouse_button.pressed(MouseButton::Middle), cursor, *last_drag_cursor) {
+        (true, Some(cursor), Some(previous)) => {
+            if keys.pressed(config.key_camera_rotate_modifier) {
+                let delta_x = cursor.x - previous.x;
+                if let Some(focus) = ground_intersection(transform.translation, *transform.forward()) {
+                    transform.rotate_around(focus, Quat::from_rotation_y(-delta_x * config.camera_rotate_speed));
+                }
+            } else if let (Ok(previous_ray), Ok(current_ray)) = (
+                camera.viewport_to_world(camera_transform, previous),
+                camera.viewport_to_world(camera_transform, cursor),
+            ) {
+                if let Some(pan) = drag_pan_delta(previous_ray.origin, *previous_ray.direction, current_ray.origin, *current_ray.direction) {
+                    transform.translation += pan;
+                }
+            }
+            *last_drag_cursor = Some(cursor);
+        }
+        (true, Some(cursor), None) => *last_drag_cursor = Some(cursor),
+        _ => *last_drag_cursor = None,
+    }
+
+    // Keep the focus point inside the map
+    if let Some(sim_config) = sim_config {
+        let min = Vec2::new(sim_config.map_size.top_left.x.to_num(), sim_config.map_size.top_left.y.to_num());
+        let max = Vec2::new(sim_config.map_size.bottom_right.x.to_num(), sim_config.map_size.bottom_right.y.to_num());
+        transform.translation = clamp_focus_to_bounds(transform.translation, *transform.forward(), min, max);
+    }
+}
+
+/// Intersect a ray with the ground plane (y = 0).
+///
+/// Returns `None` if the ray is parallel to or pointing away from the ground.
+fn ground_intersection(origin: Vec3, direction: Vec3) -> Option<Vec3> {
+    let denom = direction.dot(Vec3::Y);
+    if denom.abs() <= 0.0001 {
+        return None;
+    }
+    let t = -origin.y / denom;
+    if t < 0.0 {
+        return None;
+    }
+    Some(origin + direction * t)
+}
+
+/// Convert a cursor drag (as the two viewport rays) into a camera translation.
+///
+/// The ground point under the previous cursor is moved to stay under the current
+/// cursor ("grab the map"), so the result is `previous_hit - current_hit`.
+fn drag_pan_delta(previous_origin: Vec3, previous_dir: Vec3, current_origin: Vec3, current_dir: Vec3) -> Option<Vec3> {
+    let previous_hit = ground_intersection(previous_origin, previous_dir)?;
+    let current_hit = ground_intersection(current_origin, current_dir)?;
+    let delta = previous_hit - current_hit;
+    Some(Vec3::new(delta.x, 0.0, delta.z))
+}
+
+/// Shift the camera so the ground point it looks at stays within `min..=max` (world XZ).
+///
+/// Map coordinates are (x, y) in the simulation and (x, z) in the world.
+fn clamp_focus_to_bounds(translation: Vec3, forward: Vec3, min: Vec2, max: Vec2) -> Vec3 {
+    let focus = ground_intersection(translation, forward)
+        .unwrap_or(Vec3::new(translation.x, 0.0, translation.z));
+    let clamped_x = focus.x.clamp(min.x, max.x);
+    let clamped_z = focus.z.clamp(min.y, max.y);
+    translation + Vec3::new(clamped_x - focus.x, 0.0, clamped_z - focus.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at the default spawn pose: 15 up, 15 back, looking at the origin (45° pitch)
+    const CAMERA_POS: Vec3 = Vec3::new(0.0, 15.0, 15.0);
+
+    fn dir_to(target: Vec3) -> Vec3 {
+        (target - CAMERA_POS).normalize()
+    }
+
+    #[test]
+    fn test_ground_intersection_hits_target() {
+        let hit = ground_intersection(CAMERA_POS, dir_to(Vec3::new(3.0, 0.0, -2.0))).unwrap();
+        assert!((hit - Vec3::new(3.0, 0.0, -2.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_ground_intersection_rejects_upward_ray() {
+        assert!(ground_intersection(CAMERA_POS, Vec3::Y).is_none());
+        assert!(ground_intersection(CAMERA_POS, Vec3::X).is_none());
+    }
+
+    #[test]
+    fn test_drag_pan_delta_moves_grabbed_point_under_cursor() {
+        // Cursor moves from the point at the origin to the point at (2, 0, 0):
+        // the camera must move -2 in X so the origin ends up under the cursor.
+        let pan = drag_pan_delta(CAMERA_POS, dir_to(Vec3::ZERO), CAMERA_POS, dir_to(Vec3::new(2.0, 0.0, 0.0))).unwrap();
+        assert!((pan - Vec3::new(-2.0, 0.0, 0.0)).length() < 1e-4);
+
+        // Dragging "down" the screen (towards the camera) pushes the camera away
+        let pan = drag_pan_delta(CAMERA_POS, dir_to(Vec3::ZERO), CAMERA_POS, dir_to(Vec3::new(0.0, 0.0, 4.0))).unwrap();
+        assert!((pan - Vec3::new(0.0, 0.0, -4.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_drag_pan_delta_scales_with_height() {
+        // Same screen-space angles from twice the height cover twice the ground distance
+        let high = CAMERA_POS * 2.0;
+        let angle_a = dir_to(Vec3::ZERO);
+        let angle_b = dir_to(Vec3::new(2.0, 0.0, 0.0));
+        let low_pan = drag_pan_delta(CAMERA_POS, angle_a, CAMERA_POS, angle_b).unwrap();
+        let high_pan = drag_pan_delta(high, angle_a, high, angle_b).unwrap();
+        assert!((high_pan - low_pan * 2.0).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_clamp_focus_to_bounds() {
+        let forward = dir_to(Vec3::ZERO);
+        let min = Vec2::new(-10.0, -10.0);
+        let max = Vec2::new(10.0, 10.0);
+
+        // Focus inside bounds: unchanged
+        assert_eq!(clamp_focus_to_bounds(CAMERA_POS, forward, min, max), CAMERA_POS);
+
+        // Focus at x = 50 is pulled back to x = 10, height untouched
+        let shifted = CAMERA_POS + Vec3::new(50.0, 0.0, 0.0);
+        let clamped = clamp_focus_to_bounds(shifted, forward, min, max);
+        assert!((clamped - (CAMERA_POS + Vec3::new(10.0, 0.0, 0.0))).length() < 1e-4);
+    }
 }
@@ -0,0 +1,185 @@
+//! Grid2D: Dense 2D grid stored in a flat Vec.
+//!
+//! # Purpose
+//!
+//! One place for the "2D coordinate → 1D index" logic shared by the flow field, cost field,
+//! and future per-cell maps (fog of war, visibility). Keeping bounds checks and world↔grid
+//! conversion here avoids re-deriving them (and their underflow edge cases) per grid.
+//!
+//! # Layout
+//!
+//! - Row-major: `index = y * width + x`
+//! - `origin` is the bottom-left corner of cell (0, 0) in world space
+//! - Cell (x, y) covers `[origin + (x, y) * cell_size, origin + (x + 1, y + 1) * cell_size)`
+
+use serde::{Serialize, Deserialize};
+use crate::game::fixed_math::{FixedNum, FixedVec2};
+
+/// Dimensions and world placement of a grid, without any cell data.
+///
+/// `Copy`, so structs that keep their cell data in separate Vecs (like `FlowField`)
+/// can build one on the fly and share the same indexing logic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridLayout {
+    pub width: usize,
+    pub height: usize,
+    pub cell_size: FixedNum,
+    pub origin: FixedVec2,
+}
+
+impl GridLayout {
+    pub fn new(width: usize, height: usize, cell_size: FixedNum, origin: FixedVec2) -> Self {
+        Self { width, height, cell_size, origin }
+    }
+
+    /// Total number of cells
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// True if the grid has no cells
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether grid coordinates are inside the grid
+    #[inline]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Flat index for in-bounds coordinates (no bounds check in release)
+    #[inline]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        debug_assert!(self.in_bounds(x, y), "Grid coords ({}, {}) out of bounds ({}x{})", x, y, self.width, self.height);
+        y * self.width + x
+    }
+
+    /// Flat index, or `None` if the coordinates are out of bounds
+    #[inline]
+    pub fn checked_index(&self, x: usize, y: usize) -> Option<usize> {
+        self.in_bounds(x, y).then(|| y * self.width + x)
+    }
+
+    /// Convert a world position to the grid cell containing it.
+    ///
+    /// Returns `None` for positions outside the grid, including positions just left of /
+    /// below the origin (floors rather than truncating towards zero).
+    pub fn world_to_grid(&self, world_pos: FixedVec2) -> Option<(usize, usize)> {
+        let local_pos = world_pos - self.origin;
+        let grid_x = (local_pos.x / self.cell_size).floor().to_num::<i64>();
+        let grid_y = (local_pos.y / self.cell_size).floor().to_num::<i64>();
+
+        if grid_x < 0 || grid_y < 0 || grid_x >= self.width as i64 || grid_y >= self.height as i64 {
+            return None;
+        }
+
+        Some((grid_x as usize, grid_y as usize))
+    }
+
+    /// World position of a cell's center
+    pub fn grid_to_world(&self, x: usize, y: usize) -> FixedVec2 {
+        let offset = self.cell_size / FixedNum::from_num(2.0);
+        self.origin + FixedVec2::new(
+            FixedNum::from_num(x) * self.cell_size + offset,
+            FixedNum::from_num(y) * self.cell_size + offset,
+        )
+    }
+}
+
+/// Dense 2D grid of `T` with bounds-checked access and world↔grid conversion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grid2D<T> {
+    layout: GridLayout,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid2D<T> {
+    /// Create a grid with every cell set to `fill`
+    pub fn new(layout: GridLayout, fill: T) -> Self {
+        Self {
+            cells: vec![fill; layout.len()],
+            layout,
+        }
+    }
+
+    /// Set every cell to `value`
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+}
+
+impl<T> Grid2D<T> {
+    /// Wrap existing row-major cell data.
+    ///
+    /// # Panics
+    /// Panics if `cells.len() != layout.len()`.
+    pub fn from_vec(layout: GridLayout, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), layout.len(), "Grid2D cell count must match layout {}x{}", layout.width, layout.height);
+        Self { layout, cells }
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &GridLayout {
+        &self.layout
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.layout.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.layout.height
+    }
+
+    #[inline]
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        self.layout.in_bounds(x, y)
+    }
+
+    #[inline]
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        self.layout.index(x, y)
+    }
+
+    /// Cell at (x, y), or `None` if out of bounds
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.layout.checked_index(x, y).map(|idx| &self.cells[idx])
+    }
+
+    /// Mutable cell at (x, y), or `None` if out of bounds
+    #[inline]
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.layout.checked_index(x, y).map(|idx| &mut self.cells[idx])
+    }
+
+    pub fn world_to_grid(&self, world_pos: FixedVec2) -> Option<(usize, usize)> {
+        self.layout.world_to_grid(world_pos)
+    }
+
+    pub fn grid_to_world(&self, x: usize, y: usize) -> FixedVec2 {
+        self.layout.grid_to_world(x, y)
+    }
+
+    /// Row-major cell data
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Mutable row-major cell data
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// Consume the grid, returning the row-major cell data
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells
+    }
+}
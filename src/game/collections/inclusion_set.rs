@@ -17,6 +17,15 @@
 //!
 //! When crossing threshold, **migrates ALL items** to new mode (no dual-iteration).
 //!
+//! ## Hot Tier Semantics
+//!
+//! The hot tier is all-or-nothing: either every member is hot or none is.
+//! - **Promotion**: `sweep()` in bitset mode moves every member into a fresh hot Vec once
+//!   `count < hot_capacity - hysteresis_buffer`.
+//! - **Demotion**: `include()` on a full hot Vec drops the Vec; every member is then bitset-only.
+//!
+//! Use `iter_hot()` / `hot_len()` to touch only hot members without walking the bitset.
+//!
 //! # Type Constraints (CRITICAL)
 //!
 //! **T must convert to DENSE, SEQUENTIAL indices (0, 1, 2, 3, ...)**
@@ -77,12 +86,29 @@ impl Default for SetConfig {
 /// Result of inclusion operation
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IncludeResult {
-    /// Item was added to hot storage at this index (insert InclusionIndex component)
-    Hot(InclusionIndex),
-    /// Item was added to bitset storage (no component needed)
-    Bitset,
+    /// Item was added to the set.
+    /// - `Some(index)`: stored in hot storage at this index (insert InclusionIndex component)
+    /// - `None`: stored in bitset storage (no component needed)
+    Inserted(Option<InclusionIndex>),
     /// Item already existed (keep existing component state)
     AlreadyPresent,
+    /// Item's index is >= `max_capacity` and cannot be tracked (set unchanged)
+    AtCapacity,
+}
+
+impl IncludeResult {
+    /// Hot storage index assigned by this insert, if any
+    pub fn index(&self) -> Option<InclusionIndex> {
+        match self {
+            IncludeResult::Inserted(index) => *index,
+            IncludeResult::AlreadyPresent | IncludeResult::AtCapacity => None,
+        }
+    }
+
+    /// Whether the item is in the set after the operation (newly inserted or already present)
+    pub fn is_included(&self) -> bool {
+        !matches!(self, IncludeResult::AtCapacity)
+    }
 }
 
 /// Index update information returned by sweep
@@ -340,16 +366,17 @@ where
     /// Include an item in the set.
     /// 
     /// Returns:
-    /// - `IncludeResult::Hot(index)` - Item added to hot storage, insert InclusionIndex component
-    /// - `IncludeResult::Bitset` - Item added to bitset storage, no component needed
+    /// - `IncludeResult::Inserted(Some(index))` - Item added to hot storage, insert InclusionIndex component
+    /// - `IncludeResult::Inserted(None)` - Item added to bitset storage, no component needed
     /// - `IncludeResult::AlreadyPresent` - Item was already in the set
+    /// - `IncludeResult::AtCapacity` - Item's index exceeds `max_capacity`, set unchanged
     pub fn include(&mut self, item: T) -> IncludeResult {
         let key = item.into();
         
         // Reject indices exceeding max_capacity
         if key >= self.config.max_capacity {
             warn!("InclusionSet: Index {} exceeds max_capacity {} - REJECTING", key, self.config.max_capacity);
-            return IncludeResult::AlreadyPresent; // Treat as no-op
+            return IncludeResult::AtCapacity;
         }
         
         // Check bitset first (O(1) duplicate check)
@@ -373,15 +400,15 @@ where
         match &mut self.mode {
             StorageMode::Hot(hot) => {
                 match hot.insert(item) {
-                    Some(index) => IncludeResult::Hot(InclusionIndex(index)),
+                    Some(index) => IncludeResult::Inserted(Some(InclusionIndex(index))),
                     None => {
                         // Hot storage full - migrate to bitset-only
                         self.migrate_to_bitset();
-                        IncludeResult::Bitset
+                        IncludeResult::Inserted(None)
                     }
                 }
             }
-            StorageMode::BitsetOnly => IncludeResult::Bitset,
+            StorageMode::BitsetOnly => IncludeResult::Inserted(None),
         }
     }
 
@@ -472,6 +499,25 @@ where
         }
     }
 
+    /// Iterate over hot-tier members only (empty while in bitset mode).
+    ///
+    /// Unlike `iter()`, this never falls back to scanning the bitset.
+    pub fn iter_hot(&self) -> impl Iterator<Item = T> + '_ {
+        let hot = match &self.mode {
+            StorageMode::Hot(hot) => Some(hot.iter()),
+            StorageMode::BitsetOnly => None,
+        };
+        hot.into_iter().flatten()
+    }
+
+    /// Number of hot-tier members (O(1), excludes tombstones, 0 in bitset mode).
+    pub fn hot_len(&self) -> usize {
+        match &self.mode {
+            StorageMode::Hot(hot) => hot.count(),
+            StorageMode::BitsetOnly => 0,
+        }
+    }
+
     /// Get count of included items.
     pub fn count(&self) -> usize {
         match &self.mode {
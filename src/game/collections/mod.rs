@@ -23,9 +23,11 @@
 
 pub mod components;
 pub mod inclusion_set;
+pub mod grid;
 
 #[cfg(test)]
 mod tests;
 
 pub use components::InclusionIndex;
 pub use inclusion_set::{SetConfig, InclusionSet, IncludeResult, IndexUpdate, SetStats};
+pub use grid::{Grid2D, GridLayout};
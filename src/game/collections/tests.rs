@@ -1,4 +1,4 @@
-//! Tests for InclusionSet
+//! Tests for collections (InclusionSet, Grid2D)
 
 #[cfg(test)]
 mod tests {
@@ -35,9 +35,9 @@ mod tests {
         let r3 = set.include(TestId(3));
         
         // Should all be in hot storage
-        assert!(matches!(r1, IncludeResult::Hot(_)));
-        assert!(matches!(r2, IncludeResult::Hot(_)));
-        assert!(matches!(r3, IncludeResult::Hot(_)));
+        assert!(matches!(r1, IncludeResult::Inserted(Some(_))));
+        assert!(matches!(r2, IncludeResult::Inserted(Some(_))));
+        assert!(matches!(r3, IncludeResult::Inserted(Some(_))));
 
         assert_eq!(set.count(), 3);
         assert!(set.contains(TestId(1)));
@@ -59,13 +59,13 @@ mod tests {
 
         // Add items up to capacity
         for i in 0..5 {
-            assert!(matches!(set.include(TestId(i)), IncludeResult::Hot(_)));
+            assert!(matches!(set.include(TestId(i)), IncludeResult::Inserted(Some(_))));
         }
         assert_eq!(set.stats().mode, "Hot");
 
         // This should trigger migration
         let result = set.include(TestId(10));
-        assert!(matches!(result, IncludeResult::Bitset));
+        assert!(matches!(result, IncludeResult::Inserted(None)));
         assert_eq!(set.stats().mode, "Bitset");
         assert_eq!(set.count(), 6);
 
@@ -164,9 +164,9 @@ mod tests {
 
         let mut set = InclusionSet::<TestId>::new(config);
 
-        assert!(matches!(set.include(TestId(10)), IncludeResult::Bitset));
-        assert!(matches!(set.include(TestId(20)), IncludeResult::Bitset));
-        assert!(matches!(set.include(TestId(30)), IncludeResult::Bitset));
+        assert!(matches!(set.include(TestId(10)), IncludeResult::Inserted(None)));
+        assert!(matches!(set.include(TestId(20)), IncludeResult::Inserted(None)));
+        assert!(matches!(set.include(TestId(30)), IncludeResult::Inserted(None)));
 
         assert_eq!(set.stats().mode, "Bitset");
         assert_eq!(set.count(), 3);
@@ -212,9 +212,9 @@ mod tests {
         let mut set = InclusionSet::<TestId>::new(config);
 
         // Include items and save indices
-        let _idx1 = if let IncludeResult::Hot(idx) = set.include(TestId(1)) { idx } else { panic!() };
-        let idx2 = if let IncludeResult::Hot(idx) = set.include(TestId(2)) { idx } else { panic!() };
-        let _idx3 = if let IncludeResult::Hot(idx) = set.include(TestId(3)) { idx } else { panic!() };
+        let _idx1 = if let IncludeResult::Inserted(Some(idx)) = set.include(TestId(1)) { idx } else { panic!() };
+        let idx2 = if let IncludeResult::Inserted(Some(idx)) = set.include(TestId(2)) { idx } else { panic!() };
+        let _idx3 = if let IncludeResult::Inserted(Some(idx)) = set.include(TestId(3)) { idx } else { panic!() };
 
         assert_eq!(set.count(), 3);
 
@@ -238,4 +238,174 @@ mod tests {
         assert_eq!(updates.len(), 1);
         assert_eq!(updates[0], (2, 1));
     }
+
+    #[test]
+    fn test_include_result_outcomes() {
+        let config = SetConfig {
+            max_capacity: 100,
+            hot_capacity: Some(2),
+            hysteresis_buffer: Some(1),
+            sorted: false,
+        };
+
+        let mut set = InclusionSet::<TestId>::new(config);
+
+        // Inserted into hot storage carries the assigned index
+        assert_eq!(set.include(TestId(1)), IncludeResult::Inserted(Some(InclusionIndex(0))));
+        assert_eq!(set.include(TestId(2)), IncludeResult::Inserted(Some(InclusionIndex(1))));
+
+        // Duplicate
+        assert_eq!(set.include(TestId(1)), IncludeResult::AlreadyPresent);
+        assert_eq!(set.include(TestId(1)).index(), None);
+
+        // Hot storage full: still inserted, but into the bitset (no index)
+        let result = set.include(TestId(3));
+        assert_eq!(result, IncludeResult::Inserted(None));
+        assert!(result.is_included());
+
+        // Beyond max_capacity
+        let result = set.include(TestId(100));
+        assert_eq!(result, IncludeResult::AtCapacity);
+        assert!(!result.is_included());
+        assert!(!set.contains(TestId(100)));
+        assert_eq!(set.count(), 3);
+    }
+
+    #[test]
+    fn test_include_at_capacity_in_bitset_mode() {
+        let config = SetConfig {
+            max_capacity: 10,
+            hot_capacity: None,
+            hysteresis_buffer: None,
+            sorted: false,
+        };
+
+        let mut set = InclusionSet::<TestId>::new(config);
+
+        assert_eq!(set.include(TestId(9)), IncludeResult::Inserted(None));
+        assert_eq!(set.include(TestId(9)), IncludeResult::AlreadyPresent);
+        assert_eq!(set.include(TestId(10)), IncludeResult::AtCapacity);
+        assert_eq!(set.count(), 1);
+    }
+
+    #[test]
+    fn test_iter_hot_visits_exactly_hot_members() {
+        let config = SetConfig {
+            max_capacity: 1000,
+            hot_capacity: Some(10),
+            hysteresis_buffer: Some(2),
+            sorted: false,
+        };
+
+        let mut set = InclusionSet::<TestId>::new(config);
+
+        let mut indices = Vec::new();
+        for i in 0..5 {
+            indices.push(set.include(TestId(i)).index().unwrap());
+        }
+
+        // Tombstoned members are not hot
+        set.exclude(TestId(2), Some(indices[2]));
+
+        let mut hot: Vec<_> = set.iter_hot().collect();
+        hot.sort();
+        assert_eq!(hot, vec![TestId(0), TestId(1), TestId(3), TestId(4)]);
+        assert_eq!(set.hot_len(), 4);
+        assert_eq!(set.hot_len(), set.iter_hot().count());
+
+        // Overflow demotes everything to the bitset: no hot members remain
+        for i in 5..12 {
+            set.include(TestId(i));
+        }
+        assert_eq!(set.stats().mode, "Bitset");
+        assert_eq!(set.hot_len(), 0);
+        assert_eq!(set.iter_hot().count(), 0);
+        assert_eq!(set.count(), 11);
+
+        // Dropping below the hysteresis threshold promotes everything back on sweep
+        for i in 5..12 {
+            set.exclude(TestId(i), None);
+        }
+        set.sweep(|_, _| {});
+        assert_eq!(set.stats().mode, "Hot");
+        assert_eq!(set.hot_len(), 4);
+        assert_eq!(set.iter_hot().count(), 4);
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::super::*;
+    use crate::game::fixed_math::{FixedNum, FixedVec2};
+
+    fn layout() -> GridLayout {
+        // 4x3 grid of 2-unit cells starting at (-4, -3)
+        GridLayout::new(4, 3, FixedNum::from_num(2.0), FixedVec2::from_f32(-4.0, -3.0))
+    }
+
+    #[test]
+    fn test_grid_bounds() {
+        let grid = Grid2D::new(layout(), 0u8);
+
+        assert!(grid.in_bounds(0, 0));
+        assert!(grid.in_bounds(3, 2));
+        assert!(!grid.in_bounds(4, 0));
+        assert!(!grid.in_bounds(0, 3));
+        assert!(!grid.in_bounds(usize::MAX, 0));
+
+        assert_eq!(grid.get(3, 2), Some(&0));
+        assert_eq!(grid.get(4, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid.as_slice().len(), 12);
+    }
+
+    #[test]
+    fn test_grid_index_row_major() {
+        let mut grid = Grid2D::new(layout(), 0u32);
+        assert_eq!(grid.index(0, 0), 0);
+        assert_eq!(grid.index(3, 0), 3);
+        assert_eq!(grid.index(0, 1), 4);
+        assert_eq!(grid.index(3, 2), 11);
+
+        *grid.get_mut(1, 2).unwrap() = 7;
+        assert_eq!(grid.as_slice()[grid.index(1, 2)], 7);
+        assert!(grid.get_mut(4, 2).is_none());
+    }
+
+    #[test]
+    fn test_grid_world_to_grid() {
+        let layout = layout();
+
+        // Origin corner and interior points
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(-4.0, -3.0)), Some((0, 0)));
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(-2.1, -1.1)), Some((0, 0)));
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(0.0, 0.0)), Some((2, 1)));
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(3.9, 2.9)), Some((3, 2)));
+
+        // Just outside every edge (left/below must not truncate into cell 0)
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(-4.5, 0.0)), None);
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(0.0, -3.5)), None);
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(4.0, 0.0)), None);
+        assert_eq!(layout.world_to_grid(FixedVec2::from_f32(0.0, 3.0)), None);
+    }
+
+    #[test]
+    fn test_grid_to_world_round_trip() {
+        let grid = Grid2D::new(layout(), false);
+
+        assert_eq!(grid.grid_to_world(0, 0), FixedVec2::from_f32(-3.0, -2.0));
+        assert_eq!(grid.grid_to_world(3, 2), FixedVec2::from_f32(3.0, 2.0));
+
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                assert_eq!(grid.world_to_grid(grid.grid_to_world(x, y)), Some((x, y)));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grid_from_vec_size_mismatch() {
+        let _ = Grid2D::from_vec(layout(), vec![0u8; 5]);
+    }
 }
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_common_assets::ron::RonAssetPlugin;
 use serde::{Deserialize, Serialize};
+use crate::game::map::MapEdges;
 
 /// Static configuration loaded once at startup. These values define fundamental
 /// game parameters that should not change during gameplay (e.g., physics constants,
@@ -9,15 +10,27 @@ use serde::{Deserialize, Serialize};
 pub struct InitialConfig {
     // Physics & Simulation (deterministic, must not change mid-game)
     pub tick_rate: f64,
+    /// Most fixed ticks simulated per frame when behind; the rest is dropped (slow motion)
+    pub max_catchup_ticks: u32,
+    /// Most path requests resolved per tick; the rest wait in a queue for later ticks
+    pub path_requests_per_tick: usize,
     pub unit_speed: f32,
     pub map_width: f32,
     pub map_height: f32,
+    /// What units do at the map border
+    pub map_edges: MapEdges,
     pub unit_radius: f32,
     pub collision_push_strength: f32,
     pub collision_restitution: f32,
     pub collision_drag: f32,
     pub collision_iterations: usize,
     pub collision_search_radius_multiplier: f32,
+    /// See `SimConfig::collision_detection_margin`
+    pub collision_detection_margin: f32,
+    /// See `SimConfig::collision_neighbor_slots` (0 = no neighbor cache)
+    pub collision_neighbor_slots: usize,
+    /// Ticks between collision neighbor cache rebuilds (1 = every tick)
+    pub collision_neighbor_refresh_interval: u32,
     pub obstacle_search_range: i32,
     pub epsilon: f32,
     pub obstacle_push_strength: f32,
@@ -28,8 +41,14 @@ pub struct InitialConfig {
     pub repulsion_force: f32,
     pub repulsion_decay: f32,
     pub friction: f32,
+    /// Tick rate `friction` (velocity kept per tick) is tuned at; scaled for other tick rates
+    pub friction_reference_tick_rate: f64,
     pub min_velocity: f32,
     pub max_velocity: f32,
+    /// Fastest a unit turns to face its movement, in radians per second
+    pub unit_turn_rate: f32,
+    /// Longest move per integration sub-step as a fraction of the unit radius (0 disables sub-stepping)
+    pub max_move_per_substep: f32,
     pub braking_force: f32,
     pub touch_dist_multiplier: f32,
     pub check_dist_multiplier: f32,
@@ -40,7 +59,10 @@ pub struct InitialConfig {
     pub cohesion_weight: f32,
     pub neighbor_radius: f32,
     pub separation_radius: f32,
+    /// Nearest neighbors each unit considers for boids (0 = every neighbor in radius)
     pub boids_max_neighbors: usize,
+    /// Ticks between boids recomputes per unit (1 = every tick)
+    pub boids_update_interval: u32,
     
     // Force sources
     pub black_hole_strength: f32,
@@ -52,8 +74,13 @@ pub struct InitialConfig {
     pub editor_obstacle_min_radius: f32,
     pub editor_obstacle_max_radius: f32,
     pub editor_default_obstacle_radius: f32,
+    /// How deep a click-placed obstacle may overlap an existing one, as a fraction of the
+    /// smaller diameter (0 = touching at most, 1 = allow stacking)
+    pub editor_max_obstacle_overlap: f32,
     pub editor_map_size_x: f32,
     pub editor_map_size_y: f32,
+    /// Seconds between auto-saves of the map being edited (0 disables auto-save)
+    pub editor_autosave_interval_secs: f32,
     
     // Pathfinding settings
     pub pathfinding_build_batch_size: usize,
@@ -80,6 +107,10 @@ pub struct GameConfig {
     pub key_camera_right: KeyCode,
     pub key_debug_graph: KeyCode,
     pub key_debug_path: KeyCode,
+    pub key_debug_spatial_hash: KeyCode,        // Cycles off -> size class 0 -> 1 -> ... -> off
+    pub key_debug_spatial_hash_grids: KeyCode,  // Tints Grid A / Grid B differently
+    pub key_debug_perf_overlay: KeyCode,        // FPS / TPS / unit count overlay
+    pub key_debug_collisions: KeyCode,          // Colliding pairs and their resolution pushes
     pub key_spawn_black_hole: KeyCode,
     pub key_spawn_wind_spot: KeyCode,
     pub key_spawn_unit: KeyCode,
@@ -87,19 +118,50 @@ pub struct GameConfig {
     pub key_pause: KeyCode,
     pub key_toggle_health_bars: KeyCode,
     pub key_clear_force_sources: KeyCode,
+    pub key_stop_units: KeyCode,
+    pub key_camera_rotate_modifier: KeyCode,
+    pub key_paint_select: KeyCode,
+    pub key_queue_order: KeyCode,               // Hold while ordering a move to queue it
 
     // Camera (hot-reloadable)
     pub camera_speed: f32,
     pub camera_zoom_speed: f32,
+    pub camera_rotate_speed: f32,  // Radians of yaw per pixel of modifier + middle-mouse drag
 
     // UI (hot-reloadable)
     pub selection_drag_threshold: f32,
     pub selection_click_radius: f32,
+    pub selection_paint_radius: f32,  // World units around the cursor path that paint-select picks up
+    pub minimap_width: f32,   // Logical pixels, including border
+    pub minimap_height: f32,
+    pub minimap_corner: MinimapCorner,
+    pub minimap_shape: MinimapShape,
     
     // Debug visualization (hot-reloadable)
     pub debug_view_radius: f32,
     pub debug_path_trace_max_steps: usize,
     pub debug_unit_lod_height_threshold: f32,
+    pub debug_spatial_hash_high_occupancy: usize,  // Entities per cell drawn as "high"
+}
+
+/// Screen corner the HUD minimap is anchored to
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MinimapCorner {
+    #[default]
+    BottomLeft,
+    BottomRight,
+    TopLeft,
+    TopRight,
+}
+
+/// Outline of the HUD minimap
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MinimapShape {
+    #[default]
+    Rectangle,
+    /// Radar style: the circle inscribed in the (then square) minimap; the map's corners
+    /// fall outside it and are not drawn or clickable
+    Circle,
 }
 
 #[derive(Resource)]
@@ -151,15 +213,21 @@ impl Default for InitialConfig {
     fn default() -> Self {
         Self {
             tick_rate: 30.0,
+            max_catchup_ticks: 4,
+            path_requests_per_tick: 1000,
             unit_speed: 10.0,
             map_width: 2048.0,
             map_height: 2048.0,
+            map_edges: MapEdges::Open,
             unit_radius: 0.5,
             collision_push_strength: 1.0,
             collision_restitution: 0.5,
             collision_drag: 0.02,
             collision_iterations: 4,
             collision_search_radius_multiplier: 4.0,
+            collision_detection_margin: 0.1,
+            collision_neighbor_slots: 0,
+            collision_neighbor_refresh_interval: 1,
             obstacle_search_range: 1,
             epsilon: 0.0001,
             obstacle_push_strength: 1.0,
@@ -170,8 +238,11 @@ impl Default for InitialConfig {
             repulsion_force: 20.0,
             repulsion_decay: 2.0,
             friction: 0.98,
+            friction_reference_tick_rate: 30.0,
             min_velocity: 0.01,
             max_velocity: 50.0,
+            unit_turn_rate: 12.0,
+            max_move_per_substep: 0.5,
             braking_force: 5.0,
             touch_dist_multiplier: 2.1,
             check_dist_multiplier: 4.0,
@@ -181,6 +252,7 @@ impl Default for InitialConfig {
             neighbor_radius: 5.0,
             separation_radius: 1.5,
             boids_max_neighbors: 8,
+            boids_update_interval: 1,
             black_hole_strength: 50.0,
             wind_spot_strength: -50.0,
             force_source_radius: 10.0,
@@ -188,8 +260,10 @@ impl Default for InitialConfig {
             editor_obstacle_min_radius: 10.0,
             editor_obstacle_max_radius: 50.0,
             editor_default_obstacle_radius: 20.0,
+            editor_max_obstacle_overlap: 0.25,
             editor_map_size_x: 2048.0,
             editor_map_size_y: 2048.0,
+            editor_autosave_interval_secs: 60.0,
             pathfinding_build_batch_size: 5,
             spatial_hash_entity_radii: vec![0.5, 10.0, 25.0],
             spatial_hash_radius_to_cell_ratio: 4.0,
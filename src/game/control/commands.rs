@@ -1,12 +1,12 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use crate::game::unit::Selected;
-use crate::game::simulation::UnitMoveCommand;
+use crate::game::simulation::{UnitMoveCommand, UnitStopCommand};
 use crate::game::fixed_math::FixedVec2;
 use crate::game::camera::RtsCamera;
 use super::resources::*;
 use super::selection::*;
-use crate::game::unit::Unit;
+use crate::game::unit::Selectable;
 use crate::game::config::{GameConfig, GameConfigHandle};
 
 /// Main input handler - routes to appropriate handler based on input mode
@@ -15,7 +15,7 @@ pub fn handle_input(
     mouse_button: Res<ButtonInput<MouseButton>>,
     q_window: Query<&Window, With<PrimaryWindow>>,
     q_camera: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
-    q_units: Query<(Entity, &GlobalTransform), With<Unit>>,
+    q_units: Query<(Entity, &GlobalTransform), With<Selectable>>,
     q_selected: Query<Entity, With<Selected>>,
     mut drag_state: ResMut<DragState>,
     mut q_selection_box: Query<(&mut Node, &mut Visibility), With<SelectionBox>>,
@@ -23,6 +23,7 @@ pub fn handle_input(
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     mut input_mode: ResMut<InputMode>,
+    keys: Res<ButtonInput<KeyCode>>,
 ) {
     let Some((camera, camera_transform)) = q_camera.iter().next() else { return };
     let Some(window) = q_window.iter().next() else { return };
@@ -31,17 +32,28 @@ pub fn handle_input(
 
     match *input_mode {
         InputMode::Selection => {
-            handle_selection(
-                &mut commands,
-                &mouse_button,
-                cursor_position,
-                camera,
-                camera_transform,
-                &q_units,
-                &mut drag_state,
-                &mut q_selection_box,
-                config,
-            );
+            if keys.pressed(config.key_paint_select) {
+                // Paint select owns the left button (handle_paint_selection); drop any box
+                if drag_state.start.is_some() {
+                    drag_state.start = None;
+                    drag_state.current = None;
+                    if let Ok((_, mut visibility)) = q_selection_box.single_mut() {
+                        *visibility = Visibility::Hidden;
+                    }
+                }
+            } else {
+                handle_selection(
+                    &mut commands,
+                    &mouse_button,
+                    cursor_position,
+                    camera,
+                    camera_transform,
+                    &q_units,
+                    &mut drag_state,
+                    &mut q_selection_box,
+                    config,
+                );
+            }
 
             // Right Click: Movement (Smart Command)
             if mouse_button.just_pressed(MouseButton::Right) {
@@ -51,6 +63,7 @@ pub fn handle_input(
                     camera_transform,
                     &q_selected,
                     &mut move_events,
+                    keys.pressed(config.key_queue_order),
                 );
             }
         }
@@ -62,6 +75,7 @@ pub fn handle_input(
                     camera_transform,
                     &q_selected,
                     &mut move_events,
+                    keys.pressed(config.key_queue_order),
                 );
                 *input_mode = InputMode::Selection;
             } else if mouse_button.just_pressed(MouseButton::Right) {
@@ -79,13 +93,35 @@ pub fn handle_input(
     }
 }
 
-/// Issue a move command to selected units
+/// Stop hotkey - emit a stop command for every selected unit
+pub fn handle_stop_hotkey(
+    keys: Res<ButtonInput<KeyCode>>,
+    q_selected: Query<Entity, With<Selected>>,
+    mut stop_events: MessageWriter<UnitStopCommand>,
+    config_handle: Res<GameConfigHandle>,
+    game_configs: Res<Assets<GameConfig>>,
+) {
+    let Some(config) = game_configs.get(&config_handle.0) else { return };
+    if !keys.just_pressed(config.key_stop_units) {
+        return;
+    }
+
+    for entity in q_selected.iter() {
+        stop_events.write(UnitStopCommand {
+            player_id: 0, // Local player
+            entity,
+        });
+    }
+}
+
+/// Issue a move command to selected units, queued behind their current orders if `queued`
 fn issue_move_command(
     cursor_position: Vec2,
     camera: &Camera,
     camera_transform: &GlobalTransform,
     q_selected: &Query<Entity, With<Selected>>,
     move_events: &mut MessageWriter<UnitMoveCommand>,
+    queued: bool,
 ) {
     let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { return };
     let normal = Vec3::Y;
@@ -100,6 +136,7 @@ fn issue_move_command(
                     player_id: 0,
                     entity,
                     target: FixedVec2::from_f32(intersection_point.x, intersection_point.z),
+                    queued,
                 });
             }
         }
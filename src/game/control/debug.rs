@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
-use crate::game::simulation::{ForceSource, ForceType, SpawnUnitCommand, SimPosition};
+use crate::game::simulation::{ForceSource, SpawnUnitCommand};
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 use crate::game::camera::RtsCamera;
 use crate::game::config::{GameConfig, GameConfigHandle, InitialConfig};
 use rand::{rng, Rng};
+use super::resources::DebugSpawnSettings;
 
 /// Handle debug spawning via keyboard shortcuts
 pub fn handle_debug_spawning(
@@ -15,6 +16,7 @@ pub fn handle_debug_spawning(
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     initial_config: Res<InitialConfig>,
+    spawn_settings: Res<DebugSpawnSettings>,
     mut spawn_events: MessageWriter<SpawnUnitCommand>,
 ) {
     let Some((camera, camera_transform)) = q_camera.iter().next() else { return };
@@ -43,59 +45,58 @@ pub fn handle_debug_spawning(
                     info!("Spawning Black Hole at {:?}", pos_fixed);
                     commands.spawn((
                         crate::game::GameEntity,
-                        Transform::from_translation(intersection_point),
-                        GlobalTransform::default(),
-                        SimPosition(pos_fixed),
-                        ForceSource {
-                            force_type: ForceType::Radial(FixedNum::from_num(initial_config.black_hole_strength)), // Positive = Attract
-                            radius: FixedNum::from_num(initial_config.force_source_radius),
-                        }
+                        ForceSource::attract(
+                            pos_fixed,
+                            FixedNum::from_num(initial_config.black_hole_strength.abs()),
+                            FixedNum::from_num(initial_config.force_source_radius),
+                        ),
                     ));
                 } else if keys.just_pressed(config.key_spawn_wind_spot) {
                     // Spawn Wind Spot (Repel)
                     info!("Spawning Wind Spot at {:?}", pos_fixed);
-                     commands.spawn((
+                    commands.spawn((
                         crate::game::GameEntity,
-                        Transform::from_translation(intersection_point),
-                        GlobalTransform::default(),
-                        SimPosition(pos_fixed),
-                        ForceSource {
-                            force_type: ForceType::Radial(FixedNum::from_num(initial_config.wind_spot_strength)), // Negative = Repel
-                            radius: FixedNum::from_num(initial_config.force_source_radius),
-                        }
+                        ForceSource::repel(
+                            pos_fixed,
+                            // Configured negative (repel); the kind carries the sign now
+                            FixedNum::from_num(initial_config.wind_spot_strength.abs()),
+                            FixedNum::from_num(initial_config.force_source_radius),
+                        ),
                     ));
                 } else if keys.just_pressed(config.key_spawn_unit) {
                     info!("Spawning Unit at {:?}", pos_fixed);
                     spawn_events.write(SpawnUnitCommand {
                         player_id: 0,
                         position: pos_fixed,
+                        radius: None,
                     });
                 } else if keys.just_pressed(config.key_spawn_batch) {
                     info!("Spawning batch of units at {:?}", pos_fixed);
-                    spawn_batch_at(&mut spawn_events, 100, intersection_point.x, intersection_point.z);
+                    spawn_batch_at(&mut spawn_events, &spawn_settings, intersection_point.x, intersection_point.z);
                 }
             }
         }
     }
 }
 
-/// Spawn a batch of units around a center point
+/// Spawn a batch of units around a center point, with radii from the configured distribution
 fn spawn_batch_at(
     spawn_events: &mut MessageWriter<SpawnUnitCommand>,
-    count: usize,
+    settings: &DebugSpawnSettings,
     center_x: f32,
     center_z: f32,
 ) {
     let mut rng = rng();
-    let spread = 50.0; // Spread units around the click
+    let spread = settings.batch_spread; // Spread units around the click
     
-    for _ in 0..count {
+    for i in 0..settings.batch_size {
         let pos_x = center_x + rng.random_range(-spread..spread);
         let pos_z = center_z + rng.random_range(-spread..spread);
         
         spawn_events.write(SpawnUnitCommand {
             player_id: 0,
             position: FixedVec2::from_f32(pos_x, pos_z),
+            radius: settings.radius_for_index(i).map(FixedNum::from_num),
         });
     }
 }
@@ -117,3 +118,56 @@ pub fn clear_force_sources(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::game::pathfinding::PathRequest;
+    use crate::game::simulation::{systems::process_input, SimConfig, UnitMoveCommand, UnitStopCommand};
+    use crate::game::spatial_hash::SpatialHash;
+
+    #[test]
+    fn test_radius_distribution_fills_size_classes_proportionally() {
+        let mut app = App::new();
+        app.init_resource::<SimConfig>();
+        app.insert_resource(SpatialHash::new(
+            FixedNum::from_num(100.0),
+            FixedNum::from_num(100.0),
+            &[0.5, 10.0, 25.0],
+            4.0,
+            10_000,
+            1.0,
+        ));
+        app.insert_resource(DebugSpawnSettings {
+            batch_size: 100,
+            batch_spread: 30.0,
+            radius_distribution: vec![(0.5, 7), (10.0, 2), (25.0, 1)],
+        });
+        app.add_message::<SpawnUnitCommand>();
+        app.add_message::<UnitMoveCommand>();
+        app.add_message::<UnitStopCommand>();
+        app.add_message::<PathRequest>();
+
+        // The batch a debug spawn click sends, picked up by the simulation's input processing
+        app.world_mut()
+            .run_system_once(|mut spawn_events: MessageWriter<SpawnUnitCommand>, settings: Res<DebugSpawnSettings>| {
+                spawn_batch_at(&mut spawn_events, &settings, 0.0, 0.0);
+            })
+            .unwrap();
+        app.world_mut().run_system_once(process_input).unwrap();
+
+        let counts: Vec<usize> = app.world().resource::<SpatialHash>().size_classes().iter().map(|sc| sc.entity_count).collect();
+        assert_eq!(counts, vec![70, 20, 10]);
+    }
+
+    #[test]
+    fn test_radius_distribution_empty_or_zero_weight() {
+        let mut settings = DebugSpawnSettings { radius_distribution: vec![], ..Default::default() };
+        assert_eq!(settings.radius_for_index(0), None);
+
+        settings.radius_distribution = vec![(1.0, 0), (2.0, 3)];
+        assert_eq!(settings.radius_for_index(0), Some(2.0));
+        assert_eq!(settings.radius_for_index(4), Some(2.0));
+    }
+}
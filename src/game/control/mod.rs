@@ -18,8 +18,12 @@ pub struct ControlPlugin;
 impl Plugin for ControlPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DragState>()
+           .init_resource::<PaintSelectState>()
            .init_resource::<InputMode>()
+           .init_resource::<DebugSpawnSettings>()
            .add_systems(Startup, setup_selection_box)
-           .add_systems(Update, (handle_input, handle_debug_spawning, clear_force_sources).run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
+           .add_systems(OnExit(GameState::InGame), (cancel_drag, cancel_paint))
+           .add_systems(OnExit(GameState::Editor), (cancel_drag, cancel_paint))
+           .add_systems(Update, (handle_input, handle_paint_selection, handle_stop_hotkey, handle_debug_spawning, clear_force_sources).run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
     }
 }
@@ -1,10 +1,52 @@
 use bevy::prelude::*;
+use std::collections::HashSet;
+use crate::game::fixed_math::FixedVec2;
+
+/// Drag distance (screen pixels) below which a left-click gesture counts as a click
+pub const DEFAULT_DRAG_THRESHOLD: f32 = 5.0;
+
+/// What a left-button press/release gesture means for selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragGesture {
+    /// Select the nearest unit under the cursor
+    Click,
+    /// Select every unit inside the dragged rectangle
+    Box,
+}
 
 /// State for tracking mouse drag operations
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct DragState {
     pub start: Option<Vec2>,
     pub current: Option<Vec2>,
+    /// Screen pixels the cursor must travel from `start` before the gesture becomes a box
+    /// select. Refreshed from `GameConfig::selection_drag_threshold` when a drag starts.
+    pub threshold: f32,
+}
+
+impl Default for DragState {
+    fn default() -> Self {
+        Self { start: None, current: None, threshold: DEFAULT_DRAG_THRESHOLD }
+    }
+}
+
+impl DragState {
+    /// Classify a gesture from `start` to `end` (screen positions). Distances strictly
+    /// below the threshold are clicks, so a tiny wobble while clicking still single-selects.
+    pub fn classify(&self, start: Vec2, end: Vec2) -> DragGesture {
+        if start.distance(end) < self.threshold {
+            DragGesture::Click
+        } else {
+            DragGesture::Box
+        }
+    }
+}
+
+/// Paint-select drag: world point of the previous frame and units already added this drag
+#[derive(Resource, Default)]
+pub struct PaintSelectState {
+    pub last_point: Option<FixedVec2>,
+    pub painted: HashSet<Entity>,
 }
 
 /// Current input mode for player commands
@@ -16,6 +58,77 @@ pub enum InputMode {
     CommandAttack,
 }
 
+/// Debug spawning parameters used when stress-testing with the spawn hotkeys
+#[derive(Resource)]
+pub struct DebugSpawnSettings {
+    /// Number of units spawned by the batch hotkey
+    pub batch_size: usize,
+    /// Half-width of the square area batch units are scattered over
+    pub batch_spread: f32,
+    /// Weighted radius buckets `(radius, weight)` for batch spawns.
+    /// Pick radii matching the spatial hash size classes to exercise all of them.
+    pub radius_distribution: Vec<(f32, u32)>,
+}
+
+impl Default for DebugSpawnSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            batch_spread: 50.0,
+            radius_distribution: vec![(0.5, 1)],  // All units the default unit radius
+        }
+    }
+}
+
+impl DebugSpawnSettings {
+    /// Radius for the `index`-th unit of a batch.
+    ///
+    /// Cycles through the buckets by weight, so any batch whose size is a multiple of
+    /// the total weight matches the distribution exactly (and is deterministic).
+    /// Returns `None` if the distribution is empty or all weights are zero.
+    pub fn radius_for_index(&self, index: usize) -> Option<f32> {
+        let total_weight: usize = self.radius_distribution.iter().map(|&(_, w)| w as usize).sum();
+        if total_weight == 0 {
+            return None;
+        }
+        let mut slot = index % total_weight;
+        for &(radius, weight) in &self.radius_distribution {
+            if slot < weight as usize {
+                return Some(radius);
+            }
+            slot -= weight as usize;
+        }
+        None
+    }
+}
+
 /// Marker component for the selection box UI element
 #[derive(Component)]
 pub struct SelectionBox;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drag_state(threshold: f32) -> DragState {
+        DragState { threshold, ..Default::default() }
+    }
+
+    #[test]
+    fn test_small_drag_is_a_click() {
+        let state = drag_state(5.0);
+        let start = Vec2::new(100.0, 100.0);
+        assert_eq!(state.classify(start, start), DragGesture::Click);
+        assert_eq!(state.classify(start, Vec2::new(103.0, 103.9)), DragGesture::Click);
+        assert_eq!(state.classify(start, Vec2::new(97.0, 96.0)), DragGesture::Box, "Exactly the threshold is a box");
+        assert_eq!(state.classify(start, Vec2::new(140.0, 100.0)), DragGesture::Box);
+    }
+
+    #[test]
+    fn test_threshold_is_configurable() {
+        let (start, end) = (Vec2::ZERO, Vec2::new(12.0, 0.0));
+        assert_eq!(drag_state(5.0).classify(start, end), DragGesture::Box);
+        assert_eq!(drag_state(20.0).classify(start, end), DragGesture::Click);
+        assert_eq!(drag_state(0.0).classify(start, start), DragGesture::Box, "Zero threshold disables click select");
+    }
+}
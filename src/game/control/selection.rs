@@ -1,6 +1,12 @@
 use bevy::prelude::*;
-use crate::game::unit::{Unit, Selected};
-use crate::game::config::GameConfig;
+use bevy::window::PrimaryWindow;
+use std::collections::HashSet;
+use crate::game::unit::{Selectable, Selected};
+use crate::game::config::{GameConfig, GameConfigHandle};
+use crate::game::camera::RtsCamera;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::simulation::SimPosition;
+use crate::game::spatial_hash::{SpatialHash, SpatialHashScratch};
 use super::resources::*;
 
 /// Setup the selection box UI element
@@ -18,14 +24,35 @@ pub fn setup_selection_box(mut commands: Commands) {
     ));
 }
 
-/// Handle unit selection via mouse drag or click
+/// Drop an in-progress drag when input stops being handled (e.g. pausing mid-drag).
+///
+/// Otherwise the release after resuming would replace the selection using a box
+/// started before the pause.
+pub fn cancel_drag(
+    mut drag_state: ResMut<DragState>,
+    mut q_selection_box: Query<&mut Visibility, With<SelectionBox>>,
+) {
+    drag_state.start = None;
+    drag_state.current = None;
+    if let Ok(mut visibility) = q_selection_box.single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// End a paint-select drag when input stops being handled (see [`cancel_drag`])
+pub fn cancel_paint(mut paint_state: ResMut<PaintSelectState>) {
+    paint_state.last_point = None;
+    paint_state.painted.clear();
+}
+
+/// Handle unit selection via mouse drag or click (only [`Selectable`] entities are considered)
 pub fn handle_selection(
     commands: &mut Commands,
     mouse_button: &Res<ButtonInput<MouseButton>>,
     cursor_position: Vec2,
     camera: &Camera,
     camera_transform: &GlobalTransform,
-    q_units: &Query<(Entity, &GlobalTransform), With<Unit>>,
+    q_units: &Query<(Entity, &GlobalTransform), With<Selectable>>,
     drag_state: &mut ResMut<DragState>,
     q_selection_box: &mut Query<(&mut Node, &mut Visibility), With<SelectionBox>>,
     config: &GameConfig,
@@ -34,17 +61,19 @@ pub fn handle_selection(
     if mouse_button.just_pressed(MouseButton::Left) {
         drag_state.start = Some(cursor_position);
         drag_state.current = Some(cursor_position);
-        
-        if let Ok((_, mut visibility)) = q_selection_box.single_mut() {
-            *visibility = Visibility::Visible;
-        }
+        drag_state.threshold = config.selection_drag_threshold;
     }
 
     if mouse_button.pressed(MouseButton::Left) {
         if let Some(start) = drag_state.start {
             drag_state.current = Some(cursor_position);
 
-            if let Ok((mut node, _)) = q_selection_box.single_mut() {
+            // The box only shows up once the drag is long enough to box select
+            if let Ok((mut node, mut visibility)) = q_selection_box.single_mut() {
+                *visibility = match drag_state.classify(start, cursor_position) {
+                    DragGesture::Click => Visibility::Hidden,
+                    DragGesture::Box => Visibility::Visible,
+                };
                 let min = start.min(cursor_position);
                 let max = start.max(cursor_position);
                 let size = max - min;
@@ -69,14 +98,12 @@ pub fn handle_selection(
 
             let min = start.min(end);
             let max = start.max(end);
-            let size = max - min;
-            let is_click = size.length() < config.selection_drag_threshold;
 
             for (entity, _) in q_units.iter() {
                 commands.entity(entity).remove::<Selected>();
             }
 
-            if is_click {
+            if drag_state.classify(start, end) == DragGesture::Click {
                 let Ok(ray) = camera.viewport_to_world(camera_transform, end) else { return };
                 let mut closest_hit: Option<(Entity, f32)> = None;
                 for (entity, unit_transform) in q_units.iter() {
@@ -97,16 +124,226 @@ pub fn handle_selection(
                     commands.entity(hit_entity).insert(Selected);
                 }
             } else {
-                for (entity, unit_transform) in q_units.iter() {
-                    let unit_pos = unit_transform.translation();
-                    if let Ok(screen_pos) = camera.world_to_viewport(camera_transform, unit_pos) {
-                        if screen_pos.x >= min.x && screen_pos.x <= max.x &&
-                           screen_pos.y >= min.y && screen_pos.y <= max.y {
-                            commands.entity(entity).insert(Selected);
-                        }
-                    }
+                let to_viewport = |pos| camera.world_to_viewport(camera_transform, pos).ok();
+                for entity in box_select(q_units, min, max, to_viewport) {
+                    commands.entity(entity).insert(Selected);
                 }
             }
         }
     }
 }
+
+/// Selectable entities whose screen position (per `to_viewport`) lies inside the
+/// `min..=max` screen rectangle
+pub fn box_select(
+    q_units: &Query<(Entity, &GlobalTransform), With<Selectable>>,
+    min: Vec2,
+    max: Vec2,
+    to_viewport: impl Fn(Vec3) -> Option<Vec2>,
+) -> Vec<Entity> {
+    q_units.iter().filter_map(|(entity, transform)| {
+        let screen_pos = to_viewport(transform.translation())?;
+        (screen_pos.x >= min.x && screen_pos.x <= max.x && screen_pos.y >= min.y && screen_pos.y <= max.y)
+            .then_some(entity)
+    }).collect()
+}
+
+/// Paint select: while the paint key is held, left-dragging adds every unit the cursor
+/// passes near to the selection (box select is suspended meanwhile, see `handle_input`)
+pub fn handle_paint_selection(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    q_units: Query<&SimPosition, With<Selectable>>,
+    spatial_hash: Res<SpatialHash>,
+    mut scratch: ResMut<SpatialHashScratch>,
+    mut paint_state: ResMut<PaintSelectState>,
+    input_mode: Res<InputMode>,
+    config_handle: Res<GameConfigHandle>,
+    game_configs: Res<Assets<GameConfig>>,
+) {
+    let Some(config) = game_configs.get(&config_handle.0) else { return };
+    let painting = *input_mode == InputMode::Selection
+        && keys.pressed(config.key_paint_select)
+        && mouse_button.pressed(MouseButton::Left);
+    if !painting {
+        if paint_state.last_point.is_some() {
+            paint_state.last_point = None;
+            paint_state.painted.clear();
+        }
+        return;
+    }
+
+    let Some((camera, camera_transform)) = q_camera.iter().next() else { return };
+    let Some(cursor_position) = q_window.iter().next().and_then(|window| window.cursor_position()) else { return };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { return };
+    if ray.direction.y.abs() <= 0.0001 {
+        return;
+    }
+    let t = -ray.origin.y / ray.direction.y;
+    if t < 0.0 {
+        return;
+    }
+    let hit = ray.origin + ray.direction * t;
+    let point = FixedVec2::from_f32(hit.x, hit.z);
+
+    // First frame of the drag paints just the point under the cursor
+    let from = paint_state.last_point.unwrap_or(point);
+    let newly_painted = paint_select_segment(
+        from,
+        point,
+        FixedNum::from_num(config.selection_paint_radius),
+        &spatial_hash,
+        &mut scratch,
+        |entity| q_units.get(entity).ok().map(|pos| pos.0),
+        &mut paint_state.painted,
+    );
+    for entity in newly_painted {
+        commands.entity(entity).insert(Selected);
+    }
+    paint_state.last_point = Some(point);
+}
+
+/// Units within `radius` of the segment `from -> to` that aren't in `painted` yet.
+///
+/// Radius queries are spaced `radius` apart along the segment, so a fast cursor that jumps
+/// far between frames still picks up everything it swept over. `unit_position` returns
+/// `None` for entities that can't be selected. New units are added to `painted` as well.
+pub fn paint_select_segment(
+    from: FixedVec2,
+    to: FixedVec2,
+    radius: FixedNum,
+    spatial_hash: &SpatialHash,
+    scratch: &mut SpatialHashScratch,
+    unit_position: impl Fn(Entity) -> Option<FixedVec2>,
+    painted: &mut HashSet<Entity>,
+) -> Vec<Entity> {
+    let mut newly_painted = Vec::new();
+    if radius <= FixedNum::ZERO {
+        return newly_painted;
+    }
+
+    let segment = to - from;
+    let steps = (segment.length() / radius).ceil().to_num::<usize>().max(1);
+    for step in 0..=steps {
+        let sample = from + segment * (FixedNum::from_num(step) / FixedNum::from_num(steps));
+        // Samples are `radius` apart, so 2x covers every point within `radius` of the segment
+        spatial_hash.query_radius(sample, radius * 2, None, scratch);
+        for &entity in &scratch.query_results {
+            if painted.contains(&entity) {
+                continue;
+            }
+            let Some(pos) = unit_position(entity) else { continue };
+            if distance_sq_to_segment(pos, from, to) <= radius * radius {
+                painted.insert(entity);
+                newly_painted.push(entity);
+            }
+        }
+    }
+    newly_painted
+}
+
+fn distance_sq_to_segment(point: FixedVec2, from: FixedVec2, to: FixedVec2) -> FixedNum {
+    let segment = to - from;
+    let len_sq = segment.length_squared();
+    if len_sq == FixedNum::ZERO {
+        return (point - from).length_squared();
+    }
+    let t = ((point - from).dot(segment) / len_sq).clamp(FixedNum::ZERO, FixedNum::ONE);
+    (point - (from + segment * t)).length_squared()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn test_entity(id: u32) -> Entity {
+        Entity::from_bits((id as u64) << 32 | 1)
+    }
+
+    /// Hash plus a position lookup for units at the given points
+    fn units_at(points: &[(f32, f32)]) -> (SpatialHash, HashMap<Entity, FixedVec2>) {
+        let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 1000, 1.0);
+        let mut positions = HashMap::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            let entity = test_entity(i as u32 + 1);
+            let pos = FixedVec2::from_f32(x, y);
+            hash.insert(entity, pos, FixedNum::from_num(0.5)).unwrap();
+            positions.insert(entity, pos);
+        }
+        (hash, positions)
+    }
+
+    #[test]
+    fn test_paint_accumulates_units_along_the_drag() {
+        // A row of units on y = 0, a parallel row just out of reach, one past the end
+        let mut points: Vec<_> = (0..8).map(|i| (i as f32 * 3.0 - 10.0, 0.3)).collect();
+        points.extend((0..8).map(|i| (i as f32 * 3.0 - 10.0, 4.0)));
+        points.push((25.0, 0.0));
+        let (hash, positions) = units_at(&points);
+        let mut scratch = SpatialHashScratch::new(256);
+        let radius = FixedNum::from_num(1.5);
+
+        // Cursor trajectory with big jumps between frames (far more than the paint radius)
+        let trajectory = [(-11.0, 0.0), (-11.0, 0.0), (0.0, 0.0), (6.0, -0.5), (12.0, 0.0)];
+        let mut painted = HashSet::new();
+        let mut per_frame = Vec::new();
+        for pair in trajectory.windows(2) {
+            let (from, to) = (FixedVec2::from_f32(pair[0].0, pair[0].1), FixedVec2::from_f32(pair[1].0, pair[1].1));
+            let new = paint_select_segment(from, to, radius, &hash, &mut scratch, |e| positions.get(&e).copied(), &mut painted);
+            per_frame.push(new.len());
+        }
+
+        let expected: HashSet<Entity> = positions.iter()
+            .filter(|(_, pos)| pos.y < FixedNum::from_num(1.0) && pos.x <= FixedNum::from_num(12.0))
+            .map(|(&e, _)| e)
+            .collect();
+        assert_eq!(expected.len(), 8);
+        assert_eq!(painted, expected);
+        // Each unit is reported once, in the frame the cursor first reached it
+        assert_eq!(per_frame.iter().sum::<usize>(), 8);
+        assert_eq!(per_frame, vec![1, 3, 2, 2]);
+    }
+
+    #[test]
+    fn test_paint_skips_non_units_and_already_painted() {
+        let (hash, positions) = units_at(&[(0.0, 0.0), (1.0, 0.0)]);
+        let mut scratch = SpatialHashScratch::new(64);
+        let non_unit = test_entity(1);
+        let lookup = |e: Entity| if e == non_unit { None } else { positions.get(&e).copied() };
+
+        let mut painted = HashSet::new();
+        let first = paint_select_segment(FixedVec2::ZERO, FixedVec2::ZERO, FixedNum::from_num(2.0), &hash, &mut scratch, lookup, &mut painted);
+        assert_eq!(first, vec![test_entity(2)]);
+        let again = paint_select_segment(FixedVec2::ZERO, FixedVec2::ZERO, FixedNum::from_num(2.0), &hash, &mut scratch, lookup, &mut painted);
+        assert!(again.is_empty());
+    }
+
+    #[test]
+    fn test_box_select_only_picks_selectable_entities() {
+        use bevy::ecs::system::RunSystemOnce;
+        use crate::game::simulation::{Collider, ObstacleBundle, layers};
+        use crate::game::unit::UnitBundle;
+
+        let mut world = World::new();
+        let at = |x: f32, z: f32| GlobalTransform::from_translation(Vec3::new(x, 0.0, z));
+        let units: Vec<Entity> = [(1.0, 1.0), (4.0, 2.0), (20.0, 20.0)].into_iter()
+            .map(|(x, z)| world.spawn((UnitBundle::new(FixedVec2::from_f32(x, z), FixedNum::from_num(0.5), 0), at(x, z))).id())
+            .collect();
+        world.spawn((ObstacleBundle::new(FixedVec2::from_f32(2.0, 2.0), FixedNum::from_num(1.0)), at(2.0, 2.0)));
+        world.spawn((
+            SimPosition(FixedVec2::from_f32(3.0, 3.0)),
+            Collider { layer: layers::PROJECTILE, ..Default::default() },
+            at(3.0, 3.0),
+        ));
+
+        // Top-down view: screen position is the ground position
+        let selected = world.run_system_once(|q_units: Query<(Entity, &GlobalTransform), With<Selectable>>| {
+            box_select(&q_units, Vec2::ZERO, Vec2::splat(10.0), |pos| Some(Vec2::new(pos.x, pos.z))).into_iter().collect::<HashSet<_>>()
+        }).unwrap();
+        assert_eq!(selected, HashSet::from([units[0], units[1]]));
+    }
+}
@@ -1,13 +1,14 @@
 use bevy::prelude::*;
-use crate::game::structures::{FlowField, CELL_SIZE};
-use crate::game::fixed_math::{FixedVec2, FixedNum};
-use crate::game::simulation::{StaticObstacle, SimPosition, Collider, MapFlowField};
+use crate::game::simulation::{StaticObstacle, SimPosition, Collider, MapDimensions, MapFlowField, MapStatus};
 use crate::game::pathfinding::{CLUSTER_SIZE, HierarchicalGraph};
 use crate::game::config::{GameConfig, GameConfigHandle};
 use crate::game::map::{MapData, MapObstacle, save_map, MAP_VERSION};
 use super::components::*;
+use super::autosave::EditorAutosave;
+use super::terrain::PaintedTerrain;
 use super::ui::spawn_generation_dialog;
 use super::ui::spawn_loading_overlay;
+use super::validation::validate_generation_input;
 
 /// System that handles all editor button interactions
 pub fn editor_button_system(
@@ -20,12 +21,16 @@ pub fn editor_button_system(
     obstacle_query: Query<Entity, With<StaticObstacle>>,
     all_obstacles_query: Query<(&SimPosition, &Collider), With<StaticObstacle>>,
     _editor_resources: Res<EditorResources>,
+    map_dimensions: Res<MapDimensions>,
     mut map_flow_field: ResMut<MapFlowField>,
+    mut map_status: ResMut<MapStatus>,
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     dialog_query: Query<Entity, With<GenerationDialogRoot>>,
     mut graph: ResMut<HierarchicalGraph>,
     mut active_field: ResMut<ActiveInputField>,
+    mut painted_terrain: ResMut<PaintedTerrain>,
+    mut autosave: ResMut<EditorAutosave>,
 ) {
     let Some(_config) = game_configs.get(&config_handle.0) else { return };
 
@@ -46,8 +51,14 @@ pub fn editor_button_system(
                             if editor_state.input_num_obstacles.is_empty() {
                                 editor_state.input_num_obstacles = "0".to_string();
                             }
-                            if editor_state.input_obstacle_size.is_empty() {
-                                editor_state.input_obstacle_size = "2.0".to_string();
+                            if editor_state.input_min_obstacle_radius.is_empty() {
+                                editor_state.input_min_obstacle_radius = "1.0".to_string();
+                            }
+                            if editor_state.input_max_obstacle_radius.is_empty() {
+                                editor_state.input_max_obstacle_radius = "4.0".to_string();
+                            }
+                            if editor_state.input_seed.is_empty() {
+                                editor_state.input_seed = "1".to_string();
                             }
                             editor_state.show_generation_dialog = true;
                             spawn_generation_dialog(&mut commands, &editor_state, &active_field);
@@ -112,17 +123,41 @@ pub fn editor_button_system(
                         }
                         spawn_generation_dialog(&mut commands, &editor_state, &active_field);
                     }
-                    EditorButtonAction::IncrementObstacleSize => {
-                        let val = editor_state.input_obstacle_size.parse::<f32>().unwrap_or(2.0);
-                        editor_state.input_obstacle_size = format!("{:.1}", (val + 0.5).min(20.0));
+                    EditorButtonAction::IncrementSeed => {
+                        let val = editor_state.input_seed.parse::<u64>().unwrap_or(0);
+                        editor_state.input_seed = (val + 1).to_string();
+                        for entity in dialog_query.iter() {
+                            commands.entity(entity).despawn();
+                        }
+                        spawn_generation_dialog(&mut commands, &editor_state, &active_field);
+                    }
+                    EditorButtonAction::DecrementSeed => {
+                        let val = editor_state.input_seed.parse::<u64>().unwrap_or(0);
+                        editor_state.input_seed = val.saturating_sub(1).to_string();
+                        for entity in dialog_query.iter() {
+                            commands.entity(entity).despawn();
+                        }
+                        spawn_generation_dialog(&mut commands, &editor_state, &active_field);
+                    }
+                    EditorButtonAction::IncrementMinObstacleRadius
+                    | EditorButtonAction::DecrementMinObstacleRadius
+                    | EditorButtonAction::IncrementMaxObstacleRadius
+                    | EditorButtonAction::DecrementMaxObstacleRadius => {
+                        let (input, delta) = match action {
+                            EditorButtonAction::IncrementMinObstacleRadius => (&mut editor_state.input_min_obstacle_radius, 0.5),
+                            EditorButtonAction::DecrementMinObstacleRadius => (&mut editor_state.input_min_obstacle_radius, -0.5),
+                            EditorButtonAction::IncrementMaxObstacleRadius => (&mut editor_state.input_max_obstacle_radius, 0.5),
+                            _ => (&mut editor_state.input_max_obstacle_radius, -0.5),
+                        };
+                        let val = input.parse::<f32>().unwrap_or(2.0);
+                        *input = format!("{:.1}", (val + delta).clamp(0.5, 20.0));
                         for entity in dialog_query.iter() {
                             commands.entity(entity).despawn();
                         }
                         spawn_generation_dialog(&mut commands, &editor_state, &active_field);
                     }
-                    EditorButtonAction::DecrementObstacleSize => {
-                        let val = editor_state.input_obstacle_size.parse::<f32>().unwrap_or(2.0);
-                        editor_state.input_obstacle_size = format!("{:.1}", (val - 0.5).max(0.5));
+                    EditorButtonAction::CycleObstacleSizeDistribution => {
+                        editor_state.obstacle_size_distribution = editor_state.obstacle_size_distribution.next();
                         for entity in dialog_query.iter() {
                             commands.entity(entity).despawn();
                         }
@@ -130,28 +165,31 @@ pub fn editor_button_system(
                     }
                     
                     EditorButtonAction::DialogGenerate => {
+                        // Invalid fields: keep the dialog open (it lists the errors)
+                        let validated = validate_generation_input(&editor_state);
+                        if !validated.is_valid() {
+                            warn!("Dialog: Refusing map generation - {}", validated.errors.join("; "));
+                            continue;
+                        }
+                        for warning in &validated.warnings {
+                            warn!("Dialog: {}", warning);
+                        }
+
                         editor_state.show_generation_dialog = false;
+                        active_field.field = None;
                         for entity in dialog_query.iter() {
                             commands.entity(entity).despawn();
                         }
                         
-                        // Parse input values
-                        let map_width = editor_state.input_map_width.parse::<f32>().unwrap_or(50.0);
-                        let map_height = editor_state.input_map_height.parse::<f32>().unwrap_or(50.0);
-                        let num_obstacles = editor_state.input_num_obstacles.parse::<usize>().unwrap_or(0);
-                        let obstacle_radius = editor_state.input_obstacle_size.parse::<f32>().unwrap_or(2.0);
+                        let params = validated.params;
+                        info!("Dialog: Requesting map generation - {}x{}, {} obstacles of radius {}..{} ({}), seed {}",
+                            params.map_width, params.map_height, params.num_obstacles,
+                            params.min_radius, params.max_radius, params.size_distribution.label(), params.seed);
                         
-                        info!("Dialog: Requesting map generation - {}x{}, {} obstacles of radius {}", map_width, map_height, num_obstacles, obstacle_radius);
-                        
-                        // Start generation process
+                        // Start generation process (its obstacles stay out of the cost field until finalized)
                         editor_state.is_generating = true;
-                        editor_state.generation_params = GenerationParams {
-                            map_width,
-                            map_height,
-                            num_obstacles,
-                            min_radius: obstacle_radius * 0.8,  // Slight variation
-                            max_radius: obstacle_radius * 1.2,
-                        };
+                        map_status.terrain_baked = false;
+                        editor_state.generation_params = params;
                         spawn_loading_overlay(&mut commands, "Generating Map...");
                     }
                     EditorButtonAction::ClearMap => {
@@ -163,19 +201,27 @@ pub fn editor_button_system(
 
 
                         // Reset FlowField
-                        let map_width = editor_state.current_map_size.x;
-                        let map_height = editor_state.current_map_size.y;
-                        map_flow_field.0 = FlowField::new(
-                            (map_width / CELL_SIZE) as usize, 
-                            (map_height / CELL_SIZE) as usize, 
-                            FixedNum::from_num(CELL_SIZE), 
-                            FixedVec2::new(FixedNum::from_num(-map_width/2.0), FixedNum::from_num(-map_height/2.0))
-                        );
+                        map_flow_field.0 = map_dimensions.flow_field();
+                        painted_terrain.clear();
                     }
                     EditorButtonAction::TogglePlaceObstacle => {
                         editor_state.placing_obstacle = !editor_state.placing_obstacle;
+                        if editor_state.placing_obstacle {
+                            editor_state.cost_brush.active = false;
+                        }
                         info!("Placing obstacle: {}", editor_state.placing_obstacle);
                     }
+                    EditorButtonAction::TogglePaintCost => {
+                        editor_state.cost_brush.active = !editor_state.cost_brush.active;
+                        if editor_state.cost_brush.active {
+                            editor_state.placing_obstacle = false;
+                        }
+                        info!("{}", editor_state.cost_brush.label());
+                    }
+                    EditorButtonAction::IncrementPaintCost => editor_state.cost_brush.adjust_cost(5),
+                    EditorButtonAction::DecrementPaintCost => editor_state.cost_brush.adjust_cost(-5),
+                    EditorButtonAction::IncrementBrushRadius => editor_state.cost_brush.adjust_radius(0.5),
+                    EditorButtonAction::DecrementBrushRadius => editor_state.cost_brush.adjust_radius(-0.5),
                     EditorButtonAction::FinalizeMap => {
                         editor_state.is_finalizing = true;
                         spawn_loading_overlay(&mut commands, "Finalizing Map...");
@@ -183,7 +229,7 @@ pub fn editor_button_system(
                         // Synchronous Flow Field Update
                         use crate::game::simulation::apply_obstacle_to_flow_field;
                         let flow_field = &mut map_flow_field.0;
-                        flow_field.cost_field.fill(1);
+                        painted_terrain.bake_into(flow_field);
                         for (pos, collider) in all_obstacles_query.iter() {
                             apply_obstacle_to_flow_field(flow_field, pos.0, collider.radius);
                         }
@@ -210,15 +256,10 @@ pub fn editor_button_system(
                         let stats = graph.get_stats();
                         info!("Saving map with {} regions in {} clusters", stats.region_count, stats.cluster_count);
                         
-                        let half_width = FixedNum::from_num(editor_state.current_map_size.x) / FixedNum::from_num(2.0);
-                        let half_height = FixedNum::from_num(editor_state.current_map_size.y) / FixedNum::from_num(2.0);
                         let map_data = MapData {
                             version: MAP_VERSION,
-                            size: crate::game::map::MapSize {
-                                top_left: FixedVec2::new(-half_width, -half_height),
-                                bottom_right: FixedVec2::new(half_width, half_height),
-                            },
-                            cell_size: FixedNum::from_num(CELL_SIZE),
+                            size: map_dimensions.map_size(),
+                            cell_size: map_dimensions.cell_size,
                             cluster_size: CLUSTER_SIZE,
                             obstacles,
                             start_locations: vec![], // TODO: Add start locations
@@ -230,6 +271,8 @@ pub fn editor_button_system(
                             error!("Failed to save map: {}", e);
                         } else {
                             info!("Map saved to assets/maps/default.pmap");
+                            let obstacles = all_obstacles_query.iter().map(|(pos, collider)| (pos.0, collider.radius));
+                            autosave.mark_saved(&map_dimensions, obstacles, &painted_terrain);
                         }
                     }
                 }
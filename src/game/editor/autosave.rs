@@ -0,0 +1,465 @@
+//! Editor work-in-progress auto-save.
+//!
+//! While a map is being edited, its obstacles, size and cost field (painted terrain with the
+//! obstacles baked in) are written every
+//! `editor_autosave_interval_secs` (see [`InitialConfig`]) to a `.pmap` in the temp dir.
+//! The pathfinding graph is left out to keep saves quick; loading rebuilds it anyway.
+//! The work is also saved when the app exits from the editor, so nothing since the last
+//! interval is lost. The first time the editor opens after a launch, a leftover auto-save
+//! is offered for recovery.
+//!
+//! A manual save deletes the auto-save, and work unchanged since then isn't auto-saved
+//! again: exiting right after saving leaves nothing to recover.
+
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+use crate::game::GameState;
+use crate::game::config::InitialConfig;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::launch::load_startup_map;
+use crate::game::loading::{PendingMapLoad, TargetGameState};
+use crate::game::map::{MapData, MapObstacle, save_map, MAP_VERSION};
+use crate::game::pathfinding::{CLUSTER_SIZE, HierarchicalGraph};
+use crate::game::simulation::{apply_obstacle_to_flow_field, StaticObstacle, SimPosition, Collider, MapDimensions};
+use super::components::EditorState;
+use super::terrain::PaintedTerrain;
+
+/// File name of the auto-save inside the temp dir
+pub const AUTOSAVE_FILE_NAME: &str = "peregrine_editor_autosave.pmap";
+
+/// Auto-save location and timing
+#[derive(Resource, Debug)]
+pub struct EditorAutosave {
+    pub path: PathBuf,
+    /// Seconds since the last save
+    elapsed: f32,
+    /// Whether this launch has looked for an auto-save to recover yet
+    recovery_checked: bool,
+    /// The work as of the last manual save
+    saved_work: Option<SavedWork>,
+}
+
+impl EditorAutosave {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, elapsed: 0.0, recovery_checked: false, saved_work: None }
+    }
+
+    /// The map was saved manually: delete the auto-save, and don't auto-save this work again
+    /// until it changes
+    pub fn mark_saved(
+        &mut self,
+        dimensions: &MapDimensions,
+        obstacles: impl IntoIterator<Item = (FixedVec2, FixedNum)>,
+        painted_terrain: &PaintedTerrain,
+    ) {
+        self.saved_work = Some(SavedWork::new(dimensions, obstacles, painted_terrain));
+        self.elapsed = 0.0;
+        self.delete();
+    }
+
+    /// Delete the auto-save file, if there is one
+    fn delete(&self) {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => info!("Deleted auto-save {}", self.path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to delete auto-save {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// What an auto-save would hold, compared to tell whether work has changed since a save
+#[derive(Debug, PartialEq)]
+struct SavedWork {
+    /// Sorted, so spawn order doesn't matter
+    obstacles: Vec<(FixedVec2, FixedNum)>,
+    cost_field: Vec<u8>,
+}
+
+impl SavedWork {
+    fn new(
+        dimensions: &MapDimensions,
+        obstacles: impl IntoIterator<Item = (FixedVec2, FixedNum)>,
+        painted_terrain: &PaintedTerrain,
+    ) -> Self {
+        let mut obstacles: Vec<_> = obstacles.into_iter().collect();
+        obstacles.sort_by_key(|&(position, radius)| (position.x, position.y, radius));
+        let (cols, rows) = dimensions.flow_field_cells();
+        Self { obstacles, cost_field: painted_terrain.to_cost_field(cols * rows) }
+    }
+}
+
+impl Default for EditorAutosave {
+    fn default() -> Self {
+        Self::new(std::env::temp_dir().join(AUTOSAVE_FILE_NAME))
+    }
+}
+
+/// Buttons of the recovery prompt
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutosaveRecoveryAction {
+    Recover,
+    Discard,
+}
+
+/// Marker component for the recovery prompt
+#[derive(Component)]
+pub struct AutosaveRecoveryRoot;
+
+/// Work-in-progress map data: obstacles, the map size and the cost field finalizing would
+/// give (painted terrain with the obstacles on top), so the graph rebuilt on recovery routes
+/// around them. The graph itself is left empty.
+pub fn build_autosave(
+    dimensions: &MapDimensions,
+    obstacles: impl IntoIterator<Item = (FixedVec2, FixedNum)>,
+    painted_terrain: &PaintedTerrain,
+) -> MapData {
+    let obstacles: Vec<_> = obstacles.into_iter().map(|(position, radius)| MapObstacle { position, radius }).collect();
+    let mut flow_field = dimensions.flow_field();
+    painted_terrain.bake_into(&mut flow_field);
+    for obstacle in &obstacles {
+        apply_obstacle_to_flow_field(&mut flow_field, obstacle.position, obstacle.radius);
+    }
+    MapData {
+        version: MAP_VERSION,
+        size: dimensions.map_size(),
+        cell_size: dimensions.cell_size,
+        cluster_size: CLUSTER_SIZE,
+        obstacles,
+        start_locations: vec![],
+        cost_field: flow_field.cost_field,
+        graph: HierarchicalGraph::default(),
+    }
+}
+
+/// Write `map_data` next to `path` and move it into place, so a crash mid-save leaves the
+/// previous auto-save intact
+pub fn write_autosave(path: &Path, map_data: &MapData) -> Result<(), String> {
+    let partial = path.with_extension("pmap.partial");
+    save_map(&partial.to_string_lossy(), map_data)
+        .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+    std::fs::rename(&partial, path)
+        .map_err(|e| format!("Failed to move auto-save to {}: {}", path.display(), e))
+}
+
+/// What goes into an auto-save
+#[derive(SystemParam)]
+pub struct EditedMap<'w, 's> {
+    dimensions: Res<'w, MapDimensions>,
+    painted_terrain: Res<'w, PaintedTerrain>,
+    obstacles: Query<'w, 's, (&'static SimPosition, &'static Collider), With<StaticObstacle>>,
+}
+
+/// Save the map being edited every `editor_autosave_interval_secs`.
+///
+/// Skipped while a map is generating or finalizing (the world is half-built) and while the
+/// recovery prompt is up, so the file on offer isn't overwritten before the player answers.
+pub fn autosave_editor_work(
+    time: Res<Time>,
+    initial_config: Res<InitialConfig>,
+    mut autosave: ResMut<EditorAutosave>,
+    editor_state: Res<EditorState>,
+    edited: EditedMap,
+    prompt_query: Query<(), With<AutosaveRecoveryRoot>>,
+) {
+    let interval = initial_config.editor_autosave_interval_secs;
+    if interval <= 0.0 || !prompt_query.is_empty() || editor_state.is_generating || editor_state.is_finalizing {
+        return;
+    }
+    autosave.elapsed += time.delta_secs();
+    if autosave.elapsed < interval {
+        return;
+    }
+    autosave.elapsed = 0.0;
+    save_edited_map(&autosave, &edited);
+}
+
+/// Save the map being edited one last time when the app exits from the editor.
+///
+/// Runs in `PostUpdate`, before the log file is flushed in `Last`. Same skips as
+/// [`autosave_editor_work`], except for the interval. Work saved manually is a clean exit:
+/// nothing is written and no auto-save is left behind.
+pub fn autosave_on_exit(
+    mut exits: MessageReader<AppExit>,
+    autosave: Res<EditorAutosave>,
+    editor_state: Res<EditorState>,
+    edited: EditedMap,
+    prompt_query: Query<(), With<AutosaveRecoveryRoot>>,
+) {
+    if exits.read().next().is_none() || !prompt_query.is_empty() || editor_state.is_generating || editor_state.is_finalizing {
+        return;
+    }
+    save_edited_map(&autosave, &edited);
+}
+
+/// Auto-save `edited`, unless it's empty (not worth a save, and would clobber a useful one).
+/// Work unchanged since the last manual save isn't written, and any stale auto-save is deleted.
+fn save_edited_map(autosave: &EditorAutosave, edited: &EditedMap) {
+    if edited.obstacles.is_empty() && edited.painted_terrain.is_empty() {
+        return;
+    }
+    let obstacles = || edited.obstacles.iter().map(|(pos, collider)| (pos.0, collider.radius));
+    if let Some(saved_work) = &autosave.saved_work {
+        if *saved_work == SavedWork::new(&edited.dimensions, obstacles(), &edited.painted_terrain) {
+            autosave.delete();
+            return;
+        }
+    }
+    let map_data = build_autosave(&edited.dimensions, obstacles(), &edited.painted_terrain);
+    match write_autosave(&autosave.path, &map_data) {
+        Ok(()) => info!("Auto-saved {} obstacles to {}", map_data.obstacles.len(), autosave.path.display()),
+        Err(e) => error!("Auto-save failed: {}", e),
+    }
+}
+
+/// On the first editor visit after a launch, offer to recover a leftover auto-save
+pub fn offer_autosave_recovery(mut commands: Commands, mut autosave: ResMut<EditorAutosave>) {
+    if autosave.recovery_checked {
+        return;
+    }
+    autosave.recovery_checked = true;
+    if !autosave.path.is_file() {
+        return;
+    }
+    let map_data = match load_startup_map(&autosave.path) {
+        Ok(map_data) => map_data,
+        Err(e) => {
+            warn!("Ignoring unusable auto-save: {}", e);
+            return;
+        }
+    };
+    spawn_recovery_prompt(&mut commands, map_data.obstacles.len());
+}
+
+fn spawn_recovery_prompt(commands: &mut Commands, obstacle_count: usize) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(30.0),
+            top: Val::Percent(30.0),
+            width: Val::Percent(40.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            border: UiRect::all(Val::Px(2.0)),
+            padding: UiRect::all(Val::Px(20.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        BorderColor::from(Color::WHITE),
+        AutosaveRecoveryRoot,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("Recover unsaved work from the last session? ({} obstacles)", obstacle_count)),
+            TextFont { font_size: 18.0, ..default() },
+            TextColor(Color::WHITE),
+            Node { margin: UiRect::bottom(Val::Px(10.0)), ..default() },
+        ));
+        parent.spawn(Node { flex_direction: FlexDirection::Row, ..default() }).with_children(|row| {
+            for (text, action) in [("Recover", AutosaveRecoveryAction::Recover), ("Discard", AutosaveRecoveryAction::Discard)] {
+                row.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(120.0),
+                        height: Val::Px(40.0),
+                        margin: UiRect::all(Val::Px(5.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                    action,
+                )).with_children(|btn| {
+                    btn.spawn((
+                        Text::new(text),
+                        TextFont { font_size: 16.0, ..default() },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            }
+        });
+    });
+}
+
+/// Recover reloads the editor with the auto-saved map (through the loading screen, like
+/// opening a map file); discard deletes it
+pub fn handle_autosave_recovery_buttons(
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &AutosaveRecoveryAction), Changed<Interaction>>,
+    mut commands: Commands,
+    autosave: Res<EditorAutosave>,
+    mut painted_terrain: ResMut<PaintedTerrain>,
+    mut next_state: ResMut<NextState<GameState>>,
+    prompt_query: Query<Entity, With<AutosaveRecoveryRoot>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = BackgroundColor(Color::srgb(0.1, 0.1, 0.1));
+                for entity in prompt_query.iter() {
+                    commands.entity(entity).despawn();
+                }
+                match action {
+                    AutosaveRecoveryAction::Recover => match load_startup_map(&autosave.path) {
+                        Ok(map_data) => {
+                            info!("Recovering auto-save with {} obstacles", map_data.obstacles.len());
+                            *painted_terrain = PaintedTerrain::from_cost_field(map_data.cost_field.clone());
+                            commands.insert_resource(PendingMapLoad(map_data));
+                            commands.insert_resource(TargetGameState(GameState::Editor));
+                            next_state.set(GameState::Loading);
+                        }
+                        Err(e) => error!("Failed to recover auto-save: {}", e),
+                    },
+                    AutosaveRecoveryAction::Discard => autosave.delete(),
+                }
+            }
+            Interaction::Hovered => {
+                *color = BackgroundColor(Color::srgb(0.25, 0.25, 0.25));
+            }
+            Interaction::None => {
+                *color = BackgroundColor(Color::srgb(0.3, 0.3, 0.3));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use bevy::time::TimeUpdateStrategy;
+    use crate::game::map::load_map;
+    use crate::game::simulation::ObstacleBundle;
+    use crate::game::structures::OBSTACLE_COST;
+    use crate::game::editor::terrain::BASE_TERRAIN_COST;
+
+    /// Path in the temp dir, unique per test
+    fn temp_autosave_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("peregrine_autosave_{}_{}.pmap", name, std::process::id()))
+    }
+
+    /// Auto-save on a 64x32 map, saving every 0.5 s of a clock advancing 0.2 s per update
+    fn setup_autosave_app(path: PathBuf) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(200)));
+        let mut initial_config = InitialConfig::default();
+        initial_config.editor_autosave_interval_secs = 0.5;
+        app.insert_resource(initial_config);
+        app.insert_resource(EditorAutosave::new(path));
+        app.init_resource::<EditorState>();
+        app.init_resource::<PaintedTerrain>();
+        app.insert_resource(MapDimensions::from_f32(64.0, 32.0));
+        app.add_systems(Update, autosave_editor_work);
+        app
+    }
+
+    #[test]
+    fn test_autosave_writes_loadable_work_in_progress() {
+        let path = temp_autosave_path("wip");
+        let mut app = setup_autosave_app(path.clone());
+        let obstacles = [
+            (FixedVec2::from_f32(-10.0, 4.0), FixedNum::from_num(3)),
+            (FixedVec2::from_f32(20.5, -8.0), FixedNum::from_num(1.5)),
+        ];
+        for (position, radius) in obstacles {
+            app.world_mut().spawn(ObstacleBundle::new(position, radius));
+        }
+
+        // The first update's clock hasn't moved yet
+        for _ in 0..4 {
+            app.update();
+        }
+        let loaded = load_map(&path.to_string_lossy());
+        let checked = load_startup_map(&path);
+        std::fs::remove_file(&path).ok();
+
+        let loaded = loaded.expect("Auto-save should be written once the interval has passed");
+        let mut saved: Vec<_> = loaded.obstacles.iter().map(|obstacle| (obstacle.position, obstacle.radius)).collect();
+        saved.sort_by_key(|&(position, _)| position.x);
+        assert_eq!(saved, obstacles.to_vec());
+        assert_eq!((loaded.size.get_width(), loaded.size.get_height()), (FixedNum::from_num(64), FixedNum::from_num(32)));
+        assert!(!loaded.graph.initialized, "The graph is left out of auto-saves");
+        assert!(checked.is_ok(), "Auto-save should pass the map file checks: {:?}", checked.err());
+
+        // The recovered cost field blocks the obstacles, so the rebuilt graph goes around them
+        let field = MapDimensions::from_f32(64.0, 32.0).flow_field();
+        let cost_at = |position| {
+            let (x, y) = field.world_to_grid(position).unwrap();
+            loaded.cost_field[field.get_index(x, y)]
+        };
+        for (position, _) in obstacles {
+            assert_eq!(cost_at(position), OBSTACLE_COST, "Obstacle at {:?} should be baked in", position);
+        }
+        assert_eq!(cost_at(FixedVec2::from_f32(0.0, 0.0)), BASE_TERRAIN_COST);
+    }
+
+    #[test]
+    fn test_exit_saves_work_before_the_interval() {
+        let path = temp_autosave_path("exit");
+        let mut app = setup_autosave_app(path.clone());
+        app.add_message::<AppExit>();
+        app.add_systems(PostUpdate, autosave_on_exit);
+        app.world_mut().spawn(ObstacleBundle::new(FixedVec2::from_f32(3.0, 4.0), FixedNum::from_num(2)));
+
+        app.update();
+        assert!(!path.exists(), "Interval not reached yet");
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+        let loaded = load_map(&path.to_string_lossy());
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.expect("Exiting should auto-save").obstacles.len(), 1);
+    }
+
+    #[test]
+    fn test_manual_save_leaves_nothing_to_recover() {
+        let path = temp_autosave_path("saved");
+        let mut app = setup_autosave_app(path.clone());
+        app.add_message::<AppExit>();
+        app.add_systems(PostUpdate, autosave_on_exit);
+        let obstacle = (FixedVec2::from_f32(3.0, 4.0), FixedNum::from_num(2));
+        app.world_mut().spawn(ObstacleBundle::new(obstacle.0, obstacle.1));
+        for _ in 0..4 {
+            app.update();
+        }
+        assert!(path.exists(), "Unsaved work should be auto-saved");
+
+        // As the editor's Save button does once the map file is written
+        app.world_mut().resource_scope(|world, mut autosave: Mut<EditorAutosave>| {
+            autosave.mark_saved(world.resource::<MapDimensions>(), [obstacle], world.resource::<PaintedTerrain>());
+        });
+        assert!(!path.exists(), "Saving should delete the auto-save");
+        for _ in 0..4 {
+            app.update();
+        }
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+        assert!(!path.exists(), "Saved work shouldn't be auto-saved again, even on exit");
+
+        // Relaunch
+        let mut relaunched = App::new();
+        relaunched.add_plugins(MinimalPlugins);
+        relaunched.insert_resource(EditorAutosave::new(path.clone()));
+        relaunched.add_systems(Update, offer_autosave_recovery);
+        relaunched.update();
+        let prompts = relaunched.world_mut().query_filtered::<(), With<AutosaveRecoveryRoot>>().iter(relaunched.world()).count();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(prompts, 0, "Nothing should be offered for recovery");
+
+        // Editing again after the save is unsaved work
+        app.world_mut().spawn(ObstacleBundle::new(FixedVec2::from_f32(-3.0, 4.0), FixedNum::from_num(2)));
+        app.world_mut().write_message(AppExit::Success);
+        app.update();
+        let edited = path.exists();
+        std::fs::remove_file(&path).ok();
+        assert!(edited, "Changes since the save should be auto-saved on exit");
+    }
+
+    #[test]
+    fn test_empty_map_is_not_autosaved() {
+        let path = temp_autosave_path("empty");
+        let mut app = setup_autosave_app(path.clone());
+        for _ in 0..8 {
+            app.update();
+        }
+        assert!(!path.exists(), "An empty map shouldn't overwrite an earlier auto-save");
+    }
+}
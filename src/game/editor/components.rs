@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use crate::game::fixed_math::FixedNum;
+use super::terrain::CostBrush;
 
 /// Resource for pending map generation requests
 #[derive(Resource)]
@@ -8,6 +10,23 @@ pub struct PendingMapGeneration {
     pub num_obstacles: usize,
     pub min_radius: f32,
     pub max_radius: f32,
+    /// Same seed and parameters, same map
+    pub seed: u64,
+}
+
+impl PendingMapGeneration {
+    /// The same request as editor generation parameters, for [`generate_obstacle_layout`](super::generate_obstacle_layout)
+    pub fn params(&self) -> GenerationParams {
+        GenerationParams {
+            map_width: self.map_width,
+            map_height: self.map_height,
+            num_obstacles: self.num_obstacles,
+            min_radius: self.min_radius,
+            max_radius: self.max_radius,
+            size_distribution: ObstacleSizeDistribution::Uniform,
+            seed: self.seed,
+        }
+    }
 }
 
 /// Resources needed for editor rendering
@@ -21,12 +40,21 @@ pub struct EditorResources {
 #[derive(Component)]
 pub struct EditorUiRoot;
 
+/// Marker component for the text showing the cost brush settings
+#[derive(Component)]
+pub struct CostBrushLabel;
+
 /// Button actions available in the editor
 #[derive(Component)]
 pub enum EditorButtonAction {
     OpenGenerateDialog,
     SaveMap,
     TogglePlaceObstacle,
+    TogglePaintCost,
+    IncrementPaintCost,
+    DecrementPaintCost,
+    IncrementBrushRadius,
+    DecrementBrushRadius,
     ClearMap,
     FinalizeMap,
     
@@ -41,14 +69,20 @@ pub enum EditorButtonAction {
     DecrementMapHeight,
     IncrementObstacles,
     DecrementObstacles,
-    IncrementObstacleSize,
-    DecrementObstacleSize,
+    IncrementMinObstacleRadius,
+    DecrementMinObstacleRadius,
+    IncrementMaxObstacleRadius,
+    DecrementMaxObstacleRadius,
+    IncrementSeed,
+    DecrementSeed,
+    CycleObstacleSizeDistribution,
 }
 
 /// Editor state tracking
 #[derive(Resource, Default)]
 pub struct EditorState {
     pub placing_obstacle: bool,
+    pub cost_brush: CostBrush,
     pub show_generation_dialog: bool,
     pub is_generating: bool,
     pub is_finalizing: bool,
@@ -58,17 +92,59 @@ pub struct EditorState {
     pub input_map_width: String,
     pub input_map_height: String,
     pub input_num_obstacles: String,
-    pub input_obstacle_size: String, // Combined min/max for simplicity
+    pub input_min_obstacle_radius: String,
+    pub input_max_obstacle_radius: String,
+    pub input_seed: String,
+    pub obstacle_size_distribution: ObstacleSizeDistribution,
 }
 
 /// Parameters for map generation
-#[derive(Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub struct GenerationParams {
     pub map_width: f32,
     pub map_height: f32,
     pub num_obstacles: usize,
     pub min_radius: f32,
     pub max_radius: f32,
+    pub size_distribution: ObstacleSizeDistribution,
+    /// Same seed and parameters, same map
+    pub seed: u64,
+}
+
+/// How random obstacle radii are spread between `min_radius` and `max_radius`
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObstacleSizeDistribution {
+    /// Every radius in the range equally likely
+    #[default]
+    Uniform,
+    /// Mostly small obstacles with the occasional large one
+    MostlySmall,
+}
+
+impl ObstacleSizeDistribution {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Uniform => "Uniform",
+            Self::MostlySmall => "Mostly Small",
+        }
+    }
+
+    /// Next option, for the dialog's cycle button
+    pub fn next(self) -> Self {
+        match self {
+            Self::Uniform => Self::MostlySmall,
+            Self::MostlySmall => Self::Uniform,
+        }
+    }
+
+    /// Map `t` in [0, 1] to a point in `[min, max]` following this distribution
+    pub fn lerp(self, min: FixedNum, max: FixedNum, t: FixedNum) -> FixedNum {
+        let t = match self {
+            Self::Uniform => t,
+            Self::MostlySmall => t * t,
+        };
+        min + (max - min) * t
+    }
 }
 
 /// Marker component for generation dialog
@@ -85,7 +161,9 @@ pub enum InputFieldType {
     MapWidth,
     MapHeight,
     NumObstacles,
-    ObstacleSize,
+    MinObstacleRadius,
+    MaxObstacleRadius,
+    Seed,
 }
 
 /// Tracks which input field is currently active
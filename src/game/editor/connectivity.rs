@@ -0,0 +1,269 @@
+//! Connectivity pass for generated maps.
+//!
+//! Random placement can wall off pockets of the map. The obstacles are rasterized onto a
+//! coarse walkability grid, and while the largest 4-connected walkable region holds less than
+//! [`MIN_CONNECTED_FRACTION`] of the walkable cells, a corridor is carved from it to the
+//! nearest isolated region by removing the obstacles along the way. Nothing here is random,
+//! so the result only depends on the placed obstacles (and thus on the generation seed).
+
+use std::collections::VecDeque;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+
+/// Share of the walkable cells the largest region must hold for a map to count as playable
+pub const MIN_CONNECTED_FRACTION: f32 = 0.95;
+/// Cells per side cap; larger maps are sampled with coarser cells
+pub const MAX_GRID_SIDE: f32 = 512.0;
+
+const NO_REGION: u32 = u32::MAX;
+
+/// Walkable-region summary of an obstacle layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectivityReport {
+    pub walkable_cells: usize,
+    pub largest_region_cells: usize,
+    pub region_count: usize,
+}
+
+impl ConnectivityReport {
+    /// Share of the walkable cells in the largest region (1.0 for a fully blocked map)
+    pub fn largest_fraction(&self) -> f32 {
+        if self.walkable_cells == 0 {
+            1.0
+        } else {
+            self.largest_region_cells as f32 / self.walkable_cells as f32
+        }
+    }
+
+    pub fn is_playable(&self) -> bool {
+        self.walkable_cells > 0 && self.largest_fraction() >= MIN_CONNECTED_FRACTION
+    }
+}
+
+/// Obstacles rasterized onto a grid covering the map (centered on the origin)
+struct WalkabilityGrid {
+    cols: usize,
+    rows: usize,
+    cell_size: FixedNum,
+    top_left: FixedVec2,
+    blocked: Vec<bool>,
+}
+
+impl WalkabilityGrid {
+    /// A cell is blocked if its center lies inside any obstacle
+    fn rasterize(map_width: f32, map_height: f32, obstacles: &[(FixedVec2, FixedNum)]) -> Self {
+        // Laid out in fixed point so generated maps carve identically on every platform
+        let (map_width, map_height) = (FixedNum::from_num(map_width), FixedNum::from_num(map_height));
+        let cell_size = (map_width.max(map_height) / FixedNum::from_num(MAX_GRID_SIDE)).ceil().max(FixedNum::ONE);
+        let cols = (map_width / cell_size).ceil().max(FixedNum::ONE).to_num::<usize>();
+        let rows = (map_height / cell_size).ceil().max(FixedNum::ONE).to_num::<usize>();
+        let mut grid = Self {
+            cols,
+            rows,
+            cell_size,
+            top_left: FixedVec2::new(-map_width / 2, -map_height / 2),
+            blocked: vec![false; cols * rows],
+        };
+
+        for &(position, radius) in obstacles {
+            let (min_col, min_row) = grid.clamped_cell(position - FixedVec2::new(radius, radius));
+            let (max_col, max_row) = grid.clamped_cell(position + FixedVec2::new(radius, radius));
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    let idx = row * cols + col;
+                    if (grid.cell_center(idx) - position).length_squared() < radius * radius {
+                        grid.blocked[idx] = true;
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    fn clamped_cell(&self, pos: FixedVec2) -> (usize, usize) {
+        let local = pos - self.top_left;
+        let col = (local.x / self.cell_size).floor().to_num::<i64>().clamp(0, self.cols as i64 - 1);
+        let row = (local.y / self.cell_size).floor().to_num::<i64>().clamp(0, self.rows as i64 - 1);
+        (col as usize, row as usize)
+    }
+
+    fn cell_center(&self, idx: usize) -> FixedVec2 {
+        let half = self.cell_size / 2;
+        self.top_left + FixedVec2::new(
+            FixedNum::from_num(idx % self.cols) * self.cell_size + half,
+            FixedNum::from_num(idx / self.cols) * self.cell_size + half,
+        )
+    }
+
+    /// 4-connected neighbors in a fixed order
+    fn neighbors(&self, idx: usize) -> impl Iterator<Item = usize> {
+        let (col, row, cols, rows) = (idx % self.cols, idx / self.cols, self.cols, self.rows);
+        [
+            (row > 0).then(|| idx - cols),
+            (col + 1 < cols).then(|| idx + 1),
+            (row + 1 < rows).then(|| idx + cols),
+            (col > 0).then(|| idx - 1),
+        ].into_iter().flatten()
+    }
+
+    /// Region id per cell (`NO_REGION` for blocked cells) and the size of each region,
+    /// numbered in scan order
+    fn label_regions(&self) -> (Vec<u32>, Vec<usize>) {
+        let mut labels = vec![NO_REGION; self.blocked.len()];
+        let mut sizes = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..self.blocked.len() {
+            if self.blocked[start] || labels[start] != NO_REGION {
+                continue;
+            }
+            let region = sizes.len() as u32;
+            let mut size = 0;
+            labels[start] = region;
+            stack.push(start);
+            while let Some(idx) = stack.pop() {
+                size += 1;
+                for next in self.neighbors(idx) {
+                    if !self.blocked[next] && labels[next] == NO_REGION {
+                        labels[next] = region;
+                        stack.push(next);
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        (labels, sizes)
+    }
+
+    /// Blocked cells on a shortest 4-connected route from `main` to the nearest other region
+    fn corridor_from(&self, labels: &[u32], main: u32) -> Vec<usize> {
+        let mut parent = vec![usize::MAX; labels.len()];
+        let mut queue: VecDeque<usize> = (0..labels.len()).filter(|&idx| labels[idx] == main).collect();
+        for &idx in &queue {
+            parent[idx] = idx;
+        }
+
+        while let Some(idx) = queue.pop_front() {
+            for next in self.neighbors(idx) {
+                if parent[next] != usize::MAX {
+                    continue;
+                }
+                parent[next] = idx;
+                if labels[next] != NO_REGION {
+                    // Reached another region: walk back to `main`
+                    let mut corridor = Vec::new();
+                    let mut cell = idx;
+                    while labels[cell] != main {
+                        corridor.push(cell);
+                        cell = parent[cell];
+                    }
+                    return corridor;
+                }
+                queue.push_back(next);
+            }
+        }
+        Vec::new()
+    }
+
+    fn report(sizes: &[usize]) -> ConnectivityReport {
+        ConnectivityReport {
+            walkable_cells: sizes.iter().sum(),
+            largest_region_cells: sizes.iter().copied().max().unwrap_or(0),
+            region_count: sizes.len(),
+        }
+    }
+}
+
+/// Walkable-region summary of `obstacles` on a `map_width` x `map_height` map
+pub fn analyze_connectivity(map_width: f32, map_height: f32, obstacles: &[(FixedVec2, FixedNum)]) -> ConnectivityReport {
+    let grid = WalkabilityGrid::rasterize(map_width, map_height, obstacles);
+    WalkabilityGrid::report(&grid.label_regions().1)
+}
+
+/// Remove obstacles until the map is playable (see module docs). Returns how many were removed.
+pub fn ensure_connectivity(obstacles: &mut Vec<(FixedVec2, FixedNum)>, map_width: f32, map_height: f32) -> usize {
+    let initial_count = obstacles.len();
+    loop {
+        let grid = WalkabilityGrid::rasterize(map_width, map_height, obstacles);
+        let (labels, sizes) = grid.label_regions();
+        let report = WalkabilityGrid::report(&sizes);
+        if report.is_playable() {
+            break;
+        }
+
+        // Fully blocked: open up the middle of the map. Otherwise connect the largest
+        // region (lowest id on ties) to its nearest neighbor.
+        let carve = match (0..sizes.len()).max_by_key(|&region| (sizes[region], std::cmp::Reverse(region))) {
+            Some(main) => grid.corridor_from(&labels, main as u32),
+            None => {
+                let (col, row) = grid.clamped_cell(FixedVec2::ZERO);
+                vec![row * grid.cols + col]
+            }
+        };
+
+        // Clear a cell of margin around the corridor so units fit through
+        let before = obstacles.len();
+        for idx in carve {
+            let center = grid.cell_center(idx);
+            obstacles.retain(|&(position, radius)| {
+                let reach = radius + grid.cell_size;
+                (center - position).length_squared() >= reach * reach
+            });
+        }
+        if obstacles.len() == before {
+            break;
+        }
+    }
+    initial_count - obstacles.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obstacle(x: f32, y: f32, radius: f32) -> (FixedVec2, FixedNum) {
+        (FixedVec2::from_f32(x, y), FixedNum::from_num(radius))
+    }
+
+    /// A closed ring of obstacles around the origin, walling off its inside
+    fn ring(radius: f32) -> Vec<(FixedVec2, FixedNum)> {
+        (0..32).map(|i| {
+            let angle = i as f32 / 32.0 * std::f32::consts::TAU;
+            obstacle(angle.cos() * radius, angle.sin() * radius, 2.0)
+        }).collect()
+    }
+
+    #[test]
+    fn test_open_map_is_one_region() {
+        let report = analyze_connectivity(40.0, 20.0, &[]);
+        assert_eq!(report, ConnectivityReport { walkable_cells: 800, largest_region_cells: 800, region_count: 1 });
+        assert!(report.is_playable());
+    }
+
+    #[test]
+    fn test_walled_off_pocket_gets_a_corridor() {
+        let mut obstacles = ring(12.0);
+        // Pocket inside the ring is ~10% of the walkable area
+        let before = analyze_connectivity(60.0, 60.0, &obstacles);
+        assert_eq!(before.region_count, 2);
+        assert!(!before.is_playable(), "{:?}", before);
+
+        let removed = ensure_connectivity(&mut obstacles, 60.0, 60.0);
+        let after = analyze_connectivity(60.0, 60.0, &obstacles);
+        assert!(removed > 0 && removed < 8, "Only the corridor should be cleared, removed {}", removed);
+        assert_eq!(after.region_count, 1, "{:?}", after);
+    }
+
+    #[test]
+    fn test_fully_blocked_map_is_opened_up() {
+        let mut obstacles = vec![obstacle(0.0, 0.0, 20.0), obstacle(10.0, 10.0, 20.0)];
+        assert_eq!(analyze_connectivity(20.0, 20.0, &obstacles).walkable_cells, 0);
+
+        ensure_connectivity(&mut obstacles, 20.0, 20.0);
+        assert!(analyze_connectivity(20.0, 20.0, &obstacles).is_playable());
+    }
+
+    #[test]
+    fn test_large_maps_use_a_capped_grid() {
+        let report = analyze_connectivity(4096.0, 1024.0, &[]);
+        assert_eq!(report.walkable_cells, 512 * 128);
+    }
+}
@@ -1,15 +1,13 @@
 use bevy::prelude::*;
 use crate::game::GroundPlane;
-use crate::game::structures::{FlowField, CELL_SIZE};
-use crate::game::fixed_math::{FixedVec2, FixedNum};
-use crate::game::map::MapSize;
-use crate::game::simulation::{StaticObstacle, MapFlowField, SimConfig};
+use crate::game::fixed_math::{FixedVec2, FixedNum, FixedRng};
+use crate::game::simulation::{StaticObstacle, MapDimensions, MapFlowField, MapStatus, SimConfig};
 use crate::game::camera::RtsCamera;
 use crate::game::pathfinding::HierarchicalGraph;
 use crate::game::spatial_hash::SpatialHash;
-use crate::game::config::{GameConfig, GameConfigHandle, InitialConfig};
-use rand::Rng;
+use crate::game::config::{GameConfig, GameConfigHandle};
 use super::components::*;
+use super::connectivity::{analyze_connectivity, ensure_connectivity};
 use super::input::spawn_obstacle;
 use peregrine_macros::profile;
 
@@ -22,7 +20,7 @@ pub fn handle_generation(
     editor_resources: Res<EditorResources>,
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
-    initial_config: Res<InitialConfig>,
+    mut map_dimensions: ResMut<MapDimensions>,
     loading_query: Query<Entity, With<LoadingOverlayRoot>>,
     mut map_flow_field: ResMut<MapFlowField>,
     mut graph: ResMut<HierarchicalGraph>,
@@ -77,25 +75,11 @@ pub fn handle_generation(
     
     editor_state.current_map_size = Vec2::new(map_width, map_height);
     
-    // Update SimConfig with new map dimensions
-    let half_width = FixedNum::from_num(map_width) / FixedNum::from_num(2.0);
-    let half_height = FixedNum::from_num(map_height) / FixedNum::from_num(2.0);
-    sim_config.map_size = MapSize {
-        top_left: FixedVec2::new(-half_width, -half_height),
-        bottom_right: FixedVec2::new(half_width, half_height),
-    };
-    info!("Updated SimConfig: map size = {}x{}", map_width, map_height);
-    
-    // Update SpatialHash with new map dimensions using InitialConfig values
-    spatial_hash.resize(
-        FixedNum::from_num(map_width),
-        FixedNum::from_num(map_height),
-        &initial_config.spatial_hash_entity_radii,
-        initial_config.spatial_hash_radius_to_cell_ratio,
-        initial_config.spatial_hash_max_entity_count,
-        initial_config.spatial_hash_arena_overcapacity_ratio
-    );
-    info!("Updated SpatialHash for new map size");
+    // Resize SimConfig, SpatialHash and FlowField together for the new map
+    *map_dimensions = MapDimensions::from_f32(map_width, map_height);
+    map_dimensions.apply(&mut sim_config, &mut map_flow_field.0, &mut spatial_hash);
+    let (ff_width, ff_height) = map_dimensions.flow_field_cells();
+    info!("Resized map to {}x{} (FlowField: {} x {} cells)", map_width, map_height, ff_width, ff_height);
 
     // Adjust camera to view the entire map
     if let Ok(mut camera_transform) = camera_query.single_mut() {
@@ -108,19 +92,6 @@ pub fn handle_generation(
               map_width, map_height, camera_height, camera_distance);
     }
 
-    let ff_width = (map_width / CELL_SIZE) as usize;
-    let ff_height = (map_height / CELL_SIZE) as usize;
-    info!("Creating FlowField: {} x {} cells ({}x{} world units)", 
-          ff_width, ff_height, map_width, map_height);
-
-    map_flow_field.0 = FlowField::new(
-        ff_width, 
-        ff_height, 
-        FixedNum::from_num(CELL_SIZE), 
-        FixedVec2::new(FixedNum::from_num(-map_width/2.0), FixedNum::from_num(-map_height/2.0))
-    );
-    info!("FlowField created successfully (total cells: {})", ff_width * ff_height);
-    
     // Update ground plane mesh to match new map size
     for (entity, _mesh3d) in ground_plane_query.iter() {
         let new_mesh = meshes.add(Plane3d::default().mesh().size(map_width, map_height));
@@ -141,9 +112,9 @@ pub fn handle_generation(
     );
 
     // Spawn obstacles (if any)
-    let num_obstacles = params.num_obstacles;
-    if num_obstacles > 0 {
-        spawn_obstacles(&mut commands, params, map_width, map_height, num_obstacles, &editor_resources);
+    if params.num_obstacles > 0 {
+        let obstacles = generate_obstacle_layout(&params);
+        spawn_obstacles(&mut commands, &obstacles, &editor_resources);
     } else {
         info!("No obstacles to spawn.");
     }
@@ -163,6 +134,7 @@ pub fn check_finalization_complete(
     mut editor_state: ResMut<EditorState>,
     loading_query: Query<Entity, With<LoadingOverlayRoot>>,
     graph: Res<HierarchicalGraph>,
+    mut map_status: ResMut<MapStatus>,
 ) {
     if !editor_state.is_finalizing {
         return;
@@ -170,6 +142,7 @@ pub fn check_finalization_complete(
 
     if graph.initialized {
         editor_state.is_finalizing = false;
+        map_status.terrain_baked = true;
         let stats = graph.get_stats();
         info!("Map finalization COMPLETE! Graph has {} regions in {} clusters", 
               stats.region_count, stats.cluster_count);
@@ -198,30 +171,171 @@ fn clear_obstacles(
     info!("Cleared {} existing obstacles", obstacle_count);
 }
 
-/// Spawn random obstacles across the map
+/// Seeded obstacle placement followed by the connectivity pass; the same params always
+/// give the same layout.
+///
+/// Placement is all fixed-point (see [`FixedRng`]), so the layout is bit-identical on every
+/// platform and can be generated independently by each lockstep client.
+pub fn generate_obstacle_layout(params: &GenerationParams) -> Vec<(FixedVec2, FixedNum)> {
+    let mut rng = FixedRng::new(params.seed);
+    let (map_width, map_height) = (FixedNum::from_num(params.map_width), FixedNum::from_num(params.map_height));
+    let mut obstacles: Vec<_> = (0..params.num_obstacles)
+        .map(|_| random_obstacle(params, map_width, map_height, &mut rng))
+        .collect();
+
+    let removed = ensure_connectivity(&mut obstacles, params.map_width, params.map_height);
+    if removed > 0 {
+        let report = analyze_connectivity(params.map_width, params.map_height, &obstacles);
+        info!("Removed {} of {} obstacles to connect isolated regions (largest region now {:.0}% of the walkable map)",
+            removed, params.num_obstacles, report.largest_fraction() * 100.0);
+    }
+    obstacles
+}
+
+/// Spawn the generated obstacles
 #[profile(1)]
 fn spawn_obstacles(
     commands: &mut Commands,
-    params: GenerationParams,
-    map_width: f32,
-    map_height: f32,
-    num_obstacles: usize,
+    obstacles: &[(FixedVec2, FixedNum)],
     editor_resources: &EditorResources,
 ) {
+    let num_obstacles = obstacles.len();
     info!("Starting to spawn {} obstacles...", num_obstacles);
-    let mut rng = rand::rng();
     
-    for i in 0..num_obstacles {
+    for (i, &(position, radius)) in obstacles.iter().enumerate() {
         if i % 100 == 0 && i > 0 {
             info!("  Spawned {}/{} obstacles ({:.1}%)", 
                   i, num_obstacles, (i as f32 / num_obstacles as f32) * 100.0);
         }
-        let x = rng.random_range(-map_width/2.0..map_width/2.0);
-        let y = rng.random_range(-map_height/2.0..map_height/2.0);
-        let radius = rng.random_range(params.min_radius..params.max_radius);
-        
-        spawn_obstacle(commands, FixedVec2::new(FixedNum::from_num(x), FixedNum::from_num(y)), FixedNum::from_num(radius), editor_resources);
+        spawn_obstacle(commands, position, radius, editor_resources);
     }
     info!("Finished spawning all {} obstacles", num_obstacles);
 }
 
+/// Random position on the map and radius in `[min_radius, max_radius]` (per `size_distribution`)
+fn random_obstacle(params: &GenerationParams, map_width: FixedNum, map_height: FixedNum, rng: &mut FixedRng) -> (FixedVec2, FixedNum) {
+    let x = rng.range(-map_width / 2, map_width / 2);
+    let y = rng.range(-map_height / 2, map_height / 2);
+    let (min_radius, max_radius) = (FixedNum::from_num(params.min_radius), FixedNum::from_num(params.max_radius));
+    let radius = params.size_distribution.lerp(min_radius, max_radius, rng.next_unit());
+    (FixedVec2::new(x, y), radius)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::editor::connectivity::MIN_CONNECTED_FRACTION;
+
+    fn sample_radii(size_distribution: ObstacleSizeDistribution) -> Vec<f32> {
+        let params = GenerationParams {
+            map_width: 200.0,
+            map_height: 100.0,
+            num_obstacles: 500,
+            min_radius: 1.0,
+            max_radius: 12.0,
+            size_distribution,
+            seed: 7,
+        };
+        let mut rng = FixedRng::new(7);
+        (0..params.num_obstacles)
+            .map(|_| {
+                let (position, radius) = random_obstacle(&params, FixedNum::from_num(200), FixedNum::from_num(100), &mut rng);
+                assert!(position.x.abs() <= FixedNum::from_num(100.0) && position.y.abs() <= FixedNum::from_num(50.0));
+                radius.to_num::<f32>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random_obstacle_radii_span_requested_range() {
+        for distribution in [ObstacleSizeDistribution::Uniform, ObstacleSizeDistribution::MostlySmall] {
+            let radii = sample_radii(distribution);
+            let min = radii.iter().copied().fold(f32::MAX, f32::min);
+            let max = radii.iter().copied().fold(f32::MIN, f32::max);
+            assert!(min >= 1.0 - 1e-3 && max <= 12.0 + 1e-3, "{:?}: radii out of range {}..{}", distribution, min, max);
+            // Both ends of the range actually occur, not just the middle
+            assert!(min < 2.0 && max > 11.0, "{:?}: radii should span the range, got {}..{}", distribution, min, max);
+        }
+    }
+
+    #[test]
+    fn test_mostly_small_distribution_favors_small_obstacles() {
+        let small = |radii: &[f32]| radii.iter().filter(|&&r| r < 6.5).count();
+        let uniform = sample_radii(ObstacleSizeDistribution::Uniform);
+        let mostly_small = sample_radii(ObstacleSizeDistribution::MostlySmall);
+        assert!(small(&mostly_small) > small(&uniform) + 50);
+    }
+
+    #[test]
+    fn test_equal_min_and_max_radius_is_allowed() {
+        let params = GenerationParams { min_radius: 3.0, max_radius: 3.0, ..default() };
+        let mut rng = FixedRng::new(1);
+        let (_, radius) = random_obstacle(&params, FixedNum::from_num(10), FixedNum::from_num(10), &mut rng);
+        assert_eq!(radius, FixedNum::from_num(3.0));
+    }
+
+    /// Dense enough that raw placement regularly walls off pockets
+    fn dense_params(seed: u64) -> GenerationParams {
+        GenerationParams {
+            map_width: 80.0,
+            map_height: 60.0,
+            num_obstacles: 120,
+            min_radius: 1.0,
+            max_radius: 5.0,
+            size_distribution: ObstacleSizeDistribution::Uniform,
+            seed,
+        }
+    }
+
+    #[test]
+    fn test_generated_maps_have_one_large_connected_region() {
+        let mut carved = 0;
+        for seed in 0..20 {
+            let params = dense_params(seed);
+            let obstacles = generate_obstacle_layout(&params);
+            let report = analyze_connectivity(params.map_width, params.map_height, &obstacles);
+            assert!(report.is_playable(), "seed {}: largest region holds {:.2} of the walkable map ({:?})",
+                seed, report.largest_fraction(), report);
+            assert!(report.largest_region_cells as f32 >= MIN_CONNECTED_FRACTION * report.walkable_cells as f32);
+            if obstacles.len() < params.num_obstacles {
+                carved += 1;
+            }
+        }
+        assert!(carved > 0, "These params should need carving for at least some seeds");
+    }
+
+    #[test]
+    fn test_layout_is_deterministic_per_seed() {
+        let layout = generate_obstacle_layout(&dense_params(3));
+        assert_eq!(layout, generate_obstacle_layout(&dense_params(3)));
+        assert_ne!(layout, generate_obstacle_layout(&dense_params(4)));
+    }
+
+    /// Golden hash of the `dense_params(3)` layout with the default `fixed_i48f16` precision.
+    ///
+    /// If a change to the generator is intended, update this with the value the test prints.
+    const DENSE_LAYOUT_HASH: u64 = 0x2e2b_48fc_9341_21e4;
+
+    /// FNV-1a over the raw bits of every obstacle, in order
+    fn layout_hash(layout: &[(FixedVec2, FixedNum)]) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for &(position, radius) in layout {
+            for value in [position.x, position.y, radius] {
+                for byte in value.to_bits().to_le_bytes() {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+                }
+            }
+        }
+        hash
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "fixed_i40f24", feature = "fixed_i32f32")))]
+    fn test_layout_matches_golden_hash() {
+        let layout = generate_obstacle_layout(&dense_params(3));
+        let hash = layout_hash(&layout);
+        assert_eq!(hash, DENSE_LAYOUT_HASH, "Layout changed: got {:#018x}", hash);
+    }
+}
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use crate::game::camera::RtsCamera;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
-use crate::game::simulation::{StaticObstacle, SimPosition, Collider, layers};
+use crate::game::simulation::{ObstacleBundle, StaticObstacle, SimPosition, Collider};
 use super::components::*;
+use super::terrain::PaintedTerrain;
 use super::ui::spawn_generation_dialog;
 
 /// Handles keyboard input for typing in input fields
@@ -20,7 +21,9 @@ pub fn keyboard_input_system(
         InputFieldType::MapWidth => &mut editor_state.input_map_width,
         InputFieldType::MapHeight => &mut editor_state.input_map_height,
         InputFieldType::NumObstacles => &mut editor_state.input_num_obstacles,
-        InputFieldType::ObstacleSize => &mut editor_state.input_obstacle_size,
+        InputFieldType::MinObstacleRadius => &mut editor_state.input_min_obstacle_radius,
+        InputFieldType::MaxObstacleRadius => &mut editor_state.input_max_obstacle_radius,
+        InputFieldType::Seed => &mut editor_state.input_seed,
     };
     
     let mut changed = false;
@@ -68,8 +71,9 @@ pub fn keyboard_input_system(
         }
     }
     
-    // Handle decimal point for obstacle size
-    if field_type == InputFieldType::ObstacleSize && keys.just_pressed(KeyCode::Period) {
+    // Handle decimal point for obstacle radii
+    let is_radius = matches!(field_type, InputFieldType::MinObstacleRadius | InputFieldType::MaxObstacleRadius);
+    if is_radius && keys.just_pressed(KeyCode::Period) {
         if active_field.first_input {
             input_str.clear();
             active_field.first_input = false;
@@ -125,6 +129,8 @@ pub fn handle_editor_input(
     editor_state: Res<EditorState>,
     editor_resources: Res<EditorResources>,
     initial_config: Res<crate::game::config::InitialConfig>,
+    mut map_status: ResMut<crate::game::simulation::MapStatus>,
+    obstacle_query: Query<(&SimPosition, &Collider), With<StaticObstacle>>,
 ) {
     if !editor_state.placing_obstacle {
         return;
@@ -141,7 +147,20 @@ pub fn handle_editor_input(
                     let t = -ray.origin.y / ray.direction.y;
                     if t >= 0.0 {
                         let intersection = ray.origin + ray.direction * t;
-                        spawn_obstacle(&mut commands, FixedVec2::new(FixedNum::from_num(intersection.x), FixedNum::from_num(intersection.z)), FixedNum::from_num(initial_config.editor_default_obstacle_radius), &editor_resources);
+                        let position = FixedVec2::new(FixedNum::from_num(intersection.x), FixedNum::from_num(intersection.z));
+                        let radius = FixedNum::from_num(initial_config.editor_default_obstacle_radius);
+                        let max_overlap = FixedNum::from_num(initial_config.editor_max_obstacle_overlap);
+
+                        // Obstacles aren't in the spatial hash; scanning them is fine at click rate
+                        let existing = obstacle_query.iter().map(|(pos, collider)| (pos.0, collider.radius));
+                        if let Some((other_pos, _)) = find_blocking_obstacle(position, radius, existing, max_overlap) {
+                            info!("Not placing obstacle at {:?}: overlaps the one at {:?}", position, other_pos);
+                            return;
+                        }
+
+                        spawn_obstacle(&mut commands, position, radius, &editor_resources);
+                        // Not in the cost field until the map is finalized again
+                        map_status.terrain_baked = false;
                     }
                 }
             }
@@ -149,16 +168,43 @@ pub fn handle_editor_input(
     }
 }
 
+/// Paints terrain cost under the cursor while the cost brush is active and the left
+/// button is held. Takes effect in the cost field right away; cached routes pick it up
+/// when the map is finalized again.
+pub fn handle_cost_painting(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<RtsCamera>>,
+    editor_state: Res<EditorState>,
+    mut painted_terrain: ResMut<PaintedTerrain>,
+    mut map_flow_field: ResMut<crate::game::simulation::MapFlowField>,
+) {
+    let brush = editor_state.cost_brush;
+    if !brush.active || !mouse_button_input.pressed(MouseButton::Left) {
+        return;
+    }
+    let Ok((camera, camera_transform)) = camera_q.single() else { return };
+    let Ok(window) = windows.single() else { return };
+    let Some(cursor_position) = window.cursor_position() else { return };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) else { return };
+
+    // Intersect with plane Y=0
+    if ray.direction.y.abs() <= 0.0001 {
+        return;
+    }
+    let t = -ray.origin.y / ray.direction.y;
+    if t < 0.0 {
+        return;
+    }
+    let intersection = ray.origin + ray.direction * t;
+    let center = FixedVec2::from_f32(intersection.x, intersection.z);
+    painted_terrain.paint(&mut map_flow_field.0, center, FixedNum::from_num(brush.radius), brush.cost);
+}
+
 /// Helper function to spawn an obstacle entity
 pub fn spawn_obstacle(commands: &mut Commands, position: FixedVec2, radius: FixedNum, resources: &EditorResources) {
     commands.spawn((
-        StaticObstacle,
-        SimPosition(position),
-        Collider {
-            radius,
-            layer: layers::OBSTACLE,
-            mask: layers::ALL,
-        },
+        ObstacleBundle::new(position, radius),
         Transform::from_translation(Vec3::new(position.x.to_num(), 1.0, position.y.to_num()))
             .with_scale(Vec3::new(radius.to_num::<f32>(), 1.0, radius.to_num::<f32>())),
         GlobalTransform::default(),
@@ -166,3 +212,68 @@ pub fn spawn_obstacle(commands: &mut Commands, position: FixedVec2, radius: Fixe
         MeshMaterial3d(resources.obstacle_material.clone()),
     ));
 }
+
+/// First obstacle in `existing` that a new `(position, radius)` one would overlap by more than
+/// `max_overlap` (depth as a fraction of the smaller diameter)
+pub fn find_blocking_obstacle(
+    position: FixedVec2,
+    radius: FixedNum,
+    existing: impl IntoIterator<Item = (FixedVec2, FixedNum)>,
+    max_overlap: FixedNum,
+) -> Option<(FixedVec2, FixedNum)> {
+    existing.into_iter().find(|&(other_pos, other_radius)| {
+        let reach = radius + other_radius;
+        let distance_sq = (position - other_pos).length_squared();
+        if distance_sq >= reach * reach {
+            return false;
+        }
+        // Capped at the smaller diameter: fully inside is as deep as it gets
+        let smaller_diameter = radius.min(other_radius) * 2;
+        let depth = (reach - distance_sq.sqrt()).min(smaller_diameter);
+        depth > max_overlap * smaller_diameter
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obstacle(x: f32, y: f32, radius: f32) -> (FixedVec2, FixedNum) {
+        (FixedVec2::from_f32(x, y), FixedNum::from_num(radius))
+    }
+
+    #[test]
+    fn test_obstacle_on_top_of_existing_is_rejected() {
+        let existing = [obstacle(0.0, 0.0, 5.0), obstacle(30.0, 0.0, 5.0)];
+        let (position, radius) = obstacle(30.5, 0.0, 5.0);
+        for max_overlap in [0.0, 0.25, 0.9] {
+            let blocking = find_blocking_obstacle(position, radius, existing, FixedNum::from_num(max_overlap));
+            assert_eq!(blocking, Some(existing[1]), "max_overlap {}", max_overlap);
+        }
+        // Stacking explicitly allowed
+        assert_eq!(find_blocking_obstacle(position, radius, existing, FixedNum::ONE), None);
+    }
+
+    #[test]
+    fn test_partial_overlap_follows_the_setting() {
+        let existing = [obstacle(0.0, 0.0, 5.0)];
+        // 2 units deep into a 10-wide obstacle: 20% of the smaller diameter
+        let (position, radius) = obstacle(8.0, 0.0, 5.0);
+        assert!(find_blocking_obstacle(position, radius, existing, FixedNum::from_num(0.1)).is_some());
+        assert!(find_blocking_obstacle(position, radius, existing, FixedNum::from_num(0.25)).is_none());
+
+        // Touching or apart is always fine
+        for (x, y) in [(10.0, 0.0), (0.0, -12.0), (40.0, 40.0)] {
+            let (position, radius) = obstacle(x, y, 5.0);
+            assert!(find_blocking_obstacle(position, radius, existing, FixedNum::ZERO).is_none(), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_small_obstacle_inside_large_one_is_rejected() {
+        // Depth is measured against the smaller obstacle, so it can't hide inside a big one
+        let existing = [obstacle(0.0, 0.0, 20.0)];
+        let (position, radius) = obstacle(5.0, 5.0, 1.0);
+        assert!(find_blocking_obstacle(position, radius, existing, FixedNum::from_num(0.9)).is_some());
+    }
+}
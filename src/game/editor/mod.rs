@@ -3,16 +3,23 @@ mod ui;
 mod input;
 mod generation;
 mod actions;
+mod validation;
+mod connectivity;
+mod terrain;
+mod autosave;
 
 use bevy::prelude::*;
 use crate::game::GameState;
 
 pub use components::*;
+use terrain::PaintedTerrain;
 pub use input::spawn_obstacle;  // Re-export for use in loading system
+pub use generation::generate_obstacle_layout;
 use ui::*;
 use input::*;
 use generation::*;
 use actions::*;
+use autosave::*;
 
 /// Plugin for the map editor functionality
 pub struct EditorPlugin;
@@ -21,18 +28,25 @@ impl Plugin for EditorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EditorState>()
            .init_resource::<ActiveInputField>()
+           .init_resource::<PaintedTerrain>()
+           .init_resource::<EditorAutosave>()
            .add_systems(Startup, setup_editor_resources)
-           .add_systems(OnEnter(GameState::Editor), setup_editor_ui)
+           .add_systems(OnEnter(GameState::Editor), (setup_editor_ui, offer_autosave_recovery).chain())
            .add_systems(OnExit(GameState::Editor), cleanup_editor_ui)
            .add_systems(Update, (
                editor_button_system, 
                handle_editor_input, 
+               handle_cost_painting,
+               update_cost_brush_label,
                handle_generation, 
                cleanup_generation_overlay, 
                check_finalization_complete, 
                keyboard_input_system, 
-               handle_input_field_clicks
-           ).run_if(in_state(GameState::Editor)));
+               handle_input_field_clicks,
+               autosave_editor_work,
+               handle_autosave_recovery_buttons,
+           ).run_if(in_state(GameState::Editor)))
+           .add_systems(PostUpdate, autosave_on_exit.run_if(in_state(GameState::Editor)));
     }
 }
 
@@ -0,0 +1,215 @@
+//! Weighted terrain painting.
+//!
+//! The cost brush writes intermediate costs (roads cheap, rough ground expensive) into the
+//! flow field's `cost_field`. Finalizing rebuilds the cost field from scratch, so painted
+//! cells are also kept in [`PaintedTerrain`] and re-applied underneath the obstacles.
+
+use bevy::prelude::*;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::structures::{FlowField, OBSTACLE_COST};
+
+/// Cost of unpainted ground
+pub const BASE_TERRAIN_COST: u8 = 1;
+/// Most expensive paintable cost (one below an obstacle)
+pub const MAX_PAINT_COST: u8 = OBSTACLE_COST - 1;
+
+/// Cost brush settings (part of [`EditorState`](super::EditorState))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostBrush {
+    pub active: bool,
+    /// Cost written to painted cells, in `BASE_TERRAIN_COST..=MAX_PAINT_COST`
+    pub cost: u8,
+    /// World units
+    pub radius: f32,
+}
+
+impl Default for CostBrush {
+    fn default() -> Self {
+        Self { active: false, cost: 10, radius: 2.0 }
+    }
+}
+
+impl CostBrush {
+    pub fn adjust_cost(&mut self, delta: i32) {
+        self.cost = (self.cost as i32 + delta).clamp(BASE_TERRAIN_COST as i32, MAX_PAINT_COST as i32) as u8;
+    }
+
+    pub fn adjust_radius(&mut self, delta: f32) {
+        self.radius = (self.radius + delta).clamp(0.5, 20.0);
+    }
+
+    /// Text for the editor panel
+    pub fn label(&self) -> String {
+        format!("Cost brush {}: cost {}, radius {:.1}", if self.active { "ON" } else { "off" }, self.cost, self.radius)
+    }
+}
+
+/// Painted cell costs, row-major over the flow field grid. Empty until something is painted.
+#[derive(Resource, Default)]
+pub struct PaintedTerrain {
+    costs: Vec<u8>,
+}
+
+impl PaintedTerrain {
+    /// Paint `cost` onto every cell whose center lies within `radius` of `center`.
+    ///
+    /// Obstacle cells keep blocking in the cost field but remember the paint, so it shows
+    /// up once the obstacle is gone and the map is finalized again. Returns the number of
+    /// cells painted.
+    pub fn paint(&mut self, flow_field: &mut FlowField, center: FixedVec2, radius: FixedNum, cost: u8) -> usize {
+        let cost = cost.clamp(BASE_TERRAIN_COST, MAX_PAINT_COST);
+        if self.costs.len() != flow_field.cost_field.len() {
+            self.costs = vec![BASE_TERRAIN_COST; flow_field.cost_field.len()];
+        }
+
+        let Some((center_x, center_y)) = flow_field.world_to_grid(center) else { return 0 };
+        let reach = (radius / flow_field.cell_size).ceil().to_num::<usize>();
+        let max_x = (center_x + reach).min(flow_field.width - 1);
+        let max_y = (center_y + reach).min(flow_field.height - 1);
+
+        let mut painted = 0;
+        for y in center_y.saturating_sub(reach)..=max_y {
+            for x in center_x.saturating_sub(reach)..=max_x {
+                if (flow_field.grid_to_world(x, y) - center).length_squared() > radius * radius {
+                    continue;
+                }
+                let idx = flow_field.get_index(x, y);
+                self.costs[idx] = cost;
+                flow_field.set_terrain_cost(x, y, cost);
+                painted += 1;
+            }
+        }
+        painted
+    }
+
+    /// Reset `flow_field`'s cost field to the painted terrain (base cost where nothing is
+    /// painted), ready for the obstacles to be applied on top
+    pub fn bake_into(&self, flow_field: &mut FlowField) {
+        if self.costs.len() == flow_field.cost_field.len() {
+            flow_field.cost_field.copy_from_slice(&self.costs);
+        } else {
+            flow_field.cost_field.fill(BASE_TERRAIN_COST);
+        }
+    }
+
+    /// Painted costs over a grid of `cells` cells, base cost where nothing is painted
+    pub fn to_cost_field(&self, cells: usize) -> Vec<u8> {
+        if self.costs.len() == cells {
+            self.costs.clone()
+        } else {
+            vec![BASE_TERRAIN_COST; cells]
+        }
+    }
+
+    /// Painted terrain restored from a saved cost field. Obstacle cells go back to base cost
+    /// (the obstacles are restored on their own), losing whatever was painted under them.
+    pub fn from_cost_field(mut costs: Vec<u8>) -> Self {
+        for cost in costs.iter_mut().filter(|cost| **cost == OBSTACLE_COST) {
+            *cost = BASE_TERRAIN_COST;
+        }
+        Self { costs }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.costs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.costs.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::simulation::apply_obstacle_to_flow_field;
+    use crate::game::structures::UNREACHABLE;
+
+    /// 21x21 map of 1.0 cells centered on the origin
+    fn field() -> FlowField {
+        FlowField::new(21, 21, FixedNum::ONE, FixedVec2::from_f32(-10.5, -10.5))
+    }
+
+    /// Cells visited walking the integration field downhill from `start` to the goal
+    fn walk(flow_field: &FlowField, start: (usize, usize), goal: (usize, usize)) -> Vec<(usize, usize)> {
+        let integration = flow_field.compute_integration_field(goal);
+        assert_ne!(integration[flow_field.get_index(start.0, start.1)], UNREACHABLE);
+        let mut cell = start;
+        let mut visited = vec![cell];
+        while cell != goal {
+            let (x, y) = cell;
+            cell = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)]
+                .into_iter()
+                .filter(|&(nx, ny)| nx < flow_field.width && ny < flow_field.height)
+                .min_by_key(|&(nx, ny)| integration[flow_field.get_index(nx, ny)])
+                .unwrap();
+            visited.push(cell);
+        }
+        visited
+    }
+
+    #[test]
+    fn test_painting_sets_brush_cost_inside_radius() {
+        let mut flow_field = field();
+        let mut terrain = PaintedTerrain::default();
+        let painted = terrain.paint(&mut flow_field, FixedVec2::ZERO, FixedNum::from_num(2), 40);
+
+        // Radius 2 around the center cell: the 13 cells within distance 2
+        assert_eq!(painted, 13);
+        assert_eq!(flow_field.cost(10, 10), Some(40));
+        assert_eq!(flow_field.cost(12, 10), Some(40));
+        assert_eq!(flow_field.cost(11, 11), Some(40));
+        assert_eq!(flow_field.cost(12, 11), Some(BASE_TERRAIN_COST), "Outside the radius");
+        assert_eq!(flow_field.cost_field.iter().filter(|&&c| c == 40).count(), 13);
+
+        // Painting never produces an obstacle
+        terrain.paint(&mut flow_field, FixedVec2::ZERO, FixedNum::ONE, OBSTACLE_COST);
+        assert_eq!(flow_field.cost(10, 10), Some(MAX_PAINT_COST));
+    }
+
+    #[test]
+    fn test_rough_terrain_is_routed_around_after_refinalize() {
+        let mut flow_field = field();
+        let mut terrain = PaintedTerrain::default();
+        terrain.paint(&mut flow_field, FixedVec2::ZERO, FixedNum::from_num(3), 100);
+
+        // Finalize: cost field rebuilt from the painted terrain, obstacles on top
+        let obstacle = (FixedVec2::from_f32(0.0, 6.0), FixedNum::from_num(5));
+        terrain.bake_into(&mut flow_field);
+        apply_obstacle_to_flow_field(&mut flow_field, obstacle.0, obstacle.1);
+        assert_eq!(flow_field.cost(10, 10), Some(100), "Paint survives finalizing");
+
+        let path = walk(&flow_field, (2, 10), (18, 10));
+        assert!(path.iter().all(|&(x, y)| flow_field.cost(x, y) == Some(BASE_TERRAIN_COST)), "{:?}", path);
+        // The obstacle walls off the way round above the rough patch
+        assert!(path.iter().any(|&(_, y)| y < 7), "{:?}", path);
+    }
+
+    #[test]
+    fn test_road_is_followed_through_expensive_ground() {
+        let mut flow_field = field();
+        let mut terrain = PaintedTerrain::default();
+        // Rough everywhere, with a cheap road looping up through row 16
+        terrain.paint(&mut flow_field, FixedVec2::ZERO, FixedNum::from_num(20), 20);
+        let road = (2..=18).map(|x| (x, 16)).chain((10..16).flat_map(|y| [(2, y), (18, y)]));
+        for (x, y) in road {
+            let center = flow_field.grid_to_world(x, y);
+            terrain.paint(&mut flow_field, center, FixedNum::ZERO, BASE_TERRAIN_COST);
+        }
+        terrain.bake_into(&mut flow_field);
+
+        let path = walk(&flow_field, (2, 10), (18, 10));
+        assert!(path.iter().all(|&(x, y)| flow_field.cost(x, y) == Some(BASE_TERRAIN_COST)), "Should stay on the road: {:?}", path);
+        assert!(path.contains(&(10, 16)));
+    }
+
+    #[test]
+    fn test_clear_resets_the_bake() {
+        let mut flow_field = field();
+        let mut terrain = PaintedTerrain::default();
+        terrain.paint(&mut flow_field, FixedVec2::ZERO, FixedNum::from_num(3), 100);
+        terrain.clear();
+        terrain.bake_into(&mut flow_field);
+        assert!(flow_field.cost_field.iter().all(|&c| c == BASE_TERRAIN_COST));
+    }
+}
@@ -1,23 +1,14 @@
 use bevy::prelude::*;
 use super::components::*;
+use super::validation::validate_generation_input;
 
 /// Sets up editor UI when entering editor state
 pub fn setup_editor_ui(
     mut commands: Commands,
     mut editor_state: ResMut<EditorState>,
-    map_flow_field: Res<crate::game::simulation::MapFlowField>,
-    initial_config: Res<crate::game::config::InitialConfig>,
+    map_dimensions: Res<crate::game::simulation::MapDimensions>,
 ) {
-    // Initialize map size from flow field or initial config
-    let flow_field = &map_flow_field.0;
-    if flow_field.width > 0 {
-        editor_state.current_map_size = Vec2::new(
-            flow_field.width as f32 * flow_field.cell_size.to_num::<f32>(),
-            flow_field.height as f32 * flow_field.cell_size.to_num::<f32>()
-        );
-    } else {
-        editor_state.current_map_size = Vec2::new(initial_config.map_width, initial_config.map_height);
-    }
+    editor_state.current_map_size = Vec2::new(map_dimensions.width.to_num(), map_dimensions.height.to_num());
 
     commands
         .spawn((
@@ -65,12 +56,51 @@ pub fn setup_editor_ui(
             spawn_button!("Generate Random Map", EditorButtonAction::OpenGenerateDialog);
             spawn_button!("Clear Map", EditorButtonAction::ClearMap);
             spawn_button!("Toggle Place Obstacle", EditorButtonAction::TogglePlaceObstacle);
+            spawn_button!("Toggle Paint Cost", EditorButtonAction::TogglePaintCost);
+
+            // Cost brush settings: current values plus small -/+ buttons
+            parent.spawn((
+                Text::new(editor_state.cost_brush.label()),
+                TextFont { font_size: 16.0, ..default() },
+                TextColor(Color::WHITE),
+                Node { margin: UiRect::horizontal(Val::Px(5.0)), ..default() },
+                CostBrushLabel,
+            ));
+            parent.spawn(Node { flex_direction: FlexDirection::Row, ..default() }).with_children(|row| {
+                for (text, action) in [
+                    ("Cost -", EditorButtonAction::DecrementPaintCost),
+                    ("Cost +", EditorButtonAction::IncrementPaintCost),
+                    ("Brush -", EditorButtonAction::DecrementBrushRadius),
+                    ("Brush +", EditorButtonAction::IncrementBrushRadius),
+                ] {
+                    row.spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(70.0),
+                            height: Val::Px(30.0),
+                            margin: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+                        action,
+                    )).with_children(|btn| {
+                        btn.spawn((
+                            Text::new(text),
+                            TextFont { font_size: 14.0, ..default() },
+                            TextColor(Color::WHITE),
+                        ));
+                    });
+                }
+            });
+
             spawn_button!("Finalize / Bake Map", EditorButtonAction::FinalizeMap);
             spawn_button!("Save Map", EditorButtonAction::SaveMap);
             
             // Instructions
             parent.spawn((
-                Text::new("Press 'Toggle Place Obstacle' then click on map to place obstacles."),
+                Text::new("Press 'Toggle Place Obstacle' then click on map to place obstacles.\n'Toggle Paint Cost' then drag to paint terrain cost (1 = road, higher = rough); finalize to update routes."),
                 TextFont {
                     font_size: 16.0,
                     ..default()
@@ -84,12 +114,26 @@ pub fn setup_editor_ui(
         });
 }
 
+/// Keeps the cost brush text in sync with the editor state
+pub fn update_cost_brush_label(
+    editor_state: Res<EditorState>,
+    mut label_query: Query<&mut Text, With<CostBrushLabel>>,
+) {
+    if !editor_state.is_changed() {
+        return;
+    }
+    for mut text in &mut label_query {
+        **text = editor_state.cost_brush.label();
+    }
+}
+
 /// Cleans up editor UI when exiting editor state
 pub fn cleanup_editor_ui(
     mut commands: Commands, 
     query: Query<Entity, With<EditorUiRoot>>, 
     dialog_query: Query<Entity, With<GenerationDialogRoot>>, 
-    loading_query: Query<Entity, With<LoadingOverlayRoot>>
+    loading_query: Query<Entity, With<LoadingOverlayRoot>>,
+    recovery_query: Query<Entity, With<super::autosave::AutosaveRecoveryRoot>>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
@@ -100,6 +144,9 @@ pub fn cleanup_editor_ui(
     for entity in loading_query.iter() {
         commands.entity(entity).despawn();
     }
+    for entity in recovery_query.iter() {
+        commands.entity(entity).despawn();
+    }
 }
 
 /// Spawns the map generation parameter dialog
@@ -233,7 +280,58 @@ pub fn spawn_generation_dialog(commands: &mut Commands, editor_state: &EditorSta
         create_value_row!("Map Width:", &editor_state.input_map_width, EditorButtonAction::DecrementMapWidth, EditorButtonAction::IncrementMapWidth, InputFieldType::MapWidth);
         create_value_row!("Map Height:", &editor_state.input_map_height, EditorButtonAction::DecrementMapHeight, EditorButtonAction::IncrementMapHeight, InputFieldType::MapHeight);
         create_value_row!("Num Obstacles:", &editor_state.input_num_obstacles, EditorButtonAction::DecrementObstacles, EditorButtonAction::IncrementObstacles, InputFieldType::NumObstacles);
-        create_value_row!("Obstacle Radius:", &editor_state.input_obstacle_size, EditorButtonAction::DecrementObstacleSize, EditorButtonAction::IncrementObstacleSize, InputFieldType::ObstacleSize);
+        create_value_row!("Min Obstacle Radius:", &editor_state.input_min_obstacle_radius, EditorButtonAction::DecrementMinObstacleRadius, EditorButtonAction::IncrementMinObstacleRadius, InputFieldType::MinObstacleRadius);
+        create_value_row!("Max Obstacle Radius:", &editor_state.input_max_obstacle_radius, EditorButtonAction::DecrementMaxObstacleRadius, EditorButtonAction::IncrementMaxObstacleRadius, InputFieldType::MaxObstacleRadius);
+        create_value_row!("Seed:", &editor_state.input_seed, EditorButtonAction::DecrementSeed, EditorButtonAction::IncrementSeed, InputFieldType::Seed);
+
+        // Size distribution (click to cycle)
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                margin: UiRect::bottom(Val::Px(15.0)),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+        )).with_children(|row| {
+            row.spawn((
+                Text::new("Size Distribution:"),
+                TextFont { font_size: 18.0, ..default() },
+                TextColor(Color::WHITE),
+                Node { width: Val::Px(180.0), ..default() },
+            ));
+            row.spawn((
+                Button,
+                Node {
+                    width: Val::Px(200.0),
+                    height: Val::Px(35.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.3, 0.3, 0.5)),
+                EditorButtonAction::CycleObstacleSizeDistribution,
+            )).with_children(|btn| {
+                btn.spawn((
+                    Text::new(editor_state.obstacle_size_distribution.label()),
+                    TextFont { font_size: 18.0, ..default() },
+                    TextColor(Color::WHITE),
+                ));
+            });
+        });
+
+        // Inline feedback on the current field values
+        let validated = validate_generation_input(editor_state);
+        for (message, color) in validated.errors.iter().map(|m| (m, Color::srgb(1.0, 0.4, 0.4)))
+            .chain(validated.warnings.iter().map(|m| (m, Color::srgb(1.0, 0.85, 0.3))))
+        {
+            parent.spawn((
+                Text::new(message.clone()),
+                TextFont { font_size: 14.0, ..default() },
+                TextColor(color),
+            ));
+        }
 
         // Info text
         parent.spawn((
@@ -0,0 +1,192 @@
+//! Validation of the map generation dialog's text fields.
+//!
+//! The fields are free text, so anything can end up in them. Values that can't be used
+//! (unparseable, zero or negative sizes) block generation; values that are merely out of
+//! range are clamped, e.g. so a typo can't request a 100000x100000 flow field.
+
+use super::components::*;
+
+/// Smallest map side (matches the dialog's decrement floor)
+pub const MIN_MAP_SIZE: f32 = 10.0;
+/// Largest map side: the flow field already holds 4096² cells at this size
+pub const MAX_MAP_SIZE: f32 = 4096.0;
+/// Map area (world units²) per allowed obstacle
+pub const MAP_AREA_PER_OBSTACLE: f32 = 25.0;
+pub const MIN_OBSTACLE_RADIUS: f32 = 0.5;
+pub const MAX_OBSTACLE_RADIUS: f32 = 20.0;
+
+/// Outcome of [`validate_generation_input`]
+#[derive(Debug, Clone, Default)]
+pub struct ValidatedGeneration {
+    /// Parameters after clamping (only meaningful if [`is_valid`](Self::is_valid))
+    pub params: GenerationParams,
+    /// Values that were adjusted to fit the limits; generation still runs
+    pub warnings: Vec<String>,
+    /// Values that can't be used; generation is refused while any are present
+    pub errors: Vec<String>,
+}
+
+impl ValidatedGeneration {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Parse and clamp the dialog fields into [`GenerationParams`]
+pub fn validate_generation_input(editor_state: &EditorState) -> ValidatedGeneration {
+    let mut result = ValidatedGeneration::default();
+
+    let map_width = parse_size(&editor_state.input_map_width, "Map width", &mut result)
+        .map(|v| clamp_noted(v, MIN_MAP_SIZE, MAX_MAP_SIZE, "Map width", &mut result));
+    let map_height = parse_size(&editor_state.input_map_height, "Map height", &mut result)
+        .map(|v| clamp_noted(v, MIN_MAP_SIZE, MAX_MAP_SIZE, "Map height", &mut result));
+
+    let num_obstacles = match editor_state.input_num_obstacles.trim().parse::<usize>() {
+        Ok(count) => count,
+        Err(_) => {
+            result.errors.push(format!("Num obstacles: '{}' is not a whole number", editor_state.input_num_obstacles));
+            0
+        }
+    };
+    let num_obstacles = match (map_width, map_height) {
+        (Some(width), Some(height)) => {
+            let max_obstacles = (width * height / MAP_AREA_PER_OBSTACLE) as usize;
+            if num_obstacles > max_obstacles {
+                result.warnings.push(format!("Num obstacles clamped to {} for a {}x{} map", max_obstacles, width, height));
+            }
+            num_obstacles.min(max_obstacles)
+        }
+        _ => num_obstacles,
+    };
+
+    let radius_a = parse_size(&editor_state.input_min_obstacle_radius, "Min obstacle radius", &mut result)
+        .map(|v| clamp_noted(v, MIN_OBSTACLE_RADIUS, MAX_OBSTACLE_RADIUS, "Min obstacle radius", &mut result));
+    let radius_b = parse_size(&editor_state.input_max_obstacle_radius, "Max obstacle radius", &mut result)
+        .map(|v| clamp_noted(v, MIN_OBSTACLE_RADIUS, MAX_OBSTACLE_RADIUS, "Max obstacle radius", &mut result));
+
+    let seed = match editor_state.input_seed.trim().parse::<u64>() {
+        Ok(seed) => seed,
+        Err(_) => {
+            result.errors.push(format!("Seed: '{}' is not a whole number", editor_state.input_seed));
+            0
+        }
+    };
+
+    if let (Some(map_width), Some(map_height), Some(radius_a), Some(radius_b)) = (map_width, map_height, radius_a, radius_b) {
+        // Accept the bounds in either order
+        result.params = GenerationParams {
+            map_width,
+            map_height,
+            num_obstacles,
+            min_radius: radius_a.min(radius_b),
+            max_radius: radius_a.max(radius_b),
+            size_distribution: editor_state.obstacle_size_distribution,
+            seed,
+        };
+    }
+    result
+}
+
+/// Positive, finite number, or an error entry
+fn parse_size(input: &str, name: &str, result: &mut ValidatedGeneration) -> Option<f32> {
+    match input.trim().parse::<f32>() {
+        Ok(value) if value.is_finite() && value > 0.0 => Some(value),
+        Ok(_) => {
+            result.errors.push(format!("{} must be greater than zero", name));
+            None
+        }
+        Err(_) => {
+            result.errors.push(format!("{}: '{}' is not a number", name, input));
+            None
+        }
+    }
+}
+
+fn clamp_noted(value: f32, min: f32, max: f32, name: &str, result: &mut ValidatedGeneration) -> f32 {
+    let clamped = value.clamp(min, max);
+    if clamped != value {
+        result.warnings.push(format!("{} clamped to {}", name, clamped));
+    }
+    clamped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(width: &str, height: &str, obstacles: &str, min_radius: &str, max_radius: &str) -> EditorState {
+        EditorState {
+            input_map_width: width.to_string(),
+            input_map_height: height.to_string(),
+            input_num_obstacles: obstacles.to_string(),
+            input_min_obstacle_radius: min_radius.to_string(),
+            input_max_obstacle_radius: max_radius.to_string(),
+            input_seed: "1".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_input_passes_through_unchanged() {
+        let result = validate_generation_input(&state("200", "100", "40", "1.0", "4.5"));
+        assert!(result.is_valid() && result.warnings.is_empty(), "{:?}", result);
+        assert_eq!(result.params.map_width, 200.0);
+        assert_eq!(result.params.map_height, 100.0);
+        assert_eq!(result.params.num_obstacles, 40);
+        assert_eq!((result.params.min_radius, result.params.max_radius), (1.0, 4.5));
+    }
+
+    #[test]
+    fn test_limits_are_inclusive() {
+        let result = validate_generation_input(&state("10", "4096", "1638", "0.5", "20"));
+        assert!(result.is_valid() && result.warnings.is_empty(), "{:?}", result);
+        assert_eq!(result.params.num_obstacles, 1638);
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped_with_warnings() {
+        let result = validate_generation_input(&state("100000", "5", "999999999", "0.1", "50"));
+        assert!(result.is_valid(), "{:?}", result);
+        assert_eq!(result.params.map_width, MAX_MAP_SIZE);
+        assert_eq!(result.params.map_height, MIN_MAP_SIZE);
+        assert_eq!(result.params.num_obstacles, (MAX_MAP_SIZE * MIN_MAP_SIZE / MAP_AREA_PER_OBSTACLE) as usize);
+        assert_eq!((result.params.min_radius, result.params.max_radius), (MIN_OBSTACLE_RADIUS, MAX_OBSTACLE_RADIUS));
+        assert_eq!(result.warnings.len(), 5);
+    }
+
+    #[test]
+    fn test_zero_negative_and_garbage_are_rejected() {
+        for (width, height, obstacles, min_radius) in [
+            ("0", "100", "10", "1"),
+            ("100", "-50", "10", "1"),
+            ("", "100", "10", "1"),
+            ("abc", "100", "10", "1"),
+            ("inf", "100", "10", "1"),
+            ("NaN", "100", "10", "1"),
+            ("100", "100", "-3", "1"),
+            ("100", "100", "2.5", "1"),
+            ("100", "100", "10", "0"),
+        ] {
+            let result = validate_generation_input(&state(width, height, obstacles, min_radius, "4"));
+            assert!(!result.is_valid(), "{:?} should be rejected", (width, height, obstacles, min_radius));
+            assert_eq!(result.errors.len(), 1, "One error per bad field: {:?}", result.errors);
+        }
+    }
+
+    #[test]
+    fn test_seed_is_parsed_or_rejected() {
+        let mut input = state("100", "100", "10", "1", "4");
+        input.input_seed = "4242".to_string();
+        assert_eq!(validate_generation_input(&input).params.seed, 4242);
+
+        input.input_seed = "-1".to_string();
+        assert_eq!(validate_generation_input(&input).errors.len(), 1);
+    }
+
+    #[test]
+    fn test_swapped_radius_bounds_are_reordered() {
+        let result = validate_generation_input(&state("100", "100", "10", "8", "2"));
+        assert!(result.is_valid());
+        assert_eq!((result.params.min_radius, result.params.max_radius), (2.0, 8.0));
+    }
+}
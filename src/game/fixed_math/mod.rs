@@ -4,15 +4,108 @@
 //! arithmetic to ensure identical behavior across different platforms and architectures.
 //! This is critical for multiplayer lockstep networking where all clients must simulate
 //! identically.
-
-use fixed::types::I48F16;
+//!
+//! # Precision Features
+//!
+//! The bit split of [`FixedNum`] is chosen at compile time:
+//!
+//! | Feature        | Format | Range       | Resolution  |
+//! |----------------|--------|-------------|-------------|
+//! | `fixed_i48f16` | I48F16 | ±1.4e14     | ~0.000015   |
+//! | `fixed_i40f24` | I40F24 | ±5.5e11     | ~0.00000006 |
+//! | `fixed_i32f32` | I32F32 | ±2.1e9      | ~0.0000000002 |
+//!
+//! `fixed_i48f16` is the default. Enabling a higher-precision feature overrides it, so
+//! `cargo run --features fixed_i40f24` works without `--no-default-features`.
+//!
+//! **DETERMINISM:** The precision feature changes every simulation result (and the bits
+//! written to snapshots/replays). It MUST match across all lockstep clients.
+//!
+//! Code must not assume a particular split: use `FixedNum::DELTA`, `FixedNum::FRAC_NBITS`,
+//! `FixedNum::MAX` etc. instead of hard-coded bit counts or raw-bit constants.
+//! Note that squared distances shrink the usable range (I32F32 overflows `length_squared`
+//! at a distance of ~46k).
+//!
+//! Run the math tests under another precision with e.g.
+//! `cargo test --lib fixed_math --features fixed_i32f32`.
 
 pub use vec2::FixedVec2;
+pub use rng::FixedRng;
+pub use trig::{atan2, wrap_angle};
+pub use pow::pow;
 
 mod vec2;
+mod rng;
+mod trig;
+mod pow;
+
+#[cfg(all(feature = "fixed_i40f24", feature = "fixed_i32f32"))]
+compile_error!("Features `fixed_i40f24` and `fixed_i32f32` are mutually exclusive");
+
+/// Fixed-point number type used throughout the simulation.
+///
+/// I48F16 by default (48 integer bits, 16 fractional bits); see the module docs for the
+/// other precision features.
+#[cfg(not(any(feature = "fixed_i40f24", feature = "fixed_i32f32")))]
+pub type FixedNum = fixed::types::I48F16;
+
+/// Fixed-point number type used throughout the simulation.
+///
+/// I40F24 (`fixed_i40f24` feature): 40 integer bits, 24 fractional bits.
+#[cfg(all(feature = "fixed_i40f24", not(feature = "fixed_i32f32")))]
+pub type FixedNum = fixed::types::I40F24;
 
 /// Fixed-point number type used throughout the simulation.
-/// 
-/// Uses I48F16 format: 48 bits for the integer part, 16 bits for the fractional part.
-/// This provides a range of approximately ±140 trillion with a precision of ~0.000015.
-pub type FixedNum = I48F16;
+///
+/// I32F32 (`fixed_i32f32` feature): 32 integer bits, 32 fractional bits.
+#[cfg(all(feature = "fixed_i32f32", not(feature = "fixed_i40f24")))]
+pub type FixedNum = fixed::types::I32F32;
+
+/// Human-readable name of the active `FixedNum` format (e.g. "I48F16"), for logs and
+/// lockstep handshakes.
+pub fn fixed_precision_name() -> String {
+    format!("I{}F{}", FixedNum::INT_NBITS, FixedNum::FRAC_NBITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precision_matches_feature() {
+        let expected_frac_bits = if cfg!(feature = "fixed_i32f32") {
+            32
+        } else if cfg!(feature = "fixed_i40f24") {
+            24
+        } else {
+            16
+        };
+        assert_eq!(FixedNum::FRAC_NBITS, expected_frac_bits);
+        assert_eq!(FixedNum::INT_NBITS + FixedNum::FRAC_NBITS, 64);
+        assert_eq!(fixed_precision_name(), format!("I{}F{}", 64 - expected_frac_bits, expected_frac_bits));
+    }
+
+    #[test]
+    fn test_core_math_is_precision_independent() {
+        // Values exactly representable under every split give exact results
+        let a = FixedNum::from_num(1.5);
+        let b = FixedNum::from_num(-0.25);
+        assert_eq!(a * b, FixedNum::from_num(-0.375));
+        assert_eq!(a / FixedNum::from_num(4), FixedNum::from_num(0.375));
+        assert_eq!(FixedNum::from_num(16).sqrt(), FixedNum::from_num(4));
+
+        // Inexact results stay within a few ULPs of the active resolution
+        let third = FixedNum::ONE / FixedNum::from_num(3);
+        assert!((third * FixedNum::from_num(3) - FixedNum::ONE).abs() <= FixedNum::DELTA * 3);
+
+        let len = FixedVec2::from_f32(3.0, 4.0).length();
+        assert!((len - FixedNum::from_num(5)).abs() <= FixedNum::DELTA * 4);
+    }
+
+    #[test]
+    fn test_map_scale_values_fit_every_precision() {
+        // Map-scale coordinates (a few thousand units) must not overflow length_squared
+        let span = FixedVec2::from_f32(4096.0, 4096.0);
+        assert_eq!(span.length_squared(), FixedNum::from_num(4096 * 4096 * 2));
+    }
+}
@@ -0,0 +1,69 @@
+use super::FixedNum;
+
+/// Wider format the power is worked out in, so the chain of square roots keeps its precision
+type Wide = fixed::types::I64F64;
+
+/// `base` raised to `exponent`, for `base >= 0`.
+///
+/// Fixed-point only, so every platform gets the same bits: the whole part of the exponent
+/// by squaring and multiplying, each fractional bit by one more square root of `base`
+/// (`base^(1/2)`, `base^(1/4)`, ...), all in [`Wide`] and rounded once at the end. A zero
+/// or negative base gives 0 (1 for a zero exponent); results too large for `FixedNum`
+/// saturate.
+pub fn pow(base: FixedNum, exponent: FixedNum) -> FixedNum {
+    if exponent < FixedNum::ZERO {
+        return FixedNum::ONE / pow(base, -exponent);
+    }
+    if exponent == FixedNum::ZERO {
+        return FixedNum::ONE;
+    }
+    if base <= FixedNum::ZERO {
+        return FixedNum::ZERO;
+    }
+
+    let base = Wide::from_num(base);
+    let mut result = Wide::ONE;
+    let mut square = base;
+    let mut whole = exponent.int().to_num::<u64>();
+    while whole > 0 {
+        if whole & 1 == 1 {
+            result = result.saturating_mul(square);
+        }
+        square = square.saturating_mul(square);
+        whole >>= 1;
+    }
+
+    let mut fraction = exponent.frac();
+    let mut root = base;
+    while fraction > FixedNum::ZERO {
+        root = root.sqrt();
+        fraction *= 2;
+        if fraction >= FixedNum::ONE {
+            result = result.saturating_mul(root);
+            fraction -= FixedNum::ONE;
+        }
+    }
+    FixedNum::saturating_from_num(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: FixedNum, expected: f64) -> bool {
+        (actual.to_num::<f64>() - expected).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_pow_matches_float() {
+        for (base, exponent) in [(0.9, 0.3), (0.9, 1.5), (0.5, 2.0 / 3.0), (2.0, 3.25), (0.99, 0.2), (1.0, 7.7)] {
+            let fixed = pow(FixedNum::from_num(base), FixedNum::from_num(exponent));
+            let expected = f64::powf(base, exponent);
+            assert!(close(fixed, expected), "{}^{} = {}, expected {}", base, exponent, fixed, expected);
+        }
+        assert_eq!(pow(FixedNum::from_num(3), FixedNum::from_num(2)), FixedNum::from_num(9));
+        assert_eq!(pow(FixedNum::from_num(0.5), -FixedNum::ONE), FixedNum::from_num(2));
+        assert_eq!(pow(FixedNum::ZERO, FixedNum::ZERO), FixedNum::ONE);
+        assert_eq!(pow(FixedNum::ZERO, FixedNum::from_num(0.5)), FixedNum::ZERO);
+    }
+}
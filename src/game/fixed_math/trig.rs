@@ -0,0 +1,81 @@
+use super::FixedNum;
+
+/// Angle of the vector `(x, y)` in radians, in `(-π, π]`, counterclockwise from +x.
+///
+/// Fixed-point only (octant reduction plus a minimax polynomial on `[0, 1]`), so every
+/// platform gets the same bits. Error is under 1e-5 rad before quantization; `(0, 0)`
+/// gives 0.
+pub fn atan2(y: FixedNum, x: FixedNum) -> FixedNum {
+    if x == FixedNum::ZERO && y == FixedNum::ZERO {
+        return FixedNum::ZERO;
+    }
+    let (ax, ay) = (x.abs(), y.abs());
+    let steep = ay > ax;
+    let ratio = if steep { ax / ay } else { ay / ax };
+
+    let mut angle = atan_unit(ratio);
+    if steep {
+        angle = FixedNum::FRAC_PI_2 - angle;
+    }
+    if x < FixedNum::ZERO {
+        angle = FixedNum::PI - angle;
+    }
+    if y < FixedNum::ZERO {
+        angle = -angle;
+    }
+    angle
+}
+
+/// `atan(z)` for `z` in `[0, 1]` (Abramowitz & Stegun 4.4.49)
+fn atan_unit(z: FixedNum) -> FixedNum {
+    let z2 = z * z;
+    let mut poly = FixedNum::from_num(0.020_835_1);
+    for coefficient in [-0.085_133, 0.180_141, -0.330_299_5, 0.999_866] {
+        poly = FixedNum::from_num(coefficient) + z2 * poly;
+    }
+    z * poly
+}
+
+/// `angle` wrapped into `(-π, π]`
+pub fn wrap_angle(angle: FixedNum) -> FixedNum {
+    let tau = FixedNum::TAU;
+    let mut wrapped = angle % tau;
+    if wrapped > FixedNum::PI {
+        wrapped -= tau;
+    } else if wrapped <= -FixedNum::PI {
+        wrapped += tau;
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: FixedNum, expected: f64) -> bool {
+        (actual.to_num::<f64>() - expected).abs() < 1e-4
+    }
+
+    #[test]
+    fn test_atan2_matches_float_in_every_octant() {
+        for step in 0..64 {
+            let angle = step as f64 / 64.0 * std::f64::consts::TAU - std::f64::consts::PI + 0.01;
+            let (y, x) = (angle.sin() * 7.0, angle.cos() * 7.0);
+            let fixed = atan2(FixedNum::from_num(y), FixedNum::from_num(x));
+            assert!(close(fixed, y.atan2(x)), "atan2({}, {}) = {}, expected {}", y, x, fixed, y.atan2(x));
+        }
+        assert_eq!(atan2(FixedNum::ZERO, FixedNum::ZERO), FixedNum::ZERO);
+        assert_eq!(atan2(FixedNum::ZERO, FixedNum::ONE), FixedNum::ZERO);
+        assert_eq!(atan2(FixedNum::ZERO, -FixedNum::ONE), FixedNum::PI);
+        assert_eq!(atan2(FixedNum::ONE, FixedNum::ZERO), FixedNum::FRAC_PI_2);
+    }
+
+    #[test]
+    fn test_wrap_angle_range() {
+        let pi = FixedNum::PI;
+        assert_eq!(wrap_angle(pi), pi);
+        assert_eq!(wrap_angle(-pi), pi);
+        assert_eq!(wrap_angle(FixedNum::ONE + FixedNum::TAU * FixedNum::from_num(3)), FixedNum::ONE);
+        assert!(close(wrap_angle(FixedNum::from_num(-4.0)), -4.0 + std::f64::consts::TAU));
+    }
+}
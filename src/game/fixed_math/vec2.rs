@@ -3,6 +3,13 @@ use serde::{Deserialize, Serialize};
 use super::FixedNum;
 
 /// A 2D vector using fixed-point arithmetic for deterministic calculations.
+///
+/// # Serialization
+///
+/// Serializes as `{ x: { bits: i64 }, y: { bits: i64 } }` (the `fixed` crate encodes the raw
+/// bits, never a float), so snapshots, replays and maps round-trip bit-exactly on every
+/// platform. The bits are only meaningful for the `FixedNum` precision feature they were
+/// written with.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FixedVec2 {
     pub x: FixedNum,
@@ -58,6 +65,36 @@ impl FixedVec2 {
     pub fn cross(self, other: Self) -> FixedNum {
         self.x * other.y - self.y * other.x
     }
+
+    /// Component-wise minimum
+    pub fn min(self, other: Self) -> Self {
+        Self::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// Component-wise maximum
+    pub fn max(self, other: Self) -> Self {
+        Self::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    /// Component-wise absolute value
+    pub fn abs(self) -> Self {
+        Self::new(self.x.abs(), self.y.abs())
+    }
+
+    /// Component-wise floor (toward negative infinity)
+    pub fn floor(self) -> Self {
+        Self::new(self.x.floor(), self.y.floor())
+    }
+
+    /// Component-wise ceiling (toward positive infinity)
+    pub fn ceil(self) -> Self {
+        Self::new(self.x.ceil(), self.y.ceil())
+    }
+
+    /// Component-wise sign: -1, 0 or 1 (zero stays zero)
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
 }
 
 impl std::ops::Add for FixedVec2 {
@@ -226,4 +263,86 @@ mod tests {
         // 2*5 - 3*4 = 10 - 12 = -2
         assert_eq!(cross, FixedNum::from_num(-2.0));
     }
+
+    #[test]
+    fn test_fixed_vec2_min_max() {
+        let a = FixedVec2::from_f32(-2.0, 3.0);
+        let b = FixedVec2::from_f32(1.5, 0.0);
+        assert_eq!(a.min(b), FixedVec2::from_f32(-2.0, 0.0));
+        assert_eq!(a.max(b), FixedVec2::from_f32(1.5, 3.0));
+        assert_eq!(a.min(a), a);
+        assert_eq!(FixedVec2::ZERO.max(-b), FixedVec2::ZERO);
+    }
+
+    #[test]
+    fn test_fixed_vec2_abs() {
+        assert_eq!(FixedVec2::from_f32(-2.5, 3.0).abs(), FixedVec2::from_f32(2.5, 3.0));
+        assert_eq!(FixedVec2::from_f32(0.0, -0.25).abs(), FixedVec2::from_f32(0.0, 0.25));
+        assert_eq!(FixedVec2::new(-FixedNum::DELTA, FixedNum::DELTA).abs(), FixedVec2::new(FixedNum::DELTA, FixedNum::DELTA));
+    }
+
+    #[test]
+    fn test_fixed_vec2_floor_ceil() {
+        let v = FixedVec2::from_f32(2.5, -2.5);
+        assert_eq!(v.floor(), FixedVec2::from_f32(2.0, -3.0));
+        assert_eq!(v.ceil(), FixedVec2::from_f32(3.0, -2.0));
+
+        // Whole numbers and zero are unchanged
+        let whole = FixedVec2::from_f32(-4.0, 0.0);
+        assert_eq!(whole.floor(), whole);
+        assert_eq!(whole.ceil(), whole);
+
+        // The smallest fractions still round away from zero on their side
+        let tiny = FixedVec2::new(FixedNum::DELTA, -FixedNum::DELTA);
+        assert_eq!(tiny.floor(), FixedVec2::from_f32(0.0, -1.0));
+        assert_eq!(tiny.ceil(), FixedVec2::from_f32(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_fixed_vec2_signum() {
+        assert_eq!(FixedVec2::from_f32(-7.5, 0.25).signum(), FixedVec2::from_f32(-1.0, 1.0));
+        assert_eq!(FixedVec2::ZERO.signum(), FixedVec2::ZERO);
+        assert_eq!(FixedVec2::new(FixedNum::ZERO, -FixedNum::DELTA).signum(), FixedVec2::from_f32(0.0, -1.0));
+    }
+
+    fn awkward_vectors() -> Vec<FixedVec2> {
+        vec![
+            FixedVec2::ZERO,
+            FixedVec2::new(FixedNum::DELTA, -FixedNum::DELTA),
+            FixedVec2::new(FixedNum::MAX, FixedNum::MIN),
+            // 1/3 has no exact decimal or float form
+            FixedVec2::new(FixedNum::ONE / FixedNum::from_num(3), FixedNum::from_num(-1234.5) / FixedNum::from_num(7)),
+        ]
+    }
+
+    #[test]
+    fn test_fixed_vec2_serde_json_round_trip_is_bit_exact() {
+        for v in awkward_vectors() {
+            let json = serde_json::to_string(&v).unwrap();
+            assert!(json.contains("\"bits\""), "Should encode raw bits, got {}", json);
+            let back: FixedVec2 = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.x.to_bits(), v.x.to_bits());
+            assert_eq!(back.y.to_bits(), v.y.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_fixed_vec2_serde_binary_round_trip_is_bit_exact() {
+        for v in awkward_vectors() {
+            let bytes = bincode::serialize(&v).unwrap();
+            assert_eq!(bytes.len(), 16, "Two raw i64s, no float or length prefix");
+            let back: FixedVec2 = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(back.x.to_bits(), v.x.to_bits());
+            assert_eq!(back.y.to_bits(), v.y.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_fixed_vec2_ron_round_trip_is_bit_exact() {
+        let v = FixedVec2::new(FixedNum::ONE / FixedNum::from_num(3), -FixedNum::DELTA);
+        let text = ron::to_string(&v).unwrap();
+        let back: FixedVec2 = ron::from_str(&text).unwrap();
+        assert_eq!(back, v);
+        assert_eq!(back.x.to_bits(), v.x.to_bits());
+    }
 }
@@ -0,0 +1,381 @@
+//! Headless scenario runner for CI regression checks.
+//!
+//! Runs the deterministic simulation without a window, renderer or asset server: a
+//! [`Scenario`] (map size, units, obstacles and timed commands, written in RON) is loaded,
+//! simulated for a fixed number of ticks, and reduced to a single [`sim_checksum`]. Any
+//! change to simulation behavior shows up as a checksum change, so CI can compare it
+//! against a golden value.
+//!
+//! # Scenario format
+//!
+//! ```ron
+//! (
+//!     map_width: 64.0,
+//!     map_height: 64.0,
+//!     ticks: 200,
+//!     units: [(x: -10.0, y: 0.0), (x: 10.0, y: 0.0, team: 1)],
+//!     obstacles: [(x: 0.0, y: 8.0, radius: 2.0)],
+//!     commands: [
+//!         (tick: 1, action: Move(units: [0, 1], x: 0.0, y: -5.0)),
+//!         (tick: 60, action: Spawn(x: 0.0, y: 20.0)),
+//!         (tick: 120, action: Stop(units: [1])),
+//!     ],
+//!     expected_checksum: None,
+//! )
+//! ```
+//!
+//! Units are referenced by index: the scenario's `units` in order, followed by units from
+//! `Spawn` commands in the order they were spawned.
+//!
+//! # Schedule
+//!
+//! [`HeadlessSimPlugin`] (used by [`HeadlessSim`]) registers the same tick as `SimulationPlugin`
+//! ([`simulation::schedule::tick_systems`]), on a single-threaded executor and without the
+//! `GameState` gating. Checksums depend on the `FixedNum` precision feature, so golden
+//! values are only valid for one precision.
+
+use bevy::prelude::*;
+use bevy::ecs::schedule::ExecutorKind;
+use serde::{Deserialize, Serialize};
+use std::path::Path as FsPath;
+use crate::game::fixed_math::{FixedNum, FixedVec2};
+use crate::game::pathfinding::{ActivePathSet, HierarchicalGraph, IntegrationFieldCache, NavigationLookup, NavigationRouting, PathFailed, PathRequest, PathRequestStats, PendingPathRequests};
+use crate::game::simulation::{self, collision, systems, *};
+use crate::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use crate::game::structures::FlowField;
+use crate::game::unit::{spawn_unit_in_world, Unit};
+
+/// Flow field resolution used for scenarios (matches the game's default map cell size)
+const SCENARIO_CELL_SIZE: f32 = 1.0;
+
+/// A scripted simulation run (see module docs for the RON format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    pub map_width: f32,
+    pub map_height: f32,
+    /// Number of fixed ticks to simulate
+    pub ticks: u64,
+    #[serde(default)]
+    pub units: Vec<ScenarioUnit>,
+    #[serde(default)]
+    pub obstacles: Vec<ScenarioObstacle>,
+    #[serde(default)]
+    pub commands: Vec<ScenarioCommand>,
+    /// Golden checksum; the headless binary exits with an error if the run doesn't match
+    #[serde(default)]
+    pub expected_checksum: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioUnit {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub team: u8,
+    /// Collider radius (defaults to `SimConfig::unit_radius`)
+    #[serde(default)]
+    pub radius: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioObstacle {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+}
+
+/// An action issued at the start of `tick` (ticks count from 1, like `SimTick`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioCommand {
+    pub tick: u64,
+    pub action: ScenarioAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScenarioAction {
+    Move { units: Vec<usize>, x: f32, y: f32 },
+    Stop { units: Vec<usize> },
+    Spawn { x: f32, y: f32 },
+}
+
+impl Scenario {
+    /// Parse a scenario from RON text
+    pub fn from_ron_str(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+
+    /// Load a scenario from a RON file
+    pub fn load(path: &FsPath) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scenario {}: {}", path.display(), e))?;
+        Self::from_ron_str(&text).map_err(|e| format!("Failed to parse scenario {}: {}", path.display(), e))
+    }
+}
+
+/// Outcome of [`run_scenario`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScenarioResult {
+    pub ticks: u64,
+    pub unit_count: usize,
+    pub checksum: u64,
+}
+
+/// Empty flow field and spatial hash for `map_dimensions` (also sizes `sim_config`'s map)
+fn sized_for_map(map_dimensions: &MapDimensions, sim_config: &mut SimConfig) -> (FlowField, SpatialHash) {
+    let mut flow_field = FlowField::default();
+    let mut spatial_hash = SpatialHash::new(
+        map_dimensions.width,
+        map_dimensions.height,
+        &[0.5, 10.0],
+        4.0,
+        sim_config.max_entity_count.min(10_000),
+        1.0,
+    );
+    map_dimensions.apply(sim_config, &mut flow_field, &mut spatial_hash);
+    (flow_field, spatial_hash)
+}
+
+/// Size the simulation for `map_dimensions`, rasterize the obstacles into the flow field and
+/// build the navigation graph on top of it. Call before adding [`HeadlessSimPlugin`].
+///
+/// Only sets up the map: spawn `ObstacleBundle`s as well for obstacle collision to see them.
+pub fn insert_map(app: &mut App, map_dimensions: MapDimensions, obstacles: &[(FixedVec2, FixedNum)]) {
+    let mut sim_config = app.world_mut().remove_resource::<SimConfig>().unwrap_or_default();
+    let (mut flow_field, spatial_hash) = sized_for_map(&map_dimensions, &mut sim_config);
+
+    for &(position, radius) in obstacles {
+        simulation::apply_obstacle_to_flow_field(&mut flow_field, position, radius);
+    }
+    let mut graph = HierarchicalGraph::default();
+    let mut nav_lookup = NavigationLookup::default();
+    let mut nav_routing = NavigationRouting::default();
+    graph.build_graph_with_regions_sync(&flow_field, Some(&mut nav_lookup), Some(&mut nav_routing));
+
+    app.insert_resource(spatial_hash);
+    app.insert_resource(sim_config);
+    app.insert_resource(map_dimensions);
+    app.insert_resource(MapFlowField(flow_field));
+    app.insert_resource(MapStatus { loaded: false, terrain_baked: true });
+    app.insert_resource(graph);
+    app.insert_resource(nav_lookup);
+    app.insert_resource(nav_routing);
+}
+
+/// The deterministic simulation on its own: sim resources, commands and the game's
+/// `FixedUpdate` tick (see module docs), with no rendering, HUD, menus, config assets or
+/// `GameState`.
+///
+/// Needs nothing but `MinimalPlugins` (or no plugins at all when driving `FixedUpdate` by
+/// hand). Resources inserted before the plugin is added are kept, so set up the map with
+/// [`insert_map`] first. Without one the map is an empty 2048x2048 one with no navigation
+/// graph, so move orders wait for a graph that never comes.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use peregrine::game::headless::{insert_map, HeadlessSimPlugin};
+/// use peregrine::game::simulation::MapDimensions;
+///
+/// let mut app = App::new();
+/// app.add_plugins(MinimalPlugins);
+/// insert_map(&mut app, MapDimensions::from_f32(64.0, 64.0), &[]);
+/// app.add_plugins(HeadlessSimPlugin);
+/// app.world_mut().run_schedule(FixedUpdate);
+/// ```
+pub struct HeadlessSimPlugin;
+
+impl Plugin for HeadlessSimPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<MapDimensions>() {
+            let map_dimensions = MapDimensions::default();
+            let mut sim_config = app.world_mut().remove_resource::<SimConfig>().unwrap_or_default();
+            let (flow_field, spatial_hash) = sized_for_map(&map_dimensions, &mut sim_config);
+            app.insert_resource(map_dimensions);
+            app.insert_resource(sim_config);
+            app.insert_resource(MapFlowField(flow_field));
+            app.insert_resource(spatial_hash);
+            app.insert_resource(MapStatus { loaded: false, terrain_baked: true });
+        }
+        let tick_rate = app.world().resource::<SimConfig>().tick_rate;
+        app.insert_resource(Time::<Fixed>::from_hz(tick_rate));
+
+        app.init_resource::<HierarchicalGraph>();
+        app.init_resource::<NavigationLookup>();
+        app.init_resource::<NavigationRouting>();
+        app.insert_resource(SpatialHashScratch::default_capacity());
+        app.init_resource::<ActivePathSet>();
+        app.init_resource::<PendingPathRequests>();
+        app.init_resource::<PathRequestStats>();
+        app.init_resource::<IntegrationFieldCache>();
+        app.init_resource::<SimTick>();
+        app.init_resource::<SimTime>();
+        app.init_resource::<SimPerformance>();
+        app.init_resource::<ActiveUnitSet>();
+        app.init_resource::<SpatialHashOverflow>();
+        app.init_resource::<CollisionNeighborCache>();
+        app.init_resource::<SpatialHashGrowth>();
+        app.init_resource::<systems::PendingVecIdxUpdates>();
+
+        app.add_message::<UnitMoveCommand>();
+        app.add_message::<UnitStopCommand>();
+        app.add_message::<SpawnUnitCommand>();
+        app.add_message::<PathRequest>();
+        app.add_message::<PathFailed>();
+        app.add_message::<collision::CollisionEvent>();
+        app.add_message::<EntityDespawned>();
+        app.add_observer(systems::record_despawned_entity);
+
+        app.add_systems(FixedUpdate, simulation::schedule::tick_systems());
+        // Parallel execution could reorder systems that only conflict through Commands
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+}
+
+/// A headless simulation app set up from a [`Scenario`]
+pub struct HeadlessSim {
+    pub app: App,
+    /// Scenario unit index -> entity (scenario units first, then spawned units)
+    pub units: Vec<Entity>,
+    commands: Vec<ScenarioCommand>,
+}
+
+impl HeadlessSim {
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut app = App::new();
+
+        let map_dimensions = MapDimensions::new(
+            FixedNum::from_num(scenario.map_width),
+            FixedNum::from_num(scenario.map_height),
+            FixedNum::from_num(SCENARIO_CELL_SIZE),
+        );
+        let obstacles: Vec<_> = scenario.obstacles.iter()
+            .map(|obstacle| (FixedVec2::from_f32(obstacle.x, obstacle.y), FixedNum::from_num(obstacle.radius)))
+            .collect();
+        insert_map(&mut app, map_dimensions, &obstacles);
+        app.add_plugins(HeadlessSimPlugin);
+
+        for obstacle in &scenario.obstacles {
+            let pos = FixedVec2::from_f32(obstacle.x, obstacle.y);
+            app.world_mut().spawn(ObstacleBundle::new(pos, FixedNum::from_num(obstacle.radius)));
+        }
+
+        let default_radius = app.world().resource::<SimConfig>().unit_radius;
+        let units = scenario.units.iter().map(|unit| {
+            let radius = unit.radius.map(FixedNum::from_num).unwrap_or(default_radius);
+            spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(unit.x, unit.y), radius, unit.team)
+        }).collect();
+
+        let mut commands = scenario.commands.clone();
+        commands.sort_by_key(|command| command.tick);
+
+        Self { app, units, commands }
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.app.world().resource::<SimTick>().0
+    }
+
+    /// Issue this tick's scripted commands, then run one fixed update
+    pub fn step(&mut self) {
+        let next_tick = self.tick() + 1;
+        let mut spawned = false;
+
+        for command in self.commands.iter().filter(|command| command.tick == next_tick) {
+            let world = self.app.world_mut();
+            match &command.action {
+                ScenarioAction::Move { units, x, y } => {
+                    for &index in units {
+                        if let Some(&entity) = self.units.get(index) {
+                            world.write_message(UnitMoveCommand { player_id: 0, entity, target: FixedVec2::from_f32(*x, *y), queued: false });
+                        }
+                    }
+                }
+                ScenarioAction::Stop { units } => {
+                    for &index in units {
+                        if let Some(&entity) = self.units.get(index) {
+                            world.write_message(UnitStopCommand { player_id: 0, entity });
+                        }
+                    }
+                }
+                ScenarioAction::Spawn { x, y } => {
+                    world.write_message(SpawnUnitCommand { player_id: 0, position: FixedVec2::from_f32(*x, *y), radius: None });
+                    spawned = true;
+                }
+            }
+        }
+
+        self.app.world_mut().run_schedule(FixedUpdate);
+
+        if spawned {
+            // Spawned units get the next indices, in entity order
+            let mut query = self.app.world_mut().query_filtered::<Entity, With<Unit>>();
+            let mut new_units: Vec<Entity> = query.iter(self.app.world())
+                .filter(|entity| !self.units.contains(entity))
+                .collect();
+            new_units.sort_unstable();
+            self.units.extend(new_units);
+        }
+    }
+
+    /// Run `ticks` fixed updates
+    pub fn run(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.step();
+        }
+    }
+
+    pub fn checksum(&self) -> u64 {
+        sim_checksum(self.app.world(), &self.units)
+    }
+}
+
+/// Hash the tick and the raw fixed-point position and velocity of `units`, in order,
+/// plus the remaining ticks of any unit that has a [`Cooldown`] and the heading of any
+/// that has a [`Facing`].
+///
+/// FNV-1a over the bit patterns, so the value is stable across platforms and runs.
+/// Despawned units contribute a marker instead of being skipped, so losing a unit changes
+/// the checksum even if the others are unaffected.
+pub fn sim_checksum(world: &World, units: &[Entity]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut write = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    write(world.get_resource::<SimTick>().map_or(0, |tick| tick.0));
+    for &entity in units {
+        match (world.get::<SimPosition>(entity), world.get::<SimVelocity>(entity)) {
+            (Some(pos), Some(vel)) => {
+                for value in [pos.0.x, pos.0.y, vel.0.x, vel.0.y] {
+                    write(value.to_bits() as u64);
+                }
+            }
+            _ => write(u64::MAX),
+        }
+        if let Some(cooldown) = world.get::<Cooldown>(entity) {
+            write(cooldown.remaining_ticks as u64);
+        }
+        if let Some(facing) = world.get::<Facing>(entity) {
+            write(facing.0.to_bits() as u64);
+        }
+    }
+    hash
+}
+
+/// Run a scenario to completion and return its checksum
+pub fn run_scenario(scenario: &Scenario) -> ScenarioResult {
+    let mut sim = HeadlessSim::new(scenario);
+    sim.run(scenario.ticks);
+    ScenarioResult {
+        ticks: sim.tick(),
+        unit_count: sim.units.len(),
+        checksum: sim.checksum(),
+    }
+}
@@ -0,0 +1,230 @@
+//! Command feedback pings.
+//!
+//! Issuing an order drops a short-lived expanding ring at its destination so the player
+//! can see where units were sent. Like minimap pings, lifetime is counted in simulation
+//! ticks; only the ring animation is cosmetic.
+
+use bevy::prelude::*;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::simulation::{MapFlowField, SimTime, UnitMoveCommand};
+use crate::game::pathfinding::{resolve_path_goal, HierarchicalGraph, NavigationLookup};
+
+/// Seconds a ping stays on screen
+const PING_LIFETIME_SECS: f32 = 0.6;
+/// Ring radius range in world units (grows from min to max over the lifetime)
+const PING_MIN_RADIUS: f32 = 0.3;
+const PING_MAX_RADIUS: f32 = 1.5;
+/// Height above the ground the ring is drawn at
+const PING_HEIGHT: f32 = 0.1;
+
+/// Which kind of order a ping confirms (picks its color)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandPingKind {
+    Move,
+}
+
+impl CommandPingKind {
+    pub fn color(self) -> Color {
+        match self {
+            CommandPingKind::Move => Color::srgb(0.2, 1.0, 0.3),
+        }
+    }
+}
+
+/// Live command ping at an order's destination
+#[derive(Component, Debug, Clone)]
+pub struct CommandPing {
+    pub world_pos: FixedVec2,
+    pub kind: CommandPingKind,
+    pub spawned_tick: u64,
+    pub expires_tick: u64,
+}
+
+/// Spawn one ping per distinct move destination.
+///
+/// Group orders send a `UnitMoveCommand` per unit with the same target; those share a
+/// ping. With a loaded map the ping sits where path requests send the units (see
+/// [`resolve_path_goal`]), and orders whose goal can't be resolved (and so won't path)
+/// get no ping.
+pub fn spawn_command_pings(
+    mut commands: Commands,
+    mut move_commands: MessageReader<UnitMoveCommand>,
+    map_flow_field: Option<Res<MapFlowField>>,
+    graph: Option<Res<HierarchicalGraph>>,
+    nav_lookup: Option<Res<NavigationLookup>>,
+    sim_time: Res<SimTime>,
+) {
+    let now = sim_time.current_tick();
+    let lifetime = sim_time.ticks_for(FixedNum::from_num(PING_LIFETIME_SECS)).max(1);
+    let flow_field = map_flow_field.as_ref().map(|field| &field.0).filter(|field| field.width > 0);
+
+    let mut targets: Vec<FixedVec2> = Vec::new();
+    for command in move_commands.read() {
+        if !targets.contains(&command.target) {
+            targets.push(command.target);
+        }
+    }
+
+    for target in targets {
+        let world_pos = match (flow_field, graph.as_deref(), nav_lookup.as_deref()) {
+            (Some(field), Some(graph), Some(nav_lookup)) => {
+                let resolved = field.world_to_grid(target)
+                    .and_then(|cell| resolve_path_goal(nav_lookup, graph, field, cell));
+                match resolved {
+                    Some(resolved) => resolved.goal(target),
+                    None => continue,
+                }
+            }
+            _ => target,
+        };
+        commands.spawn(CommandPing {
+            world_pos,
+            kind: CommandPingKind::Move,
+            spawned_tick: now,
+            expires_tick: now + lifetime,
+        });
+    }
+}
+
+/// Despawn pings whose lifetime has elapsed
+pub fn expire_command_pings(
+    mut commands: Commands,
+    q_pings: Query<(Entity, &CommandPing)>,
+    sim_time: Res<SimTime>,
+) {
+    let now = sim_time.current_tick();
+    for (entity, ping) in q_pings.iter() {
+        if now >= ping.expires_tick {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Draw each live ping as a ring that expands and fades over its lifetime
+pub fn draw_command_pings(
+    mut gizmos: Gizmos,
+    q_pings: Query<&CommandPing>,
+    sim_time: Res<SimTime>,
+) {
+    let now = sim_time.current_tick();
+    for ping in q_pings.iter() {
+        let lifetime = ping.expires_tick.saturating_sub(ping.spawned_tick).max(1) as f32;
+        let progress = (now.saturating_sub(ping.spawned_tick) as f32 / lifetime).min(1.0);
+        let radius = PING_MIN_RADIUS + (PING_MAX_RADIUS - PING_MIN_RADIUS) * progress;
+        let Vec2 { x, y } = ping.world_pos.to_vec2();
+        gizmos.circle(
+            Isometry3d::new(Vec3::new(x, PING_HEIGHT, y), Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+            radius,
+            ping.kind.color().with_alpha(1.0 - progress),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::structures::{FlowField, OBSTACLE_COST};
+
+    fn setup_ping_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(SimTime::new(0, 30.0));
+        app.add_message::<UnitMoveCommand>();
+        app.add_systems(Update, (spawn_command_pings, expire_command_pings).chain());
+        app
+    }
+
+    fn pings(app: &mut App) -> Vec<CommandPing> {
+        app.world_mut().query::<&CommandPing>().iter(app.world()).cloned().collect()
+    }
+
+    fn order_move(app: &mut App, entity: Entity, target: FixedVec2) {
+        app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity, target, queued: false });
+    }
+
+    #[test]
+    fn test_move_command_pings_destination_until_lifetime_elapses() {
+        let mut app = setup_ping_app();
+        let target = FixedVec2::from_f32(12.0, -4.0);
+        let unit = app.world_mut().spawn_empty().id();
+        order_move(&mut app, unit, target);
+
+        app.update();
+        let live = pings(&mut app);
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].world_pos, target);
+        assert_eq!(live[0].kind, CommandPingKind::Move);
+
+        // 0.6 s at 30 TPS
+        assert_eq!(live[0].expires_tick, 18);
+        app.insert_resource(SimTime::new(17, 30.0));
+        app.update();
+        assert_eq!(pings(&mut app).len(), 1, "Ping should last its whole lifetime");
+
+        app.insert_resource(SimTime::new(18, 30.0));
+        app.update();
+        assert!(pings(&mut app).is_empty(), "Ping should expire after its lifetime");
+    }
+
+    #[test]
+    fn test_group_move_shares_one_ping() {
+        let mut app = setup_ping_app();
+        let target = FixedVec2::from_f32(3.0, 3.0);
+        for _ in 0..5 {
+            let unit = app.world_mut().spawn_empty().id();
+            order_move(&mut app, unit, target);
+        }
+
+        app.update();
+        assert_eq!(pings(&mut app).len(), 1);
+    }
+
+    #[test]
+    fn test_ping_sits_on_the_goal_units_path_to() {
+        use crate::game::pathfinding::{
+            process_path_requests, ActivePathSet, NavigationRouting, Path, PathFailed, PathRequest,
+            PathRequestStats, PathState, PendingPathRequests,
+        };
+        use crate::game::simulation::SimConfig;
+        use crate::game::unit::UnitBundle;
+
+        let mut app = setup_ping_app();
+        // 40x40 map of 1.0 cells centered on the origin, with a blocked 3x3 at the origin
+        let mut flow_field = FlowField::new(40, 40, FixedNum::ONE, FixedVec2::from_f32(-20.0, -20.0));
+        let (cx, cy) = flow_field.world_to_grid(FixedVec2::ZERO).unwrap();
+        for y in cy - 1..=cy + 1 {
+            for x in cx - 1..=cx + 1 {
+                let idx = flow_field.get_index(x, y);
+                flow_field.cost_field[idx] = OBSTACLE_COST;
+            }
+        }
+        let mut graph = HierarchicalGraph::default();
+        let mut nav_lookup = NavigationLookup::default();
+        let mut nav_routing = NavigationRouting::default();
+        graph.build_graph_with_regions_sync(&flow_field, Some(&mut nav_lookup), Some(&mut nav_routing));
+        app.insert_resource(MapFlowField(flow_field));
+        app.insert_resource(graph);
+        app.insert_resource(nav_lookup);
+        app.init_resource::<SimConfig>();
+        app.init_resource::<ActivePathSet>();
+        app.init_resource::<PendingPathRequests>();
+        app.init_resource::<PathRequestStats>();
+        app.add_message::<PathRequest>();
+        app.add_message::<PathFailed>();
+        app.add_systems(Update, process_path_requests);
+
+        let unit = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(10.0, 10.0), FixedNum::from_num(0.5), 0)).id();
+        order_move(&mut app, unit, FixedVec2::ZERO);
+        app.world_mut().write_message(PathRequest::player(unit, FixedVec2::ZERO));
+        app.update();
+
+        let Some(Path::Active(PathState::Hierarchical { goal, .. })) = app.world().get::<Path>(unit) else {
+            panic!("Unit should have a hierarchical path");
+        };
+        let goal = *goal;
+        assert_ne!(goal, FixedVec2::ZERO, "Goal on the blocked cell should move to open ground");
+        let live = pings(&mut app);
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].world_pos, goal);
+    }
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use crate::game::fixed_math::FixedVec2;
 
 /// Root marker component for HUD elements
 #[derive(Component)]
@@ -12,13 +13,18 @@ pub struct Minimap;
 #[derive(Component)]
 pub struct MinimapCameraFrame;
 
-/// Link between a minimap dot and its entity
+/// Image node the minimap's units are painted into
 #[derive(Component)]
-pub struct MinimapDot(pub Entity);
-
-/// Marker for units that have a minimap dot
-#[derive(Component)]
-pub struct UnitMinimapDot;
+pub struct MinimapUnitLayer;
+
+/// Live minimap ping spawned from a `MinimapMarker` event
+#[derive(Component, Debug, Clone)]
+pub struct MinimapPing {
+    pub world_pos: FixedVec2,
+    pub color: Color,
+    pub spawned_tick: u64,
+    pub expires_tick: u64,
+}
 
 /// Selection text display
 #[derive(Component)]
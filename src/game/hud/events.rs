@@ -0,0 +1,17 @@
+//! Events consumed by the HUD.
+//!
+//! Gameplay code (and, later, the network layer) writes these; the HUD only renders them.
+
+use bevy::prelude::*;
+use crate::game::fixed_math::FixedVec2;
+
+/// Request a temporary pulsing marker on the minimap (e.g. "unit under attack").
+///
+/// The marker lives for `ttl_ticks` simulation ticks from the tick it was received on.
+/// Expiry is tied to `SimTick` rather than wall time so every client drops it on the same tick.
+#[derive(Event, Message, Debug, Clone)]
+pub struct MinimapMarker {
+    pub world_pos: FixedVec2,
+    pub color: Color,
+    pub ttl_ticks: u32,
+}
@@ -1,82 +1,287 @@
 use bevy::prelude::*;
+use bevy::asset::RenderAssetUsages;
+use bevy::color::ColorToPacked;
+use bevy::image::ImageSampler;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use bevy::window::PrimaryWindow;
-use crate::game::unit::Selected;
+use crate::game::unit::{Selected, Team, Unit};
 use crate::game::simulation::SimPosition;
-use crate::game::simulation::SimConfig;
+use crate::game::simulation::{SimConfig, SimTick};
 use crate::game::camera::RtsCamera;
+use crate::game::config::{GameConfig, GameConfigHandle, MinimapShape};
 use super::components::*;
+use super::resources::MinimapSettings;
+use super::events::MinimapMarker;
 
-/// Update minimap dots to reflect unit positions and camera frame
-pub fn minimap_system(
-    mut commands: Commands,
-    q_minimap: Query<(Entity, &ComputedNode), (With<Minimap>, Without<MinimapDot>)>,
-    q_units: Query<(Entity, &SimPosition, Option<&Selected>), Without<UnitMinimapDot>>,
-    mut q_dots: Query<(Entity, &MinimapDot, &mut Node, &mut BackgroundColor), Without<Minimap>>,
-    q_units_lookup: Query<(&SimPosition, Option<&Selected>)>,
-    q_camera: Query<&Transform, With<RtsCamera>>,
-    mut q_camera_frame: Query<&mut Node, (With<MinimapCameraFrame>, Without<MinimapDot>, Without<Minimap>)>,
+/// Ping marker size range in logical pixels (pulses between the two)
+const PING_MIN_SIZE: f32 = 6.0;
+const PING_MAX_SIZE: f32 = 14.0;
+/// Pulses per second
+const PING_PULSE_RATE: f32 = 2.0;
+
+/// Side of the square unit layer texture, stretched over the minimap
+pub const MINIMAP_TEXTURE_SIZE: u32 = 256;
+/// Up to this many units each get their own dot; past it the layer shows unit density per pixel
+pub const MAX_MINIMAP_DOTS: u32 = 4096;
+/// Half the side of a unit dot in texture pixels (3x3 dots, 5x5 with the selection outline)
+const DOT_HALF_SIZE: u32 = 1;
+/// Units in one pixel for the density view to reach full brightness
+const DENSITY_FULL_COUNT: u32 = 8;
+/// Dimmest a populated density pixel is drawn (fraction of the team color)
+const DENSITY_MIN_BRIGHTNESS: f32 = 0.35;
+
+/// Maps between world (simulation x/y) coordinates and pixel offsets inside the minimap.
+///
+/// Built from the minimap's current size every frame, so resizing the minimap
+/// (see `MinimapSettings`) keeps both drawing and clicks correct. With
+/// [`MinimapShape::Circle`] only the inscribed circle shows the map.
+#[derive(Debug, Clone, Copy)]
+struct MinimapMapping {
+    map_min: Vec2,
+    map_size: Vec2,
+    minimap_size: Vec2,
+    shape: MinimapShape,
+}
+
+impl MinimapMapping {
+    fn new(sim_config: &SimConfig, minimap_size: Vec2) -> Self {
+        Self {
+            map_min: Vec2::new(
+                sim_config.map_size.top_left.x.to_num::<f32>(),
+                sim_config.map_size.top_left.y.to_num::<f32>(),
+            ),
+            map_size: Vec2::new(
+                sim_config.map_size.get_width().to_num::<f32>(),
+                sim_config.map_size.get_height().to_num::<f32>(),
+            ),
+            minimap_size,
+            shape: MinimapShape::Rectangle,
+        }
+    }
+
+    fn with_shape(self, shape: MinimapShape) -> Self {
+        Self { shape, ..self }
+    }
+
+    /// Whether a pixel offset from the minimap's top-left corner is inside its shape
+    fn contains(&self, local: Vec2) -> bool {
+        let pct = local / self.minimap_size;
+        match self.shape {
+            MinimapShape::Rectangle => pct.cmpge(Vec2::ZERO).all() && pct.cmple(Vec2::ONE).all(),
+            MinimapShape::Circle => (pct * 2.0 - Vec2::ONE).length_squared() <= 1.0,
+        }
+    }
+
+    /// Whether a world position is drawn on the minimap (off-map points never are)
+    fn shows(&self, world: Vec2) -> bool {
+        self.contains((world - self.map_min) / self.map_size * self.minimap_size)
+    }
+
+    /// World position under a click, or `None` outside the minimap's shape
+    fn click_to_world(&self, local: Vec2) -> Option<Vec2> {
+        self.contains(local).then(|| self.minimap_to_world(local))
+    }
+
+    /// Pixel offset from the minimap's top-left corner, clamped to the minimap
+    fn world_to_minimap(&self, world: Vec2) -> Vec2 {
+        let pct = (world - self.map_min) / self.map_size;
+        (pct * self.minimap_size).clamp(Vec2::ZERO, self.minimap_size)
+    }
+
+    /// World position under a pixel offset from the minimap's top-left corner
+    fn minimap_to_world(&self, local: Vec2) -> Vec2 {
+        local / self.minimap_size * self.map_size + self.map_min
+    }
+
+    /// Whole pixel (column, row) under a world position, for a mapping sized to a texture.
+    /// The far map edge falls into the last pixel rather than one past it.
+    fn world_to_pixel(&self, world: Vec2) -> UVec2 {
+        let local = self.world_to_minimap(world).floor().as_uvec2();
+        local.min(self.minimap_size.as_uvec2().saturating_sub(UVec2::ONE))
+    }
+}
+
+/// Units that fall into one minimap pixel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MinimapPixel {
+    count: u32,
+    selected: u32,
+    /// Majority team by a running vote: exact whenever one team holds more than half the pixel
+    team: Team,
+    votes: u32,
+}
+
+impl MinimapPixel {
+    fn add(&mut self, team: Team, selected: bool) {
+        self.count += 1;
+        self.selected += selected as u32;
+        if self.votes == 0 {
+            self.team = team;
+        }
+        if self.team == team {
+            self.votes += 1;
+        } else {
+            self.votes -= 1;
+        }
+    }
+}
+
+/// Count units per pixel of a texture-sized `mapping` into `pixels` (row-major), returning the total
+fn aggregate_unit_density(
+    mapping: &MinimapMapping,
+    units: impl Iterator<Item = (Vec2, Team, bool)>,
+    pixels: &mut Vec<MinimapPixel>,
+) -> u32 {
+    let size = mapping.minimap_size.as_uvec2();
+    pixels.clear();
+    pixels.resize((size.x * size.y) as usize, MinimapPixel::default());
+
+    let mut total = 0;
+    for (world, team, selected) in units {
+        let pixel = mapping.world_to_pixel(world);
+        pixels[(pixel.y * size.x + pixel.x) as usize].add(team, selected);
+        total += 1;
+    }
+    total
+}
+
+/// Blank, transparent texture for the minimap unit layer
+pub fn new_unit_layer_image() -> Image {
+    let mut image = Image::new_fill(
+        Extent3d { width: MINIMAP_TEXTURE_SIZE, height: MINIMAP_TEXTURE_SIZE, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::all(),
+    );
+    // Keep single-pixel dots crisp when the texture is stretched
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+/// Fill a square of `half_size` around `center` in an RGBA texture of side `size`
+fn paint_square(data: &mut [u8], size: u32, center: UVec2, half_size: u32, color: [u8; 4]) {
+    let min = center.saturating_sub(UVec2::splat(half_size));
+    let max = (center + UVec2::splat(half_size)).min(UVec2::splat(size - 1));
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            let i = ((y * size + x) * 4) as usize;
+            data[i..i + 4].copy_from_slice(&color);
+        }
+    }
+}
+
+/// Density view color: the majority team's color brightening with the unit count,
+/// pushed toward white when any of the pixel's units are selected
+fn density_color(pixel: &MinimapPixel) -> [u8; 4] {
+    let fill = (pixel.count as f32 / DENSITY_FULL_COUNT as f32).min(1.0);
+    let brightness = DENSITY_MIN_BRIGHTNESS + (1.0 - DENSITY_MIN_BRIGHTNESS) * fill;
+    let team = pixel.team.color().to_srgba();
+    let mut color = Srgba::new(team.red * brightness, team.green * brightness, team.blue * brightness, 1.0);
+    if pixel.selected > 0 {
+        color = color.mix(&Srgba::WHITE, 0.6);
+    }
+    color.to_u8_array()
+}
+
+/// Paint unit positions into the minimap's unit layer texture.
+///
+/// Each unit is a dot in its team's color, with selected units drawn on top inside a
+/// white outline. Past `MAX_MINIMAP_DOTS` units, dots would only make a blob at a much
+/// higher cost, so each pixel is instead colored by its majority team with brightness
+/// following how many units it holds. On a circular minimap, units outside the circle
+/// are left out.
+pub fn draw_minimap_units(
+    q_layer: Query<&ImageNode, With<MinimapUnitLayer>>,
+    q_units: Query<(&SimPosition, Option<&Team>, Has<Selected>), With<Unit>>,
+    mut images: ResMut<Assets<Image>>,
     sim_config: Res<SimConfig>,
+    settings: Res<MinimapSettings>,
+    mut pixels: Local<Vec<MinimapPixel>>,
 ) {
-    let Ok((minimap_entity, minimap_node)) = q_minimap.single() else { return };
-    
-    let map_width = sim_config.map_size.get_width().to_num::<f32>();
-    let map_height = sim_config.map_size.get_height().to_num::<f32>();
-    let map_min_x = sim_config.map_size.top_left.x.to_num::<f32>();
-    let map_min_y = sim_config.map_size.top_left.y.to_num::<f32>();
-    let minimap_w = minimap_node.size().x;
-    let minimap_h = minimap_node.size().y;
-
-    // Spawn new dots
-    for (unit_entity, pos, selected) in q_units.iter() {
-        let x_pct = (pos.0.x.to_num::<f32>() - map_min_x) / map_width;
-        let y_pct = (pos.0.y.to_num::<f32>() - map_min_y) / map_height;
-
-        let x = (x_pct * minimap_w).clamp(0.0, minimap_w);
-        let y = (y_pct * minimap_h).clamp(0.0, minimap_h);
-
-        let dot = commands.spawn((
-            Node {
-                position_type: PositionType::Absolute,
-                left: Val::Px(x - 2.0),
-                top: Val::Px(y - 2.0),
-                width: Val::Px(4.0),
-                height: Val::Px(4.0),
-                ..default()
-            },
-            BackgroundColor(if selected.is_some() { Color::srgb(0.0, 1.0, 0.0) } else { Color::srgb(1.0, 0.0, 0.0) }),
-            MinimapDot(unit_entity),
-        )).id();
+    let Ok(layer) = q_layer.single() else { return };
+    let Some(image) = images.get_mut(&layer.image) else { return };
+    let size = image.width();
+    let mapping = MinimapMapping::new(&sim_config, Vec2::new(size as f32, image.height() as f32)).with_shape(settings.shape);
+    let Some(data) = image.data.as_mut() else { return };
+    data.fill(0);
 
-        commands.entity(minimap_entity).add_child(dot);
-        commands.entity(unit_entity).insert(UnitMinimapDot);
+    let visible_units = || q_units.iter().filter(|(pos, ..)| mapping.shows(pos.0.to_vec2()));
+    let units = visible_units().map(|(pos, team, selected)| (pos.0.to_vec2(), team.copied().unwrap_or_default(), selected));
+    let total = aggregate_unit_density(&mapping, units, &mut pixels);
+
+    if total > MAX_MINIMAP_DOTS {
+        for (i, pixel) in pixels.iter().enumerate() {
+            if pixel.count > 0 {
+                data[i * 4..i * 4 + 4].copy_from_slice(&density_color(pixel));
+            }
+        }
+        return;
     }
 
-    // Update existing dots
-    for (dot_entity, dot_link, mut node, mut bg_color) in q_dots.iter_mut() {
-        if let Ok((pos, selected)) = q_units_lookup.get(dot_link.0) {
-             let x_pct = (pos.0.x.to_num::<f32>() - map_min_x) / map_width;
-             let y_pct = (pos.0.y.to_num::<f32>() - map_min_y) / map_height;
-             
-             let x = (x_pct * minimap_w).clamp(0.0, minimap_w);
-             let y = (y_pct * minimap_h).clamp(0.0, minimap_h);
+    for (pos, team, selected) in visible_units() {
+        if !selected {
+            let color = team.copied().unwrap_or_default().color().to_srgba().to_u8_array();
+            paint_square(data, size, mapping.world_to_pixel(pos.0.to_vec2()), DOT_HALF_SIZE, color);
+        }
+    }
+    for (pos, team, selected) in visible_units() {
+        if selected {
+            let pixel = mapping.world_to_pixel(pos.0.to_vec2());
+            let color = team.copied().unwrap_or_default().color().to_srgba().mix(&Srgba::WHITE, 0.3).to_u8_array();
+            paint_square(data, size, pixel, DOT_HALF_SIZE + 1, Srgba::WHITE.to_u8_array());
+            paint_square(data, size, pixel, DOT_HALF_SIZE, color);
+        }
+    }
+}
 
-             node.left = Val::Px(x - 2.0);
-             node.top = Val::Px(y - 2.0);
-             *bg_color = BackgroundColor(if selected.is_some() { Color::srgb(0.0, 1.0, 0.0) } else { Color::srgb(1.0, 0.0, 0.0) });
-        } else {
-            // Unit dead
-            commands.entity(dot_entity).despawn();
+/// Copy minimap size/corner from `GameConfig` when it is loaded or hot-reloaded
+pub fn sync_minimap_settings_from_config(
+    config_handle: Res<GameConfigHandle>,
+    game_configs: Res<Assets<GameConfig>>,
+    mut events: MessageReader<AssetEvent<GameConfig>>,
+    mut settings: ResMut<MinimapSettings>,
+) {
+    for event in events.read() {
+        if event.is_modified(config_handle.0.id()) || event.is_loaded_with_dependencies(config_handle.0.id()) {
+            if let Some(config) = game_configs.get(&config_handle.0) {
+                let margin = settings.margin;
+                *settings = MinimapSettings::from_config(config, margin);
+            }
         }
     }
+}
+
+/// Push `MinimapSettings` changes (size, corner, shape) to the minimap node
+pub fn apply_minimap_settings(
+    settings: Res<MinimapSettings>,
+    mut q_minimap: Query<(&mut Node, &mut BorderRadius), With<Minimap>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (mut node, mut border_radius) in q_minimap.iter_mut() {
+        settings.apply_to_node(&mut node);
+        *border_radius = settings.border_radius();
+    }
+}
+
+/// Move the minimap's camera frame to follow the camera
+pub fn minimap_system(
+    q_minimap: Query<&ComputedNode, With<Minimap>>,
+    q_camera: Query<&Transform, With<RtsCamera>>,
+    mut q_camera_frame: Query<&mut Node, (With<MinimapCameraFrame>, Without<Minimap>)>,
+    sim_config: Res<SimConfig>,
+) {
+    let Ok(minimap_node) = q_minimap.single() else { return };
+
+    // The frame is positioned with logical-pixel Vals; ComputedNode sizes are physical
+    let mapping = MinimapMapping::new(&sim_config, minimap_node.size() * minimap_node.inverse_scale_factor());
 
     // Update Camera Frame
     if let Ok(camera_transform) = q_camera.single() {
         if let Ok(mut frame_node) = q_camera_frame.single_mut() {
-            let x_pct = (camera_transform.translation.x - map_min_x) / map_width;
-            let y_pct = (camera_transform.translation.z - map_min_y) / map_height;
-
-            let x = (x_pct * minimap_w).clamp(0.0, minimap_w);
-            let y = (y_pct * minimap_h).clamp(0.0, minimap_h);
+            let Vec2 { x, y } = mapping.world_to_minimap(camera_transform.translation.xz());
 
             // Assuming frame size 40x30
             frame_node.left = Val::Px(x - 20.0);
@@ -85,20 +290,95 @@ pub fn minimap_system(
     }
 }
 
-/// Handle minimap click to move camera
+/// Spawn a ping node on the minimap for each `MinimapMarker` event
+pub fn spawn_minimap_markers(
+    mut commands: Commands,
+    mut markers: MessageReader<MinimapMarker>,
+    q_minimap: Query<Entity, With<Minimap>>,
+    sim_tick: Res<SimTick>,
+) {
+    let Ok(minimap_entity) = q_minimap.single() else {
+        markers.clear();
+        return;
+    };
+
+    let now = sim_tick.get();
+    for marker in markers.read() {
+        if marker.ttl_ticks == 0 {
+            continue;
+        }
+        let ping = commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BorderColor::from(marker.color),
+            MinimapPing {
+                world_pos: marker.world_pos,
+                color: marker.color,
+                spawned_tick: now,
+                expires_tick: now + marker.ttl_ticks as u64,
+            },
+        )).id();
+        commands.entity(minimap_entity).add_child(ping);
+    }
+}
+
+/// Position and pulse live pings; despawn them once their TTL has elapsed.
+/// Pings outside a circular minimap are hidden.
+pub fn update_minimap_markers(
+    mut commands: Commands,
+    q_minimap: Query<&ComputedNode, With<Minimap>>,
+    mut q_pings: Query<(Entity, &MinimapPing, &mut Node, &mut BorderColor)>,
+    sim_tick: Res<SimTick>,
+    sim_config: Res<SimConfig>,
+    settings: Res<MinimapSettings>,
+    time: Res<Time>,
+) {
+    let Ok(minimap_node) = q_minimap.single() else { return };
+    let mapping = MinimapMapping::new(&sim_config, minimap_node.size() * minimap_node.inverse_scale_factor())
+        .with_shape(settings.shape);
+    let now = sim_tick.get();
+
+    for (entity, ping, mut node, mut border) in q_pings.iter_mut() {
+        if now >= ping.expires_tick {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let pulse = 0.5 + 0.5 * (time.elapsed_secs() * PING_PULSE_RATE * std::f32::consts::TAU).sin();
+        let size = PING_MIN_SIZE + (PING_MAX_SIZE - PING_MIN_SIZE) * pulse;
+        let Vec2 { x, y } = mapping.world_to_minimap(ping.world_pos.to_vec2());
+        node.left = Val::Px(x - size / 2.0);
+        node.top = Val::Px(y - size / 2.0);
+        node.width = Val::Px(size);
+        node.height = Val::Px(size);
+        node.display = if mapping.shows(ping.world_pos.to_vec2()) { Display::Flex } else { Display::None };
+
+        // Fade out over the marker's lifetime
+        let lifetime = (ping.expires_tick - ping.spawned_tick) as f32;
+        let remaining = (ping.expires_tick - now) as f32 / lifetime;
+        *border = BorderColor::from(ping.color.with_alpha(0.3 + 0.7 * remaining));
+    }
+}
+
+/// Handle minimap click to move camera (clicks outside a circular minimap's circle are ignored)
 pub fn minimap_input_system(
     mouse_button: Res<ButtonInput<MouseButton>>,
     q_window: Query<&Window, With<PrimaryWindow>>,
     q_minimap: Query<(&ComputedNode, &GlobalTransform), With<Minimap>>,
     mut q_camera: Query<&mut Transform, With<RtsCamera>>,
     sim_config: Res<SimConfig>,
+    settings: Res<MinimapSettings>,
 ) {
     if !mouse_button.pressed(MouseButton::Left) {
         return;
     }
 
     let Some(window) = q_window.iter().next() else { return };
-    let Some(cursor_pos) = window.cursor_position() else { return };
+    // UI node transforms and sizes are in physical pixels, so compare against the physical cursor
+    let Some(cursor_pos) = window.physical_cursor_position() else { return };
     let Ok((computed_node, transform)) = q_minimap.single() else { return };
 
     let size = computed_node.size();
@@ -106,18 +386,8 @@ pub fn minimap_input_system(
     let rect = Rect::from_center_size(pos, size);
 
     if rect.contains(cursor_pos) {
-        let relative_x = cursor_pos.x - rect.min.x;
-        let relative_y = cursor_pos.y - rect.min.y;
-        
-        let pct_x = relative_x / rect.width();
-        let pct_y = relative_y / rect.height();
-        
-        let map_width = sim_config.map_size.get_width().to_num::<f32>();
-        let map_height = sim_config.map_size.get_height().to_num::<f32>();
-        let map_min_x = sim_config.map_size.top_left.x.to_num::<f32>();
-        let map_min_y = sim_config.map_size.top_left.y.to_num::<f32>();
-        let map_x = pct_x * map_width + map_min_x;
-        let map_z = pct_y * map_height + map_min_y;
+        let mapping = MinimapMapping::new(&sim_config, size).with_shape(settings.shape);
+        let Some(Vec2 { x: map_x, y: map_z }) = mapping.click_to_world(cursor_pos - rect.min) else { return };
         
         for mut cam_transform in q_camera.iter_mut() {
             // Simple move. Ideally we'd account for camera angle offset.
@@ -130,3 +400,170 @@ pub fn minimap_input_system(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::game::fixed_math::FixedVec2;
+
+    fn mapping(minimap_size: Vec2) -> MinimapMapping {
+        // Default map: 2048x2048 centered on the origin
+        MinimapMapping::new(&SimConfig::default(), minimap_size)
+    }
+
+    #[test]
+    fn test_minimap_mapping_round_trip_after_resize() {
+        let world_points = [Vec2::new(0.0, 0.0), Vec2::new(-30.0, 12.5), Vec2::new(49.0, -49.0)];
+
+        for minimap_size in [Vec2::new(200.0, 200.0), Vec2::new(320.0, 180.0)] {
+            let mapping = mapping(minimap_size);
+            for world in world_points {
+                let local = mapping.world_to_minimap(world);
+                let back = mapping.minimap_to_world(local);
+                assert!((back - world).length() < 1e-3, "{:?} -> {:?} -> {:?} at size {:?}", world, local, back, minimap_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_minimap_mapping_corners_follow_size() {
+        let map_size = SimConfig::default().map_size;
+        let map_min = Vec2::new(map_size.top_left.x.to_num(), map_size.top_left.y.to_num());
+        let map_max = Vec2::new(map_size.bottom_right.x.to_num(), map_size.bottom_right.y.to_num());
+
+        let resized = mapping(Vec2::new(320.0, 180.0));
+        assert_eq!(resized.world_to_minimap(map_min), Vec2::ZERO);
+        assert_eq!(resized.world_to_minimap(map_max), Vec2::new(320.0, 180.0));
+        assert_eq!(resized.minimap_to_world(Vec2::new(160.0, 90.0)), (map_min + map_max) / 2.0);
+
+        // Positions off the map are clamped to the minimap edge
+        assert_eq!(resized.world_to_minimap(map_max + Vec2::splat(500.0)), Vec2::new(320.0, 180.0));
+    }
+
+    #[test]
+    fn test_world_to_pixel_covers_texture() {
+        // 2048x2048 map onto 256x256 pixels: 8 world units per pixel
+        let texture = mapping(Vec2::splat(MINIMAP_TEXTURE_SIZE as f32));
+        assert_eq!(texture.world_to_pixel(Vec2::new(-1024.0, -1024.0)), UVec2::ZERO);
+        assert_eq!(texture.world_to_pixel(Vec2::new(-1020.0, -1015.0)), UVec2::new(0, 1));
+        assert_eq!(texture.world_to_pixel(Vec2::ZERO), UVec2::new(128, 128));
+        assert_eq!(texture.world_to_pixel(Vec2::new(100.0, -200.0)), UVec2::new(140, 103));
+
+        // The far edge and anything past it land in the last pixel
+        assert_eq!(texture.world_to_pixel(Vec2::new(1024.0, 1024.0)), UVec2::splat(255));
+        assert_eq!(texture.world_to_pixel(Vec2::new(9000.0, -9000.0)), UVec2::new(255, 0));
+    }
+
+    #[test]
+    fn test_density_aggregation_counts_units_per_pixel() {
+        let texture = mapping(Vec2::splat(MINIMAP_TEXTURE_SIZE as f32));
+        let crowded = Vec2::new(0.1, 0.1);
+        let units = [
+            (crowded, Team(1), false),
+            (crowded, Team(2), true),
+            (crowded + Vec2::splat(0.2), Team(1), false),
+            (crowded, Team(1), true),
+            (Vec2::new(-40.0, 30.0), Team(3), false),
+        ];
+
+        let mut pixels = Vec::new();
+        let total = aggregate_unit_density(&texture, units.into_iter(), &mut pixels);
+        assert_eq!(total, 5);
+        assert_eq!(pixels.len(), (MINIMAP_TEXTURE_SIZE * MINIMAP_TEXTURE_SIZE) as usize);
+        assert_eq!(pixels.iter().map(|pixel| pixel.count).sum::<u32>(), 5);
+
+        let at = |pixels: &[MinimapPixel], world| {
+            let pixel = texture.world_to_pixel(world);
+            pixels[(pixel.y * MINIMAP_TEXTURE_SIZE + pixel.x) as usize]
+        };
+        let crowd = at(&pixels, crowded);
+        assert_eq!((crowd.count, crowd.selected, crowd.team), (4, 2, Team(1)));
+        let lone = at(&pixels, Vec2::new(-40.0, 30.0));
+        assert_eq!((lone.count, lone.selected, lone.team), (1, 0, Team(3)));
+
+        // The buffer is reused: a later pass starts from empty
+        let total = aggregate_unit_density(&texture, std::iter::once((crowded, Team(2), false)), &mut pixels);
+        assert_eq!(total, 1);
+        let crowd = at(&pixels, crowded);
+        assert_eq!((crowd.count, crowd.team), (1, Team(2)));
+        assert_eq!(at(&pixels, Vec2::new(-40.0, 30.0)).count, 0);
+    }
+
+    #[test]
+    fn test_circular_minimap_click_mapping() {
+        let rectangle = mapping(Vec2::splat(200.0));
+        let circle = rectangle.with_shape(MinimapShape::Circle);
+
+        // Corners are on the rectangle only
+        for corner in [Vec2::new(5.0, 5.0), Vec2::new(195.0, 5.0), Vec2::new(190.0, 190.0)] {
+            assert!(rectangle.click_to_world(corner).is_some());
+            assert_eq!(circle.click_to_world(corner), None, "{:?} is outside the circle", corner);
+        }
+
+        // Inside the circle, clicks land where they would on the rectangle
+        assert_eq!(circle.click_to_world(Vec2::splat(100.0)), Some(Vec2::ZERO));
+        for inside in [Vec2::new(100.0, 1.0), Vec2::new(30.0, 60.0), Vec2::new(170.0, 140.0)] {
+            assert_eq!(circle.click_to_world(inside), rectangle.click_to_world(inside), "{:?}", inside);
+        }
+        // 2048-unit map over 200 pixels: 10.24 world units per pixel
+        assert_eq!(circle.click_to_world(Vec2::new(150.0, 100.0)), Some(Vec2::new(512.0, 0.0)));
+
+        // Drawing clips the same way: the map's corners are off the radar, its center isn't
+        assert!(!circle.shows(Vec2::new(-1000.0, 1000.0)));
+        assert!(circle.shows(Vec2::new(-700.0, 700.0)));
+        assert!(rectangle.shows(Vec2::new(-1000.0, 1000.0)));
+        assert!(!rectangle.shows(Vec2::new(-3000.0, 0.0)), "Off the map is never shown");
+    }
+
+    fn setup_marker_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<SimConfig>();
+        app.init_resource::<SimTick>();
+        app.init_resource::<MinimapSettings>();
+        app.add_message::<MinimapMarker>();
+        app.add_systems(Update, (spawn_minimap_markers, update_minimap_markers).chain());
+        app.world_mut().spawn((Minimap, Node::default()));
+        app
+    }
+
+    fn ping_count(app: &mut App) -> usize {
+        app.world_mut().query::<&MinimapPing>().iter(app.world()).count()
+    }
+
+    #[test]
+    fn test_minimap_marker_drawn_until_ttl_elapses() {
+        let mut app = setup_marker_app();
+        app.world_mut().write_message(MinimapMarker {
+            world_pos: FixedVec2::from_f32(10.0, -5.0),
+            color: Color::srgb(1.0, 0.5, 0.0),
+            ttl_ticks: 5,
+        });
+
+        app.update();
+        assert_eq!(ping_count(&mut app), 1, "Marker should be drawn on the tick it arrives");
+
+        // Still alive on the last tick of its TTL
+        app.world_mut().resource_mut::<SimTick>().0 = 4;
+        app.update();
+        assert_eq!(ping_count(&mut app), 1);
+
+        app.world_mut().resource_mut::<SimTick>().0 = 5;
+        app.update();
+        assert_eq!(ping_count(&mut app), 0, "Marker should be removed once its TTL has elapsed");
+    }
+
+    #[test]
+    fn test_minimap_marker_zero_ttl_is_ignored() {
+        let mut app = setup_marker_app();
+        app.world_mut().write_message(MinimapMarker {
+            world_pos: FixedVec2::ZERO,
+            color: Color::WHITE,
+            ttl_ticks: 0,
+        });
+
+        app.update();
+        assert_eq!(ping_count(&mut app), 0);
+    }
+}
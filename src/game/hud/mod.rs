@@ -1,29 +1,52 @@
 use bevy::prelude::*;
-use crate::game::GameState;
+use crate::game::{GameState, is_pause_transition};
 
 mod components;
+mod events;
+mod resources;
 mod setup;
 mod minimap;
 mod selection;
 mod commands;
+mod command_ping;
+mod perf_overlay;
 
 use setup::*;
 use minimap::*;
 use selection::*;
 use commands::*;
+use command_ping::*;
+use perf_overlay::*;
+use resources::MinimapSettings;
+
+pub use events::MinimapMarker;
 
 pub struct HudPlugin;
 
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_hud)
-           .add_systems(OnExit(GameState::InGame), cleanup_hud)
+        app.init_resource::<MinimapSettings>()
+           .init_resource::<PerfOverlayStats>()
+           .add_message::<MinimapMarker>()
+           .add_systems(OnEnter(GameState::InGame), setup_hud)
+           .add_systems(OnExit(GameState::InGame), cleanup_hud.run_if(not(is_pause_transition)))
+           .add_systems(OnExit(GameState::Paused), cleanup_hud.run_if(not(is_pause_transition)))
            .add_systems(Update, (
                update_selection_hud,
                button_system,
                command_handler,
+               sync_minimap_settings_from_config,
+               apply_minimap_settings,
                minimap_system,
+               draw_minimap_units,
+               spawn_minimap_markers,
+               update_minimap_markers,
                minimap_input_system,
-           ).run_if(in_state(GameState::InGame)));
+               spawn_command_pings,
+               expire_command_pings,
+               draw_command_pings,
+               sample_perf_rates,
+               update_perf_overlay,
+           ).chain().run_if(in_state(GameState::InGame)));
     }
 }
@@ -0,0 +1,183 @@
+//! Performance overlay.
+//!
+//! Toggled with `DebugConfig::show_perf_overlay`, it shows render FPS, simulation TPS and the
+//! live unit count in the top-left corner, so slowdowns can be read off in the real game and
+//! not only in the perf tests. Both rates are averaged over the last second of wall time;
+//! TPS counts `SimTick` advances, so it drops below the configured tick rate when the
+//! simulation falls behind.
+
+use std::collections::VecDeque;
+use bevy::prelude::*;
+use crate::game::simulation::{DebugConfig, SimConfig, SimTick};
+use crate::game::unit::Unit;
+use super::components::HudRoot;
+
+/// Seconds of wall time both rates are averaged over
+const RATE_WINDOW_SECS: f64 = 1.0;
+
+/// Rate of a monotonic counter over a sliding wall-time window
+#[derive(Debug, Clone)]
+pub struct RateSampler {
+    window_secs: f64,
+    /// (counter value, wall time in seconds), oldest first
+    samples: VecDeque<(u64, f64)>,
+}
+
+impl RateSampler {
+    pub fn new(window_secs: f64) -> Self {
+        Self { window_secs, samples: VecDeque::new() }
+    }
+
+    /// Record the counter's value at `now_secs`. A counter that went backwards (a new game
+    /// restarting the tick count) starts the window over.
+    pub fn push(&mut self, count: u64, now_secs: f64) {
+        if self.samples.back().is_some_and(|&(last, _)| count < last) {
+            self.samples.clear();
+        }
+        self.samples.push_back((count, now_secs));
+        // Keep the newest sample at or before the window start, so the span covers it fully
+        while self.samples.get(1).is_some_and(|&(_, time)| time <= now_secs - self.window_secs) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Counter increments per second across the window; `None` until two samples apart in time
+    pub fn rate(&self) -> Option<f64> {
+        let &(first_count, first_time) = self.samples.front()?;
+        let &(last_count, last_time) = self.samples.back()?;
+        let elapsed = last_time - first_time;
+        (elapsed > 0.0).then(|| (last_count - first_count) as f64 / elapsed)
+    }
+}
+
+/// Frame and tick samplers behind the overlay
+#[derive(Resource, Debug, Clone)]
+pub struct PerfOverlayStats {
+    pub frames: RateSampler,
+    pub ticks: RateSampler,
+    frame_count: u64,
+}
+
+impl Default for PerfOverlayStats {
+    fn default() -> Self {
+        Self {
+            frames: RateSampler::new(RATE_WINDOW_SECS),
+            ticks: RateSampler::new(RATE_WINDOW_SECS),
+            frame_count: 0,
+        }
+    }
+}
+
+/// Overlay text node
+#[derive(Component)]
+pub struct PerfOverlayText;
+
+/// Sample this frame and the current tick (runs every frame, overlay shown or not, so the
+/// rates are ready the moment it is turned on)
+pub fn sample_perf_rates(
+    real_time: Res<Time<Real>>,
+    tick: Res<SimTick>,
+    mut stats: ResMut<PerfOverlayStats>,
+) {
+    let now = real_time.elapsed_secs_f64();
+    stats.frame_count += 1;
+    let frame_count = stats.frame_count;
+    stats.frames.push(frame_count, now);
+    stats.ticks.push(tick.0, now);
+}
+
+/// Show, hide and refresh the overlay
+pub fn update_perf_overlay(
+    mut commands: Commands,
+    debug_config: Res<DebugConfig>,
+    sim_config: Res<SimConfig>,
+    stats: Res<PerfOverlayStats>,
+    q_units: Query<(), With<Unit>>,
+    mut q_text: Query<(Entity, &mut Text), With<PerfOverlayText>>,
+) {
+    if !debug_config.show_perf_overlay {
+        for (entity, _) in q_text.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let rate = |rate: Option<f64>| rate.map_or_else(|| "--".to_string(), |rate| format!("{:.0}", rate));
+    let label = format!(
+        "FPS: {}\nTPS: {} / {:.0}\nUnits: {}",
+        rate(stats.frames.rate()),
+        rate(stats.ticks.rate()),
+        sim_config.tick_rate,
+        q_units.iter().count(),
+    );
+
+    if let Ok((_, mut text)) = q_text.single_mut() {
+        if text.0 != label {
+            text.0 = label;
+        }
+        return;
+    }
+    // Below the top bar; part of the HUD so it goes with it
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(44.0),
+            left: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        Text::new(label),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        HudRoot,
+        PerfOverlayText,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tps_from_tick_samples_over_window() {
+        let mut sampler = RateSampler::new(1.0);
+        assert_eq!(sampler.rate(), None);
+        sampler.push(0, 0.0);
+        assert_eq!(sampler.rate(), None, "One sample has no rate yet");
+
+        // 30 ticks per second, sampled at 60 FPS
+        for frame in 1..=120 {
+            sampler.push(frame / 2, frame as f64 / 60.0);
+        }
+        assert_eq!(sampler.rate(), Some(30.0));
+
+        // The sim stalls: a second later the window only sees the stall
+        for frame in 121..=180 {
+            sampler.push(60, frame as f64 / 60.0);
+        }
+        assert_eq!(sampler.rate(), Some(0.0));
+
+        // A new game restarts the tick count
+        sampler.push(0, 3.5);
+        assert_eq!(sampler.rate(), None);
+        sampler.push(5, 4.0);
+        assert_eq!(sampler.rate(), Some(10.0));
+    }
+
+    #[test]
+    fn test_window_keeps_only_the_last_second() {
+        let mut sampler = RateSampler::new(1.0);
+        // 10 TPS for two seconds, then 40 TPS for one
+        for step in 0..=20 {
+            sampler.push(step, step as f64 / 10.0);
+        }
+        for step in 1..=10 {
+            sampler.push(20 + step * 4, 2.0 + step as f64 / 10.0);
+        }
+        assert_eq!(sampler.rate(), Some(40.0));
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use crate::game::config::{GameConfig, MinimapCorner, MinimapShape};
+
+/// Minimap size and placement (logical pixels).
+///
+/// Filled from `GameConfig` (`minimap_width`, `minimap_height`, `minimap_corner`,
+/// `minimap_shape`) on load and hot-reload. Changes are applied to the minimap node by `apply_minimap_settings`;
+/// dot drawing and click handling read the node's computed size, so they follow automatically.
+#[derive(Resource, Debug, Clone)]
+pub struct MinimapSettings {
+    /// Width and height of the minimap, including its border
+    pub size: Vec2,
+    pub corner: MinimapCorner,
+    pub shape: MinimapShape,
+    /// Distance from the window edges
+    pub margin: f32,
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self {
+            size: Vec2::new(200.0, 200.0),
+            corner: MinimapCorner::BottomLeft,
+            shape: MinimapShape::Rectangle,
+            margin: 10.0,
+        }
+    }
+}
+
+impl MinimapSettings {
+    /// Settings from the runtime config (margin is kept)
+    pub fn from_config(config: &GameConfig, margin: f32) -> Self {
+        Self {
+            size: Vec2::new(config.minimap_width, config.minimap_height),
+            corner: config.minimap_corner,
+            shape: config.minimap_shape,
+            margin,
+        }
+    }
+
+    /// Size the minimap is drawn at: a circle fits in a square of the smaller side
+    pub fn node_size(&self) -> Vec2 {
+        match self.shape {
+            MinimapShape::Rectangle => self.size,
+            MinimapShape::Circle => Vec2::splat(self.size.min_element()),
+        }
+    }
+
+    /// Corner rounding that gives the minimap node its shape
+    pub fn border_radius(&self) -> BorderRadius {
+        match self.shape {
+            MinimapShape::Rectangle => BorderRadius::ZERO,
+            MinimapShape::Circle => BorderRadius::MAX,
+        }
+    }
+
+    /// Apply size and corner anchoring to the minimap's (absolutely positioned) node
+    pub fn apply_to_node(&self, node: &mut Node) {
+        let size = self.node_size();
+        node.position_type = PositionType::Absolute;
+        node.width = Val::Px(size.x);
+        node.height = Val::Px(size.y);
+
+        let margin = Val::Px(self.margin);
+        let (left, right, top, bottom) = match self.corner {
+            MinimapCorner::BottomLeft => (margin, Val::Auto, Val::Auto, margin),
+            MinimapCorner::BottomRight => (Val::Auto, margin, Val::Auto, margin),
+            MinimapCorner::TopLeft => (margin, Val::Auto, margin, Val::Auto),
+            MinimapCorner::TopRight => (Val::Auto, margin, margin, Val::Auto),
+        };
+        node.left = left;
+        node.right = right;
+        node.top = top;
+        node.bottom = bottom;
+    }
+}
@@ -1,18 +1,31 @@
 use bevy::prelude::*;
-use crate::game::unit::{Selected, Health};
+use std::collections::BTreeMap;
+use crate::game::unit::{Selected, Health, UnitType};
 use super::components::*;
 
-/// Update the selection HUD to show selected unit info
+/// Update the selection HUD to show selected unit info.
+///
+/// Only rebuilds the text when the selection changes (units selected/deselected),
+/// when a single selected unit's health changes, or when the HUD is (re)spawned.
 pub fn update_selection_hud(
-    selected_units: Query<(Entity, Option<&Health>), With<Selected>>,
+    selected_units: Query<(Entity, Option<&Health>, Option<&UnitType>), With<Selected>>,
+    newly_selected: Query<(), Added<Selected>>,
+    mut deselected: RemovedComponents<Selected>,
+    health_changed: Query<(), (With<Selected>, Changed<Health>)>,
+    new_text: Query<(), Added<SelectionText>>,
     mut text_query: Query<&mut Text, With<SelectionText>>,
 ) {
+    let selection_changed = deselected.read().count() > 0 || !newly_selected.is_empty();
+    if !selection_changed && health_changed.is_empty() && new_text.is_empty() {
+        return;
+    }
+
     let count = selected_units.iter().count();
     for mut text in &mut text_query {
         if count == 0 {
             **text = "No Selection".to_string();
         } else if count == 1 {
-            if let Ok((entity, health)) = selected_units.single() {
+            if let Ok((entity, health, _)) = selected_units.single() {
                 let health_str = if let Some(h) = health {
                     format!("HP: {:.0}/{:.0}", h.current, h.max)
                 } else {
@@ -21,7 +34,56 @@ pub fn update_selection_hud(
                 **text = format!("Unit ID: {:?}\n{}", entity, health_str);
             }
         } else {
-            **text = format!("Selected: {} units", count);
+            let groups = group_by_unit_type(
+                selected_units.iter().map(|(_, _, unit_type)| unit_type.copied().unwrap_or_default()),
+            );
+            **text = format!("Selected: {} units\n{}", count, format_type_summary(&groups));
         }
     }
 }
+
+/// Count units per type, ordered by `UnitType` (deterministic, no HashMap iteration).
+fn group_by_unit_type(unit_types: impl Iterator<Item = UnitType>) -> Vec<(UnitType, usize)> {
+    let mut counts: BTreeMap<UnitType, usize> = BTreeMap::new();
+    for unit_type in unit_types {
+        *counts.entry(unit_type).or_insert(0) += 1;
+    }
+    counts.into_iter().collect()
+}
+
+/// Render grouped counts as e.g. "40 soldiers, 10 tanks"
+fn format_type_summary(groups: &[(UnitType, usize)]) -> String {
+    groups
+        .iter()
+        .map(|(unit_type, count)| format!("{} {}", count, unit_type.display_name(*count)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_unit_type_mixed_selection() {
+        let selection = std::iter::repeat(UnitType::Tank).take(10)
+            .chain(std::iter::repeat(UnitType::Soldier).take(40));
+
+        let groups = group_by_unit_type(selection);
+
+        assert_eq!(groups, vec![(UnitType::Soldier, 40), (UnitType::Tank, 10)]);
+        assert_eq!(format_type_summary(&groups), "40 soldiers, 10 tanks");
+    }
+
+    #[test]
+    fn test_group_by_unit_type_single_type() {
+        let groups = group_by_unit_type([UnitType::Tank].into_iter());
+        assert_eq!(groups, vec![(UnitType::Tank, 1)]);
+        assert_eq!(format_type_summary(&groups), "1 tank");
+    }
+
+    #[test]
+    fn test_group_by_unit_type_empty() {
+        assert!(group_by_unit_type(std::iter::empty()).is_empty());
+    }
+}
@@ -1,8 +1,21 @@
 use bevy::prelude::*;
 use super::components::*;
+use super::resources::MinimapSettings;
+use super::minimap::new_unit_layer_image;
 
 /// Setup the HUD UI elements
-pub fn setup_hud(mut commands: Commands) {
+pub fn setup_hud(
+    mut commands: Commands,
+    minimap_settings: Res<MinimapSettings>,
+    mut images: ResMut<Assets<Image>>,
+    q_root: Query<(), With<HudRoot>>,
+) {
+    // Resuming from pause: the HUD was kept
+    if !q_root.is_empty() {
+        return;
+    }
+    let unit_layer = images.add(new_unit_layer_image());
+
     // Root node for the HUD
     commands
         .spawn((
@@ -17,19 +30,30 @@ pub fn setup_hud(mut commands: Commands) {
             HudRoot,
         ))
         .with_children(|parent| {
-            // Bottom Left: Minimap Placeholder
+            // Minimap: absolutely positioned in the configured corner (see MinimapSettings)
+            let mut minimap_node = Node {
+                border: UiRect::all(Val::Px(2.0)),
+                ..default()
+            };
+            minimap_settings.apply_to_node(&mut minimap_node);
             parent.spawn((
-                Node {
-                    width: Val::Px(200.0),
-                    height: Val::Px(200.0),
-                    border: UiRect::all(Val::Px(2.0)),
-                    margin: UiRect::all(Val::Px(10.0)),
-                    ..default()
-                },
+                minimap_node,
                 BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
                 BorderColor::from(Color::WHITE),
+                minimap_settings.border_radius(),
                 Minimap,
             )).with_children(|p| {
+                // Units are painted into a texture stretched over the whole minimap
+                p.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ImageNode::new(unit_layer),
+                    MinimapUnitLayer,
+                ));
                  p.spawn((
                     Node {
                         position_type: PositionType::Absolute,
@@ -43,6 +67,13 @@ pub fn setup_hud(mut commands: Commands) {
                 ));
             });
 
+            // Bottom Left: Spacer matching the command card, keeps the selection info centered
+            parent.spawn(Node {
+                width: Val::Px(200.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            });
+
             // Bottom Center: Selection Info
             parent.spawn((
                 Node {
@@ -161,12 +192,8 @@ pub fn setup_hud(mut commands: Commands) {
 pub fn cleanup_hud(
     mut commands: Commands,
     query: Query<Entity, With<HudRoot>>,
-    unit_query: Query<Entity, With<UnitMinimapDot>>,
 ) {
     for entity in &query {
         commands.entity(entity).despawn();
     }
-    for entity in &unit_query {
-        commands.entity(entity).remove::<UnitMinimapDot>();
-    }
 }
@@ -0,0 +1,151 @@
+//! Command-line options for the game binary.
+//!
+//! Usage: `peregrine [--map <path.pmap>]`
+//!
+//! `--map` skips the main menu: the map file is read and checked before the app is built
+//! (so a bad path fails with a message instead of a broken game), then handed to the
+//! loading screen as a [`PendingMapLoad`].
+
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use crate::game::GameState;
+use crate::game::loading::{PendingMapLoad, TargetGameState};
+use crate::game::map::{load_map, MapData, MAP_VERSION};
+use crate::game::simulation::MapDimensions;
+
+pub const USAGE: &str = "Usage: peregrine [--map <path.pmap>]";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// Map to start straight into instead of the main menu
+    pub map: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    /// Parse the arguments after the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--map" => {
+                    let path = args.next().ok_or_else(|| format!("--map needs a path\n{}", USAGE))?;
+                    options.map = Some(PathBuf::from(path));
+                }
+                _ => return Err(format!("Unknown argument: {}\n{}", arg, USAGE)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Read a map file and check it can be played
+pub fn load_startup_map(path: &Path) -> Result<MapData, String> {
+    if !path.is_file() {
+        return Err(format!("Map file not found: {}", path.display()));
+    }
+    let map_data = load_map(&path.to_string_lossy())
+        .map_err(|e| format!("Failed to read map {}: {}", path.display(), e))?;
+
+    if map_data.version != MAP_VERSION {
+        return Err(format!("Map {} has version {}, expected {}", path.display(), map_data.version, MAP_VERSION));
+    }
+    let width = map_data.size.get_width();
+    let height = map_data.size.get_height();
+    if width <= 0 || height <= 0 || map_data.cell_size <= 0 {
+        return Err(format!("Map {} has an invalid size", path.display()));
+    }
+    let (cols, rows) = MapDimensions::new(width, height, map_data.cell_size).flow_field_cells();
+    if map_data.cost_field.len() != cols * rows {
+        return Err(format!(
+            "Map {} has {} cost field cells, expected {} for a {}x{} map",
+            path.display(), map_data.cost_field.len(), cols * rows, width, height,
+        ));
+    }
+    Ok(map_data)
+}
+
+/// Go from startup straight through the loading screen into `map_data`
+pub fn start_with_map(app: &mut App, map_data: MapData) {
+    app.insert_resource(PendingMapLoad(map_data));
+    app.insert_resource(TargetGameState(GameState::InGame));
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Loading);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::fixed_math::FixedNum;
+    use crate::game::map::save_map;
+    use crate::game::pathfinding::HierarchicalGraph;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Path in the temp dir, unique per test
+    fn temp_map_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("peregrine_launch_{}_{}.pmap", name, std::process::id()))
+    }
+
+    fn small_map() -> MapData {
+        let dimensions = MapDimensions::new(FixedNum::from_num(16), FixedNum::from_num(8), FixedNum::ONE);
+        MapData {
+            version: MAP_VERSION,
+            size: dimensions.map_size(),
+            cell_size: dimensions.cell_size,
+            cluster_size: 8,
+            obstacles: vec![],
+            start_locations: vec![],
+            cost_field: vec![1; 16 * 8],
+            graph: HierarchicalGraph::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_map_argument() {
+        assert_eq!(LaunchOptions::parse(args(&[])), Ok(LaunchOptions { map: None }));
+        assert_eq!(
+            LaunchOptions::parse(args(&["--map", "assets/maps/default.pmap"])),
+            Ok(LaunchOptions { map: Some(PathBuf::from("assets/maps/default.pmap")) }),
+        );
+        assert!(LaunchOptions::parse(args(&["--map"])).unwrap_err().contains("--map needs a path"));
+        assert!(LaunchOptions::parse(args(&["--mpa", "x"])).unwrap_err().contains("Unknown argument: --mpa"));
+    }
+
+    #[test]
+    fn test_missing_map_file_is_reported() {
+        let path = temp_map_path("missing");
+        let err = load_startup_map(&path).err().unwrap();
+        assert_eq!(err, format!("Map file not found: {}", path.display()));
+    }
+
+    #[test]
+    fn test_invalid_map_file_is_reported() {
+        let path = temp_map_path("garbage");
+        std::fs::write(&path, b"not a map").unwrap();
+        let err = load_startup_map(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.starts_with("Failed to read map"), "{}", err);
+
+        // Decodes, but the cost field doesn't cover the map
+        let path = temp_map_path("truncated");
+        let mut map = small_map();
+        map.cost_field.truncate(10);
+        save_map(&path.to_string_lossy(), &map).unwrap();
+        let err = load_startup_map(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(err.contains("has 10 cost field cells, expected 128"), "{}", err);
+    }
+
+    #[test]
+    fn test_saved_map_loads() {
+        let path = temp_map_path("valid");
+        save_map(&path.to_string_lossy(), &small_map()).unwrap();
+        let loaded = load_startup_map(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.size.get_width(), FixedNum::from_num(16));
+        assert_eq!(loaded.cost_field.len(), 128);
+    }
+}
@@ -1,13 +1,19 @@
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use crate::game::GameState;
 use crate::game::editor::PendingMapGeneration;
-use crate::game::map::MapSize;
+use crate::game::map::MapData;
 
 pub struct LoadingPlugin;
 
 #[derive(Resource)]
 pub struct TargetGameState(pub GameState);
 
+/// Map file to play, read ahead of time (see `launch::load_startup_map`).
+/// Consumed on entering `GameState::Loading`.
+#[derive(Resource)]
+pub struct PendingMapLoad(pub MapData);
+
 #[derive(Resource, Default)]
 pub struct LoadingProgress {
     pub progress: f32,
@@ -20,6 +26,7 @@ impl Plugin for LoadingPlugin {
         app.add_systems(OnEnter(GameState::Loading), (
             setup_loading_screen, 
             handle_pending_map_generation,
+            handle_pending_map_load,
             build_graph_after_map_ready,
         ).chain());
         app.add_systems(OnExit(GameState::Loading), cleanup_loading_screen);
@@ -137,7 +144,7 @@ fn check_loading_complete(
 fn handle_pending_map_generation(
     mut commands: Commands,
     pending: Option<Res<PendingMapGeneration>>,
-    initial_config: Res<crate::game::config::InitialConfig>,
+    mut map_dimensions: ResMut<crate::game::simulation::MapDimensions>,
     mut sim_config: ResMut<crate::game::simulation::SimConfig>,
     mut spatial_hash: ResMut<crate::game::spatial_hash::SpatialHash>,
     mut map_flow_field: ResMut<crate::game::simulation::MapFlowField>,
@@ -153,9 +160,7 @@ fn handle_pending_map_generation(
         return;
     };
     
-    use crate::game::fixed_math::{FixedVec2, FixedNum};
-    use crate::game::structures::{FlowField, CELL_SIZE};
-    use rand::Rng;
+    use crate::game::simulation::MapDimensions;
     
     info!("=== GENERATING RANDOM MAP DURING LOADING ===");
     info!("Generating map: {}x{} with {} obstacles...", pending_gen.map_width, pending_gen.map_height, pending_gen.num_obstacles);
@@ -167,38 +172,11 @@ fn handle_pending_map_generation(
     graph.reset();
 
     
-    // Update SimConfig with new map dimensions
-    let half_width = FixedNum::from_num(map_width) / FixedNum::from_num(2.0);
-    let half_height = FixedNum::from_num(map_height) / FixedNum::from_num(2.0);
-    sim_config.map_size = MapSize {
-        top_left: FixedVec2::new(-half_width, -half_height),
-        bottom_right: FixedVec2::new(half_width, half_height),
-    };
-    info!("Updated SimConfig: map size = {}x{}", map_width, map_height);
-    
-    // Update SpatialHash with new map dimensions using InitialConfig values
-    spatial_hash.resize(
-        FixedNum::from_num(map_width),
-        FixedNum::from_num(map_height),
-        &initial_config.spatial_hash_entity_radii,
-        initial_config.spatial_hash_radius_to_cell_ratio,
-        initial_config.spatial_hash_max_entity_count,
-        initial_config.spatial_hash_arena_overcapacity_ratio
-    );
-    info!("Updated SpatialHash for new map size");
-
-    let ff_width = (map_width / CELL_SIZE) as usize;
-    let ff_height = (map_height / CELL_SIZE) as usize;
-    info!("Creating FlowField: {} x {} cells ({}x{} world units)", 
-          ff_width, ff_height, map_width, map_height);
-
-    map_flow_field.0 = FlowField::new(
-        ff_width, 
-        ff_height, 
-        FixedNum::from_num(CELL_SIZE), 
-        FixedVec2::new(FixedNum::from_num(-map_width/2.0), FixedNum::from_num(-map_height/2.0))
-    );
-    info!("FlowField created successfully (total cells: {})", ff_width * ff_height);
+    // Resize SimConfig, SpatialHash and FlowField together for the new map
+    *map_dimensions = MapDimensions::from_f32(map_width, map_height);
+    map_dimensions.apply(&mut sim_config, &mut map_flow_field.0, &mut spatial_hash);
+    let (ff_width, ff_height) = map_dimensions.flow_field_cells();
+    info!("Resized map to {}x{} (FlowField: {} x {} cells)", map_width, map_height, ff_width, ff_height);
     
     // Update ground plane mesh to match new map size
     for (entity, _mesh3d) in ground_plane_query.iter() {
@@ -207,45 +185,26 @@ fn handle_pending_map_generation(
         info!("Updated ground plane mesh to {}x{}", map_width, map_height);
     }
 
-    // Spawn obstacles if any and if we have editor resources
+    // Same placement and connectivity pass as editor generation. Obstacles go into the flow
+    // field immediately, before building the graph - otherwise it is built on an empty map
     if pending_gen.num_obstacles > 0 {
+        let obstacles = crate::game::editor::generate_obstacle_layout(&pending_gen.params());
+        for &(pos, rad) in &obstacles {
+            crate::game::simulation::apply_obstacle_to_flow_field(&mut map_flow_field.0, pos, rad);
+        }
         if let Some(resources) = editor_resources {
-            let mut rng = rand::rng();
-            let margin = 50.0;
-            
-            for _ in 0..pending_gen.num_obstacles {
-                let x = rng.random_range((-map_width/2.0 + margin)..(map_width/2.0 - margin));
-                let z = rng.random_range((-map_height/2.0 + margin)..(map_height/2.0 - margin));
-                let radius = rng.random_range(pending_gen.min_radius..pending_gen.max_radius);
-                
-                let pos = FixedVec2::from_f32(x, z);
-                let rad = FixedNum::from_num(radius);
-                
-                // Reuse the shared spawn_obstacle function instead of duplicating code
-                crate::game::editor::spawn_obstacle(
-                    &mut commands,
-                    pos,
-                    rad,
-                    &resources  // Dereference the Res<EditorResources>
-                );
-                
-                // CRITICAL: Apply obstacle to flow field immediately, before building graph
-                // Otherwise the graph will be built on an empty map!
-                crate::game::simulation::apply_obstacle_to_flow_field(
-                    &mut map_flow_field.0,
-                    pos,
-                    rad
-                );
+            for &(pos, rad) in &obstacles {
+                crate::game::editor::spawn_obstacle(&mut commands, pos, rad, &resources);
             }
-            
-            info!("Spawned {} random obstacles", pending_gen.num_obstacles);
         } else {
-            warn!("EditorResources not available, skipping obstacle generation");
+            warn!("EditorResources not available, obstacles only exist in the cost field");
         }
+        info!("Placed {} of {} random obstacles", obstacles.len(), pending_gen.num_obstacles);
     }
     
-    // Mark map as not loaded from file (since we generated it)
+    // Mark map as not loaded from file (since we generated it); obstacles are in the cost field
     map_status.loaded = false;
+    map_status.terrain_baked = true;
     
     // Build graph using new region-based system (synchronous, fast)
     info!("Building pathfinding graph with region-based system...");
@@ -259,6 +218,84 @@ fn handle_pending_map_generation(
     info!("=== RANDOM MAP GENERATION COMPLETE ===");
 }
 
+/// Everything sized by [`MapDimensions`](crate::game::simulation::MapDimensions)
+#[derive(SystemParam)]
+struct MapResources<'w> {
+    dimensions: ResMut<'w, crate::game::simulation::MapDimensions>,
+    sim_config: ResMut<'w, crate::game::simulation::SimConfig>,
+    spatial_hash: ResMut<'w, crate::game::spatial_hash::SpatialHash>,
+    flow_field: ResMut<'w, crate::game::simulation::MapFlowField>,
+}
+
+impl MapResources<'_> {
+    fn resize(&mut self, dimensions: crate::game::simulation::MapDimensions) {
+        *self.dimensions = dimensions;
+        dimensions.apply(&mut self.sim_config, &mut self.flow_field.0, &mut self.spatial_hash);
+    }
+}
+
+/// Set up the world from a map file: resize for it, restore its cost field and spawn its
+/// obstacles. The pathfinding graph is rebuilt from the cost field by
+/// `build_graph_after_map_ready`, which also fills the navigation lookup the saved graph lacks.
+///
+/// A map loaded to be played (not edited) also gets its starting units, handed out by the
+/// [`TeamAssignments`](crate::game::unit::TeamAssignments) resource or, without one, one team
+/// per start location.
+fn handle_pending_map_load(
+    mut commands: Commands,
+    pending: Option<Res<PendingMapLoad>>,
+    mut map: MapResources,
+    mut graph: ResMut<crate::game::pathfinding::HierarchicalGraph>,
+    mut map_status: ResMut<crate::game::simulation::MapStatus>,
+    editor_resources: Option<Res<crate::game::editor::EditorResources>>,
+    (target_state, team_assignments): (Option<Res<TargetGameState>>, Option<Res<crate::game::unit::TeamAssignments>>),
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+    let map_data = &pending.0;
+
+    use crate::game::simulation::MapDimensions;
+
+    info!("=== LOADING MAP FROM FILE ===");
+    graph.reset();
+
+    map.resize(MapDimensions::new(map_data.size.get_width(), map_data.size.get_height(), map_data.cell_size));
+    let map_flow_field = &mut map.flow_field.0;
+    if map_flow_field.cost_field.len() == map_data.cost_field.len() {
+        map_flow_field.cost_field.copy_from_slice(&map_data.cost_field);
+    } else {
+        warn!("Map cost field doesn't match its size, rasterizing obstacles instead");
+        for obstacle in &map_data.obstacles {
+            crate::game::simulation::apply_obstacle_to_flow_field(map_flow_field, obstacle.position, obstacle.radius);
+        }
+    }
+
+    if let Some(resources) = editor_resources {
+        for obstacle in &map_data.obstacles {
+            crate::game::editor::spawn_obstacle(&mut commands, obstacle.position, obstacle.radius, &resources);
+        }
+    } else {
+        warn!("EditorResources not available, obstacles only exist in the cost field");
+    }
+    info!("Loaded {}x{} map with {} obstacles", map.dimensions.width, map.dimensions.height, map_data.obstacles.len());
+
+    if target_state.is_none_or(|target| target.0 == GameState::InGame) {
+        use crate::game::unit::{spawn_starting_units, TeamAssignment, DEFAULT_STARTING_UNITS};
+        let assignments = match team_assignments {
+            Some(assignments) => assignments.0.clone(),
+            None => TeamAssignment::one_team_per_player(map_data, DEFAULT_STARTING_UNITS),
+        };
+        let spawned = spawn_starting_units(&mut commands, &mut map.spatial_hash, map_data, &map.flow_field.0, &assignments);
+        info!("Spawned {} starting units at {} start locations", spawned.len(), map_data.start_locations.len());
+    }
+
+    map_status.loaded = true;
+    map_status.terrain_baked = true;
+
+    commands.remove_resource::<PendingMapLoad>();
+}
+
 /// Build pathfinding graph after map is ready (either loaded or generated)
 /// This runs after handle_pending_map_generation, so if a map was generated,
 /// the graph is already built. This handles the case where no map generation
@@ -268,8 +305,13 @@ fn build_graph_after_map_ready(
     mut nav_lookup: ResMut<crate::game::pathfinding::NavigationLookup>,
     mut nav_routing: ResMut<crate::game::pathfinding::NavigationRouting>,
     map_flow_field: Res<crate::game::simulation::MapFlowField>,
+    mut map_status: ResMut<crate::game::simulation::MapStatus>,
     mut loading_progress: ResMut<LoadingProgress>,
 ) {
+    // A map that goes through Loading is played from its cost field (obstacles spawned
+    // with it are rasterized by apply_new_obstacles)
+    map_status.terrain_baked = true;
+
     // Skip if graph was already built (e.g., by map generation)
     if graph.initialized {
         loading_progress.task = "Ready!".to_string();
@@ -289,3 +331,117 @@ fn build_graph_after_map_ready(
     loading_progress.task = "Ready!".to_string();
     loading_progress.progress = 1.0;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::game::editor::{generate_obstacle_layout, EditorResources};
+    use crate::game::pathfinding::{HierarchicalGraph, NavigationLookup, NavigationRouting};
+    use crate::game::fixed_math::{FixedNum, FixedVec2};
+    use crate::game::map::{StartLocation, MAP_VERSION};
+    use crate::game::pathfinding::CLUSTER_SIZE;
+    use crate::game::unit::{Team, Unit, DEFAULT_STARTING_UNITS};
+    use crate::game::simulation::{apply_obstacle_to_flow_field, MapDimensions, MapFlowField, MapStatus, SimConfig, SimPosition, StaticObstacle};
+    use crate::game::spatial_hash::SpatialHash;
+    use crate::game::structures::FlowField;
+
+    /// Dense enough that raw placement walls off pockets for some seeds
+    fn dense_generation(seed: u64) -> PendingMapGeneration {
+        PendingMapGeneration { map_width: 80.0, map_height: 60.0, num_obstacles: 120, min_radius: 1.0, max_radius: 5.0, seed }
+    }
+
+    #[test]
+    fn test_generated_map_gets_the_connectivity_pass() {
+        let pending = (0..20)
+            .map(dense_generation)
+            .find(|pending| generate_obstacle_layout(&pending.params()).len() < pending.num_obstacles)
+            .expect("Some seed should need carving");
+        let layout = generate_obstacle_layout(&pending.params());
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<MapDimensions>();
+        app.init_resource::<SimConfig>();
+        app.insert_resource(SpatialHash::new(FixedNum::from_num(10), FixedNum::from_num(10), &[0.5], 4.0, 100, 1.0));
+        app.init_resource::<MapFlowField>();
+        app.init_resource::<HierarchicalGraph>();
+        app.init_resource::<NavigationLookup>();
+        app.init_resource::<NavigationRouting>();
+        app.init_resource::<MapStatus>();
+        app.init_resource::<Assets<Mesh>>();
+        app.insert_resource(EditorResources { obstacle_mesh: Handle::default(), obstacle_material: Handle::default() });
+        app.insert_resource(pending);
+        app.world_mut().run_system_once(handle_pending_map_generation).unwrap();
+
+        // Exactly the carved layout, in the flow field and as entities
+        let mut spawned: Vec<_> = app.world_mut()
+            .query_filtered::<&SimPosition, With<StaticObstacle>>()
+            .iter(app.world())
+            .map(|position| position.0)
+            .collect();
+        let mut expected: Vec<_> = layout.iter().map(|&(position, _)| position).collect();
+        spawned.sort_by_key(|position| (position.x, position.y));
+        expected.sort_by_key(|position| (position.x, position.y));
+        assert_eq!(spawned, expected);
+
+        let field = &app.world().resource::<MapFlowField>().0;
+        let mut expected_field = FlowField::new(field.width, field.height, field.cell_size, field.origin);
+        for &(position, radius) in &layout {
+            apply_obstacle_to_flow_field(&mut expected_field, position, radius);
+        }
+        assert!(field.cost_field == expected_field.cost_field, "Cost field should hold exactly the carved layout");
+        assert!(app.world().resource::<HierarchicalGraph>().initialized);
+        assert!(!app.world().contains_resource::<PendingMapGeneration>());
+    }
+
+    fn map_load_app(target: GameState) -> App {
+        let dimensions = MapDimensions::from_f32(60.0, 60.0);
+        let start = |player_id: u8, x: f32, y: f32| StartLocation { player_id, position: FixedVec2::from_f32(x, y) };
+        let map_data = MapData {
+            version: MAP_VERSION,
+            size: dimensions.map_size(),
+            cell_size: dimensions.cell_size,
+            cluster_size: CLUSTER_SIZE,
+            obstacles: vec![],
+            start_locations: vec![start(1, -20.0, -20.0), start(2, 20.0, 20.0)],
+            cost_field: dimensions.flow_field().cost_field,
+            graph: HierarchicalGraph::default(),
+        };
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<MapDimensions>();
+        app.init_resource::<SimConfig>();
+        app.insert_resource(SpatialHash::new(FixedNum::from_num(10), FixedNum::from_num(10), &[0.5], 4.0, 100, 1.0));
+        app.init_resource::<MapFlowField>();
+        app.init_resource::<HierarchicalGraph>();
+        app.init_resource::<MapStatus>();
+        app.insert_resource(PendingMapLoad(map_data));
+        app.insert_resource(TargetGameState(target));
+        app.world_mut().run_system_once(handle_pending_map_load).unwrap();
+        app
+    }
+
+    #[test]
+    fn test_map_load_spawns_starting_units_for_play() {
+        let mut app = map_load_app(GameState::InGame);
+        let mut units: Vec<(u8, FixedVec2)> = app.world_mut()
+            .query_filtered::<(&Team, &SimPosition), With<Unit>>()
+            .iter(app.world())
+            .map(|(team, position)| (team.0, position.0))
+            .collect();
+        units.sort_by_key(|&(team, _)| team);
+        assert_eq!(units.len(), 2 * DEFAULT_STARTING_UNITS);
+        // One team per start location, gathered around it
+        for (i, &(team, position)) in units.iter().enumerate() {
+            let (expected_team, centre) = if i < DEFAULT_STARTING_UNITS { (1, -20.0) } else { (2, 20.0) };
+            assert_eq!(team, expected_team);
+            assert!((position - FixedVec2::from_f32(centre, centre)).length() < FixedNum::from_num(5));
+        }
+
+        // Editing a map doesn't populate it
+        let mut app = map_load_app(GameState::Editor);
+        assert_eq!(app.world_mut().query_filtered::<(), With<Unit>>().iter(app.world()).count(), 0);
+    }
+}
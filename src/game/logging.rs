@@ -0,0 +1,309 @@
+//! Logging helpers used by `main.rs`'s tracing setup.
+//!
+//! # Log file housekeeping
+//!
+//! `main.rs` writes one timestamped `peregrine_*.log` per run into `logs/`. Before opening
+//! a new one, [`cleanup_old_logs`] prunes older runs according to a [`LogRetention`] policy,
+//! configured through environment variables:
+//!
+//! - `PEREGRINE_LOG_KEEP=<n>` - keep the `n` newest logs (default: 25)
+//! - `PEREGRINE_LOG_KEEP_BYTES=<bytes>` - keep the newest logs that fit in this total size
+//!   (takes precedence over `PEREGRINE_LOG_KEEP`)
+//!
+//! # Tick stamping
+//!
+//! `sim_start` publishes the current `SimTick` with [`set_sim_tick`] and `sim_end` clears it.
+//! Wrapping a fmt layer's event format in [`SimTickFormat`] prefixes every line logged in
+//! between with `tick=N`, so call sites don't need `profile_log!` just to say which tick
+//! they're on.
+//!
+//! The tick is process-wide rather than thread-local: Bevy runs `FixedUpdate` systems on
+//! its task pool, so a log inside `detect_collisions` usually comes from a different thread
+//! than `sim_start`. There is one simulation per process, so a single slot is enough.
+//!
+//! # Flushing on shutdown
+//!
+//! The log file is written through `tracing_appender`'s non-blocking writer, which buffers
+//! lines for a worker thread. `main.rs` hands the writer's guard to [`keep_log_guard`];
+//! [`flush_logs`] drops it, which blocks until every buffered line is on disk. It runs on
+//! `AppExit` ([`flush_logs_on_exit`]) and from the panic hook installed by
+//! [`flush_logs_on_panic`], so the lines leading up to a crash aren't lost.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use bevy::app::AppExit;
+use bevy::ecs::message::MessageReader;
+use bevy::log::tracing::{Event, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::fmt::{format::Writer, FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// How many old log files to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRetention {
+    /// Keep the N newest files
+    KeepCount(usize),
+    /// Keep the newest files whose combined size fits in this many bytes
+    KeepTotalBytes(u64),
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        LogRetention::KeepCount(Self::DEFAULT_KEEP_COUNT)
+    }
+}
+
+impl LogRetention {
+    pub const DEFAULT_KEEP_COUNT: usize = 25;
+    pub const KEEP_COUNT_VAR: &'static str = "PEREGRINE_LOG_KEEP";
+    pub const KEEP_BYTES_VAR: &'static str = "PEREGRINE_LOG_KEEP_BYTES";
+
+    /// Read the policy from the environment (see module docs)
+    pub fn from_env() -> Self {
+        Self::from_vars(
+            std::env::var(Self::KEEP_COUNT_VAR).ok().as_deref(),
+            std::env::var(Self::KEEP_BYTES_VAR).ok().as_deref(),
+        )
+    }
+
+    /// Parse the policy from raw variable values; unparsable values fall back to the default
+    pub fn from_vars(keep_count: Option<&str>, keep_bytes: Option<&str>) -> Self {
+        if let Some(bytes) = keep_bytes.and_then(|v| v.trim().parse::<u64>().ok()) {
+            return LogRetention::KeepTotalBytes(bytes);
+        }
+        match keep_count.and_then(|v| v.trim().parse::<usize>().ok()) {
+            Some(count) => LogRetention::KeepCount(count),
+            None => LogRetention::default(),
+        }
+    }
+}
+
+/// True for files this game wrote (`peregrine*.log`)
+fn is_game_log(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|s| s.starts_with("peregrine") && s.ends_with(".log"))
+}
+
+/// Delete old game logs in `log_dir` beyond what `retention` allows.
+///
+/// Only regular `peregrine*.log` files are considered; anything else in the directory is
+/// left alone. Files whose metadata or modification time can't be read are skipped (never
+/// deleted), as are files that fail to delete. Returns the paths that were removed.
+pub fn cleanup_old_logs(log_dir: &Path, retention: LogRetention) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(log_dir) else { return Vec::new() };
+
+    let mut log_files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| is_game_log(&e.path()))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+            let modified = metadata.modified().ok()?;
+            Some((modified, metadata.len(), e.path()))
+        })
+        .collect();
+
+    // Newest first (ties broken by name so the result doesn't depend on read_dir order)
+    log_files.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.2.cmp(&a.2)));
+
+    let keep = match retention {
+        LogRetention::KeepCount(count) => count.min(log_files.len()),
+        LogRetention::KeepTotalBytes(limit) => {
+            let mut total = 0u64;
+            log_files.iter()
+                .take_while(|(_, size, _)| {
+                    total += size;
+                    total <= limit
+                })
+                .count()
+        }
+    };
+
+    log_files.into_iter()
+        .skip(keep)
+        .filter_map(|(_, _, path)| fs::remove_file(&path).ok().map(|_| path))
+        .collect()
+}
+
+/// Sentinel for "not inside a fixed tick"
+const NO_TICK: u64 = u64::MAX;
+
+static CURRENT_SIM_TICK: AtomicU64 = AtomicU64::new(NO_TICK);
+
+/// Mark the start of simulation tick `tick` (called from `sim_start`)
+pub fn set_sim_tick(tick: u64) {
+    CURRENT_SIM_TICK.store(tick, Ordering::Relaxed);
+}
+
+/// Mark the end of the current tick (called from `sim_end`)
+pub fn clear_sim_tick() {
+    CURRENT_SIM_TICK.store(NO_TICK, Ordering::Relaxed);
+}
+
+/// The tick currently being simulated, or `None` outside `FixedUpdate`
+pub fn current_sim_tick() -> Option<u64> {
+    match CURRENT_SIM_TICK.load(Ordering::Relaxed) {
+        NO_TICK => None,
+        tick => Some(tick),
+    }
+}
+
+/// Event format wrapper that prefixes `tick=N ` while a simulation tick is running.
+///
+/// Usage:
+/// ```rust,ignore
+/// fmt::layer().event_format(SimTickFormat(fmt::format().with_target(false)))
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SimTickFormat<F>(pub F);
+
+impl<S, N, F> FormatEvent<S, N> for SimTickFormat<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        if let Some(tick) = current_sim_tick() {
+            write!(writer, "tick={} ", tick)?;
+        }
+        self.0.format_event(ctx, writer, event)
+    }
+}
+
+/// Guard of the non-blocking log file writer; dropping it flushes the file
+static LOG_GUARD: Mutex<Option<WorkerGuard>> = Mutex::new(None);
+
+/// Keep the non-blocking writer's guard alive until [`flush_logs`]
+pub fn keep_log_guard(guard: WorkerGuard) {
+    *LOG_GUARD.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(guard);
+}
+
+/// Write every buffered log line to the file before returning.
+///
+/// Drops the guard from [`keep_log_guard`], which shuts the writer down: lines logged
+/// afterwards are discarded, so only call this on the way out. Returns false if there was
+/// nothing to flush (no guard, or already flushed).
+pub fn flush_logs() -> bool {
+    let guard = LOG_GUARD.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+    guard.is_some()
+}
+
+/// Flush the log file when the app is asked to exit (window close, quit button)
+pub fn flush_logs_on_exit(mut exits: MessageReader<AppExit>) {
+    if exits.read().next().is_some() {
+        flush_logs();
+    }
+}
+
+/// Flush the log file after the current panic hook has reported a panic
+pub fn flush_logs_on_panic() {
+    let report = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        report(info);
+        flush_logs();
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use std::time::Duration;
+
+    /// Fresh, empty directory under the system temp dir
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("peregrine_log_test_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write `name` with `size` bytes, last modified `age_secs` ago
+    fn write_file(dir: &Path, name: &str, size: usize, age_secs: u64) {
+        let mut file = File::create(dir.join(name)).unwrap();
+        file.write_all(&vec![b'x'; size]).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs)).unwrap();
+    }
+
+    fn remaining(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir).unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_keep_count_keeps_newest_and_ignores_foreign_files() {
+        let dir = temp_dir("count");
+        for i in 0..6 {
+            // peregrine_0 is the newest
+            write_file(&dir, &format!("peregrine_{}.log", i), 10, 100 * (i as u64 + 1));
+        }
+        // Older than every game log, but not ours
+        write_file(&dir, "notes.txt", 10, 10_000);
+        write_file(&dir, "other.log", 10, 10_000);
+        write_file(&dir, "peregrine_config.ron", 10, 10_000);
+        fs::create_dir(dir.join("peregrine_archive.log")).unwrap();
+
+        let removed = cleanup_old_logs(&dir, LogRetention::KeepCount(3));
+
+        assert_eq!(removed.len(), 3);
+        assert_eq!(remaining(&dir), vec![
+            "notes.txt", "other.log", "peregrine_0.log", "peregrine_1.log", "peregrine_2.log",
+            "peregrine_archive.log", "peregrine_config.ron",
+        ]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_keep_total_bytes_keeps_newest_that_fit() {
+        let dir = temp_dir("bytes");
+        write_file(&dir, "peregrine_new.log", 400, 10);
+        write_file(&dir, "peregrine_mid.log", 400, 20);
+        write_file(&dir, "peregrine_old.log", 400, 30);
+
+        cleanup_old_logs(&dir, LogRetention::KeepTotalBytes(1000));
+
+        assert_eq!(remaining(&dir), vec!["peregrine_mid.log", "peregrine_new.log"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cleanup_missing_dir_is_a_no_op() {
+        let dir = std::env::temp_dir().join("peregrine_log_test_does_not_exist");
+        assert!(cleanup_old_logs(&dir, LogRetention::KeepCount(0)).is_empty());
+    }
+
+    #[test]
+    fn test_flush_drains_buffered_lines_to_the_file() {
+        let dir = temp_dir("flush");
+        let path = dir.join("peregrine_flush.log");
+        let (mut writer, guard) = tracing_appender::non_blocking(File::create(&path).unwrap());
+        keep_log_guard(guard);
+        for i in 0..5000 {
+            writeln!(writer, "line {}", i).unwrap();
+        }
+
+        assert!(flush_logs());
+        let written = fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().count(), 5000, "Every buffered line should be on disk");
+        assert_eq!(written.lines().last(), Some("line 4999"));
+        assert!(!flush_logs(), "Already flushed");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_retention_from_vars() {
+        assert_eq!(LogRetention::from_vars(None, None), LogRetention::KeepCount(25));
+        assert_eq!(LogRetention::from_vars(Some("5"), None), LogRetention::KeepCount(5));
+        assert_eq!(LogRetention::from_vars(Some("lots"), None), LogRetention::KeepCount(25));
+        assert_eq!(LogRetention::from_vars(Some("5"), Some("1048576")), LogRetention::KeepTotalBytes(1_048_576));
+    }
+}
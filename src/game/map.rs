@@ -24,6 +24,68 @@ impl MapSize {
     pub fn get_height(&self) -> FixedNum {
         self.bottom_right.y - self.top_left.y
     }
+
+    /// Check whether a point lies within the map (edges inclusive)
+    pub fn contains(&self, position: FixedVec2) -> bool {
+        position.x >= self.top_left.x && position.x <= self.bottom_right.x
+            && position.y >= self.top_left.y && position.y <= self.bottom_right.y
+    }
+
+    /// Clamp a point into the map, keeping it at least `margin` away from the edges.
+    ///
+    /// If the margin is larger than half the map, the point ends up on the far edge
+    /// rather than panicking.
+    pub fn clamp_point(&self, position: FixedVec2, margin: FixedNum) -> FixedVec2 {
+        let inset = FixedVec2::new(margin, margin);
+        position.max(self.top_left + inset).min(self.bottom_right - inset)
+    }
+
+    /// Bring a point that stepped off one edge back in through the opposite one (toroidal
+    /// map). Only shifts by one map size, which covers any move shorter than the map.
+    pub fn wrap_point(&self, position: FixedVec2) -> FixedVec2 {
+        let wrap = |coord: FixedNum, min: FixedNum, max: FixedNum| {
+            if coord < min {
+                coord + (max - min)
+            } else if coord >= max {
+                coord - (max - min)
+            } else {
+                coord
+            }
+        };
+        FixedVec2::new(
+            wrap(position.x, self.top_left.x, self.bottom_right.x),
+            wrap(position.y, self.top_left.y, self.bottom_right.y),
+        )
+    }
+
+    /// `to - from` on a toroidal map: the shortest offset, which may cross a seam
+    pub fn wrapped_delta(&self, from: FixedVec2, to: FixedVec2) -> FixedVec2 {
+        let shortest = |delta: FixedNum, size: FixedNum| {
+            let half = size / FixedNum::from_num(2);
+            if delta > half {
+                delta - size
+            } else if delta < -half {
+                delta + size
+            } else {
+                delta
+            }
+        };
+        let delta = to - from;
+        FixedVec2::new(shortest(delta.x, self.get_width()), shortest(delta.y, self.get_height()))
+    }
+}
+
+/// What happens to units at the map border
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MapEdges {
+    /// Unit centers are clamped onto the border and velocity into it is dropped
+    #[default]
+    Open,
+    /// The border is solid: units collide with it and stay a radius inside the map
+    Walls,
+    /// Toroidal map: leaving one edge enters through the opposite one, and neighbor
+    /// queries and distances reach across the seam. Pathfinding does not route across it.
+    Wrap,
 }
 
 #[derive(Serialize, Deserialize)]
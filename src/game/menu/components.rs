@@ -42,6 +42,7 @@ pub enum BindableAction {
     SpawnBatch,
     Pause,
     ToggleHealthBars,
+    StopUnits,
 }
 
 impl BindableAction {
@@ -59,6 +60,7 @@ impl BindableAction {
             BindableAction::SpawnBatch => "Spawn Batch".to_string(),
             BindableAction::Pause => "Pause".to_string(),
             BindableAction::ToggleHealthBars => "Toggle Health Bars".to_string(),
+            BindableAction::StopUnits => "Stop Units".to_string(),
         }
     }
 }
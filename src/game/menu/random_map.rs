@@ -46,6 +46,7 @@ pub fn handle_random_map_dialog(
                             num_obstacles: obstacles,
                             min_radius: size * 0.5,
                             max_radius: size * 1.5,
+                            seed: rand::random(),
                         });
                         commands.insert_resource(TargetGameState(GameState::InGame));
                         next_state.set(GameState::Loading);
@@ -62,6 +62,7 @@ pub fn setup_settings_menu(
                 (BindableAction::SpawnBatch, config.key_spawn_batch),
                 (BindableAction::Pause, config.key_pause),
                 (BindableAction::ToggleHealthBars, config.key_toggle_health_bars),
+                (BindableAction::StopUnits, config.key_stop_units),
             ];
 
             for (action, key) in actions {
@@ -224,6 +225,7 @@ pub fn handle_rebinding(
                         BindableAction::SpawnBatch => config.key_spawn_batch = new_key,
                         BindableAction::Pause => config.key_pause = new_key,
                         BindableAction::ToggleHealthBars => config.key_toggle_health_bars = new_key,
+                        BindableAction::StopUnits => config.key_stop_units = new_key,
                     }
                 }
             }
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
-use std::collections::{BTreeMap, BinaryHeap};
-use crate::game::fixed_math::FixedNum;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use crate::game::fixed_math::{FixedVec2, FixedNum};
 use super::types::{CLUSTER_SIZE, Portal, ClusterIslandId, IslandId, MAX_ISLANDS};
 use super::cluster::Cluster;
 use std::cmp::Reverse;
@@ -74,6 +74,13 @@ pub struct HierarchicalGraph {
     /// ARENA: Portal-to-portal connections (O(1) access by portal ID)
     /// portals[id] -> Vec of (neighbor_portal_id, cost)
     pub portal_connections: Vec<Vec<(usize, FixedNum)>>,
+    
+    /// ARENA: Whether each island is a sealed pocket, by linear island ID: it routes to no
+    /// other island and has fewer walkable cells than the main network of islands. A cut-off
+    /// landmass spanning several clusters routes between its own islands and isn't one.
+    /// Filled in with the routing table.
+    #[serde(default)]
+    pub sealed_pockets: Vec<bool>,
 }
 
 impl Default for HierarchicalGraph {
@@ -100,6 +107,7 @@ impl HierarchicalGraph {
             next_portal_id: 0,
             portal_island_map: Vec::new(),
             portal_connections: Vec::new(),
+            sealed_pockets: Vec::new(),
         }
     }
     
@@ -216,6 +224,7 @@ impl HierarchicalGraph {
         for route in &mut self.island_routing_storage {
             *route = NO_ROUTE;
         }
+        self.sealed_pockets.clear();
         
         self.initialized = false;
     }
@@ -246,6 +255,8 @@ impl HierarchicalGraph {
             self.build_routing_for_island(source);
         }
         
+        self.find_sealed_pockets(&cluster_islands);
+        
         // Count actual routes (exclude NO_ROUTE entries)
         let total_entries = self.island_routing_storage.iter().filter(|&&r| r != NO_ROUTE).count();
         info!(
@@ -255,6 +266,40 @@ impl HierarchicalGraph {
         );
     }
     
+    /// Fill `sealed_pockets` from the routing table
+    fn find_sealed_pockets(&mut self, cluster_islands: &[ClusterIslandId]) {
+        let mut area = vec![0; self.total_island_capacity];
+        for &island in cluster_islands {
+            let Some(cluster) = self.get_cluster(island.cluster.0, island.cluster.1) else { continue };
+            area[self.island_to_linear_id(island)] = cluster.regions[..cluster.region_count].iter()
+                .flatten()
+                .filter(|region| region.island == island.island)
+                .map(|region| region.cell_count())
+                .sum();
+        }
+        
+        // Walkable cells of each island plus every island it has a route to
+        let mut reach = vec![0; self.total_island_capacity];
+        for &source in cluster_islands {
+            reach[self.island_to_linear_id(source)] = cluster_islands.iter()
+                .filter(|&&dest| dest == source || self.get_island_route(source, dest).is_some())
+                .map(|&dest| area[self.island_to_linear_id(dest)])
+                .sum();
+        }
+        let main_reach = reach.iter().copied().max().unwrap_or(0);
+        
+        self.sealed_pockets = vec![false; self.total_island_capacity];
+        for &island in cluster_islands {
+            let id = self.island_to_linear_id(island);
+            self.sealed_pockets[id] = reach[id] == area[id] && reach[id] < main_reach;
+        }
+    }
+    
+    /// Whether `island` is a walled-in pocket cut off from the rest of the map, see `sealed_pockets`
+    pub fn is_sealed_pocket(&self, island: ClusterIslandId) -> bool {
+        self.sealed_pockets.get(self.island_to_linear_id(island)).copied().unwrap_or(false)
+    }
+    
     /// Build routing from one (cluster, island) to all others
     fn build_routing_for_island(&mut self, source: ClusterIslandId) {
         let mut distances: BTreeMap<ClusterIslandId, FixedNum> = BTreeMap::new();
@@ -360,6 +405,60 @@ impl HierarchicalGraph {
         self.get_island_route(current, goal)
     }
     
+    /// The (cluster, island) reached by crossing `portal_id` into the neighboring cluster
+    pub fn portal_exit(&self, portal_id: usize) -> Option<ClusterIslandId> {
+        let portal = self.portals.get(portal_id)?;
+        self.portal_connections.get(portal_id)?.iter().find_map(|&(other_id, _cost)| {
+            let other = self.portals.get(other_id)?;
+            if other.cluster == portal.cluster {
+                return None;
+            }
+            let island = (*self.portal_island_map.get(other_id)?)?;
+            Some(ClusterIslandId::new(other.cluster, island))
+        })
+    }
+
+    /// Full ordered portal sequence from `source` to `goal`, following the routing table
+    /// one hop at a time.
+    ///
+    /// Returns `Some(vec![])` if both are the same island and `None` if the goal is
+    /// unreachable (or the table loops, which means it is stale).
+    pub fn route_portals(&self, source: ClusterIslandId, goal: ClusterIslandId) -> Option<Vec<usize>> {
+        let mut route = Vec::new();
+        let mut visited = BTreeSet::new();
+        let mut current = source;
+        while current != goal {
+            if !visited.insert(current) {
+                debug!("[ROUTING] Loop at {:?} while routing {:?} -> {:?}", current, source, goal);
+                return None;
+            }
+            let portal_id = self.get_next_portal_for_island(current, goal)?;
+            route.push(portal_id);
+            current = self.portal_exit(portal_id)?;
+        }
+        Some(route)
+    }
+
+    /// The (cluster, island) containing `pos`.
+    ///
+    /// Regions live in cluster-local space, so the cell center is looked up in local
+    /// coordinates (as `NavigationLookup` does). `None` off the map, on a blocked cell,
+    /// or where no cluster has been built.
+    pub fn island_at(&self, pos: FixedVec2, flow_field: &crate::game::structures::FlowField) -> Option<ClusterIslandId> {
+        use super::region_decomposition::{get_region_id, world_to_cluster_local};
+
+        let (gx, gy) = flow_field.world_to_grid(pos)?;
+        if !flow_field.is_walkable(gx, gy) {
+            return None;
+        }
+        let cluster_id = (gx / CLUSTER_SIZE, gy / CLUSTER_SIZE);
+        let cluster = self.get_cluster(cluster_id.0, cluster_id.1)?;
+        let local = world_to_cluster_local(pos, cluster_id, flow_field)?;
+        let region = get_region_id(&cluster.regions, cluster.region_count, local)?;
+        let island = cluster.regions[region.0 as usize].as_ref()?.island;
+        Some(ClusterIslandId::new(cluster_id, island))
+    }
+    
     /// Populate neighbor_connectivity: link each island to portals in each direction
     /// 
     /// For each cluster, determines which portals each island can access.
@@ -370,68 +469,7 @@ impl HierarchicalGraph {
         
         info!("[CONNECTIVITY] Linking islands to their boundary portals...");
         
-        // Collect all clusters first to avoid borrow checker issues
-        let cluster_ids: Vec<_> = self.clusters_iter().map(|(id, _)| id).collect();
-        
-        // For each cluster
-        for &cluster_id in &cluster_ids {
-            let (cx, cy) = cluster_id;
-            
-            // For each portal owned by this cluster
-            for (_portal_id, portal) in self.portals.iter().enumerate() {
-                if portal.cluster != cluster_id {
-                    continue; // Portal belongs to a different cluster
-                }
-                
-                // Determine which direction this portal is in (relative to cluster)
-                let cluster_x_tiles = cluster_id.0 * CLUSTER_SIZE;
-                let cluster_y_tiles = cluster_id.1 * CLUSTER_SIZE;
-                let cluster_max_x = cluster_x_tiles + CLUSTER_SIZE - 1;
-                let cluster_max_y = cluster_y_tiles + CLUSTER_SIZE - 1;
-                
-                // Detect portal direction (including diagonals) - check corners first
-                let _direction = if portal.node.x == cluster_x_tiles && portal.node.y == cluster_y_tiles {
-                    Direction::SouthWest  // Bottom-left corner
-                } else if portal.node.x == cluster_max_x && portal.node.y == cluster_y_tiles {
-                    Direction::SouthEast  // Bottom-right corner
-                } else if portal.node.x == cluster_x_tiles && portal.node.y == cluster_max_y {
-                    Direction::NorthWest  // Top-left corner
-                } else if portal.node.x == cluster_max_x && portal.node.y == cluster_max_y {
-                    Direction::NorthEast  // Top-right corner
-                } else if portal.node.y == cluster_y_tiles {
-                    Direction::South  // Bottom edge
-                } else if portal.node.y == cluster_max_y {
-                    Direction::North  // Top edge
-                } else if portal.node.x == cluster_x_tiles {
-                    Direction::West  // Left edge
-                } else if portal.node.x == cluster_max_x {
-                    Direction::East  // Right edge
-                } else {
-                    continue; // Portal not on cluster edge or corner (shouldn't happen)
-                };
-                
-                // Find which island can access this portal
-                // Convert portal position to world coordinates, then to cluster-local
-                let portal_world = flow_field.grid_to_world(portal.node.x, portal.node.y);
-                
-                if let Some(portal_local) = world_to_cluster_local(portal_world, cluster_id, flow_field) {
-                    if let Some(cluster) = self.get_cluster(cx, cy) {
-                        if let Some(region_id) = get_region_id(&cluster.regions, cluster.region_count, portal_local) {
-                            // Find which island this region belongs to
-                            if let Some(region) = &cluster.regions[region_id.0 as usize] {
-                                let _island_id = region.island;
-                                
-                                // Link this island to this portal in this direction
-                                // Note: We need to modify the cluster, so we'll collect updates first
-                                // For now, we'll just track (cluster_id, island_idx, direction, portal_id)
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Now apply updates (need to do this in a second pass to avoid borrow checker issues)
+        // Collect updates first, then apply them (the clusters are borrowed while scanning)
         let mut updates: Vec<((usize, usize), usize, Direction, usize, IslandId)> = Vec::new();
         
         for (cluster_id, cluster) in self.clusters_iter() {
@@ -440,30 +478,11 @@ impl HierarchicalGraph {
                     continue;
                 }
                 
-                let cluster_x_tiles = cluster_id.0 * CLUSTER_SIZE;
-                let cluster_y_tiles = cluster_id.1 * CLUSTER_SIZE;
-                let cluster_max_x = cluster_x_tiles + CLUSTER_SIZE - 1;
-                let cluster_max_y = cluster_y_tiles + CLUSTER_SIZE - 1;
-                
-                // Detect portal direction - check corners first, then edges
-                let direction = if portal.node.x == cluster_max_x && portal.node.y == cluster_max_y {
-                    Direction::NorthEast  // Top-right corner
-                } else if portal.node.x == cluster_x_tiles && portal.node.y == cluster_max_y {
-                    Direction::NorthWest  // Top-left corner
-                } else if portal.node.x == cluster_max_x && portal.node.y == cluster_y_tiles {
-                    Direction::SouthEast  // Bottom-right corner
-                } else if portal.node.x == cluster_x_tiles && portal.node.y == cluster_y_tiles {
-                    Direction::SouthWest  // Bottom-left corner
-                } else if portal.node.y == cluster_max_y {
-                    Direction::North  // Top edge
-                } else if portal.node.y == cluster_y_tiles {
-                    Direction::South  // Bottom edge
-                } else if portal.node.x == cluster_max_x {
-                    Direction::East  // Right edge
-                } else if portal.node.x == cluster_x_tiles {
-                    Direction::West  // Left edge
-                } else {
-                    continue; // Portal not on this cluster's boundary
+                // The side of the cluster the portal leads out of, from the cluster it connects
+                // to. Its tile alone can't tell: in an edge cluster one or two tiles thick
+                // (maps that aren't a multiple of CLUSTER_SIZE) side portals sit on corners.
+                let Some(direction) = self.portal_direction(portal_id) else {
+                    continue; // Portal doesn't cross into a neighboring cluster
                 };
                 
                 let portal_world = flow_field.grid_to_world(portal.node.x, portal.node.y);
@@ -533,6 +552,17 @@ impl HierarchicalGraph {
         info!("[CONNECTIVITY] Populated neighbor_connectivity for {} clusters", cluster_count);
     }
     
+    /// Direction from `portal_id`'s cluster to the neighboring cluster it crosses into
+    fn portal_direction(&self, portal_id: usize) -> Option<super::types::Direction> {
+        let portal = self.portals.get(portal_id)?;
+        self.portal_connections.get(portal_id)?.iter()
+            .filter_map(|&(other_id, _)| self.portals.get(other_id))
+            .find_map(|other| super::types::Direction::from_offset(
+                other.cluster.0 as isize - portal.cluster.0 as isize,
+                other.cluster.1 as isize - portal.cluster.1 as isize,
+            ))
+    }
+
     /// Populate NavigationRouting resource with routing tables from graph
     /// 
     /// Copies data from HierarchicalGraph into NavigationRouting arenas:
@@ -636,6 +666,24 @@ impl HierarchicalGraph {
         flow_field: &crate::game::structures::FlowField, 
         nav_lookup: Option<&mut super::navigation_lookup::NavigationLookup>,
         nav_routing: Option<&mut super::navigation_routing::NavigationRouting>
+    ) {
+        self.build_graph_with_regions_sync_progress(flow_field, nav_lookup, nav_routing, &mut |_, _, _| {});
+    }
+
+    /// [`build_graph_with_regions_sync`](Self::build_graph_with_regions_sync), reporting
+    /// progress to `progress(phase, done, total)` for callers outside Bevy.
+    ///
+    /// Called after each unit of work: per cluster in the region and connectivity phases,
+    /// per portal pass (vertical, horizontal, diagonal), and once for each remaining phase.
+    /// Phases arrive in [`GraphBuildPhase`] order and `done` counts up to `total` within
+    /// each; the navigation phases are skipped when no lookup/routing is passed. Nothing is
+    /// reported for an empty flow field.
+    pub fn build_graph_with_regions_sync_progress(
+        &mut self,
+        flow_field: &crate::game::structures::FlowField,
+        nav_lookup: Option<&mut super::navigation_lookup::NavigationLookup>,
+        nav_routing: Option<&mut super::navigation_routing::NavigationRouting>,
+        progress: &mut dyn FnMut(GraphBuildPhase, usize, usize),
     ) {
         use super::region_decomposition::decompose_cluster_into_regions;
         use super::region_decomposition::build_region_lookup_grid;
@@ -678,6 +726,7 @@ impl HierarchicalGraph {
                 build_region_lookup_grid(&mut cluster, cluster_id, flow_field);
                 
                 self.set_cluster(cx, cy, cluster);
+                progress(GraphBuildPhase::Regions, cy * width_clusters + cx + 1, width_clusters * height_clusters);
             }
         }
         
@@ -686,11 +735,12 @@ impl HierarchicalGraph {
         
         // Phase 2: Build region connectivity and local routing within each cluster
         let cluster_ids: Vec<_> = self.clusters_iter().map(|(id, _)| id).collect();
-        for &(cx, cy) in &cluster_ids {
+        for (done, &(cx, cy)) in cluster_ids.iter().enumerate() {
             if let Some(cluster) = self.get_cluster_mut(cx, cy) {
                 build_region_connectivity(cluster);
                 identify_islands(cluster);
             }
+            progress(GraphBuildPhase::Connectivity, done + 1, cluster_ids.len());
         }
         
         let total_islands: usize = self.clusters_iter().map(|(_, c)| c.island_count).sum();
@@ -712,9 +762,7 @@ impl HierarchicalGraph {
                     
                     let mut start_segment = None;
                     for y in min_y..max_y {
-                        let idx1 = flow_field.get_index(x1, y);
-                        let idx2 = flow_field.get_index(x2, y);
-                        let walkable = flow_field.cost_field[idx1] != 255 && flow_field.cost_field[idx2] != 255;
+                        let walkable = flow_field.is_walkable(x1, y) && flow_field.is_walkable(x2, y);
 
                         if walkable {
                             if start_segment.is_none() {
@@ -733,6 +781,8 @@ impl HierarchicalGraph {
                 }
             }
 
+            progress(GraphBuildPhase::Portals, 1, 3);
+
             // Horizontal portals
             for cx in 0..width_clusters {
                 for cy in 0..height_clusters.saturating_sub(1) {
@@ -745,9 +795,7 @@ impl HierarchicalGraph {
                     
                     let mut start_segment = None;
                     for x in min_x..max_x {
-                        let idx1 = flow_field.get_index(x, y1);
-                        let idx2 = flow_field.get_index(x, y2);
-                        let walkable = flow_field.cost_field[idx1] != 255 && flow_field.cost_field[idx2] != 255;
+                        let walkable = flow_field.is_walkable(x, y1) && flow_field.is_walkable(x, y2);
 
                         if walkable {
                             if start_segment.is_none() {
@@ -766,6 +814,8 @@ impl HierarchicalGraph {
                 }
             }
             
+            progress(GraphBuildPhase::Portals, 2, 3);
+
             // Diagonal portals at cluster corners
             // Each of the 4 clusters at a corner gets its own diagonal portal (like edge portals)
             // But there are only 2 diagonal paths: NE-SW and NW-SE
@@ -786,13 +836,8 @@ impl HierarchicalGraph {
                     let mut walkable = false;
                     for dx in 0..2 {
                         for dy in 0..2 {
-                            let x = check_x.saturating_sub(1) + dx;
-                            let y = check_y.saturating_sub(1) + dy;
-                            if x < flow_field.width && y < flow_field.height {
-                                let idx = flow_field.get_index(x, y);
-                                if flow_field.cost_field[idx] != 255 {
-                                    walkable = true;
-                                }
+                            if flow_field.is_walkable(check_x.saturating_sub(1) + dx, check_y.saturating_sub(1) + dy) {
+                                walkable = true;
                             }
                         }
                     }
@@ -808,17 +853,12 @@ impl HierarchicalGraph {
                     let sw_y = (cy + 1) * CLUSTER_SIZE;      // Min y of cluster (cx+1, cy+1)
                     
                     // Only create if both positions are in bounds and walkable
-                    if ne_x < flow_field.width && ne_y < flow_field.height &&
-                       sw_x < flow_field.width && sw_y < flow_field.height {
-                        let idx_ne = flow_field.get_index(ne_x, ne_y);
-                        let idx_sw = flow_field.get_index(sw_x, sw_y);
-                        if flow_field.cost_field[idx_ne] != 255 && flow_field.cost_field[idx_sw] != 255 {
-                            super::cluster::create_portal_diagonal(
-                                self, ne_x, ne_y, cx, cy,
-                                sw_x, sw_y, cx + 1, cy + 1,
-                                flow_field,
-                            );
-                        }
+                    if flow_field.is_walkable(ne_x, ne_y) && flow_field.is_walkable(sw_x, sw_y) {
+                        super::cluster::create_portal_diagonal(
+                            self, ne_x, ne_y, cx, cy,
+                            sw_x, sw_y, cx + 1, cy + 1,
+                            flow_field,
+                        );
                     }
                     
                     // Path 2: NW-SE diagonal  
@@ -828,29 +868,27 @@ impl HierarchicalGraph {
                     let se_x = (cx + 1) * CLUSTER_SIZE - 1;  // Max x of cluster (cx, cy+1)
                     let se_y = (cy + 1) * CLUSTER_SIZE;      // Min y of cluster (cx, cy+1)
                     
-                    if nw_x < flow_field.width && nw_y < flow_field.height &&
-                       se_x < flow_field.width && se_y < flow_field.height {
-                        let idx_nw = flow_field.get_index(nw_x, nw_y);
-                        let idx_se = flow_field.get_index(se_x, se_y);
-                        if flow_field.cost_field[idx_nw] != 255 && flow_field.cost_field[idx_se] != 255 {
-                            super::cluster::create_portal_diagonal(
-                                self, nw_x, nw_y, cx + 1, cy,
-                                se_x, se_y, cx, cy + 1,
-                                flow_field,
-                            );
-                        }
+                    if flow_field.is_walkable(nw_x, nw_y) && flow_field.is_walkable(se_x, se_y) {
+                        super::cluster::create_portal_diagonal(
+                            self, nw_x, nw_y, cx + 1, cy,
+                            se_x, se_y, cx, cy + 1,
+                            flow_field,
+                        );
                     }
                 }
             }
             
+            progress(GraphBuildPhase::Portals, 3, 3);
             info!("[REGION BUILD] Created {} portals between clusters", self.portals.len());
         }
         
         // Phase 3.5: Link islands to their accessible portals (neighbor_connectivity)
         self.populate_island_portal_connectivity(flow_field);
+        progress(GraphBuildPhase::IslandPortals, 1, 1);
         
         // Phase 4: Build island-aware routing table
         self.build_island_routing_table();
+        progress(GraphBuildPhase::RoutingTable, 1, 1);
         
         self.initialized = true;
         info!("[REGION BUILD] Graph build complete!");
@@ -858,11 +896,13 @@ impl HierarchicalGraph {
         // Phase 5: Populate navigation lookup for O(1) queries
         if let Some(lookup) = nav_lookup {
             lookup.populate_from_graph(self, flow_field);
+            progress(GraphBuildPhase::NavigationLookup, 1, 1);
         }
         
         // Phase 6: Populate navigation routing tables for O(1) path queries
         if let Some(routing) = nav_routing {
             self.populate_navigation_routing(routing);
+            progress(GraphBuildPhase::NavigationRouting, 1, 1);
         }
     }
     
@@ -897,6 +937,71 @@ impl HierarchicalGraph {
     pub fn cluster_count(&self) -> usize {
         self.cluster_storage.iter().filter(|c| c.is_some()).count()
     }
+
+    /// Graphviz (DOT) rendering of the island graph, for inspecting map connectivity offline
+    /// (`dot -Tsvg graph.dot -o graph.svg`).
+    ///
+    /// One node per (cluster, island), grouped in a box per cluster; one directed edge per
+    /// portal crossing into a neighboring cluster, labelled with the portal ID and its
+    /// connection cost. Portal-to-portal links inside a cluster are left out.
+    pub fn to_dot(&self) -> String {
+        use std::fmt::Write;
+
+        let node_id = |island: ClusterIslandId| format!("\"{}_{}_{}\"", island.cluster.0, island.cluster.1, island.island.0);
+        let mut dot = String::from("digraph navigation {\n    node [shape=ellipse];\n");
+        for ((cx, cy), cluster) in self.clusters_iter() {
+            let _ = writeln!(dot, "    subgraph cluster_{}_{} {{", cx, cy);
+            let _ = writeln!(dot, "        label=\"cluster ({}, {})\";", cx, cy);
+            for island in 0..cluster.island_count {
+                let id = ClusterIslandId::new((cx, cy), IslandId(island as u8));
+                let _ = writeln!(dot, "        {} [label=\"({}, {}) island {}\"];", node_id(id), cx, cy, island);
+            }
+            dot.push_str("    }\n");
+        }
+        for (portal_id, portal) in self.portals.iter().enumerate() {
+            let Some(Some(island)) = self.portal_island_map.get(portal_id) else { continue };
+            let from = ClusterIslandId::new(portal.cluster, *island);
+            for &(other_id, cost) in self.portal_connections.get(portal_id).into_iter().flatten() {
+                let Some(other) = self.portals.get(other_id) else { continue };
+                if other.cluster == portal.cluster {
+                    continue;
+                }
+                let Some(Some(other_island)) = self.portal_island_map.get(other_id) else { continue };
+                let to = ClusterIslandId::new(other.cluster, *other_island);
+                let _ = writeln!(dot, "    {} -> {} [label=\"{}: {}\"];", node_id(from), node_id(to), portal_id, cost);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Whether a unit at `a` can walk to `b`, without building a route.
+///
+/// Both positions must be walkable and their islands connected, either directly (same
+/// cluster island) or through the global island routing table.
+pub fn same_island(a: FixedVec2, b: FixedVec2, graph: &HierarchicalGraph, flow_field: &crate::game::structures::FlowField) -> bool {
+    let (Some(a), Some(b)) = (graph.island_at(a, flow_field), graph.island_at(b, flow_field)) else {
+        return false;
+    };
+    a == b || graph.get_island_route(a, b).is_some()
+}
+
+/// Stage of [`HierarchicalGraph::build_graph_with_regions_sync_progress`], in build order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GraphBuildPhase {
+    /// Clusters decomposed into convex regions
+    Regions,
+    /// Region connectivity and islands within each cluster
+    Connectivity,
+    /// Portals between clusters
+    Portals,
+    /// Islands linked to the portals they can reach
+    IslandPortals,
+    /// Island-aware routing table
+    RoutingTable,
+    NavigationLookup,
+    NavigationRouting,
 }
 
 /// Statistics about the pathfinding graph
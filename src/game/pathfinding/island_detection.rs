@@ -6,14 +6,15 @@ use bevy::prelude::*;
 
 /// Identify islands (connected components) within a cluster using BOUNDARY-FOCUSED approach
 /// 
-/// **CRITICAL DESIGN:** Islands represent "sides of cross-cluster obstacles", NOT every disconnected pocket
+/// **CRITICAL DESIGN:** Islands represent "sides of cross-cluster obstacles", NOT every interior region
 /// 
 /// Algorithm:
 /// 1. Identify boundary regions (touch cluster edges or contain inter-cluster portals)
 /// 2. Create islands from boundary regions using tortuosity-based flood fill
-/// 3. Merge interior isolated regions into nearest boundary island
+/// 3. Merge interior regions into the nearest boundary island they connect to
 /// 
-/// This prevents explosion of isolated interior pockets into separate islands.
+/// This prevents explosion of interior regions into separate islands. Only a sealed interior
+/// pocket, connected to no boundary region, gets an island of its own.
 pub(crate) fn identify_islands(cluster: &mut Cluster) {
     if cluster.region_count == 0 {
         cluster.island_count = 0;
@@ -134,32 +135,39 @@ pub(crate) fn identify_islands(cluster: &mut Cluster) {
         island_count += 1;
     }
     
-    // PHASE 3: Merge interior (non-boundary) regions into nearest boundary island
-    let mut interior_assignments = Vec::new();
-    
+    // PHASE 3: Merge interior (non-boundary) regions into the nearest boundary island they
+    // can walk to. Interior regions walled off from every boundary region (a sealed pocket)
+    // get an island of their own, so routing knows nothing outside reaches them.
     for i in 0..cluster.region_count {
-        if assigned[i] {
+        if assigned[i] || cluster.regions[i].is_none() {
             continue; // Already assigned to a boundary island
         }
         
-        // This is an interior region - find nearest boundary island
-        if let Some(interior_region) = &cluster.regions[i] {
-            let nearest_island = find_nearest_island(interior_region, cluster, island_count);
-            interior_assignments.push((i, interior_region.id, nearest_island));
-            assigned[i] = true;
-        }
-    }
-    
-    // Now apply the assignments
-    for (region_idx, region_id, nearest_island) in interior_assignments {
-        // Assign to nearest island
-        if let Some(region_mut) = &mut cluster.regions[region_idx] {
-            region_mut.island = nearest_island;
-        }
+        let interior_region = cluster.regions[i].as_ref().unwrap();
+        let island_id = match find_nearest_island(interior_region, cluster, island_count, Some(i)) {
+            Some(island_id) => island_id,
+            None if island_count < MAX_ISLANDS => {
+                // The pocket: this region and every other unassigned region it reaches
+                let island_id = IslandId(island_count as u8);
+                let representative = interior_region.bounds.center();
+                cluster.islands[island_count] = Some(Island { id: island_id, representative, regions: SmallVec::new() });
+                island_count += 1;
+                island_id
+            }
+            None => find_nearest_island(interior_region, cluster, island_count, None).unwrap_or(IslandId(0)),
+        };
         
-        // Add to island's region list
-        if let Some(island) = &mut cluster.islands[nearest_island.0 as usize] {
-            island.regions.push(region_id);
+        let pocket: Vec<usize> = (i..cluster.region_count)
+            .filter(|&j| j == i || (!assigned[j] && cluster.local_routing[i][j] != NO_PATH && cluster.regions[j].is_some()))
+            .collect();
+        for j in pocket {
+            assigned[j] = true;
+            let region = cluster.regions[j].as_mut().unwrap();
+            region.island = island_id;
+            let region_id = region.id;
+            if let Some(island) = &mut cluster.islands[island_id.0 as usize] {
+                island.regions.push(region_id);
+            }
         }
     }
     
@@ -194,31 +202,39 @@ fn get_cluster_bounds(_cluster_id: (usize, usize)) -> super::types::Rect {
 /// 1. Touch the cluster's edge (x=0, x=CLUSTER_SIZE, y=0, y=CLUSTER_SIZE)
 /// 2. Contain or are adjacent to inter-cluster portals (handled via portals connectivity)
 fn is_boundary_region(region: &super::types::Region, cluster_bounds: &super::types::Rect) -> bool {
+    // Region bounds run through cell centers, so a region in the edge cells is half a cell in
     let epsilon = FixedNum::from_num(0.5);
     
     // Check if region touches any cluster edge
-    let touches_left = (region.bounds.min.x - cluster_bounds.min.x).abs() < epsilon;
-    let touches_right = (region.bounds.max.x - cluster_bounds.max.x).abs() < epsilon;
-    let touches_bottom = (region.bounds.min.y - cluster_bounds.min.y).abs() < epsilon;
-    let touches_top = (region.bounds.max.y - cluster_bounds.max.y).abs() < epsilon;
+    let touches_left = (region.bounds.min.x - cluster_bounds.min.x).abs() <= epsilon;
+    let touches_right = (region.bounds.max.x - cluster_bounds.max.x).abs() <= epsilon;
+    let touches_bottom = (region.bounds.min.y - cluster_bounds.min.y).abs() <= epsilon;
+    let touches_top = (region.bounds.max.y - cluster_bounds.max.y).abs() <= epsilon;
     
     touches_left || touches_right || touches_bottom || touches_top
 }
 
 /// Find the nearest boundary island to an interior region
+///
+/// With `walkable_from`, only islands with a region the interior region (by that index) has
+/// a local route to count; `None` if there are none.
 fn find_nearest_island(
     interior_region: &super::types::Region,
     cluster: &Cluster,
     island_count: usize,
-) -> IslandId {
+    walkable_from: Option<usize>,
+) -> Option<IslandId> {
     let interior_center = interior_region.bounds.center();
-    let mut nearest_island = IslandId(0);
-    let mut min_dist_sq = FixedNum::from_num(1_000_000.0); // Very large value
+    let mut nearest_island = None;
+    let mut min_dist_sq = FixedNum::MAX;
     
     for i in 0..island_count {
         if let Some(island) = &cluster.islands[i] {
             // Find closest region in this island
             for &region_id in &island.regions {
+                if walkable_from.is_some_and(|from| cluster.local_routing[from][region_id.0 as usize] == NO_PATH) {
+                    continue;
+                }
                 if let Some(region) = &cluster.regions[region_id.0 as usize] {
                     let center = region.bounds.center();
                     let dx = center.x - interior_center.x;
@@ -227,7 +243,7 @@ fn find_nearest_island(
                     
                     if dist_sq < min_dist_sq {
                         min_dist_sq = dist_sq;
-                        nearest_island = island.id;
+                        nearest_island = Some(island.id);
                     }
                 }
             }
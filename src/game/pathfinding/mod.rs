@@ -7,6 +7,7 @@ mod debug;
 mod navigation_lookup;
 mod navigation_routing;
 mod resources;
+mod preference;
 
 // Region-based pathfinding modules
 mod region_decomposition;
@@ -20,19 +21,21 @@ mod tests;
 // PUBLIC API
 // ============================================================================
 
-pub use types::{PathRequest, Path, PathState, Portal, Node, CLUSTER_SIZE, Region, RegionId, IslandId, ClusterId, Direction, GoalNavCell};
-pub use graph::{HierarchicalGraph, GraphStats};
-pub use systems::process_path_requests;
+pub use types::{PathRequest, PathPriority, PathFailed, PathFailReason, Path, PathState, Portal, Node, CLUSTER_SIZE, Region, RegionId, IslandId, ClusterId, Direction, GoalNavCell};
+pub use graph::{HierarchicalGraph, GraphStats, GraphBuildPhase, same_island};
+pub use systems::{process_path_requests, invalidate_integration_field_cache, snap_to_walkable, relocate_goal_cell, resolve_goal, resolve_path_goal, ResolvedGoal, GOAL_SNAP_RADIUS};
 pub use navigation::{follow_path, sweep_inactive_paths};
+pub use preference::{TerrainPreference, CostBand, PortalRoute, preferred_portal_route};
 pub use navigation_lookup::NavigationLookup;
 pub use navigation_routing::NavigationRouting;
-pub use resources::ActivePathSet;
+pub use resources::{ActivePathSet, PendingPathRequests, PathRequestStats, PathFailureCounts, IntegrationFieldCache, SharedIntegrationField, DEFAULT_INTEGRATION_CACHE_CAPACITY};
 
 // ============================================================================
 // CRATE-INTERNAL API
 // ============================================================================
 
 pub(crate) use types::{ClusterIslandId, NO_PATH};
+pub(crate) use resources::EntityIndex;
 pub(crate) use region_decomposition::{get_region_id, get_region_id_by_world_pos, get_island_id_by_world_pos, world_to_cluster_local, point_in_cluster, point_in_region};
 
 use bevy::prelude::*;
@@ -43,15 +46,15 @@ pub struct PathfindingPlugin;
 impl Plugin for PathfindingPlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<PathRequest>();
+        app.add_message::<PathFailed>();
         app.init_resource::<HierarchicalGraph>();
         app.init_resource::<NavigationLookup>();        
         app.init_resource::<NavigationRouting>();
         app.init_resource::<ActivePathSet>();  // PERF: Track active paths for O(active) iteration
+        app.init_resource::<PendingPathRequests>();
+        app.init_resource::<PathRequestStats>();
+        app.init_resource::<IntegrationFieldCache>();
         app.add_systems(Update, (debug::draw_graph_gizmos).run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
-        app.add_systems(FixedUpdate, (
-            systems::process_path_requests,
-            navigation::follow_path,
-            navigation::sweep_inactive_paths,  // Batch cleanup after navigation
-        ).chain().run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
+        // The FixedUpdate systems are part of the simulation tick (simulation::schedule)
     }
 }
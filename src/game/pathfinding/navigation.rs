@@ -231,7 +231,7 @@ pub fn OLD_follow_path(
     
     let speed = sim_config.unit_speed;
     let max_force = sim_config.steering_force;
-    let dt = FixedNum::ONE / FixedNum::from_num(sim_config.tick_rate);
+    let dt = sim_config.fixed_delta();
     let step_dist = speed * dt;
     let threshold = if step_dist > sim_config.arrival_threshold { step_dist } else { sim_config.arrival_threshold };
     let threshold_sq = threshold * threshold;
@@ -316,6 +316,7 @@ pub fn OLD_follow_path(
                 next_expected_region,
                 current_target,
                 is_inter_cluster_target,
+                portal_route: _,
             } => {
                 // PERF OPTIMIZATION: Use cached navigation state with fast invalidation checks
                 
@@ -458,13 +459,12 @@ pub fn follow_path(
     map_flow_field: Res<MapFlowField>,
     graph: Res<HierarchicalGraph>,
 ) {
-    use crate::game::fixed_math::FixedNum;
     use crate::game::simulation::physics::seek;
     use super::types::LocalRegionId;
     
     let speed = sim_config.unit_speed;
     let max_force = sim_config.steering_force;
-    let dt = FixedNum::ONE / FixedNum::from_num(sim_config.tick_rate);
+    let dt = sim_config.fixed_delta();
     let step_dist = speed * dt;
     let threshold = if step_dist > sim_config.arrival_threshold { step_dist } else { sim_config.arrival_threshold };
     let threshold_sq = threshold * threshold;
@@ -491,6 +491,7 @@ pub fn follow_path(
                 next_expected_region: _,
                 current_target: _,
                 is_inter_cluster_target: _,
+                portal_route,
             } => {
                 // Convert current position to grid coordinates (only 1 conversion needed!)
                 let Some((grid_x, grid_y)) = map_flow_field.0.world_to_grid(pos.0) else {
@@ -579,11 +580,14 @@ pub fn follow_path(
                     continue;
                 }
                 
-                // CASE 3: Different cluster - use island routing to find portal
-                let Some(next_portal_id) = nav_routing.island_routing.find_next_portal(
+                // CASE 3: Different cluster - take the unit's own route if it has one and is
+                // still on it, otherwise use island routing to find portal
+                let current_cluster = (grid_x / CLUSTER_SIZE, grid_y / CLUSTER_SIZE);
+                let route_portal = portal_route.as_mut().and_then(|route| route.next_portal(&graph, current_cluster));
+                let Some(next_portal_id) = route_portal.or_else(|| nav_routing.island_routing.find_next_portal(
                     current_nav.island_idx,
                     goal_nav.island_idx,
-                ) else {
+                )) else {
                     warn!("No path from island {:?} to island {:?}", 
                           current_nav.island_idx, goal_nav.island_idx);
                     *path = super::Path::Blocked;
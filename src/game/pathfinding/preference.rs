@@ -0,0 +1,195 @@
+//! Per-entity terrain cost preferences.
+//!
+//! The shared island routing table picks the shortest portal route for everyone. A unit
+//! with a [`TerrainPreference`] instead gets its own portal route, found at path request
+//! time by a Dijkstra over the portal graph where each leg costs its length weighted by
+//! the multipliers of the cost field cells it crosses. The route is stored as a
+//! [`PortalRoute`] on the unit's path; `follow_path` takes its portals in place of the
+//! table's while crossing clusters, and falls back to the table if the unit strays off it.
+
+use bevy::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use crate::game::fixed_math::{FixedNum, FixedVec2};
+use crate::game::structures::FlowField;
+use super::graph::HierarchicalGraph;
+use super::types::{ClusterIslandId, Direction};
+
+/// Multiplier applied to cells whose cost lies in `min..=max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostBand {
+    pub min: u8,
+    pub max: u8,
+    pub multiplier: FixedNum,
+}
+
+impl CostBand {
+    pub fn new(min: u8, max: u8, multiplier: FixedNum) -> Self {
+        Self { min, max, multiplier }
+    }
+}
+
+/// How much a unit dislikes (or favors) each band of terrain cost.
+///
+/// Cells outside every band weigh 1; the first band containing a cell's cost wins.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct TerrainPreference {
+    pub bands: Vec<CostBand>,
+}
+
+impl TerrainPreference {
+    pub fn new(bands: Vec<CostBand>) -> Self {
+        Self { bands }
+    }
+
+    /// Weight of one cell of terrain with cost `cost`
+    pub fn multiplier_for(&self, cost: u8) -> FixedNum {
+        self.bands.iter()
+            .find(|band| (band.min..=band.max).contains(&cost))
+            .map_or(FixedNum::ONE, |band| band.multiplier)
+    }
+
+    /// Cost of moving in a straight line from `from` to `to`: the length, weighted by
+    /// sampling the cost field once per cell length along the way
+    pub fn segment_cost(&self, from: FixedVec2, to: FixedVec2, flow_field: &FlowField) -> FixedNum {
+        let delta = to - from;
+        let length = delta.length();
+        if length == FixedNum::ZERO {
+            return FixedNum::ZERO;
+        }
+        let steps = (length / flow_field.cell_size).ceil().to_num::<usize>().max(1);
+        let step_length = length / FixedNum::from_num(steps);
+        let mut total = FixedNum::ZERO;
+        for i in 0..steps {
+            // Midpoint of each step
+            let t = (FixedNum::from_num(i) + FixedNum::from_num(0.5)) / FixedNum::from_num(steps);
+            let sample = from + delta * t;
+            let multiplier = flow_field.world_to_grid(sample)
+                .and_then(|(x, y)| flow_field.cost(x, y))
+                .map_or(FixedNum::ONE, |cost| self.multiplier_for(cost));
+            total += step_length * multiplier;
+        }
+        total
+    }
+}
+
+/// A unit's own portal sequence toward its goal, with the next portal still to take
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalRoute {
+    /// Exit portals, one per cluster crossed, in order
+    pub portals: Vec<usize>,
+    pub next: usize,
+}
+
+impl PortalRoute {
+    pub fn new(portals: Vec<usize>) -> Self {
+        Self { portals, next: 0 }
+    }
+
+    /// The route's portal out of `cluster`, skipping ahead past clusters already left
+    /// behind. `None` if the rest of the route never passes through `cluster`.
+    pub fn next_portal(&mut self, graph: &HierarchicalGraph, cluster: (usize, usize)) -> Option<usize> {
+        let offset = self.portals.get(self.next..)?.iter()
+            .position(|&id| graph.portals.get(id).is_some_and(|portal| portal.cluster == cluster))?;
+        self.next += offset;
+        Some(self.portals[self.next])
+    }
+}
+
+/// Dijkstra node: where the unit starts, where it lands after crossing a portal, or the goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum RouteNode {
+    Start,
+    Arrival(usize),
+    Goal,
+}
+
+type Heap = BinaryHeap<Reverse<(FixedNum, RouteNode)>>;
+
+/// Cheapest portal route from `start` to `goal` under `preference`.
+///
+/// Returns `Some(vec![])` when both are on the same island and `None` if either is off the
+/// graph or the goal can't be reached.
+pub fn preferred_portal_route(
+    graph: &HierarchicalGraph,
+    flow_field: &FlowField,
+    start: FixedVec2,
+    goal: FixedVec2,
+    preference: &TerrainPreference,
+) -> Option<Vec<usize>> {
+    let source = graph.island_at(start, flow_field)?;
+    let target = graph.island_at(goal, flow_field)?;
+    if source == target {
+        return Some(vec![]);
+    }
+
+    let place = |node: RouteNode| -> Option<(FixedVec2, ClusterIslandId)> {
+        match node {
+            RouteNode::Start => Some((start, source)),
+            RouteNode::Arrival(id) => {
+                let portal = graph.portals.get(id)?;
+                let island = (*graph.portal_island_map.get(id)?)?;
+                Some((portal.world_pos, ClusterIslandId::new(portal.cluster, island)))
+            }
+            RouteNode::Goal => None,
+        }
+    };
+
+    let mut distances: BTreeMap<RouteNode, FixedNum> = BTreeMap::new();
+    // Node -> (node it was reached from, exit portal taken to get here)
+    let mut previous: BTreeMap<RouteNode, (RouteNode, Option<usize>)> = BTreeMap::new();
+    let mut heap: Heap = BinaryHeap::new();
+    distances.insert(RouteNode::Start, FixedNum::ZERO);
+    heap.push(Reverse((FixedNum::ZERO, RouteNode::Start)));
+
+    let mut relax = |distances: &mut BTreeMap<RouteNode, FixedNum>, heap: &mut Heap, from: RouteNode, to: RouteNode, exit: Option<usize>, cost: FixedNum| {
+        if distances.get(&to).is_none_or(|&old| cost < old) {
+            distances.insert(to, cost);
+            previous.insert(to, (from, exit));
+            heap.push(Reverse((cost, to)));
+        }
+    };
+
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if distances.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        if node == RouteNode::Goal {
+            break;
+        }
+        let Some((pos, island)) = place(node) else { continue };
+
+        if island == target {
+            let total = cost + preference.segment_cost(pos, goal, flow_field);
+            relax(&mut distances, &mut heap, node, RouteNode::Goal, None, total);
+        }
+
+        let Some(cluster) = graph.get_cluster(island.cluster.0, island.cluster.1) else { continue };
+        for direction in Direction::ALL {
+            let Some(exit_id) = cluster.neighbor_connectivity[island.island.0 as usize][direction.as_index()] else {
+                continue;
+            };
+            let Some(exit) = graph.portals.get(exit_id) else { continue };
+            let to_exit = cost + preference.segment_cost(pos, exit.world_pos, flow_field);
+            for &(arrival_id, _edge_cost) in graph.portal_connections.get(exit_id).into_iter().flatten() {
+                let Some(arrival) = graph.portals.get(arrival_id) else { continue };
+                if arrival.cluster == exit.cluster {
+                    continue;
+                }
+                let crossed = to_exit + preference.segment_cost(exit.world_pos, arrival.world_pos, flow_field);
+                relax(&mut distances, &mut heap, node, RouteNode::Arrival(arrival_id), Some(exit_id), crossed);
+            }
+        }
+    }
+
+    // Walk back from the goal collecting the exit portals
+    let mut route = Vec::new();
+    let mut node = RouteNode::Goal;
+    while node != RouteNode::Start {
+        let &(from, exit) = previous.get(&node)?;
+        route.extend(exit);
+        node = from;
+    }
+    route.reverse();
+    Some(route)
+}
@@ -50,15 +50,21 @@ fn find_region_portals(cluster: &mut Cluster) {
 }
 
 /// Find the shared edge between two regions (if any)
+///
+/// Region bounds sit on tile centers, so neighboring regions' bounds are a whole tile apart.
+/// Their tiles meet on the border half a tile out from each, which is the edge compared.
 fn find_shared_edge(a: &Region, b: &Region) -> Option<LineSegment> {
-    // For rectangles (4 vertices), check each edge of A against each edge of B
-    for i in 0..a.vertices.len() {
-        let a1 = a.vertices[i];
-        let a2 = a.vertices[(i + 1) % a.vertices.len()];
+    let a_edges = tile_border(a);
+    let b_edges = tile_border(b);
+
+    // Check each edge of A against each edge of B
+    for i in 0..a_edges.len() {
+        let a1 = a_edges[i];
+        let a2 = a_edges[(i + 1) % a_edges.len()];
         
-        for j in 0..b.vertices.len() {
-            let b1 = b.vertices[j];
-            let b2 = b.vertices[(j + 1) % b.vertices.len()];
+        for j in 0..b_edges.len() {
+            let b1 = b_edges[j];
+            let b2 = b_edges[(j + 1) % b_edges.len()];
             
             if let Some(overlap) = compute_segment_overlap(a1, a2, b1, b2) {
                 return Some(overlap);
@@ -69,6 +75,13 @@ fn find_shared_edge(a: &Region, b: &Region) -> Option<LineSegment> {
     None
 }
 
+/// The corners of a region's tiles: its bounds pushed half a tile out on every side
+fn tile_border(region: &Region) -> [FixedVec2; 4] {
+    let half = FixedVec2::new(FixedNum::from_num(0.5), FixedNum::from_num(0.5));
+    let (min, max) = (region.bounds.min - half, region.bounds.max + half);
+    [min, FixedVec2::new(max.x, min.y), max, FixedVec2::new(min.x, max.y)]
+}
+
 /// Compute the overlap between two line segments (if they are collinear and overlapping)
 fn compute_segment_overlap(
     a1: FixedVec2,
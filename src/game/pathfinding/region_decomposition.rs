@@ -1,4 +1,4 @@
-use crate::game::structures::FlowField;
+use crate::game::structures::{FlowField, OBSTACLE_COST};
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 use super::types::{Region, Rect, CLUSTER_SIZE, MAX_REGIONS, RegionId, IslandId, ClusterId};
 use super::graph::HierarchicalGraph;
@@ -12,7 +12,9 @@ use bevy::prelude::*;
 /// 2. Scan cluster row by row
 /// 3. Merge walkable tiles into largest possible horizontal strips
 /// 4. Merge vertical strips into rectangles
-/// 5. Result: Array of rectangles covering all walkable space
+/// 5. Validate each rectangle against the raw cost field, splitting any that cover a
+///    blocked tile (see `repair_non_convex_regions`)
+/// 6. Result: Array of rectangles covering all walkable space
 ///
 /// **Obstacle Dilation:** Treats obstacles as 1-2 tiles larger for pathfinding.
 /// This dramatically reduces region count for circular obstacles (60-80% reduction).
@@ -51,6 +53,7 @@ pub(crate) fn decompose_cluster_into_regions(
     
     // Merge strips vertically into rectangles
     let rectangles = merge_strips_into_rectangles(strips, start_x, start_y);
+    let rectangles = repair_non_convex_regions(rectangles, start_x, start_y, flow_field);
     
     if cluster_id.0 == 0 && cluster_id.1 == 0 {
         info!("[DECOMP] Cluster (0,0): Created {} rectangles from walkable tiles", rectangles.len());
@@ -209,9 +212,8 @@ fn is_tile_walkable_dilated(
     dilation_radius: usize,
 ) -> bool {
     // Check the tile itself
-    let idx = flow_field.get_index(x, y);
-    if idx >= flow_field.cost_field.len() || flow_field.cost_field[idx] == 255 {
-        return false; // Tile is obstacle
+    if !flow_field.is_walkable(x, y) {
+        return false; // Tile is obstacle (or off the grid)
     }
     
     // Check neighbors within dilation radius
@@ -222,16 +224,11 @@ fn is_tile_walkable_dilated(
                 continue; // Already checked center
             }
             
-            let nx = (x as isize + dx) as usize;
-            let ny = (y as isize + dy) as usize;
-            
-            // Check bounds
-            if nx >= flow_field.width || ny >= flow_field.height {
+            // Off-grid neighbors don't count as obstacles
+            let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
                 continue;
-            }
-            
-            let neighbor_idx = flow_field.get_index(nx, ny);
-            if neighbor_idx < flow_field.cost_field.len() && flow_field.cost_field[neighbor_idx] == 255 {
+            };
+            if flow_field.cost(nx, ny) == Some(OBSTACLE_COST) {
                 // Neighbor is obstacle - mark this tile as non-walkable
                 return false;
             }
@@ -241,9 +238,8 @@ fn is_tile_walkable_dilated(
     true // Tile and all neighbors are walkable
 }
 
-/// Find all horizontal strips of walkable tiles in the cluster (NO DILATION - legacy)
-/// Kept for reference/debugging, but not currently used in production.
-#[allow(dead_code)]
+/// Find all horizontal strips of walkable tiles in the cluster (NO DILATION)
+/// Used to re-decompose regions that fail validation in `repair_non_convex_regions`.
 fn find_horizontal_strips(
     min_x: usize,
     max_x: usize,
@@ -257,15 +253,9 @@ fn find_horizontal_strips(
         let mut strip_start: Option<usize> = None;
         
         for x in min_x..=max_x {
-            let is_walkable = if x < max_x && y < flow_field.height {
-                let idx = flow_field.get_index(x, y);
-                if idx < flow_field.cost_field.len() {
-                    // Walkable = any cost < 255 (obstacles are 255)
-                    // This allows variable terrain costs: 1=normal, 2-254=slow terrain, 255=impassable
-                    flow_field.cost_field[idx] < 255
-                } else {
-                    false
-                }
+            let is_walkable = if x < max_x {
+                // Any cost below OBSTACLE_COST: 1=normal, 2-254=slow terrain
+                flow_field.is_walkable(x, y)
             } else {
                 false // End of row
             };
@@ -344,6 +334,45 @@ fn merge_strips_into_rectangles(strips: Vec<(usize, usize, usize)>, cluster_star
     rectangles
 }
 
+/// Absolute grid tiles a cluster-local region rectangle covers: `(x_min, x_max, y_min, y_max)`, inclusive
+fn rect_tiles(rect: Rect, cluster_start_x: usize, cluster_start_y: usize) -> (usize, usize, usize, usize) {
+    // Bounds sit on tile centers, so flooring gives the tile index
+    (
+        cluster_start_x + rect.min.x.to_num::<usize>(),
+        cluster_start_x + rect.max.x.to_num::<usize>(),
+        cluster_start_y + rect.min.y.to_num::<usize>(),
+        cluster_start_y + rect.max.y.to_num::<usize>(),
+    )
+}
+
+/// Enforce the convexity guarantee: every tile a region covers must be walkable.
+///
+/// Navigation steers in a straight line across a region, which is only obstacle-free if
+/// the whole rectangle is open ground. Rectangles merged from dilated strips should
+/// always be, so this is a cheap check of the raw cost field; any rectangle that covers a
+/// blocked tile is split into rectangles of its own walkable tiles.
+fn repair_non_convex_regions(
+    rectangles: Vec<Rect>,
+    cluster_start_x: usize,
+    cluster_start_y: usize,
+    flow_field: &FlowField,
+) -> Vec<Rect> {
+    let mut repaired = Vec::with_capacity(rectangles.len());
+    for rect in rectangles {
+        let (x_min, x_max, y_min, y_max) = rect_tiles(rect, cluster_start_x, cluster_start_y);
+        let all_walkable = (y_min..=y_max).all(|y| (x_min..=x_max).all(|x| flow_field.is_walkable(x, y)));
+        if all_walkable {
+            repaired.push(rect);
+            continue;
+        }
+        warn!("[DECOMP] Region covering tiles ({}, {})..=({}, {}) is not convex over walkable ground, splitting",
+              x_min, y_min, x_max, y_max);
+        let strips = find_horizontal_strips(x_min, x_max + 1, y_min, y_max + 1, flow_field);
+        repaired.extend(merge_strips_into_rectangles(strips, cluster_start_x, cluster_start_y));
+    }
+    repaired
+}
+
 /// Convert a rectangle to its 4 corner vertices
 fn rect_to_vertices(rect: Rect) -> SmallVec<[FixedVec2; 8]> {
     smallvec::smallvec![
@@ -529,6 +558,81 @@ mod tests {
         assert!(rect.contains(FixedVec2::new(FixedNum::from_num(5), FixedNum::from_num(5))));
         assert!(!rect.contains(FixedVec2::new(FixedNum::from_num(15), FixedNum::from_num(5))));
     }
+
+    /// 25x25 open cluster at the origin (cell size 1, so cluster-local == world)
+    fn open_cluster() -> FlowField {
+        let mut flow_field = FlowField::new(CLUSTER_SIZE, CLUSTER_SIZE, FixedNum::ONE, FixedVec2::ZERO);
+        flow_field.cost_field.fill(1);
+        flow_field
+    }
+
+    /// True if every sample along `a -> b` lands on a walkable tile
+    fn segment_walkable(flow_field: &FlowField, a: FixedVec2, b: FixedVec2) -> bool {
+        (0..=32).all(|i| {
+            let point = a + (b - a) * (FixedNum::from_num(i) / FixedNum::from_num(32));
+            flow_field.world_to_grid(point).is_some_and(|(x, y)| flow_field.is_walkable(x, y))
+        })
+    }
+
+    #[test]
+    fn test_concave_area_decomposes_into_convex_regions() {
+        // L-shaped walkable area: the top-right quadrant is blocked
+        let mut flow_field = open_cluster();
+        for y in 12..CLUSTER_SIZE {
+            for x in 12..CLUSTER_SIZE {
+                flow_field.set_obstacle(x, y);
+            }
+        }
+        let regions = decompose_cluster_into_regions((0, 0), &flow_field);
+        assert!(regions.len() >= 2, "An L can't be one convex region");
+
+        for region in &regions {
+            let bounds = region.bounds;
+            let fraction = |t: f32| FixedNum::from_num(t);
+            let lerp = |t: f32, u: f32| FixedVec2::new(
+                bounds.min.x + bounds.width() * fraction(t),
+                bounds.min.y + bounds.height() * fraction(u),
+            );
+            // Corners, edge midpoints and interior points, every pair joined by a segment
+            let samples: Vec<FixedVec2> = [0.0, 0.25, 0.5, 0.75, 1.0].iter()
+                .flat_map(|&t| [0.0, 0.3, 0.5, 0.9, 1.0].map(|u| lerp(t, u)))
+                .collect();
+            for &a in &samples {
+                for &b in &samples {
+                    assert!(segment_walkable(&flow_field, a, b), "Region {:?}: {:?} -> {:?} crosses a blocked tile", bounds, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_region_covering_obstacle_is_split() {
+        let mut flow_field = open_cluster();
+        flow_field.set_obstacle(5, 3);
+        // Spans tiles (2, 2)..=(8, 4), including the blocked one
+        let rect = PathRect::new(FixedVec2::from_f32(2.5, 2.5), FixedVec2::from_f32(8.5, 4.5));
+        let open = PathRect::new(FixedVec2::from_f32(10.5, 10.5), FixedVec2::from_f32(14.5, 14.5));
+
+        let repaired = repair_non_convex_regions(vec![open, rect], 0, 0, &flow_field);
+        assert_eq!((repaired[0].min, repaired[0].max), (open.min, open.max), "Valid regions are kept as they are");
+        let pieces = &repaired[1..];
+        assert!(pieces.len() > 1);
+        // The pieces cover exactly the walkable tiles of the original
+        let mut covered = Vec::new();
+        for &piece in pieces {
+            let (x_min, x_max, y_min, y_max) = rect_tiles(piece, 0, 0);
+            for y in y_min..=y_max {
+                for x in x_min..=x_max {
+                    assert!(flow_field.is_walkable(x, y));
+                    covered.push((x, y));
+                }
+            }
+        }
+        covered.sort_unstable();
+        assert_eq!(covered.len(), 3 * 7 - 1);
+        covered.dedup();
+        assert_eq!(covered.len(), 3 * 7 - 1, "Pieces don't overlap");
+    }
 }
 
 /// Check if a region is convex and well-formed
@@ -1,7 +1,12 @@
 /// Pathfinding resources for active path tracking.
 
 use bevy::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 use crate::game::collections::{InclusionSet, SetConfig, InclusionIndex};
+use crate::game::fixed_math::FixedVec2;
+use crate::game::structures::FlowField;
+use super::types::{PathFailReason, PathRequest, PathPriority};
 
 /// Wrapper around Entity for use with InclusionSet.
 /// Stores the full entity bits (index + generation) as a u64 internally,
@@ -10,7 +15,7 @@ use crate::game::collections::{InclusionSet, SetConfig, InclusionIndex};
 /// 1. We validate entities via query.get() before using them
 /// 2. Invalid generations are caught naturally by Bevy's query system
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct EntityIndex(u64);  // Store full entity bits
+pub(crate) struct EntityIndex(u64);  // Store full entity bits
 
 impl From<Entity> for EntityIndex {
     fn from(entity: Entity) -> Self {
@@ -96,3 +101,207 @@ impl Default for ActivePathSet {
         }
     }
 }
+
+/// Counters from the last `process_path_requests` run.
+///
+/// Requests whose goals land on the same grid cell share one goal resolution, so with many
+/// units ordered to the same point `goal_resolutions` stays far below `requests`.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathRequestStats {
+    /// Path requests processed this run
+    pub requests: usize,
+    /// Distinct goal cells resolved to cluster/region/island
+    pub goal_resolutions: usize,
+    /// Requests left waiting for a later tick (over `SimConfig::path_requests_per_tick`)
+    pub deferred: usize,
+}
+
+/// Running count of [`PathFailed`](super::types::PathFailed) reasons (optional resource; insert it to start counting).
+///
+/// Unlike [`PathRequestStats`] this is never reset, so a playtest or a scripted run over a
+/// map ends with a tally of why orders went nowhere.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathFailureCounts {
+    counts: BTreeMap<PathFailReason, u64>,
+}
+
+impl PathFailureCounts {
+    pub fn record(&mut self, reason: PathFailReason) {
+        *self.counts.entry(reason).or_default() += 1;
+    }
+
+    pub fn get(&self, reason: PathFailReason) -> u64 {
+        self.counts.get(&reason).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// (reason, count) for every reason seen so far, in reason order
+    pub fn iter(&self) -> impl Iterator<Item = (PathFailReason, u64)> + '_ {
+        self.counts.iter().map(|(&reason, &count)| (reason, count))
+    }
+}
+
+/// Path requests waiting for a tick with budget left (one entry per entity).
+///
+/// Two FIFO lines: every high-priority request is served before any low-priority one.
+/// A newer request for a queued entity replaces its goal but keeps its place in line,
+/// unless it raises the priority, which moves the entity to the back of the high line.
+/// `cancel` (stop commands) drops it. Superseded and cancelled entries stay in their
+/// line and are skipped when they reach the front.
+///
+/// A cancelled entity also refuses requests still in flight as messages, until a new
+/// order [`resume`](Self::resume)s it or `process_path_requests` has read the backlog.
+#[derive(Resource, Default)]
+pub struct PendingPathRequests {
+    high: VecDeque<(Entity, u64)>,
+    low: VecDeque<(Entity, u64)>,
+    queued: HashMap<Entity, QueuedRequest>,
+    cancelled: HashSet<Entity>,
+    next_seq: u64,
+}
+
+/// Live entry for a queued entity; `seq` identifies its current slot in a line
+#[derive(Debug, Clone, Copy)]
+struct QueuedRequest {
+    goal: FixedVec2,
+    priority: PathPriority,
+    seq: u64,
+}
+
+impl PendingPathRequests {
+    pub fn push(&mut self, request: &PathRequest) {
+        if self.cancelled.contains(&request.entity) {
+            return;
+        }
+        if let Some(queued) = self.queued.get_mut(&request.entity) {
+            queued.goal = request.goal;
+            if request.priority <= queued.priority {
+                return;
+            }
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queued.insert(request.entity, QueuedRequest { goal: request.goal, priority: request.priority, seq });
+        match request.priority {
+            PathPriority::High => self.high.push_back((request.entity, seq)),
+            PathPriority::Low => self.low.push_back((request.entity, seq)),
+        }
+    }
+
+    /// Oldest live high-priority request, else the oldest low-priority one
+    pub fn pop(&mut self) -> Option<PathRequest> {
+        for line in [&mut self.high, &mut self.low] {
+            while let Some((entity, seq)) = line.pop_front() {
+                if self.queued.get(&entity).is_some_and(|queued| queued.seq == seq) {
+                    let queued = self.queued.remove(&entity).unwrap();
+                    return Some(PathRequest { entity, goal: queued.goal, priority: queued.priority });
+                }
+            }
+        }
+        None
+    }
+
+    /// Drop the entity's queued request and refuse the ones still in flight
+    pub fn cancel(&mut self, entity: Entity) {
+        self.queued.remove(&entity);
+        self.cancelled.insert(entity);
+    }
+
+    /// Accept requests for a cancelled entity again (it was given a new order)
+    pub fn resume(&mut self, entity: Entity) {
+        self.cancelled.remove(&entity);
+    }
+
+    /// Forget cancellations once every request sent before them has been pushed
+    pub fn clear_cancelled(&mut self) {
+        self.cancelled.clear();
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.queued.contains_key(&entity)
+    }
+
+    /// Number of live requests
+    pub fn len(&self) -> usize {
+        self.queued.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queued.is_empty()
+    }
+}
+
+/// Default number of goals whose integration fields are kept
+pub const DEFAULT_INTEGRATION_CACHE_CAPACITY: usize = 8;
+
+/// Integration field shared between the cache and its users
+pub type SharedIntegrationField = Arc<Vec<u16>>;
+
+/// LRU cache of [`FlowField::compute_integration_field`] results, keyed by goal cell.
+///
+/// Repeated mass-moves to the same rally point reuse one field instead of re-running
+/// Dijkstra over the whole map. Fields are shared as `Arc`s so holders keep a valid
+/// field even after it is evicted. Cleared by `invalidate_integration_field_cache`
+/// whenever `MapFlowField` changes, since every entry depends on the cost field.
+#[derive(Resource)]
+pub struct IntegrationFieldCache {
+    capacity: usize,
+    /// Most recently used last
+    entries: VecDeque<((usize, usize), SharedIntegrationField)>,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl IntegrationFieldCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), entries: VecDeque::new(), hits: 0, misses: 0 }
+    }
+
+    /// Cached field for `goal`, computing (and possibly evicting the oldest) on a miss
+    pub fn get_or_compute(&mut self, flow_field: &FlowField, goal: (usize, usize)) -> SharedIntegrationField {
+        if let Some(pos) = self.entries.iter().position(|(cell, _)| *cell == goal) {
+            let entry = self.entries.remove(pos).unwrap();
+            // A resized map without a change tick would leave stale fields behind
+            if entry.1.len() == flow_field.width * flow_field.height {
+                self.hits += 1;
+                let field = entry.1.clone();
+                self.entries.push_back(entry);
+                return field;
+            }
+        }
+
+        self.misses += 1;
+        let field = Arc::new(flow_field.compute_integration_field(goal));
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((goal, field.clone()));
+        field
+    }
+
+    pub fn contains(&self, goal: (usize, usize)) -> bool {
+        self.entries.iter().any(|(cell, _)| *cell == goal)
+    }
+
+    /// Drop every cached field (the hit/miss counters are kept)
+    pub fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for IntegrationFieldCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_INTEGRATION_CACHE_CAPACITY)
+    }
+}
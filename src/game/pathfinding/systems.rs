@@ -1,24 +1,28 @@
 use bevy::prelude::*;
-use crate::game::simulation::MapFlowField;
-use super::types::{PathRequest, CLUSTER_SIZE, IslandId};
+use std::collections::{HashMap, HashSet, VecDeque};
+use bevy::ecs::query::QueryEntityError;
+use crate::game::simulation::{canonical_order, MapFlowField, SimConfig, SimPosition};
+use super::types::{PathFailReason, PathFailed, PathRequest, CLUSTER_SIZE, ClusterId, ClusterIslandId, IslandId, RegionId};
 use super::graph::HierarchicalGraph;
+use super::preference::{preferred_portal_route, PortalRoute, TerrainPreference};
+use super::resources::{IntegrationFieldCache, PathFailureCounts};
 use super::world_to_cluster_local;
 use super::cluster::Cluster;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 
+/// How far (world units) path requests search for a walkable tile around a blocked goal
+pub const GOAL_SNAP_RADIUS: f32 = 10.0;
+
 /// Snap a position to the nearest walkable tile
 /// Returns None if no walkable tile found within search radius
-fn snap_to_walkable(
+pub fn snap_to_walkable(
     pos: FixedVec2, 
     flow_field: &crate::game::structures::FlowField,
     max_radius: f32,
 ) -> Option<FixedVec2> {
     // Check if already walkable
-    if let Some((gx, gy)) = flow_field.world_to_grid(pos) {
-        let idx = flow_field.get_index(gx, gy);
-        if idx < flow_field.cost_field.len() && flow_field.cost_field[idx] < 255 {
-            return Some(pos);
-        }
+    if flow_field.is_walkable_world(pos) {
+        return Some(pos);
     }
     
     // Search in expanding radius
@@ -35,11 +39,8 @@ fn snap_to_walkable(
                 pos.y + FixedNum::from_num(offset_y)
             );
             
-            if let Some((gx, gy)) = flow_field.world_to_grid(test_pos) {
-                let idx = flow_field.get_index(gx, gy);
-                if idx < flow_field.cost_field.len() && flow_field.cost_field[idx] < 255 {
-                    return Some(test_pos);
-                }
+            if flow_field.is_walkable_world(test_pos) {
+                return Some(test_pos);
             }
         }
     }
@@ -47,6 +48,105 @@ fn snap_to_walkable(
     None
 }
 
+/// Cluster, island and region a path goal at `world_pos` routes to.
+///
+/// The goal is first snapped to walkable ground (see [`snap_to_walkable`]). Walkable cells
+/// right next to obstacles lie outside every region, so a goal there takes the region of
+/// the nearest cell in its cluster that has one. `None` when neither is in reach: there is
+/// no guessed island to send units to the wrong side of a wall.
+pub fn resolve_goal(
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    world_pos: FixedVec2,
+) -> Option<(ClusterId, IslandId, RegionId)> {
+    let goal = snap_to_walkable(world_pos, flow_field, GOAL_SNAP_RADIUS)?;
+    let (grid_x, grid_y) = flow_field.world_to_grid(goal)?;
+    if let Some(resolved) = region_at_cell(graph, flow_field, grid_x, grid_y) {
+        return Some(resolved);
+    }
+
+    // Rings of growing distance around the goal cell, each scanned in a fixed order
+    let max_ring = (FixedNum::from_num(GOAL_SNAP_RADIUS) / flow_field.cell_size).to_num::<usize>();
+    let cluster_min = (grid_x / CLUSTER_SIZE * CLUSTER_SIZE, grid_y / CLUSTER_SIZE * CLUSTER_SIZE);
+    let cluster_max = (
+        (cluster_min.0 + CLUSTER_SIZE).min(flow_field.width) - 1,
+        (cluster_min.1 + CLUSTER_SIZE).min(flow_field.height) - 1,
+    );
+    for ring in 1..=max_ring {
+        for y in grid_y.saturating_sub(ring).max(cluster_min.1)..=(grid_y + ring).min(cluster_max.1) {
+            for x in grid_x.saturating_sub(ring).max(cluster_min.0)..=(grid_x + ring).min(cluster_max.0) {
+                if grid_x.abs_diff(x).max(grid_y.abs_diff(y)) != ring {
+                    continue;
+                }
+                if let Some(resolved) = region_at_cell(graph, flow_field, x, y) {
+                    return Some(resolved);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Region (with its cluster and island) containing the center of a walkable cell
+fn region_at_cell(
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    grid_x: usize,
+    grid_y: usize,
+) -> Option<(ClusterId, IslandId, RegionId)> {
+    if !flow_field.is_walkable(grid_x, grid_y) {
+        return None;
+    }
+    let (cluster_x, cluster_y) = (grid_x / CLUSTER_SIZE, grid_y / CLUSTER_SIZE);
+    let cluster = graph.get_cluster(cluster_x, cluster_y)?;
+
+    // Regions are in cluster-local cell units
+    let half = FixedNum::from_num(0.5);
+    let local = FixedVec2::new(
+        FixedNum::from_num(grid_x % CLUSTER_SIZE) + half,
+        FixedNum::from_num(grid_y % CLUSTER_SIZE) + half,
+    );
+    let region_id = super::get_region_id(&cluster.regions, cluster.region_count, local)?;
+    let island = cluster.regions[region_id.0 as usize].as_ref()?.island;
+    Some((ClusterId::new(cluster_x, cluster_y), island, region_id))
+}
+
+/// Move a goal cell off a blocked cell or out of a sealed pocket, so a click into a
+/// walled-off nook paths to the nearest open ground instead of somewhere unreachable.
+///
+/// Open ground is a cell in a region whose island isn't a sealed pocket (see
+/// [`HierarchicalGraph::is_sealed_pocket`]), however big the pocket around the goal is.
+/// Returns `cell` itself when it is open ground, otherwise the nearest cell (in 4-connected
+/// steps, at most `max_steps` away, ties in a fixed order) that is. `None` if there is no
+/// such cell in reach.
+pub fn relocate_goal_cell(
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    cell: (usize, usize),
+    max_steps: usize,
+) -> Option<(usize, usize)> {
+    let is_open = |(x, y): (usize, usize)| {
+        graph.island_at(flow_field.grid_to_world(x, y), flow_field).is_some_and(|island| !graph.is_sealed_pocket(island))
+    };
+
+    let mut visited = HashSet::from([cell]);
+    let mut frontier = VecDeque::from([(cell, 0)]);
+    while let Some(((x, y), steps)) = frontier.pop_front() {
+        if is_open((x, y)) {
+            return Some((x, y));
+        }
+        if steps == max_steps {
+            continue;
+        }
+        for (nx, ny) in [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)] {
+            if nx < flow_field.width && ny < flow_field.height && visited.insert((nx, ny)) {
+                frontier.push_back(((nx, ny), steps + 1));
+            }
+        }
+    }
+    None
+}
+
 /// Find the nearest region's island when a position is not directly in any region
 fn find_nearest_island(cluster: &Cluster, local_pos: FixedVec2) -> IslandId {
     let mut nearest_island = IslandId(0);
@@ -95,7 +195,7 @@ pub fn OLD_process_path_requests(
     // Note: Commands already batches operations internally - no need for intermediate Vec
     for request in path_requests.read() {
         // STEP 1: Snap goal to walkable tile
-        let walkable_goal = match snap_to_walkable(request.goal, flow_field, 10.0) {
+        let walkable_goal = match snap_to_walkable(request.goal, flow_field, GOAL_SNAP_RADIUS) {
             Some(pos) => pos,
             None => {
                 warn!("Path request for entity {:?} rejected: goal {:?} is not walkable and no walkable tile nearby", 
@@ -165,6 +265,7 @@ pub fn OLD_process_path_requests(
                 next_expected_region: None,
                 current_target: None,
                 is_inter_cluster_target: false,
+                portal_route: None,
             }));
         } else {
             warn!("Path request for entity {:?} rejected: goal {:?} is outside grid bounds", 
@@ -174,77 +275,221 @@ pub fn OLD_process_path_requests(
 }
 
 /// Process path requests and assign paths to entities (NEW IMPLEMENTATION)
+///
+/// New requests join `PendingPathRequests`; at most `SimConfig::path_requests_per_tick`
+/// are resolved per tick, player orders first and oldest first within a priority, so a
+/// mass move order is spread over several ticks. Requests written in the same tick count
+/// as equally old and are queued in `CanonicalOrder`.
+///
+/// A request that gives its unit no path sends [`PathFailed`] with the reason, and is
+/// tallied in [`PathFailureCounts`] if that resource exists.
 pub fn process_path_requests(
     mut path_requests: MessageReader<PathRequest>,
     map_flow_field: Res<MapFlowField>,
-    _graph: Res<HierarchicalGraph>,  // Kept for now, may be needed for validation later
+    graph: Res<HierarchicalGraph>,
     nav_lookup: Res<super::navigation_lookup::NavigationLookup>,
-    mut active_paths: ResMut<super::resources::ActivePathSet>,
+    sim_config: Res<SimConfig>,
+    (mut active_paths, mut pending, mut stats): (
+        ResMut<super::resources::ActivePathSet>,
+        ResMut<super::resources::PendingPathRequests>,
+        ResMut<super::resources::PathRequestStats>,
+    ),
+    mut resolved_goals: Local<HashMap<(usize, usize), Option<ResolvedGoal>>>,
     mut query: Query<(&mut super::types::Path, &mut super::types::GoalNavCell, &mut crate::game::collections::InclusionIndex)>,
+    starts: Query<(&SimPosition, Option<&TerrainPreference>)>,
+    mut failed: MessageWriter<PathFailed>,
+    mut failure_counts: Option<ResMut<PathFailureCounts>>,
 ) {
-    if path_requests.is_empty() {
+    let mut fail = |entity: Entity, reason: PathFailReason| {
+        debug!("[PATHFINDING] Path request for {:?} failed: {:?}", entity, reason);
+        failed.write(PathFailed { entity, reason });
+        if let Some(counts) = failure_counts.as_mut() {
+            counts.record(reason);
+        }
+    };
+
+    *stats = Default::default();
+    // This tick's requests join the queue in canonical order, not writer order
+    for request in canonical_order(path_requests.read()) {
+        pending.push(request);
+    }
+    pending.clear_cancelled();
+    if pending.is_empty() {
         return;
     }
 
     let walkability_map = &map_flow_field.0;
     if walkability_map.width == 0 {
+        stats.deferred = pending.len();
         return;
     }
 
-    for request in path_requests.read() {
-        // Validate goal (for now, just pass through)
-        let goal = request.goal;
-        
+    // Group moves: everyone ordered onto the same cell shares one resolution this run
+    resolved_goals.clear();
+
+    while stats.requests < sim_config.path_requests_per_tick {
+        let Some(request) = pending.pop() else {
+            break;
+        };
+        stats.requests += 1;
+
         // Convert world position to grid coordinates
-        let Some((grid_x, grid_y)) = walkability_map.world_to_grid(goal) else {
+        let Some(goal_cell) = walkability_map.world_to_grid(request.goal) else {
+            fail(request.entity, PathFailReason::GoalOffMap);
             continue;
         };
         
-        // O(1) lookup from NavigationLookup - gets precomputed cluster/region/island indices
-        let Some(nav_cell) = nav_lookup.lookup(grid_x, grid_y) else {
+        let resolved = *resolved_goals.entry(goal_cell).or_insert_with(|| {
+            stats.goal_resolutions += 1;
+            resolve_path_goal(&nav_lookup, &graph, walkability_map, goal_cell)
+        });
+        let Some(resolved) = resolved else {
+            fail(request.entity, PathFailReason::GoalOffGraph);
             continue;
         };
-        
-        // Extract IDs directly from arena using precomputed indices
-        let goal_cluster = nav_lookup.arenas.get_cluster(nav_cell.cluster_idx)
-            .map(|c| super::types::ClusterId::new(c.id.0, c.id.1))
-            .unwrap_or_else(|| super::types::ClusterId::new(0, 0));
-        
-        let goal_region = nav_lookup.arenas.get_region(nav_cell.region_idx).map(|r| r.id);
-        
-        let goal_island = nav_lookup.arenas.get_island(nav_cell.island_idx)
-            .map(|i| i.id)
-            .unwrap_or(IslandId(0));
+        let goal = resolved.goal(request.goal);
+        let ResolvedGoal { nav_cell, goal_cluster, goal_region, goal_island, sealed, .. } = resolved;
+
+        let start = starts.get(request.entity).ok();
+        if let Some((position, _)) = start {
+            let goal_id = ClusterIslandId::new(goal_cluster.as_tuple(), goal_island);
+            if let Err(reason) = check_reachable(&graph, walkability_map, position.0, goal_id) {
+                // A goal stuck in a pocket is why it can't be reached
+                let reason = if sealed && reason == PathFailReason::Unreachable { PathFailReason::SealedPocket } else { reason };
+                fail(request.entity, reason);
+                continue;
+            }
+        }
         
         // Mutate existing Path component (no component insertion/removal!)
         // IMPORTANT: Only add to ActivePathSet if query succeeds!
-        if let Ok((mut path, mut goal_nav_cell, mut inclusion_idx)) = query.get_mut(request.entity) {
-            // Register entity in active path set for O(active_paths) iteration
-            let include_result = active_paths.include(request.entity);
-            
-            // Update InclusionIndex if entity was added to hot storage
-            if let crate::game::collections::IncludeResult::Hot(idx) = include_result {
-                *inclusion_idx = idx;
+        match query.get_mut(request.entity) {
+            Ok((mut path, mut goal_nav_cell, mut inclusion_idx)) => {
+                // Register entity in active path set for O(active_paths) iteration
+                let include_result = active_paths.include(request.entity);
+                
+                // Update InclusionIndex if entity was added to hot storage
+                match include_result {
+                    crate::game::collections::IncludeResult::Inserted(Some(idx)) => *inclusion_idx = idx,
+                    crate::game::collections::IncludeResult::AtCapacity => {
+                        // Entity can't be tracked, so follow_path would never see it - don't activate
+                        warn!("[PATHFINDING] Active path set at capacity - dropping path request for {:?}", request.entity);
+                        fail(request.entity, PathFailReason::ActivePathSetFull);
+                        continue;
+                    }
+                    _ => {}
+                }
+                
+                // Units with terrain preferences plan their own portal route
+                let portal_route = start
+                    .and_then(|(position, preference)| preferred_portal_route(&graph, walkability_map, position.0, goal, preference?))
+                    .map(PortalRoute::new);
+                *path = super::types::Path::Active(super::types::PathState::Hierarchical {
+                    goal,
+                    goal_cluster,
+                    goal_region,
+                    goal_island,
+                    current_cluster: None,
+                    current_region: None,
+                    next_expected_cluster: None,
+                    next_expected_region: None,
+                    current_target: None,
+                    is_inter_cluster_target: false,
+                    portal_route,
+                });
+                *goal_nav_cell = super::types::GoalNavCell(nav_cell);
+            }
+            // Despawned while its request was queued
+            Err(QueryEntityError::EntityDoesNotExist(_)) => {}
+            Err(_) => {
+                // This should NEVER happen if entities are spawned correctly
+                error!("Path request for entity {:?} FAILED - entity missing Path/GoalNavCell/InclusionIndex components! This is a BUG!", request.entity);
             }
-            
-            *path = super::types::Path::Active(super::types::PathState::Hierarchical {
-                goal,
-                goal_cluster,
-                goal_region,
-                goal_island,
-                current_cluster: None,
-                current_region: None,
-                next_expected_cluster: None,
-                next_expected_region: None,
-                current_target: None,
-                is_inter_cluster_target: false,
-            });
-            *goal_nav_cell = super::types::GoalNavCell(nav_cell);
-        } else {
-            // This should NEVER happen if entities are spawned correctly
-            error!("Path request for entity {:?} FAILED - entity missing Path/GoalNavCell/InclusionIndex components! This is a BUG!", request.entity);
         }
     }
+    stats.deferred = pending.len();
+}
+
+/// Goal cell resolved to the IDs stored in `PathState::Hierarchical`
+#[derive(Clone, Copy)]
+pub struct ResolvedGoal {
+    nav_cell: super::navigation_lookup::NavigationCell,
+    goal_cluster: super::types::ClusterId,
+    goal_region: Option<super::types::RegionId>,
+    goal_island: IslandId,
+    /// Where the goal was moved to, if it was in a sealed pocket
+    relocated: Option<FixedVec2>,
+    /// The goal is in a sealed pocket with no open ground in reach, so it was kept as given
+    sealed: bool,
+}
+
+impl ResolvedGoal {
+    /// Where units ordered to `requested` (a position in the resolved goal cell) are sent
+    pub fn goal(&self, requested: FixedVec2) -> FixedVec2 {
+        self.relocated.unwrap_or(requested)
+    }
+}
+
+/// Resolve the goal of a path request landing in `goal_cell`, as `process_path_requests`
+/// does: goals in sealed pockets go to the nearest open ground (see [`relocate_goal_cell`]);
+/// without any in reach the goal is kept as given. `None` if the goal cell is off the
+/// navigation graph.
+///
+/// Anything showing where an order sends units (command pings) should go through this too,
+/// so it agrees with the path.
+pub fn resolve_path_goal(
+    nav_lookup: &super::navigation_lookup::NavigationLookup,
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    goal_cell: (usize, usize),
+) -> Option<ResolvedGoal> {
+    let max_steps = (FixedNum::from_num(GOAL_SNAP_RADIUS) / flow_field.cell_size).to_num::<usize>();
+    let relocated_cell = relocate_goal_cell(graph, flow_field, goal_cell, max_steps);
+    let target_cell = relocated_cell.unwrap_or(goal_cell);
+    let relocated = (target_cell != goal_cell).then(|| flow_field.grid_to_world(target_cell.0, target_cell.1));
+    resolve_goal_cell(nav_lookup, graph, flow_field, target_cell, relocated, relocated_cell.is_none())
+}
+
+/// Navigation cell plus cluster/island/region (see [`resolve_goal`]) of a goal cell
+fn resolve_goal_cell(
+    nav_lookup: &super::navigation_lookup::NavigationLookup,
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    (grid_x, grid_y): (usize, usize),
+    relocated: Option<FixedVec2>,
+    sealed: bool,
+) -> Option<ResolvedGoal> {
+    // The lookup only bounds the goal to the navigable grid; the ids all come from the resolved
+    // region, which snapping may have moved off the requested cell
+    nav_lookup.lookup(grid_x, grid_y)?;
+    let (goal_cluster, goal_island, goal_region) = resolve_goal(graph, flow_field, flow_field.grid_to_world(grid_x, grid_y))?;
+    let cluster_idx = super::types::ClusterArenaIdx::from_coords(goal_cluster.0, goal_cluster.1, nav_lookup.arenas.clusters_x);
+    let nav_cell = super::navigation_lookup::NavigationCell {
+        cluster_idx,
+        region_idx: super::types::RegionArenaIdx::from_cluster_and_local(cluster_idx, goal_region),
+        island_idx: super::types::IslandArenaIdx::from_cluster_and_local(cluster_idx, goal_island),
+    };
+
+    Some(ResolvedGoal { nav_cell, goal_cluster, goal_region: Some(goal_region), goal_island, relocated, sealed })
+}
+
+/// Whether a unit at `start` can walk to the island `goal`.
+///
+/// `Err(StartOffGraph)` off the map or in a cluster the graph lacks, `Err(Unreachable)` if
+/// the routing table has no route between the two islands. A start on a blocked cell (a
+/// unit pushed into a wall) passes: following the path steers it back onto open ground.
+fn check_reachable(
+    graph: &HierarchicalGraph,
+    flow_field: &crate::game::structures::FlowField,
+    start: FixedVec2,
+    goal: ClusterIslandId,
+) -> Result<(), PathFailReason> {
+    let (grid_x, grid_y) = flow_field.world_to_grid(start).ok_or(PathFailReason::StartOffGraph)?;
+    graph.get_cluster(grid_x / CLUSTER_SIZE, grid_y / CLUSTER_SIZE).ok_or(PathFailReason::StartOffGraph)?;
+    match graph.island_at(start, flow_field) {
+        Some(source) if source != goal && graph.get_island_route(source, goal).is_none() => Err(PathFailReason::Unreachable),
+        _ => Ok(()),
+    }
 }
 
 // These helper functions are deprecated - will be replaced by NavigationLookup
@@ -330,3 +575,13 @@ fn find_island(
         nearest_island
     }
 }
+
+/// Drop cached integration fields when the cost field they were computed from changes
+pub fn invalidate_integration_field_cache(
+    map_flow_field: Res<MapFlowField>,
+    mut cache: ResMut<IntegrationFieldCache>,
+) {
+    if map_flow_field.is_changed() && !cache.is_empty() {
+        cache.invalidate();
+    }
+}
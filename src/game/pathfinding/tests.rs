@@ -28,8 +28,7 @@ fn create_test_flowfield(width: usize, height: usize) -> FlowField {
 fn add_wall(ff: &mut FlowField, x: usize, y: usize, width: usize, height: usize) {
     for dy in 0..height {
         for dx in 0..width {
-            let idx = ff.get_index(x + dx, y + dy);
-            ff.cost_field[idx] = 255; // obstacle
+            ff.set_obstacle(x + dx, y + dy);
         }
     }
 }
@@ -359,6 +358,74 @@ fn test_routing_table_correctness() {
     panic!("Failed to reach goal in {} hops", max_hops);
 }
 
+/// Portal ids along the route, collected by following next hops by hand
+/// (as `test_routing_table_correctness` does; every cluster is a single island here)
+fn manual_next_hop_walk(graph: &HierarchicalGraph, start: ClusterIslandId, goal: ClusterIslandId) -> Vec<usize> {
+    let mut portals = Vec::new();
+    let mut current = start;
+    while current != goal {
+        assert!(portals.len() < 20, "No route from {:?} to {:?} within 20 hops", start, goal);
+        let next_portal_id = graph.get_next_portal_for_island(current, goal).expect("route");
+        portals.push(next_portal_id);
+        let next_cluster = graph.portal_connections[next_portal_id].iter()
+            .map(|&(other_portal_id, _cost)| graph.portals[other_portal_id].cluster)
+            .find(|&cluster| cluster != current.cluster)
+            .expect("cross-cluster portal");
+        current = ClusterIslandId::new(next_cluster, IslandId(0));
+    }
+    portals
+}
+
+#[test]
+fn test_route_portals_matches_manual_next_hop_walk() {
+    let ff = create_test_flowfield(100, 100);
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let island = |cx, cy| ClusterIslandId::new((cx, cy), IslandId(0));
+    for (start, goal) in [(island(0, 0), island(3, 3)), (island(3, 0), island(0, 2)), (island(1, 1), island(2, 1))] {
+        let route = graph.route_portals(start, goal).expect("open map is fully connected");
+        assert_eq!(route, manual_next_hop_walk(&graph, start, goal), "{:?} -> {:?}", start, goal);
+        assert!(!route.is_empty());
+        // Crossing the last portal lands on the goal
+        assert_eq!(graph.portal_exit(*route.last().unwrap()), Some(goal));
+    }
+
+    assert_eq!(graph.route_portals(island(2, 2), island(2, 2)), Some(vec![]));
+}
+
+#[test]
+fn test_route_portals_unreachable_is_none() {
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 50, 0, 1, 100);
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let left = ClusterIslandId::new((0, 0), IslandId(0));
+    let right = ClusterIslandId::new((3, 0), IslandId(0));
+    assert_eq!(graph.route_portals(left, right), None);
+    assert!(graph.route_portals(left, ClusterIslandId::new((0, 3), IslandId(0))).is_some());
+}
+
+#[test]
+fn test_same_island_across_clusters_and_walls() {
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 50, 0, 1, 100);
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let pos = |x: f32, y: f32| FixedVec2::from_f32(x, y);
+    // Same open area: same cluster, and several clusters apart
+    assert!(same_island(pos(5.5, 5.5), pos(20.5, 10.5), &graph, &ff));
+    assert!(same_island(pos(10.5, 10.5), pos(40.5, 90.5), &graph, &ff));
+    // Rooms on either side of the wall
+    assert!(!same_island(pos(10.5, 10.5), pos(80.5, 80.5), &graph, &ff));
+    assert!(!same_island(pos(49.5, 50.5), pos(51.5, 50.5), &graph, &ff));
+    // Blocked and off-map positions reach nothing
+    assert!(!same_island(pos(50.5, 50.5), pos(50.5, 50.5), &graph, &ff));
+    assert!(!same_island(pos(10.5, 10.5), pos(-5.0, 10.5), &graph, &ff));
+}
+
 #[test]
 fn test_intra_cluster_routing() {
     // Test that routing within a cluster (between regions) works correctly
@@ -696,8 +763,7 @@ fn test_obstacle_avoidance_chooses_correct_side() {
     // This blocks direct east-west movement
     for y in 0..75 {
         for x in 50..55 {
-            let idx = ff.get_index(x, y);
-            ff.cost_field[idx] = 255; // obstacle
+            ff.set_obstacle(x, y);
         }
     }
     
@@ -772,8 +838,7 @@ fn test_goal_island_detection_with_obstacles() {
     // Wall from x=55 to x=57 (middle of cluster), y=50 to y=74
     for y in 50..74 {
         for x in 55..57 {
-            let idx = ff.get_index(x, y);
-            ff.cost_field[idx] = 255; // obstacle
+            ff.set_obstacle(x, y);
         }
     }
     
@@ -829,6 +894,85 @@ fn test_goal_island_detection_with_obstacles() {
     }
 }
 
+/// 100x100 map with a wall at x 55..57 cutting cluster (2, 2) into left and right islands
+fn create_split_cluster_flowfield() -> FlowField {
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 55, 50, 2, 25);
+    ff
+}
+
+#[test]
+fn test_resolve_goal_island_on_each_side_of_wall() {
+    let ff = create_split_cluster_flowfield();
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+    let cluster = graph.get_cluster(2, 2).expect("Cluster (2,2) should exist");
+
+    let left_goal = FixedVec2::new(FixedNum::from_num(52.5), FixedNum::from_num(62.5));
+    let right_goal = FixedVec2::new(FixedNum::from_num(65.5), FixedNum::from_num(62.5));
+    let (left_cluster, left_island, left_region) = resolve_goal(&graph, &ff, left_goal).expect("Left goal should resolve");
+    let (right_cluster, right_island, right_region) = resolve_goal(&graph, &ff, right_goal).expect("Right goal should resolve");
+
+    assert_eq!(left_cluster, ClusterId::new(2, 2));
+    assert_eq!(right_cluster, ClusterId::new(2, 2));
+    assert_ne!(left_region, right_region, "Goals on opposite sides of the wall are in different regions");
+    assert_eq!(cluster.island_count, 2, "The wall should split the cluster in two");
+    assert_ne!(left_island, right_island, "Goals on opposite sides of the wall are in different islands");
+
+    // Same answer as the region polygons
+    let island_of = |goal| {
+        let local = world_to_cluster_local(goal, (2, 2), &ff).unwrap();
+        let region = get_region_id(&cluster.regions, cluster.region_count, local).unwrap();
+        (cluster.regions[region.0 as usize].as_ref().unwrap().island, region)
+    };
+    assert_eq!(island_of(left_goal), (left_island, left_region));
+    assert_eq!(island_of(right_goal), (right_island, right_region));
+
+    // Goals on the wall, or beside it outside any region, take a side's region
+    for x in [54.5, 55.5, 57.5] {
+        let goal = FixedVec2::new(FixedNum::from_num(x), FixedNum::from_num(62.5));
+        let resolved = resolve_goal(&graph, &ff, goal).expect("Goal by the wall should resolve");
+        assert!(resolved == (left_cluster, left_island, left_region) || resolved == (right_cluster, right_island, right_region),
+            "Goal at x {} resolved to {:?}", x, resolved);
+    }
+}
+
+#[test]
+fn test_path_request_routes_to_the_goal_side_of_wall() {
+    use bevy::prelude::*;
+    use crate::game::unit::UnitBundle;
+
+    let mut app = setup_path_request_app_on(create_split_cluster_flowfield());
+    let left_goal = FixedVec2::new(FixedNum::from_num(52.5), FixedNum::from_num(62.5));
+    let right_goal = FixedVec2::new(FixedNum::from_num(65.5), FixedNum::from_num(62.5));
+    let left_unit = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(5.0, 5.0), FixedNum::from_num(0.5), 0)).id();
+    let right_unit = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(6.0, 5.0), FixedNum::from_num(0.5), 0)).id();
+    app.world_mut().write_message(PathRequest::player(left_unit, left_goal));
+    app.world_mut().write_message(PathRequest::player(right_unit, right_goal));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let graph = app.world().resource::<HierarchicalGraph>();
+    let ff = &app.world().resource::<crate::game::simulation::MapFlowField>().0;
+    for (unit, goal) in [(left_unit, left_goal), (right_unit, right_goal)] {
+        let Some(Path::Active(PathState::Hierarchical { goal_cluster, goal_island, goal_region, .. })) = app.world().get::<Path>(unit) else {
+            panic!("Unit {:?} should have a hierarchical path", unit);
+        };
+        let (cluster, island, region) = resolve_goal(graph, ff, goal).unwrap();
+        assert_eq!((*goal_cluster, *goal_island, *goal_region), (cluster, island, Some(region)));
+
+        // The cached nav cell names the same region and island
+        let cluster_idx = types::ClusterArenaIdx::from_coords(cluster.0, cluster.1, app.world().resource::<NavigationLookup>().arenas.clusters_x);
+        let GoalNavCell(nav_cell) = *app.world().get::<GoalNavCell>(unit).unwrap();
+        assert_eq!(nav_cell.region_idx, types::RegionArenaIdx::from_cluster_and_local(cluster_idx, region));
+        assert_eq!(nav_cell.island_idx, types::IslandArenaIdx::from_cluster_and_local(cluster_idx, island));
+    }
+    let region = |unit| match app.world().get::<Path>(unit) {
+        Some(Path::Active(PathState::Hierarchical { goal_region, .. })) => *goal_region,
+        _ => unreachable!(),
+    };
+    assert_ne!(region(left_unit), region(right_unit), "Each unit should be sent to its own side of the wall");
+}
+
 #[test]
 fn test_world_to_cluster_local_conversion() {
     // Test that world_to_cluster_local conversion is accurate
@@ -936,8 +1080,7 @@ fn test_fallback_to_island_zero_scenario() {
     // Wall from y=60 to y=62, x=50 to x=74
     for y in 60..62 {
         for x in 50..74 {
-            let idx = ff.get_index(x, y);
-            ff.cost_field[idx] = 255; // obstacle
+            ff.set_obstacle(x, y);
         }
     }
     
@@ -998,3 +1141,551 @@ fn test_fallback_to_island_zero_scenario() {
         }
     }
 }
+
+/// App running only `process_path_requests` on an open 100x100 map
+fn setup_path_request_app() -> bevy::prelude::App {
+    setup_path_request_app_on(create_test_flowfield(100, 100))
+}
+
+/// App running only `process_path_requests` on `ff`
+fn setup_path_request_app_on(ff: FlowField) -> bevy::prelude::App {
+    use bevy::prelude::*;
+    use crate::game::simulation::{MapFlowField, SimConfig};
+
+    let mut graph = HierarchicalGraph::default();
+    let mut nav_lookup = NavigationLookup::default();
+    let mut nav_routing = NavigationRouting::default();
+    graph.build_graph_with_regions_sync(&ff, Some(&mut nav_lookup), Some(&mut nav_routing));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_message::<PathRequest>();
+    app.add_message::<PathFailed>();
+    app.insert_resource(MapFlowField(ff));
+    app.insert_resource(graph);
+    app.insert_resource(nav_lookup);
+    app.init_resource::<SimConfig>();
+    app.init_resource::<ActivePathSet>();
+    app.init_resource::<PendingPathRequests>();
+    app.init_resource::<PathRequestStats>();
+    app.add_systems(FixedUpdate, process_path_requests);
+    app
+}
+
+#[test]
+fn test_path_requests_to_same_goal_share_one_resolution() {
+    use bevy::prelude::*;
+    use crate::game::unit::UnitBundle;
+
+    let mut app = setup_path_request_app();
+    let shared_goal = FixedVec2::new(FixedNum::from_num(62.5), FixedNum::from_num(37.5));
+    let other_goal = FixedVec2::new(FixedNum::from_num(10.5), FixedNum::from_num(90.5));
+
+    let units: Vec<Entity> = (0..100)
+        .map(|i| app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(i as f32 * 0.5, 5.0), FixedNum::from_num(0.5), 0)).id())
+        .collect();
+    let loner = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(5.0, 5.0), FixedNum::from_num(0.5), 0)).id();
+    for &entity in &units {
+        app.world_mut().write_message(PathRequest::player(entity, shared_goal));
+    }
+    app.world_mut().write_message(PathRequest::player(loner, other_goal));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let stats = *app.world().resource::<PathRequestStats>();
+    assert_eq!(stats, PathRequestStats { requests: 101, goal_resolutions: 2, deferred: 0 });
+
+    // Every member of the group got the hierarchical path a lone request would get
+    let expected_cluster = ClusterId::new(62 / CLUSTER_SIZE, 37 / CLUSTER_SIZE);
+    for &entity in &units {
+        let Some(Path::Active(PathState::Hierarchical { goal, goal_cluster, goal_region, .. })) = app.world().get::<Path>(entity) else {
+            panic!("Unit {:?} should have a hierarchical path", entity);
+        };
+        assert_eq!(*goal, shared_goal);
+        assert_eq!(*goal_cluster, expected_cluster);
+        assert!(goal_region.is_some(), "Goal on open ground should resolve to a region");
+    }
+    let Some(Path::Active(PathState::Hierarchical { goal_cluster, .. })) = app.world().get::<Path>(loner) else {
+        panic!("Loner should have a hierarchical path");
+    };
+    assert_eq!(*goal_cluster, ClusterId::new(0, 3));
+    assert_eq!(app.world().resource::<ActivePathSet>().count(), 101);
+
+    // Counters describe the last run only
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(*app.world().resource::<PathRequestStats>(), PathRequestStats::default());
+}
+
+#[test]
+fn test_path_request_budget_defers_excess_to_later_ticks() {
+    use bevy::prelude::*;
+    use crate::game::simulation::SimConfig;
+    use crate::game::unit::UnitBundle;
+
+    let mut app = setup_path_request_app();
+    app.world_mut().resource_mut::<SimConfig>().path_requests_per_tick = 4;
+    let goal = FixedVec2::new(FixedNum::from_num(62.5), FixedNum::from_num(37.5));
+
+    let units: Vec<Entity> = (0..10)
+        .map(|i| app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(i as f32, 5.0), FixedNum::from_num(0.5), 0)).id())
+        .collect();
+    for &entity in &units {
+        app.world_mut().write_message(PathRequest::player(entity, goal));
+    }
+    let has_path = |app: &App, entity: Entity| matches!(app.world().get::<Path>(entity), Some(Path::Active(_)));
+
+    // 10 requests at 4 per tick: served oldest first over three ticks
+    let mut served = Vec::new();
+    for expected_deferred in [6, 2, 0] {
+        app.world_mut().run_schedule(FixedUpdate);
+        let stats = *app.world().resource::<PathRequestStats>();
+        assert_eq!(stats.deferred, expected_deferred);
+        assert_eq!(stats.requests, 10 - expected_deferred - served.len());
+        served = units.iter().copied().filter(|&e| has_path(&app, e)).collect();
+        assert_eq!(served, units[..10 - expected_deferred].to_vec(), "Requests should be served in order");
+    }
+    assert_eq!(app.world().resource::<ActivePathSet>().count(), 10);
+    assert!(app.world().resource::<PendingPathRequests>().is_empty());
+}
+
+#[test]
+fn test_pending_path_requests_supersede_and_cancel() {
+    use bevy::prelude::*;
+
+    let mut world = World::new();
+    let (a, b, c) = (world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id());
+    let goal = |x: f32| FixedVec2::from_f32(x, 0.0);
+
+    let mut pending = PendingPathRequests::default();
+    pending.push(&PathRequest::player(a, goal(1.0)));
+    pending.push(&PathRequest::player(b, goal(2.0)));
+    pending.push(&PathRequest::player(c, goal(3.0)));
+    // Re-ordered: newest goal wins, place in line is kept
+    pending.push(&PathRequest::player(a, goal(4.0)));
+    // Stopped while waiting
+    pending.cancel(b);
+    assert_eq!(pending.len(), 2);
+    assert!(!pending.contains(b));
+
+    let popped: Vec<(Entity, FixedVec2)> = std::iter::from_fn(|| pending.pop()).map(|r| (r.entity, r.goal)).collect();
+    assert_eq!(popped, vec![(a, goal(4.0)), (c, goal(3.0))]);
+    assert!(pending.is_empty());
+}
+
+#[test]
+fn test_high_priority_path_request_jumps_queued_low_priority_ones() {
+    use bevy::prelude::*;
+    use crate::game::simulation::SimConfig;
+    use crate::game::unit::UnitBundle;
+
+    let mut app = setup_path_request_app();
+    app.world_mut().resource_mut::<SimConfig>().path_requests_per_tick = 1;
+    let goal = FixedVec2::new(FixedNum::from_num(62.5), FixedNum::from_num(37.5));
+    let mut spawn = |app: &mut App, x: f32| {
+        app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(x, 5.0), FixedNum::from_num(0.5), 0)).id()
+    };
+
+    let background: Vec<Entity> = (0..3).map(|i| spawn(&mut app, i as f32)).collect();
+    for &entity in &background {
+        app.world_mut().write_message(PathRequest::background(entity, goal));
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(app.world().resource::<PathRequestStats>().deferred, 2);
+
+    // A player order arriving behind the backlog is served on the very next tick
+    let ordered = spawn(&mut app, 10.0);
+    app.world_mut().write_message(PathRequest::player(ordered, goal));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let has_path = |app: &App, entity: Entity| matches!(app.world().get::<Path>(entity), Some(Path::Active(_)));
+    assert!(has_path(&app, ordered), "Player order should not wait behind background repaths");
+    assert!(has_path(&app, background[0]));
+    assert!(!has_path(&app, background[1]) && !has_path(&app, background[2]));
+
+    // Then the low-priority backlog drains in order
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(has_path(&app, background[1]) && !has_path(&app, background[2]));
+}
+
+#[test]
+fn test_raising_priority_moves_queued_request_to_high_line() {
+    use bevy::prelude::*;
+
+    let mut world = World::new();
+    let (a, b, c) = (world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id());
+    let goal = |x: f32| FixedVec2::from_f32(x, 0.0);
+
+    let mut pending = PendingPathRequests::default();
+    pending.push(&PathRequest::background(a, goal(1.0)));
+    pending.push(&PathRequest::background(b, goal(2.0)));
+    pending.push(&PathRequest::player(c, goal(3.0)));
+    // Player re-orders a queued background unit: it joins the high line
+    pending.push(&PathRequest::player(b, goal(4.0)));
+    // A later background request doesn't demote it
+    pending.push(&PathRequest::background(b, goal(5.0)));
+    assert_eq!(pending.len(), 3);
+
+    let popped: Vec<(Entity, FixedVec2, PathPriority)> = std::iter::from_fn(|| pending.pop())
+        .map(|r| (r.entity, r.goal, r.priority))
+        .collect();
+    assert_eq!(popped, vec![
+        (c, goal(3.0), PathPriority::High),
+        (b, goal(5.0), PathPriority::High),
+        (a, goal(1.0), PathPriority::Low),
+    ]);
+    assert!(pending.is_empty());
+}
+
+/// 100x100 open map with cell (50, 50) walled in on all sides
+fn create_sealed_pocket_flowfield() -> FlowField {
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 49, 49, 3, 3);
+    ff.clear_obstacle(50, 50);
+    ff
+}
+
+#[test]
+fn test_goal_cell_in_sealed_pocket_relocates_to_open_ground() {
+    let ff = create_sealed_pocket_flowfield();
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    // Out through the wall and the cell hugging it (in no region), first direction tried is -x
+    assert_eq!(relocate_goal_cell(&graph, &ff, (50, 50), 10), Some((47, 50)));
+    // Open ground and blocked cells
+    assert_eq!(relocate_goal_cell(&graph, &ff, (10, 10), 10), Some((10, 10)));
+    assert_eq!(relocate_goal_cell(&graph, &ff, (49, 50), 10), Some((47, 50)));
+    // Open ground out of reach
+    assert_eq!(relocate_goal_cell(&graph, &ff, (50, 50), 2), None);
+}
+
+#[test]
+fn test_goal_cell_in_large_sealed_pocket_relocates_to_open_ground() {
+    // A walled room with 12x12 open cells inside, all in cluster (2, 2)
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 54, 54, 14, 14);
+    for y in 55..67 {
+        for x in 55..67 {
+            ff.clear_obstacle(x, y);
+        }
+    }
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let room = graph.island_at(ff.grid_to_world(60, 60), &ff).unwrap();
+    let outside = graph.island_at(ff.grid_to_world(52, 60), &ff).unwrap();
+    assert_eq!(room.cluster, outside.cluster);
+    assert_ne!(room, outside, "The room should be an island of its own");
+    assert!(graph.is_sealed_pocket(room));
+    assert!(!graph.is_sealed_pocket(outside));
+
+    // Out across the room, its wall and the cell hugging it, first direction tried is -x
+    assert_eq!(relocate_goal_cell(&graph, &ff, (60, 60), 10), Some((52, 60)));
+    assert_eq!(relocate_goal_cell(&graph, &ff, (60, 60), 7), None);
+}
+
+#[test]
+fn test_path_request_into_sealed_pocket_paths_to_nearest_open_cell() {
+    use bevy::prelude::*;
+    use crate::game::unit::UnitBundle;
+
+    let ff = create_sealed_pocket_flowfield();
+    let expected_goal = ff.grid_to_world(47, 50);
+    let pocket = ff.grid_to_world(50, 50);
+    let mut app = setup_path_request_app_on(ff);
+
+    let unit = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(5.0, 5.0), FixedNum::from_num(0.5), 0)).id();
+    app.world_mut().write_message(PathRequest::player(unit, pocket));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let Some(Path::Active(PathState::Hierarchical { goal, goal_cluster, goal_region, .. })) = app.world().get::<Path>(unit) else {
+        panic!("Unit should still get a path");
+    };
+    assert_eq!(*goal, expected_goal);
+    assert_eq!(*goal_cluster, ClusterId::new(47 / CLUSTER_SIZE, 50 / CLUSTER_SIZE));
+    assert!(goal_region.is_some(), "Relocated goal should be in a region");
+}
+
+#[test]
+fn test_path_request_to_unreachable_island_sends_path_failed() {
+    use bevy::prelude::*;
+    use crate::game::unit::UnitBundle;
+
+    // A wall from top to bottom splits the map into two islands
+    let mut ff = create_test_flowfield(100, 100);
+    add_wall(&mut ff, 50, 0, 2, 100);
+    let mut app = setup_path_request_app_on(ff);
+    app.init_resource::<PathFailureCounts>();
+
+    let unit = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(20.0, 30.0), FixedNum::from_num(0.5), 0)).id();
+    let off_map = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(20.0, 40.0), FixedNum::from_num(0.5), 0)).id();
+    let reachable = app.world_mut().spawn(UnitBundle::new(FixedVec2::from_f32(80.0, 30.0), FixedNum::from_num(0.5), 0)).id();
+    let goal = FixedVec2::from_f32(80.5, 60.5);
+    app.world_mut().write_message(PathRequest::player(unit, goal));
+    app.world_mut().write_message(PathRequest::player(off_map, FixedVec2::from_f32(150.0, 60.0)));
+    app.world_mut().write_message(PathRequest::player(reachable, goal));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let messages = app.world().resource::<Messages<PathFailed>>();
+    let mut failures: Vec<PathFailed> = messages.iter_current_update_messages().copied().collect();
+    failures.sort_by_key(|failure| failure.reason);
+    assert_eq!(failures, vec![
+        PathFailed { entity: off_map, reason: PathFailReason::GoalOffMap },
+        PathFailed { entity: unit, reason: PathFailReason::Unreachable },
+    ]);
+    assert!(matches!(app.world().get::<Path>(unit), Some(Path::Inactive)), "Unreachable goal should not start a path");
+    assert!(matches!(app.world().get::<Path>(reachable), Some(Path::Active(_))));
+
+    let counts = app.world().resource::<PathFailureCounts>();
+    assert_eq!(counts.get(PathFailReason::Unreachable), 1);
+    assert_eq!(counts.get(PathFailReason::GoalOffMap), 1);
+    assert_eq!(counts.total(), 2);
+}
+
+/// App running only the cache invalidation system on an open 20x20 map
+fn setup_integration_cache_app() -> bevy::prelude::App {
+    use bevy::prelude::*;
+    use crate::game::simulation::MapFlowField;
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(MapFlowField(create_test_flowfield(20, 20)));
+    app.init_resource::<IntegrationFieldCache>();
+    app.add_systems(FixedUpdate, invalidate_integration_field_cache);
+    // Insertion counts as a change; get that out of the way
+    app.world_mut().run_schedule(FixedUpdate);
+    app
+}
+
+#[test]
+fn test_integration_field_cache_hits_for_repeated_goal() {
+    use crate::game::simulation::MapFlowField;
+
+    let mut app = setup_integration_cache_app();
+    let world = app.world_mut();
+    let flow_field = world.resource::<MapFlowField>().0.clone();
+    let mut cache = world.resource_mut::<IntegrationFieldCache>();
+
+    let first = cache.get_or_compute(&flow_field, (15, 15));
+    let second = cache.get_or_compute(&flow_field, (15, 15));
+    assert!(std::sync::Arc::ptr_eq(&first, &second), "Second request should reuse the cached field");
+    assert_eq!((cache.hits, cache.misses), (1, 1));
+
+    cache.get_or_compute(&flow_field, (3, 4));
+    assert_eq!((cache.hits, cache.misses), (1, 2));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_integration_field_cache_evicts_least_recently_used() {
+    let flow_field = create_test_flowfield(10, 10);
+    let mut cache = IntegrationFieldCache::with_capacity(2);
+
+    cache.get_or_compute(&flow_field, (1, 1));
+    cache.get_or_compute(&flow_field, (2, 2));
+    cache.get_or_compute(&flow_field, (1, 1)); // (2, 2) is now the oldest
+    cache.get_or_compute(&flow_field, (3, 3));
+
+    assert!(cache.contains((1, 1)) && cache.contains((3, 3)));
+    assert!(!cache.contains((2, 2)));
+}
+
+#[test]
+fn test_cost_field_edit_invalidates_integration_field_cache() {
+    use crate::game::simulation::MapFlowField;
+
+    let mut app = setup_integration_cache_app();
+    let goal = (15, 15);
+    {
+        let world = app.world_mut();
+        let flow_field = world.resource::<MapFlowField>().0.clone();
+        world.resource_mut::<IntegrationFieldCache>().get_or_compute(&flow_field, goal);
+    }
+
+    // Untouched cost field: the entry survives a tick
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(app.world().resource::<IntegrationFieldCache>().contains(goal));
+
+    // Wall off the goal's row; the cached field no longer matches the map
+    for x in 0..20 {
+        app.world_mut().resource_mut::<MapFlowField>().0.set_obstacle(x, 10);
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(app.world().resource::<IntegrationFieldCache>().is_empty(), "Cost field edit should clear the cache");
+
+    let world = app.world_mut();
+    let flow_field = world.resource::<MapFlowField>().0.clone();
+    let mut cache = world.resource_mut::<IntegrationFieldCache>();
+    let field = cache.get_or_compute(&flow_field, goal);
+    assert_eq!(cache.misses, 2, "Request after the edit should recompute");
+    assert_eq!(field[flow_field.get_index(0, 0)], crate::game::structures::UNREACHABLE);
+}
+
+#[test]
+fn test_graph_build_progress_advances_to_totals() {
+    // 100x100 cells: a 4x4 grid of clusters
+    let ff = create_test_flowfield(100, 100);
+    let mut graph = HierarchicalGraph::default();
+    let mut nav_lookup = NavigationLookup::default();
+    let mut nav_routing = NavigationRouting::default();
+    let mut calls: Vec<(GraphBuildPhase, usize, usize)> = Vec::new();
+    graph.build_graph_with_regions_sync_progress(&ff, Some(&mut nav_lookup), Some(&mut nav_routing), &mut |phase, done, total| {
+        calls.push((phase, done, total));
+    });
+    assert!(graph.initialized);
+
+    // Phases in order, each counting up by one to its total
+    let phases: Vec<GraphBuildPhase> = calls.iter().map(|&(phase, _, _)| phase).collect();
+    assert!(phases.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", phases);
+    let mut expected_phases = phases.clone();
+    expected_phases.dedup();
+    assert_eq!(expected_phases, vec![
+        GraphBuildPhase::Regions,
+        GraphBuildPhase::Connectivity,
+        GraphBuildPhase::Portals,
+        GraphBuildPhase::IslandPortals,
+        GraphBuildPhase::RoutingTable,
+        GraphBuildPhase::NavigationLookup,
+        GraphBuildPhase::NavigationRouting,
+    ]);
+    for phase in expected_phases {
+        let counts: Vec<(usize, usize)> = calls.iter()
+            .filter(|&&(p, _, _)| p == phase)
+            .map(|&(_, done, total)| (done, total))
+            .collect();
+        let total = counts[0].1;
+        assert_eq!(counts, (1..=total).map(|done| (done, total)).collect::<Vec<_>>(), "{:?}", phase);
+    }
+    let clusters = graph.cluster_cols * graph.cluster_rows;
+    assert!(calls.contains(&(GraphBuildPhase::Regions, clusters, clusters)));
+
+    // Without navigation tables those phases are skipped; an empty field reports nothing
+    let mut phases = Vec::new();
+    HierarchicalGraph::default().build_graph_with_regions_sync_progress(&ff, None, None, &mut |phase, _, _| phases.push(phase));
+    assert_eq!(phases.last(), Some(&GraphBuildPhase::RoutingTable));
+    let mut called = false;
+    HierarchicalGraph::default().build_graph_with_regions_sync_progress(&FlowField::default(), None, None, &mut |_, _, _| called = true);
+    assert!(!called);
+}
+
+/// Length of the straight legs from `start` through each portal to `goal`
+fn portal_route_length(graph: &HierarchicalGraph, start: FixedVec2, route: &[usize], goal: FixedVec2) -> FixedNum {
+    let mut points = vec![start];
+    points.extend(route.iter().map(|&id| graph.portals[id].world_pos));
+    points.push(goal);
+    points.windows(2).map(|leg| (leg[1] - leg[0]).length()).sum()
+}
+
+#[test]
+fn test_terrain_preference_detours_around_expensive_band() {
+    // 4x2 clusters; rough ground (cost 50) fills the middle two clusters of the bottom row
+    let mut ff = create_test_flowfield(100, 50);
+    for y in 0..25 {
+        for x in 25..75 {
+            let idx = ff.get_index(x, y);
+            ff.cost_field[idx] = 50;
+        }
+    }
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let start = FixedVec2::from_f32(5.5, 10.5);
+    let goal = FixedVec2::from_f32(95.5, 10.5);
+    let indifferent = preferred_portal_route(&graph, &ff, start, goal, &TerrainPreference::default()).unwrap();
+    let avoider = TerrainPreference::new(vec![CostBand::new(20, 254, FixedNum::from_num(10))]);
+    let avoiding = preferred_portal_route(&graph, &ff, start, goal, &avoider).unwrap();
+
+    // Indifferent units take the same route as the shared table would find (straight across)
+    let source = graph.island_at(start, &ff).unwrap();
+    let target = graph.island_at(goal, &ff).unwrap();
+    assert_eq!(indifferent.len(), graph.route_portals(source, target).unwrap().len());
+    assert!(indifferent.iter().all(|&id| graph.portals[id].cluster.1 == 0), "{:?}", indifferent);
+
+    // The avoider goes up through the second cluster row, a longer but cheaper way
+    assert!(avoiding.iter().any(|&id| graph.portals[id].cluster.1 == 1), "{:?}", avoiding);
+    assert!(
+        portal_route_length(&graph, start, &avoiding, goal) > portal_route_length(&graph, start, &indifferent, goal),
+        "avoiding {:?} should be longer than indifferent {:?}", avoiding, indifferent,
+    );
+    assert!(
+        avoider.segment_cost(start, goal, &ff) > FixedNum::from_num(90),
+        "Going straight through the band costs more than its length",
+    );
+}
+
+#[test]
+fn test_dot_export_has_a_node_per_island_and_an_edge_per_crossing() {
+    // Three clusters in a row, with a wall along the west edge of the last one sealing it off
+    let mut ff = create_test_flowfield(75, 25);
+    add_wall(&mut ff, 50, 0, 1, 25);
+    let mut graph = HierarchicalGraph::default();
+    graph.build_graph(&ff, false, None);
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph navigation {") && dot.trim_end().ends_with('}'));
+    let count = |pattern: &str| dot.lines().filter(|line| line.contains(pattern)).count();
+    assert_eq!(count("subgraph cluster_"), 3);
+    assert_eq!(count("[label=\"("), 3, "One node per (cluster, island):\n{}", dot);
+    // One crossing each way over the open border; the sealed-off cluster has none
+    assert_eq!(count(" -> "), 2, "One edge per portal crossing:\n{}", dot);
+    assert_eq!(count("\"0_0_0\" -> \"1_0_0\""), 1, "{}", dot);
+    assert_eq!(count("\"1_0_0\" -> \"0_0_0\""), 1, "{}", dot);
+    assert_eq!(count("\"2_0_0\" ["), 1);
+    assert_eq!(count("\"2_0_0\" ->") + count("-> \"2_0_0\""), 0, "{}", dot);
+}
+
+#[test]
+fn test_edge_clusters_of_non_multiple_maps_get_regions_and_portals() {
+    // Edge clusters 10x12, and 1 tile thick (where side portals sit on corner tiles)
+    for (width, height) in [(60, 37), (51, 26)] {
+        let ff = create_test_flowfield(width, height);
+        let mut graph = HierarchicalGraph::default();
+        graph.build_graph(&ff, false, None);
+        assert_eq!((graph.cluster_cols, graph.cluster_rows), (width.div_ceil(CLUSTER_SIZE), height.div_ceil(CLUSTER_SIZE)));
+        let extent = |(cx, cy): (usize, usize)| {
+            ((width - cx * CLUSTER_SIZE).min(CLUSTER_SIZE), (height - cy * CLUSTER_SIZE).min(CLUSTER_SIZE))
+        };
+
+        for ((cx, cy), cluster) in graph.clusters_iter() {
+            let (w, h) = extent((cx, cy));
+            assert!(cluster.region_count > 0, "{}x{}: cluster ({}, {}) has no regions", width, height, cx, cy);
+            for region in cluster.regions[..cluster.region_count].iter().flatten() {
+                assert!(region.bounds.max.x <= FixedNum::from_num(w) && region.bounds.max.y <= FixedNum::from_num(h),
+                    "{}x{}: region {:?} of cluster ({}, {}) spills past its {}x{} tiles", width, height, region.bounds, cx, cy, w, h);
+            }
+            for y in 0..h {
+                for x in 0..w {
+                    let pos = ff.grid_to_world(cx * CLUSTER_SIZE + x, cy * CLUSTER_SIZE + y);
+                    assert!(graph.island_at(pos, &ff).is_some(), "{}x{}: tile ({}, {}) of cluster ({}, {}) has no island", width, height, x, y, cx, cy);
+                }
+            }
+
+            // Every neighbor is reachable through a portal on the matching side
+            for direction in Direction::ALL {
+                let (dx, dy) = match direction {
+                    Direction::North => (0, 1), Direction::South => (0, -1),
+                    Direction::East => (1, 0), Direction::West => (-1, 0),
+                    Direction::NorthEast => (1, 1), Direction::NorthWest => (-1, 1),
+                    Direction::SouthEast => (1, -1), Direction::SouthWest => (-1, -1),
+                };
+                let (nx, ny) = (cx as isize + dx, cy as isize + dy);
+                let exists = nx >= 0 && ny >= 0 && (nx as usize) < graph.cluster_cols && (ny as usize) < graph.cluster_rows;
+                let portal_id = cluster.neighbor_connectivity[0][direction.as_index()];
+                assert_eq!(portal_id.is_some(), exists, "{}x{}: cluster ({}, {}) {:?}", width, height, cx, cy, direction);
+                if let Some(portal_id) = portal_id {
+                    let portal = &graph.portals[portal_id];
+                    let (px, py) = (portal.node.x - cx * CLUSTER_SIZE, portal.node.y - cy * CLUSTER_SIZE);
+                    assert!(portal.cluster == (cx, cy) && px < w && py < h, "{}x{}: portal {} outside cluster ({}, {})", width, height, portal_id, cx, cy);
+                    let leads_to: Vec<_> = graph.portal_connections[portal_id].iter().map(|&(other, _)| graph.portals[other].cluster).collect();
+                    assert_eq!(leads_to, vec![(nx as usize, ny as usize)], "{}x{}: cluster ({}, {}) {:?} portal", width, height, cx, cy, direction);
+                }
+            }
+        }
+        assert!(graph.portal_island_map.iter().all(Option::is_some));
+
+        let start = ClusterIslandId::new((0, 0), IslandId(0));
+        let far = ClusterIslandId::new((graph.cluster_cols - 1, graph.cluster_rows - 1), IslandId(0));
+        assert!(graph.get_next_portal_for_island(start, far).is_some());
+        assert!(graph.get_next_portal_for_island(far, start).is_some());
+    }
+}
+
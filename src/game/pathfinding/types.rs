@@ -52,6 +52,22 @@ impl Direction {
         self as usize
     }
     
+    /// Direction of the neighboring cluster `(dx, dy)` clusters away (+y is North);
+    /// `None` unless it is one of the eight adjacent clusters
+    pub fn from_offset(dx: isize, dy: isize) -> Option<Direction> {
+        match (dx, dy) {
+            (0, 1) => Some(Direction::North),
+            (0, -1) => Some(Direction::South),
+            (1, 0) => Some(Direction::East),
+            (-1, 0) => Some(Direction::West),
+            (1, 1) => Some(Direction::NorthEast),
+            (-1, 1) => Some(Direction::NorthWest),
+            (1, -1) => Some(Direction::SouthEast),
+            (-1, -1) => Some(Direction::SouthWest),
+            _ => None,
+        }
+    }
+
     /// All eight directions (cardinal + diagonal)
     pub const ALL: [Direction; 8] = [
         Direction::North,
@@ -69,6 +85,62 @@ impl Direction {
 pub struct PathRequest {
     pub entity: Entity,
     pub goal: FixedVec2,
+    pub priority: PathPriority,
+}
+
+// Within a tick: higher priority first, then by entity index (see `process_path_requests`).
+// Requests for one entity keep their write order, so the newest goal wins.
+impl crate::game::simulation::CanonicalOrder for PathRequest {
+    type Key = (std::cmp::Reverse<PathPriority>, u32, Entity);
+    fn canonical_key(&self) -> Self::Key {
+        (std::cmp::Reverse(self.priority), self.entity.index(), self.entity)
+    }
+}
+
+impl PathRequest {
+    /// Request for a player order (served first)
+    pub fn player(entity: Entity, goal: FixedVec2) -> Self {
+        Self { entity, goal, priority: PathPriority::High }
+    }
+
+    /// Request for a background repath (AI, stuck recovery)
+    pub fn background(entity: Entity, goal: FixedVec2) -> Self {
+        Self { entity, goal, priority: PathPriority::Low }
+    }
+}
+
+/// Which line a [`PathRequest`] waits in when the per-tick budget runs out.
+///
+/// Every queued high-priority request is served before any low-priority one, so player
+/// orders stay responsive while background repaths absorb the backlog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathPriority {
+    Low,
+    High,
+}
+
+/// Sent by `process_path_requests` when a request gives its unit no path
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathFailed {
+    pub entity: Entity,
+    pub reason: PathFailReason,
+}
+
+/// Why a [`PathRequest`] was dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathFailReason {
+    /// The goal lies outside the map
+    GoalOffMap,
+    /// No cluster region near the goal (graph not built there yet)
+    GoalOffGraph,
+    /// The unit stands outside the map or in a cluster the graph doesn't have
+    StartOffGraph,
+    /// Start and goal are on islands no portal route connects
+    Unreachable,
+    /// The goal is in a walled-off pocket with no open ground in reach to move it to
+    SealedPocket,
+    /// `ActivePathSet` is full, so the path could never be followed
+    ActivePathSetFull,
 }
 
 /// Cached navigation cell for the goal position
@@ -113,6 +185,9 @@ pub enum PathState {
         current_target: Option<FixedVec2>,
         /// Whether current target is an inter-cluster portal
         is_inter_cluster_target: bool,
+        /// The unit's own portal route (units with a `TerrainPreference`); `None` follows
+        /// the shared island routing table
+        portal_route: Option<super::preference::PortalRoute>,
     }
 }
 
@@ -161,6 +236,14 @@ pub struct Region {
     pub is_dangerous: bool,
 }
 
+impl Region {
+    /// Number of tiles the region covers (its bounds run through the outer tiles' centers)
+    pub fn cell_count(&self) -> usize {
+        let tiles = |min: FixedNum, max: FixedNum| (max - min).to_num::<usize>() + 1;
+        tiles(self.bounds.min.x, self.bounds.max.x) * tiles(self.bounds.min.y, self.bounds.max.y)
+    }
+}
+
 /// A portal connecting two regions within a cluster
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RegionPortal {
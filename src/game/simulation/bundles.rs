@@ -0,0 +1,55 @@
+//! Component bundles for entities that take part in collision.
+//!
+//! The spatial hash and collision systems each filter on a different subset of
+//! components; an entity missing one of them is silently skipped by that system
+//! (the mismatch the spatial hash diagnostics hunt for). Spawning through these
+//! bundles guarantees the full set.
+
+use bevy::prelude::*;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use super::components::*;
+
+/// Everything a moving collider needs for the spatial hash and collision systems.
+///
+/// `OccupiedCell` is deliberately not part of the bundle: it is owned by the spatial hash
+/// and only valid once the entity has actually been inserted. `update_spatial_hash` adds it
+/// on the next tick (or use `unit::spawn_unit`, which inserts immediately).
+#[derive(Bundle, Debug, Clone, Copy)]
+pub struct ColliderBundle {
+    pub position: SimPosition,
+    pub position_prev: SimPositionPrev,
+    pub collider: Collider,
+    pub collision_state: CollisionState,
+}
+
+impl ColliderBundle {
+    pub fn new(position: FixedVec2, collider: Collider) -> Self {
+        Self {
+            position: SimPosition(position),
+            position_prev: SimPositionPrev(position),
+            collider,
+            collision_state: CollisionState::default(),
+        }
+    }
+}
+
+/// Static circular obstacle (not stored in the spatial hash). `resolve_obstacle_collisions`
+/// tests units against its collider until the map is baked, then against the flow field.
+#[derive(Bundle, Debug, Clone, Copy)]
+pub struct ObstacleBundle {
+    pub obstacle: StaticObstacle,
+    pub position: SimPosition,
+    pub position_prev: SimPositionPrev,
+    pub collider: Collider,
+}
+
+impl ObstacleBundle {
+    pub fn new(position: FixedVec2, radius: FixedNum) -> Self {
+        Self {
+            obstacle: StaticObstacle,
+            position: SimPosition(position),
+            position_prev: SimPositionPrev(position),
+            collider: Collider::obstacle(radius),
+        }
+    }
+}
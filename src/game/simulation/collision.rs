@@ -6,6 +6,7 @@
 /// - Unit-obstacle collision resolution
 
 use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 use crate::game::spatial_hash::{SpatialHash, SpatialHashScratch};
 use crate::game::profiling::profile;
@@ -16,41 +17,176 @@ use super::resources::*;
 // Events
 // ============================================================================
 
-/// Event fired when two entities collide
+/// Event fired when two entities collide.
+///
+/// Carries both colliders' layers so gameplay readers can pick out the pairs they care
+/// about (e.g. unit touching a pickup) without looking up any components.
 #[derive(Event, Message, Debug, Clone)]
 pub struct CollisionEvent {
     pub entity1: Entity,
     pub entity2: Entity,
+    /// How far the colliders actually overlap; 0 for a pair only within
+    /// `SimConfig::collision_detection_margin` of touching
     pub overlap: FixedNum,
     pub normal: FixedVec2,
+    /// `Collider::layer` of `entity1`
+    pub layer1: u32,
+    /// `Collider::layer` of `entity2`
+    pub layer2: u32,
+}
+
+impl CollisionEvent {
+    /// Whether one side is on a layer in `a` and the other on a layer in `b`, in either order
+    pub fn is_between(&self, a: u32, b: u32) -> bool {
+        (self.layer1 & a != 0 && self.layer2 & b != 0) || (self.layer1 & b != 0 && self.layer2 & a != 0)
+    }
+
+    /// The colliding entity on a layer in `layers` (`entity1` if both are)
+    pub fn entity_on(&self, layers: u32) -> Option<Entity> {
+        if self.layer1 & layers != 0 {
+            Some(self.entity1)
+        } else if self.layer2 & layers != 0 {
+            Some(self.entity2)
+        } else {
+            None
+        }
+    }
+
+    /// Whether either side is on a [`layers::TRIGGERS`] layer (contact only, no push)
+    pub fn is_trigger(&self) -> bool {
+        (self.layer1 | self.layer2) & layers::TRIGGERS != 0
+    }
 }
 
 // ============================================================================
 // Collision Detection
 // ============================================================================
 
+/// Where `detect_collisions` finds each unit's candidates: the spatial hash, or the
+/// neighbor cache while it is enabled
+#[derive(SystemParam)]
+pub struct CollisionCandidates<'w> {
+    spatial_hash: Res<'w, SpatialHash>,
+    scratch: ResMut<'w, SpatialHashScratch>,
+    neighbor_cache: Option<ResMut<'w, CollisionNeighborCache>>,
+}
+
 /// Detect collisions between entities by querying spatial hash directly.
 /// 
 /// Uses preallocated scratch buffer for zero-allocation spatial queries.
-/// No caching - queries fresh position data every frame for accuracy.
+/// Neighbor positions come from the spatial hash's position cache, refreshed by
+/// `update_spatial_hash` earlier in the tick, so only the neighbor's `Collider` is looked up.
+///
+/// On a wrapping map, pairs on opposite sides of a seam collide across it.
+///
+/// Pairs count as colliding within `SimConfig::collision_detection_margin` of touching;
+/// `overlap` is still measured against the radii alone, so it is 0 until they touch.
+///
+/// With `SimConfig::collision_neighbor_slots` above 0 (and a [`CollisionNeighborCache`]),
+/// units test their cached neighbors at their current positions instead, and only query
+/// the hash on the ticks the cache is rebuilt. Those positions aren't shifted across a
+/// seam the way the hash query's are; the pair delta takes the short way regardless.
 #[profile]
 pub fn detect_collisions(
     mut query: Query<(Entity, &SimPosition, &Collider, &mut CollisionState)>,
-    position_collider_query: Query<(&SimPosition, &Collider)>,
-    spatial_hash: Res<SpatialHash>,
-    mut scratch: ResMut<SpatialHashScratch>,
+    collider_query: Query<&Collider>,
+    candidates: CollisionCandidates,
     sim_config: Res<SimConfig>,
     mut events: MessageWriter<CollisionEvent>,
     mut colliding_entities: Local<std::collections::HashSet<Entity>>,
 ) {
     colliding_entities.clear();
+    let margin = sim_config.collision_detection_margin;
+
+    let CollisionCandidates { spatial_hash, mut scratch, neighbor_cache } = candidates;
+    let slots = sim_config.collision_neighbor_slots;
+    let mut neighbor_cache = neighbor_cache.and_then(|mut cache| {
+        if slots == 0 {
+            if !cache.is_empty() {
+                cache.clear();
+            }
+            return None;
+        }
+        cache.start_tick(sim_config.collision_neighbor_refresh_interval);
+        Some(cache)
+    });
+
+    // Test one pair; `entity` is reported as `entity1`
+    let mut check = |entity: Entity, pos: FixedVec2, collider: &Collider, other_entity: Entity, other_pos: FixedVec2| {
+        let Ok(other_collider) = collider_query.get(other_entity) else {
+            return;
+        };
+        
+        // Check collision layers
+        if !collider.interacts_with(other_collider) {
+            return;
+        }
+        
+        let contact_dist = collider.radius + other_collider.radius;
+        let min_dist = contact_dist + margin;
+        let min_dist_sq = min_dist * min_dist;
+
+        // Shortest way across a seam on a wrapping map
+        let delta = spatial_hash.delta(other_pos, pos);
+        let dist_sq = delta.length_squared();
+        
+        if dist_sq < min_dist_sq {
+            colliding_entities.insert(entity);
+            colliding_entities.insert(other_entity);
+            
+            let dist = dist_sq.sqrt();
+            let overlap = (contact_dist - dist).max(FixedNum::ZERO);
+            let normal = if dist > sim_config.epsilon {
+                delta / dist
+            } else {
+                // When entities are at exactly the same position, use entity IDs to generate
+                // a deterministic but different direction for each pair
+                let angle = ((entity.index() ^ other_entity.index()) as f32 * 0.618033988749895) * std::f32::consts::TAU;
+                let cos = FixedNum::from_num(angle.cos());
+                let sin = FixedNum::from_num(angle.sin());
+                FixedVec2::new(cos, sin)
+            };
+
+            events.write(CollisionEvent {
+                entity1: entity,
+                entity2: other_entity,
+                overlap,
+                normal,
+                layer1: collider.layer,
+                layer2: other_collider.layer,
+            });
+        }
+    };
 
     // Query spatial hash directly for each entity (uses preallocated scratch buffer)
     for (entity, pos, collider, _) in query.iter() {
-        let search_radius = collider.radius * sim_config.collision_search_radius_multiplier;
+        let search_radius = collider.radius * sim_config.collision_search_radius_multiplier + margin;
+
+        if let Some(cache) = neighbor_cache.as_deref_mut() {
+            if cache.neighbors(entity).is_none() {
+                let position_of = |other| query.get(other).ok().map(|(_, other_pos, _, _)| other_pos.0);
+                spatial_hash.query_radius_sorted(pos.0, search_radius, Some(entity), &mut scratch, position_of);
+                cache.insert(entity, scratch.query_results.iter().copied().take(slots));
+            }
+            // Neighbor lists aren't symmetric (each unit keeps only its nearest), so pairs
+            // are deduplicated as tested rather than by entity order
+            for other_entity in cache.untested_neighbors(entity) {
+                // A stale entry (despawned since the rebuild) is skipped
+                let Ok((_, other_pos, other_collider, _)) = query.get(other_entity) else {
+                    continue;
+                };
+                // Unshifted positions, so `check` measures across any seam itself
+                if entity < other_entity {
+                    check(entity, pos.0, collider, other_entity, other_pos.0);
+                } else {
+                    check(other_entity, other_pos.0, other_collider, entity, pos.0);
+                }
+            }
+            continue;
+        }
         
         // Zero-allocation spatial query via scratch buffer
-        spatial_hash.query_radius(
+        spatial_hash.query_radius_with_positions(
             pos.0,
             search_radius,
             Some(entity),
@@ -58,52 +194,12 @@ pub fn detect_collisions(
         );
         
         // Check each nearby entity for collision
-        for &other_entity in &scratch.query_results {
+        for (&other_entity, &other_pos) in scratch.query_results.iter().zip(&scratch.query_positions) {
             // Skip duplicates to avoid double-processing the same collision
             if entity > other_entity {
                 continue;
             }
-            
-            // Fetch current position and collider data
-            let Ok((other_pos, other_collider)) = position_collider_query.get(other_entity) else {
-                continue;
-            };
-            
-            // Check collision layers
-            if (collider.mask & other_collider.layer) == 0 && (other_collider.mask & collider.layer) == 0 {
-                continue;
-            }
-            
-            let min_dist = collider.radius + other_collider.radius;
-            let min_dist_sq = min_dist * min_dist;
-
-            let delta = pos.0 - other_pos.0;
-            let dist_sq = delta.length_squared();
-            
-            if dist_sq < min_dist_sq {
-                colliding_entities.insert(entity);
-                colliding_entities.insert(other_entity);
-                
-                let dist = dist_sq.sqrt();
-                let overlap = min_dist - dist;
-                let normal = if dist > sim_config.epsilon {
-                    delta / dist
-                } else {
-                    // When entities are at exactly the same position, use entity IDs to generate
-                    // a deterministic but different direction for each pair
-                    let angle = ((entity.index() ^ other_entity.index()) as f32 * 0.618033988749895) * std::f32::consts::TAU;
-                    let cos = FixedNum::from_num(angle.cos());
-                    let sin = FixedNum::from_num(angle.sin());
-                    FixedVec2::new(cos, sin)
-                };
-
-                events.write(CollisionEvent {
-                    entity1: entity,
-                    entity2: other_entity,
-                    overlap,
-                    normal,
-                });
-            }
+            check(entity, pos.0, collider, other_entity, other_pos);
         }
     }
 
@@ -121,23 +217,34 @@ pub fn detect_collisions(
 // Collision Resolution
 // ============================================================================
 
-/// Resolve unit-unit collisions by applying repulsion forces
+/// Repulsion `resolve_collisions` applies to `entity1` for `event` (`entity2` gets the
+/// opposite); zero for trigger collisions and for pairs not yet touching
+pub fn repulsion_force(event: &CollisionEvent, sim_config: &SimConfig) -> FixedVec2 {
+    if event.is_trigger() || event.overlap == FixedNum::ZERO {
+        return FixedVec2::ZERO;
+    }
+    let max_overlap = FixedNum::from_num(10.0); // Cap overlap to prevent overflow
+
+    // Force increases as overlap increases
+    let capped_overlap = event.overlap.min(max_overlap);
+    let force_mag = sim_config.repulsion_force * (FixedNum::ONE + capped_overlap * sim_config.repulsion_decay);
+    event.normal * force_mag
+}
+
+/// Resolve unit-unit collisions by applying repulsion forces.
+///
+/// Trigger collisions (see [`CollisionEvent::is_trigger`]) are left to gameplay systems.
 #[profile]
 pub fn resolve_collisions(
     mut query: Query<&mut SimAcceleration>,
     sim_config: Res<SimConfig>,
     mut events: MessageReader<CollisionEvent>,
 ) {
-    let repulsion_strength = sim_config.repulsion_force;
-    let decay = sim_config.repulsion_decay;
-    let max_overlap = FixedNum::from_num(10.0); // Cap overlap to prevent overflow
-    
     for event in events.read() {
-        // Apply repulsion force based on overlap
-        // Force increases as overlap increases
-        let capped_overlap = event.overlap.min(max_overlap);
-        let force_mag = repulsion_strength * (FixedNum::ONE + capped_overlap * decay);
-        let force = event.normal * force_mag;
+        if event.is_trigger() {
+            continue;
+        }
+        let force = repulsion_force(event, &sim_config);
         
         // Apply to entity 1
         if let Ok(mut acc1) = query.get_mut(event.entity1) {
@@ -152,84 +259,78 @@ pub fn resolve_collisions(
 }
 
 
-/// Resolve collisions between units and static obstacles
+/// Resolve collisions between units and static obstacles.
+///
+/// Two sources of obstacle geometry, picked by [`MapStatus::terrain_baked`]:
+/// - **Baked** (game maps): blocked cells of the flow field cost field near the unit, each
+///   treated as a circle of half a cell. O(units × searched cells), independent of how many
+///   obstacles the map has.
+/// - **Unbaked** (editor before "Finalize / Bake Map"): the cost field is stale, so test
+///   `StaticObstacle` entity colliders directly. O(units × obstacles), fine for editing.
+///
+/// Only the unit's own mask counts here (obstacles mask everything): units whose collider
+/// masks out [`layers::OBSTACLE`] (flying units) are left alone.
 #[profile]
 pub fn resolve_obstacle_collisions(
-    mut units: Query<(Entity, &SimPosition, &mut SimAcceleration, &Collider), Without<StaticObstacle>>,
+    mut units: Query<(&SimPosition, &mut SimAcceleration, &Collider), Without<StaticObstacle>>,
     obstacle_query: Query<(&SimPosition, &Collider), With<StaticObstacle>>,
-    spatial_hash: Res<SpatialHash>,
-    mut scratch: ResMut<SpatialHashScratch>,
     map_flow_field: Res<MapFlowField>,
+    map_status: Option<Res<MapStatus>>,
     sim_config: Res<SimConfig>,
 ) {
     let repulsion_strength = sim_config.repulsion_force;
     let decay = sim_config.repulsion_decay;
+    let push = |acc: &mut SimAcceleration, delta: FixedVec2, dist_sq: FixedNum, min_dist: FixedNum| {
+        if dist_sq >= min_dist * min_dist || dist_sq <= sim_config.epsilon {
+            return;
+        }
+        let dist = dist_sq.sqrt();
+        let overlap = min_dist - dist;
+        let dir = delta / dist;
+        let force_mag = repulsion_strength * (FixedNum::ONE + overlap * decay);
+        acc.0 = acc.0 + dir * force_mag;
+    };
+
+    if !map_status.is_some_and(|status| status.terrain_baked) {
+        for (u_pos, mut u_acc, u_collider) in units.iter_mut() {
+            if !u_collider.collides_with_obstacles() {
+                continue;
+            }
+            for (obs_pos, obs_collider) in obstacle_query.iter() {
+                let delta = u_pos.0 - obs_pos.0;
+                push(&mut u_acc, delta, delta.length_squared(), u_collider.radius + obs_collider.radius);
+            }
+        }
+        return;
+    }
+
     let flow_field = &map_flow_field.0;
+    if flow_field.width == 0 || flow_field.height == 0 {
+        return;
+    }
     let obstacle_radius = flow_field.cell_size / FixedNum::from_num(2.0);
-    
-    for (entity, u_pos, mut u_acc, u_collider) in units.iter_mut() {
-        let unit_radius = u_collider.radius;
-        let min_dist = unit_radius + obstacle_radius;
-        let min_dist_sq = min_dist * min_dist;
+    let range = sim_config.obstacle_search_range.max(0) as usize;
 
-        if let Some((cx, cy)) = flow_field.world_to_grid(u_pos.0) {
-            // Check 3x3 neighbors
-            let range = sim_config.obstacle_search_range as usize;
-            let min_x = if cx >= range { cx - range } else { 0 };
-            let max_x = if cx + range < flow_field.width { cx + range } else { flow_field.width - 1 };
-            let min_y = if cy >= range { cy - range } else { 0 };
-            let max_y = if cy + range < flow_field.height { cy + range } else { flow_field.height - 1 };
-
-            for y in min_y..=max_y {
-                for x in min_x..=max_x {
-                    if flow_field.cost_field[flow_field.get_index(x, y)] == 255 {
-                        let o_pos = flow_field.grid_to_world(x, y);
-                        let delta = u_pos.0 - o_pos;
-                        let dist_sq = delta.length_squared();
-                        
-                        if dist_sq < min_dist_sq {
-                            if dist_sq > sim_config.epsilon {
-                                let dist = dist_sq.sqrt();
-                                let overlap = min_dist - dist;
-                                let dir = delta / dist;
-
-                                // Apply force
-                                let force_mag = repulsion_strength * (FixedNum::ONE + overlap * decay);
-                                u_acc.0 = u_acc.0 + dir * force_mag;
-                            }
-                        }
-                    }
-                }
-            }
+    for (u_pos, mut u_acc, u_collider) in units.iter_mut() {
+        if !u_collider.collides_with_obstacles() {
+            continue;
         }
+        let Some((cx, cy)) = flow_field.world_to_grid(u_pos.0) else { continue };
+        let min_dist = u_collider.radius + obstacle_radius;
 
-        // Check free obstacles using spatial hash query
-        let search_radius = unit_radius * sim_config.collision_search_radius_multiplier;
-        spatial_hash.query_radius(u_pos.0, search_radius, Some(entity), &mut scratch);
-        
-        for &neighbor_entity in &scratch.query_results {
-            // Check if this neighbor is a static obstacle
-            let Ok((obs_pos, obs_collider)) = obstacle_query.get(neighbor_entity) else {
-                continue;
-            };
-            
-            let min_dist_free = unit_radius + obs_collider.radius;
-            let min_dist_sq_free = min_dist_free * min_dist_free;
+        // Check (2 * range + 1)² neighbors
+        let min_x = cx.saturating_sub(range);
+        let max_x = (cx + range).min(flow_field.width - 1);
+        let min_y = cy.saturating_sub(range);
+        let max_y = (cy + range).min(flow_field.height - 1);
 
-            let delta = u_pos.0 - obs_pos.0;
-            let dist_sq = delta.length_squared();
-            
-            if dist_sq >= min_dist_sq_free || dist_sq <= sim_config.epsilon {
-                continue;
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if !flow_field.is_walkable(x, y) {
+                    let delta = u_pos.0 - flow_field.grid_to_world(x, y);
+                    push(&mut u_acc, delta, delta.length_squared(), min_dist);
+                }
             }
-            
-            let dist = dist_sq.sqrt();
-            let overlap = min_dist_free - dist;
-            let dir = delta / dist;
-
-            // Apply force
-            let force_mag = repulsion_strength * (FixedNum::ONE + overlap * decay);
-            u_acc.0 = u_acc.0 + dir * force_mag;
         }
     }
 }
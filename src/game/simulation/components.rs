@@ -4,6 +4,7 @@
 /// including position, velocity, collision, and caching components.
 
 use bevy::prelude::*;
+use std::collections::VecDeque;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 
 // ============================================================================
@@ -27,17 +28,48 @@ pub struct SimVelocity(pub FixedVec2);
 #[derive(Component, Debug, Clone, Copy, Default)]
 pub struct SimAcceleration(pub FixedVec2);
 
+/// Direction an entity faces, in radians counterclockwise from +x, in `(-π, π]`.
+/// Turned toward the velocity by `update_facing`.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Facing(pub FixedNum);
+
 // ============================================================================
 // Collision Components
 // ============================================================================
 
-/// Collision layers for filtering
+/// Collision layers for filtering.
+///
+/// A collider sits on exactly one layer and its mask lists the layers it collides with.
+/// Two colliders interact if either one's mask contains the other's layer.
 pub mod layers {
     pub const NONE: u32 = 0;
+    /// Ground units
     pub const UNIT: u32 = 1 << 0;
     pub const OBSTACLE: u32 = 1 << 1;
     pub const PROJECTILE: u32 = 1 << 2;
+    /// Air units: pass over obstacles and ground units
+    pub const FLYING: u32 = 1 << 3;
+    /// Items picked up by touching them
+    pub const PICKUP: u32 = 1 << 4;
     pub const ALL: u32 = u32::MAX;
+
+    /// Layers that only report contact: their collisions emit `CollisionEvent`s for gameplay
+    /// but push neither side apart
+    pub const TRIGGERS: u32 = PICKUP;
+
+    /// Ground units block each other and are stopped by obstacles
+    pub const GROUND_UNIT_MASK: u32 = UNIT | OBSTACLE;
+    /// Air units only keep their distance from each other
+    pub const FLYING_UNIT_MASK: u32 = FLYING;
+    /// Projectiles hit units (ground and air) and obstacles, but not each other
+    pub const PROJECTILE_MASK: u32 = UNIT | FLYING | OBSTACLE;
+    /// Pickups are collected by ground units
+    pub const PICKUP_MASK: u32 = UNIT;
+
+    /// Whether `layer` is a valid collider layer (exactly one bit set)
+    pub const fn is_single_layer(layer: u32) -> bool {
+        layer.is_power_of_two()
+    }
 }
 
 /// Collider component for collision detection
@@ -48,12 +80,60 @@ pub struct Collider {
     pub mask: u32,
 }
 
+impl Collider {
+    /// Collider on `layer` (a single `layers` bit) colliding with the layers in `mask`.
+    ///
+    /// # Panics
+    /// If `layer` isn't exactly one bit.
+    pub fn new(radius: FixedNum, layer: u32, mask: u32) -> Self {
+        assert!(layers::is_single_layer(layer), "Collider layer must be a single bit, got {:#b}", layer);
+        Self { radius, layer, mask }
+    }
+
+    pub fn ground_unit(radius: FixedNum) -> Self {
+        Self::new(radius, layers::UNIT, layers::GROUND_UNIT_MASK)
+    }
+
+    pub fn flying_unit(radius: FixedNum) -> Self {
+        Self::new(radius, layers::FLYING, layers::FLYING_UNIT_MASK)
+    }
+
+    pub fn projectile(radius: FixedNum) -> Self {
+        Self::new(radius, layers::PROJECTILE, layers::PROJECTILE_MASK)
+    }
+
+    pub fn pickup(radius: FixedNum) -> Self {
+        Self::new(radius, layers::PICKUP, layers::PICKUP_MASK)
+    }
+
+    /// Obstacles block everything that doesn't mask them out
+    pub fn obstacle(radius: FixedNum) -> Self {
+        Self::new(radius, layers::OBSTACLE, layers::ALL)
+    }
+
+    /// Whether the layer is exactly one bit (colliders built field by field aren't checked)
+    pub fn is_valid(&self) -> bool {
+        layers::is_single_layer(self.layer)
+    }
+
+    /// Whether the two colliders should be tested against each other
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        (self.mask & other.layer) != 0 || (other.mask & self.layer) != 0
+    }
+
+    /// Whether obstacles (entities or baked terrain) push this collider out. Obstacles don't
+    /// move, so this is decided by this collider's mask alone.
+    pub fn collides_with_obstacles(&self) -> bool {
+        self.mask & layers::OBSTACLE != 0
+    }
+}
+
 impl Default for Collider {
     fn default() -> Self {
         Self {
             radius: FixedNum::from_num(0.5),
             layer: layers::UNIT,
-            mask: layers::UNIT | layers::OBSTACLE,
+            mask: layers::GROUND_UNIT_MASK,
         }
     }
 }
@@ -82,18 +162,59 @@ pub struct FlowFieldObstacle;
 // Force Components
 // ============================================================================
 
-/// Force source component (for black holes, wind, etc.)
-#[derive(Component, Debug, Clone)]
+/// Radial steering force on every unit within `radius` of `position`
+/// (gather points, explosion knockback, the debug black holes / wind spots).
+///
+/// Applied by `apply_forces` during `SimSet::Steering` for as long as the entity exists;
+/// despawn it to end the effect.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct ForceSource {
-    pub force_type: ForceType,
-    pub radius: FixedNum, 
+    pub position: FixedVec2,
+    /// Acceleration magnitude applied to each affected unit (should be positive; the
+    /// direction comes from `kind`)
+    pub strength: FixedNum,
+    pub radius: FixedNum,
+    pub kind: ForceKind,
 }
 
-/// Type of force applied by a ForceSource
-#[derive(Debug, Clone, Copy)]
-pub enum ForceType {
-    Radial(FixedNum), // Strength. >0 attract, <0 repel.
-    Directional(FixedVec2), // Vector force.
+/// Direction of a [`ForceSource`]'s push
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForceKind {
+    /// Towards the source
+    Attract,
+    /// Away from the source
+    Repel,
+}
+
+impl ForceSource {
+    pub fn attract(position: FixedVec2, strength: FixedNum, radius: FixedNum) -> Self {
+        Self { position, strength, radius, kind: ForceKind::Attract }
+    }
+
+    pub fn repel(position: FixedVec2, strength: FixedNum, radius: FixedNum) -> Self {
+        Self { position, strength, radius, kind: ForceKind::Repel }
+    }
+
+    /// Acceleration this source adds to a unit at `unit_pos`.
+    ///
+    /// Zero outside the radius, and within 0.1 of the center where the direction is
+    /// numerically meaningless.
+    pub fn force_on(&self, unit_pos: FixedVec2) -> FixedVec2 {
+        let delta = self.position - unit_pos;
+        let dist_sq = delta.length_squared();
+        if dist_sq > self.radius * self.radius {
+            return FixedVec2::ZERO;
+        }
+        let dist = dist_sq.sqrt();
+        if dist <= FixedNum::from_num(0.1) {
+            return FixedVec2::ZERO;
+        }
+        let towards_source = delta / dist;
+        match self.kind {
+            ForceKind::Attract => towards_source * self.strength,
+            ForceKind::Repel => -towards_source * self.strength,
+        }
+    }
 }
 
 // ============================================================================
@@ -163,3 +284,53 @@ impl Default for OccupiedCell {
     }
 }
 
+// ============================================================================
+// Order Components
+// ============================================================================
+
+/// Move targets queued behind the unit's current order, oldest first.
+///
+/// Filled by queued [`UnitMoveCommand`](super::UnitMoveCommand)s. `process_input` sends the
+/// unit to the front target once it has no path left to follow (arrived, or the path
+/// failed). Per-unit move intent: a stop command or a replacing move empties it.
+#[derive(Component, Debug, Clone, Default, PartialEq)]
+pub struct WaypointQueue(pub VecDeque<FixedVec2>);
+
+// ============================================================================
+// Combat Components
+// ============================================================================
+
+/// Cooldown counted in simulation ticks (never wall-clock time), so it is identical on
+/// every client and part of the state `sim_checksum` covers.
+///
+/// `tick_cooldowns` decrements it once per fixed tick. Abilities check [`ready`](Self::ready)
+/// and restart it with [`start`](Self::start), or do both at once with
+/// [`try_trigger`](Self::try_trigger). Convert seconds with `SimTime::ticks_for`.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cooldown {
+    pub remaining_ticks: u32,
+}
+
+impl Cooldown {
+    pub fn new(ticks: u32) -> Self {
+        Self { remaining_ticks: ticks }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.remaining_ticks == 0
+    }
+
+    pub fn start(&mut self, ticks: u32) {
+        self.remaining_ticks = ticks;
+    }
+
+    /// If ready, restart with `ticks` and return true
+    pub fn try_trigger(&mut self, ticks: u32) -> bool {
+        if self.ready() {
+            self.start(ticks);
+            true
+        } else {
+            false
+        }
+    }
+}
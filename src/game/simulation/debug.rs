@@ -3,13 +3,18 @@
 /// This module handles all debug rendering including:
 /// - Path visualization for selected units
 /// - Force source visualization
+/// - Spatial hash cell occupancy
+/// - Collision pairs and resolution pushes
 
 use bevy::prelude::*;
+use std::collections::HashMap;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
 use crate::game::config::{GameConfig, GameConfigHandle};
 use crate::game::pathfinding::{Path, PathState, HierarchicalGraph};
-use super::components::ForceSource;
-use super::resources::{DebugConfig, MapFlowField};
+use crate::game::spatial_hash::SpatialHash;
+use super::collision::{repulsion_force, CollisionEvent};
+use super::components::{ForceSource, ForceKind, SimPosition};
+use super::resources::{DebugConfig, MapFlowField, SimConfig};
 
 // ============================================================================
 // Debug Toggle
@@ -22,6 +27,7 @@ pub fn toggle_debug(
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     graph: Res<HierarchicalGraph>,
+    spatial_hash: Res<SpatialHash>,
     selected_query: Query<&Path, With<crate::game::unit::Selected>>,
 ) {
     let Some(config) = game_configs.get(&config_handle.0) else { return };
@@ -59,6 +65,44 @@ pub fn toggle_debug(
             info!("Path visualization disabled");
         }
     }
+    if keyboard.just_pressed(config.key_debug_spatial_hash) {
+        let class_count = spatial_hash.size_classes().len();
+        if !debug_config.show_spatial_hash {
+            debug_config.show_spatial_hash = class_count > 0;
+            debug_config.spatial_hash_size_class = 0;
+        } else if (debug_config.spatial_hash_size_class as usize) + 1 < class_count {
+            debug_config.spatial_hash_size_class += 1;
+        } else {
+            debug_config.show_spatial_hash = false;
+        }
+
+        if debug_config.show_spatial_hash {
+            let class = debug_config.spatial_hash_size_class;
+            let cell_size = spatial_hash.size_classes()[class as usize].cell_size;
+            info!("Spatial hash cells debug: size class {} of {} (cell size {})", class, class_count, cell_size);
+            info!("  Grey = empty, green = 1..{} entities, red = {}+", 
+                  config.debug_spatial_hash_high_occupancy.saturating_sub(1), config.debug_spatial_hash_high_occupancy);
+            info!("  Grid B cells are drawn smaller than Grid A cells");
+        } else {
+            info!("Spatial hash cells debug disabled");
+        }
+    }
+    if keyboard.just_pressed(config.key_debug_spatial_hash_grids) {
+        debug_config.spatial_hash_split_grids = !debug_config.spatial_hash_split_grids;
+        info!("Spatial hash Grid A / Grid B tint: {}", debug_config.spatial_hash_split_grids);
+    }
+    if keyboard.just_pressed(config.key_debug_perf_overlay) {
+        debug_config.show_perf_overlay = !debug_config.show_perf_overlay;
+        info!("Performance overlay: {}", debug_config.show_perf_overlay);
+    }
+    if keyboard.just_pressed(config.key_debug_collisions) {
+        debug_config.show_collisions = !debug_config.show_collisions;
+        info!("Collision pairs debug: {}", debug_config.show_collisions);
+        if debug_config.show_collisions {
+            info!("  Orange line = colliding pair, cyan = trigger contact");
+            info!("  Red arrows = velocity change resolution gives each side per tick");
+        }
+    }
 }
 
 // ============================================================================
@@ -177,36 +221,8 @@ pub fn draw_unit_paths(
                             let from_island_id = ClusterIslandId::new(current_cluster, current_island);
                             let to_island_id = ClusterIslandId::new(goal_cluster.as_tuple(), *goal_island);
                             
-                            // Collect all portals in the path
-                            let mut portal_sequence = Vec::new();
-                            let mut visited = std::collections::HashSet::new();
-                            let mut current_island_id = from_island_id;
-                            
-                            while current_island_id != to_island_id && !visited.contains(&current_island_id) && portal_sequence.len() < 20 {
-                                visited.insert(current_island_id);
-                                
-                                if let Some(next_portal_id) = graph.get_next_portal_for_island(current_island_id, to_island_id) {
-                                    portal_sequence.push(next_portal_id);
-                                    
-                                    // Determine the island ID on the other side of this portal
-
-                                    if let Some(portal) = graph.portals.get(next_portal_id) {
-                                        let next_cluster = portal.cluster;
-                                        // For simplification, just assume we reach the correct island
-                                        // In reality we'd need to query which island contains the portal endpoint
-                                            if next_cluster == to_island_id.cluster {
-                                            current_island_id = to_island_id;
-                                        } else {
-                                            // Move to next cluster with island 0 as approximation
-                                            current_island_id = ClusterIslandId::new(next_cluster, crate::game::pathfinding::IslandId(0));
-                                        }
-                                    } else {
-                                        break;
-                                    }
-                                } else {
-                                    break;
-                                }
-                            }
+                            // Unreachable (or stale table): just the straight line to the goal
+                            let portal_sequence = graph.route_portals(from_island_id, to_island_id).unwrap_or_default();
                             
                             // Draw the path through all portals
                             for portal_id in portal_sequence {
@@ -239,7 +255,7 @@ pub fn draw_unit_paths(
 
 /// Draw force sources (black holes, wind spots, etc.)
 pub fn draw_force_sources(
-    query: Query<(&Transform, &ForceSource)>,
+    query: Query<&ForceSource>,
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     mut gizmos: Gizmos,
@@ -268,8 +284,8 @@ pub fn draw_force_sources(
     let view_radius = config.debug_view_radius;
     let camera_center = Vec2::new(center_pos.x, center_pos.z);
 
-    for (transform, source) in query.iter() {
-        let source_pos = Vec2::new(transform.translation.x, transform.translation.z);
+    for source in query.iter() {
+        let source_pos = source.position.to_vec2();
         
         // Cull force sources outside view radius
         let dx = source_pos.x - camera_center.x;
@@ -279,20 +295,289 @@ pub fn draw_force_sources(
             continue;
         }
 
-        let color = match source.force_type {
-            super::components::ForceType::Radial(strength) => {
-                if strength > FixedNum::ZERO {
-                    Color::srgb(0.5, 0.0, 0.5) // Purple for Black Hole
-                } else {
-                    Color::srgb(0.0, 1.0, 1.0) // Cyan for Wind
-                }
-            },
-            super::components::ForceType::Directional(_) => Color::srgb(1.0, 1.0, 0.0),
+        let color = match source.kind {
+            ForceKind::Attract => Color::srgb(0.5, 0.0, 0.5), // Purple for Black Hole
+            ForceKind::Repel => Color::srgb(0.0, 1.0, 1.0),   // Cyan for Wind
         };
         
+        let center = Vec3::new(source_pos.x, 0.0, source_pos.y);
         let radius = source.radius.to_num::<f32>();
-        gizmos.circle(transform.translation, radius, color);
+        gizmos.circle(center, radius, color);
         // Draw a smaller inner circle to indicate center
-        gizmos.circle(transform.translation, 0.5, color);
+        gizmos.circle(center, 0.5, color);
+    }
+}
+
+// ============================================================================
+// Spatial Hash Visualization
+// ============================================================================
+
+/// Occupancy bucket of a spatial hash cell in the debug overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellOccupancyBucket {
+    Empty,
+    Low,
+    /// At least `debug_spatial_hash_high_occupancy` entities
+    High,
+}
+
+impl CellOccupancyBucket {
+    pub fn from_count(count: usize, high_threshold: usize) -> Self {
+        if count == 0 {
+            Self::Empty
+        } else if count >= high_threshold {
+            Self::High
+        } else {
+            Self::Low
+        }
+    }
+}
+
+/// Overlay color for a cell holding `count` entities. With `split_grids`, Grid B
+/// (`grid_offset` 1) cells get a bluer tint than Grid A cells of the same bucket.
+pub fn spatial_hash_cell_color(count: usize, high_threshold: usize, grid_offset: u8, split_grids: bool) -> Color {
+    let grid_b = split_grids && grid_offset == 1;
+    match (CellOccupancyBucket::from_count(count, high_threshold), grid_b) {
+        (CellOccupancyBucket::Empty, false) => Color::srgba(0.5, 0.5, 0.5, 0.15),
+        (CellOccupancyBucket::Empty, true) => Color::srgba(0.4, 0.4, 0.7, 0.15),
+        (CellOccupancyBucket::Low, false) => Color::srgb(0.2, 0.9, 0.2),
+        (CellOccupancyBucket::Low, true) => Color::srgb(0.1, 0.7, 0.9),
+        (CellOccupancyBucket::High, false) => Color::srgb(1.0, 0.2, 0.1),
+        (CellOccupancyBucket::High, true) => Color::srgb(0.9, 0.1, 0.8),
+    }
+}
+
+/// Draw the cells of the selected size class around the camera, colored by occupancy
+pub fn draw_spatial_hash_cells(
+    debug_config: Res<DebugConfig>,
+    spatial_hash: Res<SpatialHash>,
+    config_handle: Res<GameConfigHandle>,
+    game_configs: Res<Assets<GameConfig>>,
+    mut gizmos: Gizmos,
+    q_camera: Query<(&Camera, &GlobalTransform), With<crate::game::camera::RtsCamera>>,
+) {
+    if !debug_config.show_spatial_hash {
+        return;
+    }
+    let class = debug_config.spatial_hash_size_class;
+    let Some(size_class) = spatial_hash.size_classes().get(class as usize) else { return };
+    let Some(config) = game_configs.get(&config_handle.0) else { return };
+    let Ok((camera, camera_transform)) = q_camera.single() else { return };
+
+    // Get camera view center (raycast to ground)
+    let camera_pos = camera_transform.translation();
+    let center_pos = if let Ok(ray) = camera.viewport_to_world(camera_transform, Vec2::new(640.0, 360.0)) {
+        if ray.direction.y.abs() > 0.001 {
+            let t = -ray.origin.y / ray.direction.y;
+            if t >= 0.0 {
+                ray.origin + ray.direction * t
+            } else {
+                camera_pos
+            }
+        } else {
+            camera_pos
+        }
+    } else {
+        camera_pos
+    };
+
+    let view_radius = config.debug_view_radius;
+    let camera_center = Vec2::new(center_pos.x, center_pos.z);
+
+    let cell_size = size_class.cell_size.to_num::<f32>();
+    let reach = FixedVec2::new(FixedNum::from_num(view_radius), FixedNum::from_num(view_radius));
+    let view_center = FixedVec2::from_f32(camera_center.x, camera_center.y);
+    for (grid_offset, grid, shrink) in [(0u8, &size_class.grid_a, 0.96), (1u8, &size_class.grid_b, 0.86)] {
+        // Only the cells around the camera (clamped to the grid): grids can have millions of cells
+        let (min_col, min_row) = grid.pos_to_cell(view_center - reach);
+        let (max_col, max_row) = grid.pos_to_cell(view_center + reach);
+        for row in min_row..=max_row {
+            for col in min_col..=max_col {
+                let center = grid.cell_center(col, row).to_vec2();
+                if center.distance(camera_center) > view_radius {
+                    continue;
+                }
+                // Counted as in `iter_cell_occupancies`, but only for the cells on screen
+                let count = grid.get_cell_entities(col, row).iter()
+                    .filter(|&&entity| entity != Entity::PLACEHOLDER)
+                    .count();
+                let color = spatial_hash_cell_color(
+                    count, config.debug_spatial_hash_high_occupancy, grid_offset, debug_config.spatial_hash_split_grids,
+                );
+                gizmos.rect(
+                    Isometry3d::new(
+                        Vec3::new(center.x, 0.05, center.y),
+                        Quat::from_rotation_x(std::f32::consts::FRAC_PI_2),
+                    ),
+                    Vec2::splat(cell_size * shrink),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Collision Visualization
+// ============================================================================
+
+/// Height above the ground collision pairs are drawn at
+const COLLISION_DEBUG_HEIGHT: f32 = 0.1;
+
+/// One colliding pair in the collision overlay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionDebugSegment {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    pub from: Vec2,
+    pub to: Vec2,
+    /// Velocity change resolution gives `entity1` in one tick (`entity2` gets the
+    /// opposite), before the `max_acceleration` clamp. Zero for triggers.
+    pub push: Vec2,
+    pub trigger: bool,
+}
+
+/// Draw data for `events`: one segment per colliding pair, between the current positions
+/// from `position_of`. A pair reported more than once (several ticks in one frame) keeps
+/// its latest event; pairs with a despawned side are dropped.
+pub fn build_collision_segments<'a>(
+    events: impl IntoIterator<Item = &'a CollisionEvent>,
+    position_of: impl Fn(Entity) -> Option<FixedVec2>,
+    sim_config: &SimConfig,
+) -> Vec<CollisionDebugSegment> {
+    let delta = sim_config.fixed_delta();
+    let mut segments: Vec<CollisionDebugSegment> = Vec::new();
+    let mut slots: HashMap<(Entity, Entity), usize> = HashMap::new();
+    for event in events {
+        let (Some(from), Some(to)) = (position_of(event.entity1), position_of(event.entity2)) else { continue };
+        let segment = CollisionDebugSegment {
+            entity1: event.entity1,
+            entity2: event.entity2,
+            from: from.to_vec2(),
+            to: to.to_vec2(),
+            push: (repulsion_force(event, sim_config) * delta).to_vec2(),
+            trigger: event.is_trigger(),
+        };
+        match slots.get(&(event.entity1, event.entity2)) {
+            Some(&slot) => segments[slot] = segment,
+            None => {
+                slots.insert((event.entity1, event.entity2), segments.len());
+                segments.push(segment);
+            }
+        }
+    }
+    segments
+}
+
+/// Draw the pairs detected on the last tick that had any, with each side's push.
+///
+/// The pairs are kept between ticks so the overlay doesn't flicker on frames where no
+/// tick ran.
+pub fn draw_collision_pairs(
+    debug_config: Res<DebugConfig>,
+    sim_config: Res<SimConfig>,
+    mut events: MessageReader<CollisionEvent>,
+    positions: Query<&SimPosition>,
+    mut segments: Local<Vec<CollisionDebugSegment>>,
+    mut gizmos: Gizmos,
+) {
+    if !debug_config.show_collisions {
+        events.clear();
+        segments.clear();
+        return;
+    }
+    if !events.is_empty() {
+        let position_of = |entity| positions.get(entity).ok().map(|position| position.0);
+        *segments = build_collision_segments(events.read(), position_of, &sim_config);
+    }
+
+    let at = |point: Vec2| Vec3::new(point.x, COLLISION_DEBUG_HEIGHT, point.y);
+    for segment in segments.iter() {
+        let color = if segment.trigger { Color::srgb(0.0, 0.9, 0.9) } else { Color::srgb(1.0, 0.6, 0.0) };
+        gizmos.line(at(segment.from), at(segment.to), color);
+        if segment.push != Vec2::ZERO {
+            gizmos.arrow(at(segment.from), at(segment.from + segment.push), Color::srgb(1.0, 0.1, 0.1));
+            gizmos.arrow(at(segment.to), at(segment.to - segment.push), Color::srgb(1.0, 0.1, 0.1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occupancy_buckets() {
+        assert_eq!(CellOccupancyBucket::from_count(0, 8), CellOccupancyBucket::Empty);
+        assert_eq!(CellOccupancyBucket::from_count(1, 8), CellOccupancyBucket::Low);
+        assert_eq!(CellOccupancyBucket::from_count(7, 8), CellOccupancyBucket::Low);
+        assert_eq!(CellOccupancyBucket::from_count(8, 8), CellOccupancyBucket::High);
+        assert_eq!(CellOccupancyBucket::from_count(500, 8), CellOccupancyBucket::High);
+        // Threshold of 1: any entity is high, empty stays empty
+        assert_eq!(CellOccupancyBucket::from_count(1, 1), CellOccupancyBucket::High);
+        assert_eq!(CellOccupancyBucket::from_count(0, 0), CellOccupancyBucket::Empty);
+    }
+
+    #[test]
+    fn test_cell_colors_follow_bucket_and_grid_split() {
+        let colors: Vec<Color> = [0, 3, 9].iter().map(|&count| spatial_hash_cell_color(count, 8, 0, false)).collect();
+        assert_ne!(colors[0], colors[1]);
+        assert_ne!(colors[1], colors[2]);
+        assert_eq!(spatial_hash_cell_color(3, 8, 0, false), spatial_hash_cell_color(5, 8, 0, false), "Same bucket, same color");
+
+        // Grid B only differs when split
+        assert_eq!(spatial_hash_cell_color(3, 8, 1, false), spatial_hash_cell_color(3, 8, 0, false));
+        assert_ne!(spatial_hash_cell_color(3, 8, 1, true), spatial_hash_cell_color(3, 8, 0, true));
+        assert_eq!(spatial_hash_cell_color(3, 8, 0, true), spatial_hash_cell_color(3, 8, 0, false), "Grid A keeps its colors");
+    }
+
+    #[test]
+    fn test_one_segment_per_colliding_pair() {
+        use crate::game::simulation::collision::detect_collisions;
+        use crate::game::spatial_hash::SpatialHashScratch;
+        use crate::game::unit::spawn_unit_in_world;
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        let mut sim_config = SimConfig::default();
+        sim_config.collision_detection_margin = FixedNum::ZERO;
+        app.insert_resource(sim_config);
+        app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5));
+        app.insert_resource(SpatialHashScratch::default_capacity());
+        app.add_message::<CollisionEvent>();
+        app.add_systems(FixedUpdate, detect_collisions);
+
+        // A row of three overlapping neighbors (ends don't touch) and a loner
+        let radius = FixedNum::from_num(0.5);
+        let row: Vec<Entity> = [0.0, 0.8, 1.6].iter()
+            .map(|&x| spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(x, 0.0), radius, 0))
+            .collect();
+        spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(10.0, 10.0), radius, 0);
+        app.world_mut().run_schedule(FixedUpdate);
+
+        let world = app.world();
+        let events: Vec<CollisionEvent> = world.resource::<Messages<CollisionEvent>>()
+            .iter_current_update_messages().cloned().collect();
+        let position_of = |entity| world.get::<SimPosition>(entity).map(|position| position.0);
+        let config = world.resource::<SimConfig>();
+        // Reading the same tick twice (two ticks in one frame) still gives one per pair
+        let segments = build_collision_segments(events.iter().chain(&events), position_of, config);
+
+        let mut pairs: Vec<(Entity, Entity)> = segments.iter()
+            .map(|segment| (segment.entity1.min(segment.entity2), segment.entity1.max(segment.entity2)))
+            .collect();
+        pairs.sort();
+        let mut expected = vec![
+            (row[0].min(row[1]), row[0].max(row[1])),
+            (row[1].min(row[2]), row[1].max(row[2])),
+        ];
+        expected.sort();
+        assert_eq!(pairs, expected);
+
+        for segment in &segments {
+            assert!((segment.from.distance(segment.to) - 0.8).abs() < 1e-3, "Segment should join the pair");
+            assert!(!segment.trigger);
+            assert!(segment.push.dot(segment.from - segment.to) > 0.0, "Push should move entity1 away from entity2");
+        }
     }
 }
@@ -0,0 +1,221 @@
+//! Per-tick state digests for chasing determinism divergence.
+//!
+//! Inserting a [`TickDigestLog`] turns recording on: at the end of every tick,
+//! [`record_tick_digest`] stores the tick number and a hash of each simulated entity's
+//! position. Record two runs (or a run on each client), then [`diff_logs`] reports the first
+//! tick where they disagree and which entities moved differently. The checksum only says
+//! *that* runs diverged; the log says *where* to start looking.
+//!
+//! Without the resource the system does nothing, so it costs nothing in normal play.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use super::components::SimPosition;
+use super::resources::SimTick;
+
+/// One tick's digest: a position hash per entity, sorted by entity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickDigest {
+    pub tick: u64,
+    pub entities: Vec<(Entity, u64)>,
+}
+
+/// Ring buffer of the most recent [`TickDigest`]s (optional resource; enables recording)
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct TickDigestLog {
+    /// Most digests kept; older ones are dropped first
+    capacity: usize,
+    digests: VecDeque<TickDigest>,
+}
+
+impl TickDigestLog {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), digests: VecDeque::with_capacity(capacity.max(1)) }
+    }
+
+    /// Append a digest, dropping the oldest when full
+    pub fn push(&mut self, digest: TickDigest) {
+        if self.digests.len() == self.capacity {
+            self.digests.pop_front();
+        }
+        self.digests.push_back(digest);
+    }
+
+    /// Digests oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &TickDigest> {
+        self.digests.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// The digest recorded for `tick`, if it is still in the buffer
+    pub fn get(&self, tick: u64) -> Option<&TickDigest> {
+        let first = self.digests.front()?.tick;
+        let index = usize::try_from(tick.checked_sub(first)?).ok()?;
+        self.digests.get(index).filter(|digest| digest.tick == tick)
+    }
+
+    /// Write the log to a file, to diff against another client's
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(bincode::deserialize_from(reader)?)
+    }
+}
+
+/// Where two logs first disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickDivergence {
+    pub tick: u64,
+    /// Entities whose hashes differ, or that only one log has, in entity order
+    pub entities: Vec<Entity>,
+}
+
+/// FNV-1a over a position's raw fixed-point bits (stable across platforms and runs)
+fn position_hash(position: &SimPosition) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    for value in [position.0.x, position.0.y] {
+        for byte in (value.to_bits() as u64).to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Record this tick's digest into the [`TickDigestLog`], if there is one.
+///
+/// Runs after `sim_end`, so it sees the positions the tick ended with.
+pub fn record_tick_digest(
+    log: Option<ResMut<TickDigestLog>>,
+    tick: Res<SimTick>,
+    query: Query<(Entity, &SimPosition)>,
+) {
+    let Some(mut log) = log else { return };
+    let mut entities: Vec<(Entity, u64)> = query.iter()
+        .map(|(entity, position)| (entity, position_hash(position)))
+        .collect();
+    entities.sort_unstable_by_key(|&(entity, _)| entity);
+    log.push(TickDigest { tick: tick.0, entities });
+}
+
+/// First tick both logs recorded where the digests differ, or `None` if every shared tick
+/// matches. Ticks only one log still holds (ring buffer) are skipped.
+pub fn diff_logs(a: &TickDigestLog, b: &TickDigestLog) -> Option<TickDivergence> {
+    for digest_a in a.iter() {
+        let Some(digest_b) = b.get(digest_a.tick) else { continue };
+        if digest_a == digest_b {
+            continue;
+        }
+
+        // Merge the two sorted lists, keeping entities that differ or are missing from one
+        let mut entities = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        let (left, right) = (&digest_a.entities, &digest_b.entities);
+        while i < left.len() || j < right.len() {
+            match (left.get(i), right.get(j)) {
+                (Some(&(ea, ha)), Some(&(eb, hb))) if ea == eb => {
+                    if ha != hb {
+                        entities.push(ea);
+                    }
+                    i += 1;
+                    j += 1;
+                }
+                (Some(&(ea, _)), Some(&(eb, _))) if ea < eb => {
+                    entities.push(ea);
+                    i += 1;
+                }
+                (Some(_), Some(&(eb, _))) | (None, Some(&(eb, _))) => {
+                    entities.push(eb);
+                    j += 1;
+                }
+                (Some(&(ea, _)), None) => {
+                    entities.push(ea);
+                    i += 1;
+                }
+                (None, None) => break,
+            }
+        }
+        return Some(TickDivergence { tick: digest_a.tick, entities });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Digest giving the `index`th of `entities` a hash, sorted like `record_tick_digest` does
+    fn digest(tick: u64, entities: &[Entity], hashes: &[(usize, u64)]) -> TickDigest {
+        let mut entities: Vec<(Entity, u64)> = hashes.iter().map(|&(index, hash)| (entities[index], hash)).collect();
+        entities.sort_unstable_by_key(|&(entity, _)| entity);
+        TickDigest { tick, entities }
+    }
+
+    fn entities(count: usize) -> Vec<Entity> {
+        let mut world = World::new();
+        (0..count).map(|_| world.spawn_empty().id()).collect()
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_latest_ticks() {
+        let e = entities(1);
+        let mut log = TickDigestLog::new(3);
+        for tick in 1..=5 {
+            log.push(digest(tick, &e, &[(0, tick)]));
+        }
+        assert_eq!(log.iter().map(|digest| digest.tick).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert!(log.get(2).is_none());
+        assert_eq!(log.get(4), Some(&digest(4, &e, &[(0, 4)])));
+    }
+
+    #[test]
+    fn test_diff_reports_changed_and_missing_entities() {
+        let e = entities(4);
+        let mut a = TickDigestLog::new(10);
+        let mut b = TickDigestLog::new(10);
+        a.push(digest(1, &e, &[(0, 10), (1, 20)]));
+        b.push(digest(1, &e, &[(0, 10), (1, 20)]));
+        a.push(digest(2, &e, &[(0, 11), (1, 21), (3, 5)]));
+        b.push(digest(2, &e, &[(0, 11), (1, 99), (2, 7)]));
+        assert!(diff_logs(&a, &a).is_none());
+
+        let divergence = diff_logs(&a, &b).unwrap();
+        assert_eq!(divergence.tick, 2);
+        let mut expected = vec![e[1], e[2], e[3]];
+        expected.sort_unstable();
+        assert_eq!(divergence.entities, expected);
+    }
+
+    #[test]
+    fn test_saved_log_diffs_clean_against_original() {
+        let e = entities(2);
+        let mut log = TickDigestLog::new(4);
+        log.push(digest(7, &e, &[(0, 1), (1, 2)]));
+        let path = std::env::temp_dir().join(format!("peregrine_digest_{}.bin", std::process::id()));
+        log.save(&path).unwrap();
+        let loaded = TickDigestLog::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(diff_logs(&log, &loaded).is_none());
+    }
+}
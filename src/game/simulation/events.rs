@@ -4,7 +4,9 @@
 /// including movement commands, spawning, and stopping.
 
 use bevy::prelude::*;
-use crate::game::fixed_math::FixedVec2;
+use crate::game::collections::InclusionIndex;
+use crate::game::fixed_math::{FixedVec2, FixedNum};
+use super::components::OccupiedCell;
 
 // ============================================================================
 // Unit Commands
@@ -16,6 +18,9 @@ pub struct UnitMoveCommand {
     pub player_id: u8,
     pub entity: Entity,
     pub target: FixedVec2,
+    /// Append to the unit's [`WaypointQueue`](super::components::WaypointQueue) instead of
+    /// replacing its current orders (shift-click)
+    pub queued: bool,
 }
 
 /// Command to stop a unit's movement
@@ -30,4 +35,88 @@ pub struct UnitStopCommand {
 pub struct SpawnUnitCommand {
     pub player_id: u8,
     pub position: FixedVec2,
+    /// Collider radius override (None = default unit collider)
+    pub radius: Option<FixedNum>,
+}
+
+// ============================================================================
+// Deterministic Command Order
+// ============================================================================
+
+/// Total order for messages read in the same tick.
+///
+/// Messages come out in write order, and with several writers in one tick that order
+/// depends on system scheduling, which can differ between lockstep clients. Systems that
+/// apply commands sort each tick's batch by this key first (see [`canonical_order`]), so
+/// the same set of commands always has the same effect. The tick itself is implicit: a
+/// batch is one tick's messages, and command types are applied in a fixed order by the
+/// reading system.
+///
+/// Keys that name an entity order it by index first: `Entity`'s own `Ord` compares its
+/// raw bits, which don't follow spawn order.
+///
+/// Commands for an entity are keyed by who sent them and which entity they name, never by
+/// their contents: the sort is stable, so one player's commands for a unit keep the order
+/// they were issued in and the last click wins.
+pub trait CanonicalOrder {
+    type Key: Ord;
+    fn canonical_key(&self) -> Self::Key;
+}
+
+/// Queued and replacing moves share a key, so shift-clicked waypoints keep their click order
+/// and a replacing move issued after them still clears them
+impl CanonicalOrder for UnitMoveCommand {
+    type Key = (u8, u32, Entity);
+    fn canonical_key(&self) -> Self::Key {
+        (self.player_id, self.entity.index(), self.entity)
+    }
+}
+
+impl CanonicalOrder for UnitStopCommand {
+    type Key = (u8, u32, Entity);
+    fn canonical_key(&self) -> Self::Key {
+        (self.player_id, self.entity.index(), self.entity)
+    }
+}
+
+impl CanonicalOrder for SpawnUnitCommand {
+    type Key = (u8, FixedNum, FixedNum, Option<FixedNum>);
+    fn canonical_key(&self) -> Self::Key {
+        (self.player_id, self.position.x, self.position.y, self.radius)
+    }
+}
+
+/// Collect a tick's messages sorted by [`CanonicalOrder`], keeping write order among equal keys
+pub fn canonical_order<'a, T: CanonicalOrder + 'a>(messages: impl Iterator<Item = &'a T>) -> Vec<&'a T> {
+    let mut messages: Vec<&T> = messages.collect();
+    messages.sort_by_key(|message| message.canonical_key());
+    messages
+}
+
+// ============================================================================
+// Entity Lifecycle
+// ============================================================================
+
+/// Sent when an entity with a [`Collider`](super::components::Collider) is despawned, however
+/// that happens (death, editor, debug keys).
+///
+/// Written by an observer while the entity still exists, so it carries the bookkeeping
+/// needed to scrub it; `scrub_despawned_entities` does that at the start of the next tick.
+#[derive(Event, Message, Debug, Clone, Copy)]
+pub struct EntityDespawned {
+    pub entity: Entity,
+    /// Spatial hash cell it was stored in, if any
+    pub occupied: Option<OccupiedCell>,
+    /// Its slot in `ActivePathSet`, if it had one
+    pub path_index: Option<InclusionIndex>,
+}
+
+// ============================================================================
+// Simulation Status
+// ============================================================================
+
+/// Sent when the simulation starts or stops dropping time to keep up (see `SimOverloaded`)
+#[derive(Event, Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimOverloadChanged {
+    pub overloaded: bool,
 }
@@ -8,6 +8,8 @@
 /// - **physics**: Physics integration and movement
 /// - **systems**: Core systems (pathfollowing, spatial hash, etc.)
 /// - **debug**: Debug visualization (gizmos, paths, etc.)
+/// - **digest**: Per-tick state digests for finding where two runs diverge
+/// - **schedule**: The `FixedUpdate` systems of one tick, in order
 
 use bevy::prelude::*;
 use crate::game::GameState;
@@ -16,20 +18,24 @@ use crate::game::fixed_math::FixedNum;
 
 // Module declarations
 pub mod components;
+pub mod bundles;
 pub mod resources;
 pub mod events;
 pub mod collision;
 pub mod physics;
 pub mod systems;
 pub mod debug;
+pub mod digest;
+pub mod schedule;
 
 // Re-export commonly used items
 pub use components::*;
+pub use bundles::*;
 pub use resources::*;
 pub use events::*;
 
 // Re-export specific functions that are used externally
-pub use systems::apply_obstacle_to_flow_field;
+pub use systems::{apply_obstacle_to_flow_field, remove_obstacle_from_flow_field, rebuild_spatial_hash_from_world};
 
 // System sets for organizing execution order
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
@@ -52,6 +58,12 @@ impl Plugin for SimulationPlugin {
         app.init_resource::<SimConfig>();
         app.init_resource::<SimPerformance>();
         app.init_resource::<SimTick>();
+        app.init_resource::<SimTime>();
+        app.init_resource::<ActiveUnitSet>();
+        app.init_resource::<SpatialHashOverflow>();
+        app.init_resource::<CollisionNeighborCache>();
+        app.init_resource::<SpatialHashGrowth>();
+        app.init_resource::<SimOverloaded>();
         app.init_resource::<systems::PendingVecIdxUpdates>();
         app.insert_resource(SpatialHash::new(
             FixedNum::from_num(100.0),
@@ -64,6 +76,7 @@ impl Plugin for SimulationPlugin {
         // Initialize scratch buffer for zero-allocation spatial queries
         app.insert_resource(crate::game::spatial_hash::SpatialHashScratch::default_capacity());
         app.insert_resource(MapFlowField(Default::default()));
+        app.init_resource::<MapDimensions>();
         app.init_resource::<MapStatus>();
         app.init_resource::<DebugConfig>();
         
@@ -72,6 +85,9 @@ impl Plugin for SimulationPlugin {
         app.add_message::<UnitStopCommand>();
         app.add_message::<SpawnUnitCommand>();
         app.add_message::<collision::CollisionEvent>();
+        app.add_message::<SimOverloadChanged>();
+        app.add_message::<EntityDespawned>();
+        app.add_observer(systems::record_despawned_entity);
 
         // Configure System Sets
         app.configure_sets(FixedUpdate, (
@@ -82,9 +98,10 @@ impl Plugin for SimulationPlugin {
         ).chain().run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
 
         // Startup systems
+        // The config sets the map dimensions the flow field is sized from
         app.add_systems(Startup, (
+            systems::init_sim_config_from_initial,
             systems::init_flow_field,
-            systems::init_sim_config_from_initial
         ).chain());
         
         // OnEnter systems (map loading will be added back later)
@@ -97,6 +114,8 @@ impl Plugin for SimulationPlugin {
             debug::toggle_debug,
             debug::draw_force_sources,
             debug::draw_unit_paths,
+            debug::draw_spatial_hash_cells,
+            debug::draw_collision_pairs,
         ).run_if(in_state(GameState::InGame).or(in_state(GameState::Editor)).or(in_state(GameState::Loading))));
         
         app.add_systems(Update, 
@@ -106,38 +125,12 @@ impl Plugin for SimulationPlugin {
         
         // Use sequential spatial hash update (simple and efficient for typical workloads)
         
-        // Fixed update systems (deterministic simulation)
-        app.add_systems(FixedUpdate, (
-            // Increment tick counter first (before all other systems)
-            systems::increment_sim_tick.before(systems::sim_start),
-            
-            // Pre-simulation
-            systems::sim_start.before(SimSet::Input),
-            
-            // Input processing
-            physics::cache_previous_state.in_set(SimSet::Input),
-            systems::process_input.in_set(SimSet::Input),
-            
-            // Steering
-            physics::apply_friction.in_set(SimSet::Steering),
-            physics::apply_forces.in_set(SimSet::Steering),
-            
-            // Integration
-            physics::apply_velocity.in_set(SimSet::Integration),
-            
-            // Physics - Spatial Hash (full rebuild every frame)
-            systems::update_spatial_hash
-                .in_set(SimSet::Physics)
-                .before(collision::detect_collisions)
-                .before(collision::resolve_collisions),
-            collision::detect_collisions
-                .in_set(SimSet::Physics)
-                .before(collision::resolve_collisions),
-            collision::resolve_collisions.in_set(SimSet::Physics),
-            collision::resolve_obstacle_collisions.in_set(SimSet::Physics),
-            
-            // Post-simulation
-            systems::sim_end.after(SimSet::Physics),
-        ));
+        // Bound the fixed loop's catch-up before it runs (slow motion instead of a death spiral)
+        app.add_systems(RunFixedMainLoop,
+            systems::limit_sim_catchup.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop)
+        );
+        
+        // Fixed update systems (deterministic simulation), shared with HeadlessSimPlugin
+        app.add_systems(FixedUpdate, schedule::tick_systems());
     }
 }
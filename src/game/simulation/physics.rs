@@ -8,7 +8,8 @@
 /// - Map bounds constraints
 
 use bevy::prelude::*;
-use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::fixed_math::{self, FixedVec2, FixedNum};
+use crate::game::map::MapEdges;
 use super::components::*;
 use super::resources::*;
 use peregrine_macros::profile;
@@ -35,22 +36,48 @@ pub fn cache_previous_state(
 // Physics Integration
 // ============================================================================
 
+/// Most sub-steps a single unit is integrated in per tick
+pub const MAX_SUBSTEPS: usize = 16;
+
 /// Apply velocity to position
+///
+/// Only integrates units in [`ActiveUnitSet`] (all units if the resource is missing).
+///
+/// A unit that would move more than `max_move_per_substep` of its radius in one tick is
+/// integrated in sub-steps (at most [`MAX_SUBSTEPS`]) once the terrain is baked. It stops
+/// at the last sub-step before its center would enter a blocked cell, so fast units can't
+/// skip over a wall thinner than one tick's move; obstacle collision pushes it off next tick.
+/// Colliders that mask out obstacles (flying units) always move in one step.
+///
+/// At the border, [`SimConfig::map_edges`] decides: clamp the center onto it, keep the unit
+/// a radius inside it, or wrap the unit around to the opposite edge.
 #[profile(2)]
 pub fn apply_velocity(
     sim_config: Res<SimConfig>,
-    mut query: Query<(&mut SimPosition, &mut SimVelocity, &mut SimAcceleration)>,
+    mut query: Query<(&mut SimPosition, &mut SimVelocity, &mut SimAcceleration, Option<&Collider>)>,
+    active_units: Option<Res<ActiveUnitSet>>,
+    map_flow_field: Option<Res<MapFlowField>>,
+    map_status: Option<Res<MapStatus>>,
     #[allow(unused_variables)] tick: Res<SimTick>,
 ) {
-    let delta = FixedNum::from_num(1.0) / FixedNum::from_num(sim_config.tick_rate);
+    let delta = sim_config.fixed_delta();
     let max_velocity = sim_config.max_velocity;
     let max_velocity_sq = max_velocity * max_velocity;
     let max_acceleration = sim_config.max_acceleration;
     let max_acceleration_sq = max_acceleration * max_acceleration;
     let half_w = sim_config.map_size.get_width() / FixedNum::from_num(2.0);
     let half_h = sim_config.map_size.get_height() / FixedNum::from_num(2.0);
+    let substep_fraction = sim_config.max_move_per_substep;
+    let terrain = map_flow_field.as_deref()
+        .map(|map_flow_field| &map_flow_field.0)
+        .filter(|flow_field| flow_field.width > 0 && map_status.is_some_and(|status| status.terrain_baked));
+    let blocked = |pos: FixedVec2| {
+        terrain.is_some_and(|flow_field| flow_field.world_to_grid(pos).is_some_and(|(x, y)| !flow_field.is_walkable(x, y)))
+    };
 
-    for (mut pos, mut vel, mut acc) in query.iter_mut() {
+    // Only write through `Mut` when a value actually changes: `wake_units` treats
+    // any write as a reason to keep the unit active.
+    let integrate = |mut pos: Mut<SimPosition>, mut vel: Mut<SimVelocity>, mut acc: Mut<SimAcceleration>, collider: Option<&Collider>| {
         // Clamp acceleration to max_acceleration to prevent runaway forces
         let acc_sq = acc.0.length_squared();
         if acc_sq > max_acceleration_sq {
@@ -69,12 +96,53 @@ pub fn apply_velocity(
             vel.0 = vel.0.normalize() * max_velocity;
         }
 
-        // Update position
+        // Update position, in sub-steps if the move is long enough to skip a blocked cell
         if vel.0.length_squared() > FixedNum::ZERO {
-            pos.0 = pos.0 + vel.0 * delta;
+            let step = vel.0 * delta;
+            let radius = collider.map_or(sim_config.unit_radius, |collider| collider.radius);
+            let max_move = radius * substep_fraction;
+            let hits_terrain = collider.is_none_or(Collider::collides_with_obstacles);
+            let substeps = if terrain.is_some() && hits_terrain && max_move > FixedNum::ZERO {
+                (step.length() / max_move).ceil().to_num::<usize>().clamp(1, MAX_SUBSTEPS)
+            } else {
+                1
+            };
+
+            if substeps == 1 {
+                pos.0 = pos.0 + step;
+            } else {
+                let substep = step / FixedNum::from_num(substeps);
+                let mut end = pos.0;
+                for _ in 0..substeps {
+                    // A unit already pushed into a blocked cell may still move out of it
+                    if blocked(end + substep) && !blocked(end) {
+                        vel.0 = FixedVec2::ZERO;
+                        break;
+                    }
+                    end = end + substep;
+                }
+                if end != pos.0 {
+                    pos.0 = end;
+                }
+            }
         }
         
         // Immediately constrain to map bounds after position update
+        let (half_w, half_h) = match sim_config.map_edges {
+            MapEdges::Wrap => {
+                let wrapped = sim_config.map_size.wrap_point(pos.0);
+                if wrapped != pos.0 {
+                    pos.0 = wrapped;
+                }
+                return;
+            }
+            MapEdges::Open => (half_w, half_h),
+            // Solid border: keep the whole unit inside
+            MapEdges::Walls => {
+                let radius = collider.map_or(sim_config.unit_radius, |collider| collider.radius);
+                ((half_w - radius).max(FixedNum::ZERO), (half_h - radius).max(FixedNum::ZERO))
+            }
+        };
         let was_out_of_bounds = pos.0.x < -half_w || pos.0.x > half_w || 
                                  pos.0.y < -half_h || pos.0.y > half_h;
         
@@ -91,25 +159,111 @@ pub fn apply_velocity(
             if pos.0.y <= -half_h && vel.0.y < FixedNum::ZERO { vel.0.y = FixedNum::ZERO; }
             if pos.0.y >= half_h && vel.0.y > FixedNum::ZERO { vel.0.y = FixedNum::ZERO; }
         }
+    };
+
+    match active_units {
+        Some(active_units) => {
+            for entity in active_units.iter() {
+                if let Ok((pos, vel, acc, collider)) = query.get_mut(entity) {
+                    integrate(pos, vel, acc, collider);
+                }
+            }
+        }
+        None => {
+            for (pos, vel, acc, collider) in query.iter_mut() {
+                integrate(pos, vel, acc, collider);
+            }
+        }
     }
     
     profile_log!(tick, "[APPLY_VELOCITY] Entities: {}", query.iter().len());
 }
 
+/// Turn each unit's [`Facing`] toward its velocity, by at most
+/// [`SimConfig::unit_turn_rate`] per second, the short way round.
+///
+/// Units slower than `min_velocity` keep their facing, so a unit coming to rest doesn't
+/// snap to whatever direction its last bit of drift points. Only units in
+/// [`ActiveUnitSet`] are turned (all units if the resource is missing).
+#[profile(2)]
+pub fn update_facing(
+    sim_config: Res<SimConfig>,
+    mut query: Query<(&SimVelocity, &mut Facing)>,
+    active_units: Option<Res<ActiveUnitSet>>,
+    #[allow(unused_variables)] tick: Res<SimTick>,
+) {
+    let max_turn = sim_config.unit_turn_rate * sim_config.fixed_delta();
+    let min_velocity_sq = sim_config.min_velocity * sim_config.min_velocity;
+    let turn = |vel: &SimVelocity, mut facing: Mut<Facing>| {
+        if vel.0.length_squared() < min_velocity_sq {
+            return;
+        }
+        let target = fixed_math::atan2(vel.0.y, vel.0.x);
+        let offset = fixed_math::wrap_angle(target - facing.0);
+        if offset == FixedNum::ZERO {
+            return;
+        }
+        facing.0 = if offset.abs() <= max_turn {
+            target
+        } else {
+            fixed_math::wrap_angle(facing.0 + max_turn * offset.signum())
+        };
+    };
+
+    match active_units {
+        Some(active_units) => {
+            for entity in active_units.iter() {
+                if let Ok((vel, facing)) = query.get_mut(entity) {
+                    turn(vel, facing);
+                }
+            }
+        }
+        None => {
+            for (vel, facing) in query.iter_mut() {
+                turn(vel, facing);
+            }
+        }
+    }
+
+    profile_log!(tick, "[UPDATE_FACING] Entities: {}", query.iter().len());
+}
+
 /// Apply friction to slow down entities
+///
+/// Uses [`SimConfig::friction_per_tick`], so a coasting unit slows down over the same
+/// wall-clock time at any tick rate. Acceleration needs no such scaling: `apply_velocity`
+/// already integrates it with the fixed delta.
+///
+/// Idle units (not in [`ActiveUnitSet`]) are already at rest and are skipped.
 #[profile(2)]
 pub fn apply_friction(
     mut query: Query<&mut SimVelocity>,
     sim_config: Res<SimConfig>,
+    active_units: Option<Res<ActiveUnitSet>>,
     #[allow(unused_variables)] tick: Res<SimTick>,
 ) {
-    let friction = sim_config.friction;
+    let friction = sim_config.friction_per_tick();
     let min_velocity_sq = sim_config.min_velocity * sim_config.min_velocity;
-    for mut vel in query.iter_mut() {
+    let apply = |mut vel: Mut<SimVelocity>| {
+        // Don't touch resting units (a write would mark them changed and wake them)
+        if vel.0 == FixedVec2::ZERO {
+            return;
+        }
         vel.0 = vel.0 * friction;
         if vel.0.length_squared() < min_velocity_sq {
             vel.0 = FixedVec2::ZERO;
         }
+    };
+
+    match active_units {
+        Some(active_units) => {
+            for entity in active_units.iter() {
+                if let Ok(vel) = query.get_mut(entity) {
+                    apply(vel);
+                }
+            }
+        }
+        None => query.iter_mut().for_each(apply),
     }
     
     profile_log!(tick, "[APPLY_FRICTION] Entities: {}", query.iter().len());
@@ -120,35 +274,23 @@ pub fn apply_friction(
 // ============================================================================
 
 /// Apply force sources (black holes, wind, etc.)
+///
+/// Runs over all units, including idle ones: a force source must be able to push
+/// (and thereby wake) a resting unit.
 #[profile(2)]
 pub fn apply_forces(
     mut units: Query<(&SimPosition, &mut SimAcceleration)>,
-    sources: Query<(&SimPosition, &ForceSource)>,
+    sources: Query<&ForceSource>,
     #[allow(unused_variables)] tick: Res<SimTick>,
 ) {
+    if sources.is_empty() {
+        return;
+    }
     for (u_pos, mut u_acc) in units.iter_mut() {
-        for (s_pos, source) in sources.iter() {
-             let delta = s_pos.0 - u_pos.0;
-             let dist_sq = delta.length_squared();
-             
-             // Check radius
-             if source.radius > FixedNum::ZERO {
-                 let r_sq = source.radius * source.radius;
-                 if dist_sq > r_sq { continue; }
-             }
-
-             match source.force_type {
-                 ForceType::Radial(strength) => {
-                     let dist = dist_sq.sqrt();
-                     if dist > FixedNum::from_num(0.1) {
-                         let dir = delta / dist;
-                         u_acc.0 = u_acc.0 + dir * strength;
-                     }
-                 },
-                 ForceType::Directional(dir) => {
-                     u_acc.0 = u_acc.0 + dir;
-                 }
-             }
+        let force = sources.iter().fold(FixedVec2::ZERO, |sum, source| sum + source.force_on(u_pos.0));
+        // Only touch units that are actually pushed (writing wakes idle units)
+        if force != FixedVec2::ZERO {
+            u_acc.0 = u_acc.0 + force;
         }
     }
     
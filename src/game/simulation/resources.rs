@@ -5,9 +5,14 @@
 
 use bevy::prelude::*;
 use crate::game::fixed_math::{FixedNum, FixedVec2};
-use crate::game::map::MapSize;
-use crate::game::structures::FlowField;
+use crate::game::map::{MapEdges, MapSize};
+use crate::game::collections::{InclusionSet, SetConfig};
+use crate::game::pathfinding::EntityIndex;
+use crate::game::structures::{FlowField, CELL_SIZE};
+use crate::game::spatial_hash::{SpatialHash, SpatialHashError};
+use super::components::OccupiedCell;
 // NOLINT: Duration is a data type for storing time values, not for profiling/timing
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 // ============================================================================
@@ -46,6 +51,73 @@ impl SimTick {
     }
 }
 
+/// Current tick plus tick↔seconds conversions at the configured tick rate.
+///
+/// Gameplay timers (cooldowns, regen, damage over time) should go through this rather
+/// than reading `SimTick.0` and dividing by `SimConfig::tick_rate` themselves, so they
+/// all round the same way. Kept in sync by `update_sim_time` right after the tick
+/// counter increments.
+///
+/// ```rust
+/// use peregrine::game::fixed_math::FixedNum;
+/// use peregrine::game::simulation::SimTime;
+///
+/// let time = SimTime::new(90, 30.0);
+/// assert_eq!(time.seconds(time.ticks_since(30)), FixedNum::from_num(2));
+/// assert_eq!(time.ticks_for(FixedNum::from_num(0.5)), 15);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SimTime {
+    tick: u64,
+    tick_rate: FixedNum,
+}
+
+impl SimTime {
+    pub fn new(tick: u64, tick_rate: f64) -> Self {
+        Self { tick, tick_rate: FixedNum::from_num(tick_rate) }
+    }
+
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Ticks per second
+    pub fn tick_rate(&self) -> FixedNum {
+        self.tick_rate
+    }
+
+    /// Ticks elapsed since `tick` (0 if `tick` is in the future)
+    pub fn ticks_since(&self, tick: u64) -> u64 {
+        self.tick.saturating_sub(tick)
+    }
+
+    /// Duration of `ticks` in seconds
+    pub fn seconds(&self, ticks: u64) -> FixedNum {
+        FixedNum::saturating_from_num(ticks) / self.tick_rate
+    }
+
+    /// Ticks needed for `seconds` to pass, rounded up so a timer never fires early
+    /// (0 for zero or negative durations).
+    ///
+    /// Durations like 0.1 s aren't exact in fixed point; up to 1/64 tick over a whole
+    /// tick count is treated as that representation error rather than rounded up.
+    pub fn ticks_for(&self, seconds: FixedNum) -> u64 {
+        let slack = FixedNum::from_num(1) / FixedNum::from_num(64);
+        (seconds * self.tick_rate - slack).ceil().max(FixedNum::ZERO).to_num::<u64>()
+    }
+
+    /// Whether `duration` seconds have passed since `tick`
+    pub fn has_elapsed(&self, tick: u64, duration: FixedNum) -> bool {
+        self.ticks_since(tick) >= self.ticks_for(duration)
+    }
+}
+
+impl Default for SimTime {
+    fn default() -> Self {
+        Self::new(0, SimConfig::default().tick_rate)
+    }
+}
+
 // ============================================================================
 // Performance Tracking
 // ============================================================================
@@ -67,12 +139,450 @@ pub struct SimPerformance {
 #[derive(Resource, Default)]
 pub struct MapStatus {
     pub loaded: bool,
+    /// Every obstacle is rasterized into `MapFlowField`'s cost field (map generated, loaded
+    /// or finalized in the editor). Obstacle collision then reads the cost field instead of
+    /// testing `StaticObstacle` entities; cleared while the editor has unbaked changes.
+    pub terrain_baked: bool,
 }
 
 /// Flow field resource for the entire map
 #[derive(Resource, Default)]
 pub struct MapFlowField(pub FlowField);
 
+/// Authoritative size of the current map (centered on the origin) and its flow field cell size.
+///
+/// `SimConfig::map_size`, `MapFlowField` and `SpatialHash` are derived from this. Whoever
+/// changes the map size sets this resource and calls [`MapDimensions::apply`], so the three
+/// can't end up sized for different maps.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct MapDimensions {
+    pub width: FixedNum,
+    pub height: FixedNum,
+    /// Flow field cell size in world units
+    pub cell_size: FixedNum,
+}
+
+impl Default for MapDimensions {
+    fn default() -> Self {
+        Self::from_f32(2048.0, 2048.0)
+    }
+}
+
+impl MapDimensions {
+    pub fn new(width: FixedNum, height: FixedNum, cell_size: FixedNum) -> Self {
+        Self { width, height, cell_size }
+    }
+
+    /// Map of `width` x `height` world units with the default flow field `CELL_SIZE`
+    pub fn from_f32(width: f32, height: f32) -> Self {
+        Self::new(FixedNum::from_num(width), FixedNum::from_num(height), FixedNum::from_num(CELL_SIZE))
+    }
+
+    pub fn map_size(&self) -> MapSize {
+        let half_width = self.width / FixedNum::from_num(2.0);
+        let half_height = self.height / FixedNum::from_num(2.0);
+        MapSize {
+            top_left: FixedVec2::new(-half_width, -half_height),
+            bottom_right: FixedVec2::new(half_width, half_height),
+        }
+    }
+
+    /// Flow field columns and rows needed to cover the whole map
+    pub fn flow_field_cells(&self) -> (usize, usize) {
+        (
+            (self.width / self.cell_size).ceil().to_num::<usize>(),
+            (self.height / self.cell_size).ceil().to_num::<usize>(),
+        )
+    }
+
+    /// Empty (all walkable) flow field covering the map
+    pub fn flow_field(&self) -> FlowField {
+        let (width, height) = self.flow_field_cells();
+        FlowField::new(width, height, self.cell_size, self.map_size().top_left)
+    }
+
+    /// Resize everything derived from the map dimensions.
+    ///
+    /// The flow field is replaced by an empty one (rasterize obstacles afterwards) and the
+    /// spatial hash is emptied, so existing `OccupiedCell` components become stale.
+    pub fn apply(&self, sim_config: &mut SimConfig, flow_field: &mut FlowField, spatial_hash: &mut SpatialHash) {
+        sim_config.map_size = self.map_size();
+        *flow_field = self.flow_field();
+        spatial_hash.resize_map(self.width, self.height);
+    }
+}
+
+// ============================================================================
+// Activity Tracking
+// ============================================================================
+
+/// Units that are (potentially) moving this tick.
+///
+/// Most units in a large battle stand still. Idle units (zero velocity, zero acceleration,
+/// no active path) are dropped from this set by `sweep_idle_units` and skipped by
+/// friction, boids steering, integration and incremental spatial hash updates.
+///
+/// Units wake up when:
+/// - They are spawned or receive a move command (`process_input`)
+/// - Anything writes their `SimVelocity`, `SimAcceleration` or `Path` (collision
+///   pushes, force sources, external code) - detected by `wake_units`
+///
+/// Systems treat a missing resource as "everything is active".
+#[derive(Resource)]
+pub struct ActiveUnitSet {
+    inner: InclusionSet<EntityIndex>,
+}
+
+impl ActiveUnitSet {
+    /// Mark a unit as active. Returns false if the set is at capacity.
+    pub fn wake(&mut self, entity: Entity) -> bool {
+        self.inner.include(EntityIndex::from(entity)).is_included()
+    }
+
+    /// Check whether a unit is active (O(1))
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.inner.contains(EntityIndex::from(entity))
+    }
+
+    /// Iterate over all active units
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.inner.iter().map(Entity::from)
+    }
+
+    /// Number of active units
+    pub fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// Keep only units for which `keep` returns true. Returns the number removed.
+    ///
+    /// Rebuilds the set from the survivors (O(active)), so no per-entity
+    /// `InclusionIndex` bookkeeping is needed.
+    pub fn retain(&mut self, mut keep: impl FnMut(Entity) -> bool) -> usize {
+        let before = self.inner.count();
+        let survivors: Vec<Entity> = self.iter().filter(|&entity| keep(entity)).collect();
+        let removed = before - survivors.len();
+        if removed > 0 {
+            self.inner.clear();
+            for entity in survivors {
+                self.inner.include(EntityIndex::from(entity));
+            }
+        }
+        removed
+    }
+}
+
+impl Default for ActiveUnitSet {
+    fn default() -> Self {
+        Self {
+            inner: InclusionSet::new(SetConfig {
+                max_capacity: 10_000_000,         // 10M total entities
+                hot_capacity: Some(1_000_000),    // Stay in hot mode for typical battles
+                hysteresis_buffer: Some(100_000), // 10% buffer to prevent mode thrashing
+                sorted: false,
+            })
+        }
+    }
+}
+
+/// Raised when the spatial hash could not place an entity this tick.
+///
+/// In incremental mode a cell that runs out of headroom rejects the insertion
+/// (`insert_entity` returns `usize::MAX`). Left alone, that unit silently drops out of
+/// collision and proximity queries. `update_spatial_hash` sets `rebuild_pending` instead,
+/// and `rebuild_spatial_hash_on_overflow` rebuilds the hash from every entity's current
+/// position later in the same tick, redistributing headroom.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SpatialHashOverflow {
+    /// A rebuild is needed before anything queries the hash this tick
+    pub rebuild_pending: bool,
+    /// Insertions rejected since startup
+    pub rejected_insertions: usize,
+    /// Rebuilds triggered by overflow since startup
+    pub total_rebuilds: u64,
+    /// Tick of the most recent overflow rebuild
+    pub last_rebuild_tick: Option<u64>,
+}
+
+impl SpatialHashOverflow {
+    /// Ask for a rebuild before the next spatial query (e.g. headroom nearly exhausted)
+    pub fn request_rebuild(&mut self) {
+        self.rebuild_pending = true;
+    }
+}
+
+/// Each unit's nearest collision candidates, reused between rebuilds.
+///
+/// Only used while `SimConfig::collision_neighbor_slots` is above 0. `detect_collisions`
+/// rebuilds the whole cache every `collision_neighbor_refresh_interval` ticks, keeping each
+/// unit's `collision_neighbor_slots` nearest neighbors within its search radius; on the
+/// ticks in between it tests only those. Units spawned since the last rebuild query the
+/// spatial hash and get an entry of their own. Cached neighbors that were despawned (or
+/// lost their collider) are skipped.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct CollisionNeighborCache {
+    neighbors: HashMap<Entity, Vec<Entity>>,
+    /// Pairs already tested this tick (neighbor lists aren't symmetric)
+    tested_pairs: HashSet<(Entity, Entity)>,
+    /// Ticks since the last rebuild (`None` before the first)
+    age: Option<u32>,
+    rebuilds: u64,
+}
+
+impl CollisionNeighborCache {
+    /// Advance one tick, clearing the cache if it is due a rebuild. Returns whether it is.
+    pub fn start_tick(&mut self, refresh_interval: u32) -> bool {
+        self.tested_pairs.clear();
+        let age = self.age.map_or(u32::MAX, |age| age.saturating_add(1));
+        if age < refresh_interval.max(1) {
+            self.age = Some(age);
+            return false;
+        }
+        self.neighbors.clear();
+        self.age = Some(0);
+        self.rebuilds += 1;
+        true
+    }
+
+    /// Cached neighbors of `entity`, nearest first; `None` if it has no entry yet
+    pub fn neighbors(&self, entity: Entity) -> Option<&[Entity]> {
+        self.neighbors.get(&entity).map(Vec::as_slice)
+    }
+
+    pub fn insert(&mut self, entity: Entity, neighbors: impl IntoIterator<Item = Entity>) {
+        self.neighbors.insert(entity, neighbors.into_iter().collect());
+    }
+
+    /// Cached neighbors of `entity` whose pair with it wasn't tested yet this tick, marking
+    /// each as tested
+    pub fn untested_neighbors(&mut self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let tested = &mut self.tested_pairs;
+        self.neighbors.get(&entity).into_iter().flatten().copied()
+            .filter(move |&other| tested.insert((entity.min(other), entity.max(other))))
+    }
+
+    /// Rebuilds since startup
+    pub fn rebuilds(&self) -> u64 {
+        self.rebuilds
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.neighbors.is_empty()
+    }
+
+    /// Drop every entry; the next tick rebuilds
+    pub fn clear(&mut self) {
+        self.neighbors.clear();
+        self.age = None;
+    }
+}
+
+/// `Entity -> OccupiedCell` for every entity in the spatial hash, so code holding only an
+/// entity id can remove it from the hash without reading its component.
+///
+/// Opt-in: insert the resource to turn it on. `sync_entity_cell_index` keeps it in step with
+/// the `OccupiedCell` components at the end of every tick (after the hash updates, overflow
+/// rebuilds and fixups), so entries are current between ticks. Code that changes the hash
+/// itself mid-tick goes through [`insert`](Self::insert) and [`remove`](Self::remove).
+#[derive(Resource, Default, Debug, Clone)]
+pub struct EntityCellIndex {
+    cells: HashMap<Entity, OccupiedCell>,
+}
+
+impl EntityCellIndex {
+    pub fn get(&self, entity: Entity) -> Option<&OccupiedCell> {
+        self.cells.get(&entity)
+    }
+
+    /// Record `entity`'s cell, replacing any previous entry
+    pub fn set(&mut self, entity: Entity, cell: OccupiedCell) {
+        self.cells.insert(entity, cell);
+    }
+
+    /// Drop `entity`'s entry without touching the hash
+    pub fn forget(&mut self, entity: Entity) -> Option<OccupiedCell> {
+        self.cells.remove(&entity)
+    }
+
+    /// Insert `entity` into `spatial_hash` and record where it went
+    pub fn insert(
+        &mut self,
+        spatial_hash: &mut SpatialHash,
+        entity: Entity,
+        pos: FixedVec2,
+        radius: FixedNum,
+    ) -> Result<OccupiedCell, SpatialHashError> {
+        let cell = spatial_hash.insert(entity, pos, radius)?;
+        self.cells.insert(entity, cell);
+        Ok(cell)
+    }
+
+    /// Remove `entity` from `spatial_hash` through its indexed cell, like
+    /// [`SpatialHash::remove`] with its component. `None` if it has no entry or wasn't there.
+    pub fn remove(&mut self, spatial_hash: &mut SpatialHash, entity: Entity) -> Option<bool> {
+        let cell = self.cells.remove(&entity)?;
+        spatial_hash.remove(entity, &cell)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+}
+
+/// Resizes the spatial hash arena ahead of time when usage stays past a water mark.
+///
+/// Hitting hard overflow mid-battle forces a rebuild on the worst possible tick (see
+/// [`SpatialHashOverflow`]). `adapt_spatial_hash_capacity` feeds the fullest grid's usage in
+/// every tick and resizes before that happens:
+/// - usage > `high_water` for `sustain_ticks` consecutive ticks → grow
+/// - usage < `low_water` for `sustain_ticks` consecutive ticks → shrink (never below `min_capacity`)
+///
+/// Both resize so usage lands on `target_usage`, between the two marks, so one resize can't
+/// set up the opposite one. Any tick back inside the band resets the counters.
+#[derive(Resource, Debug, Clone)]
+pub struct SpatialHashGrowth {
+    pub high_water: f32,
+    pub low_water: f32,
+    pub target_usage: f32,
+    pub sustain_ticks: u32,
+    /// Capacity floor for shrinking (None = capacity seen on the first observed tick)
+    pub min_capacity: Option<usize>,
+    pub grow_count: u64,
+    pub shrink_count: u64,
+    /// Consecutive ticks above `high_water` / below `low_water`
+    pub high_ticks: u32,
+    pub low_ticks: u32,
+}
+
+impl SpatialHashGrowth {
+    /// Feed one tick's usage (`entries` in the fullest grid out of `capacity`).
+    ///
+    /// Returns the capacity to resize to, if this tick completes a sustained run.
+    pub fn observe(&mut self, entries: usize, capacity: usize) -> Option<usize> {
+        let min_capacity = *self.min_capacity.get_or_insert(capacity);
+        let usage = if capacity == 0 { 1.0 } else { entries as f32 / capacity as f32 };
+
+        if usage > self.high_water {
+            self.high_ticks += 1;
+            self.low_ticks = 0;
+        } else if usage < self.low_water {
+            self.low_ticks += 1;
+            self.high_ticks = 0;
+        } else {
+            self.high_ticks = 0;
+            self.low_ticks = 0;
+        }
+
+        let target = ((entries as f32 / self.target_usage).ceil() as usize).max(min_capacity);
+
+        if self.high_ticks >= self.sustain_ticks {
+            self.high_ticks = 0;
+            if target > capacity {
+                self.grow_count += 1;
+                return Some(target);
+            }
+        } else if self.low_ticks >= self.sustain_ticks {
+            self.low_ticks = 0;
+            if target < capacity {
+                self.shrink_count += 1;
+                return Some(target);
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for SpatialHashGrowth {
+    fn default() -> Self {
+        Self {
+            high_water: 0.9,
+            low_water: 0.3,
+            target_usage: 0.6,
+            sustain_ticks: 60,  // ~3s at 20 TPS
+            min_capacity: None,
+            grow_count: 0,
+            shrink_count: 0,
+            high_ticks: 0,
+            low_ticks: 0,
+        }
+    }
+}
+
+// ============================================================================
+// Overload Handling
+// ============================================================================
+
+/// Tracks whether the simulation is falling behind real time.
+///
+/// When a frame takes longer than `SimConfig::max_catchup_ticks` fixed ticks, the rest of
+/// the frame time is dropped instead of being simulated (see `limit_sim_catchup`): the game
+/// runs in slow motion rather than spiraling into ever longer catch-up frames.
+///
+/// `active` flips on with the first clamped frame and off again after `recovery_frames`
+/// consecutive frames that kept up, so a HUD warning doesn't flicker. Each flip also
+/// sends a [`SimOverloadChanged`](super::SimOverloadChanged) message.
+#[derive(Resource, Debug, Clone)]
+pub struct SimOverloaded {
+    pub active: bool,
+    pub recovery_frames: u32,
+    /// Frames since startup whose time was clamped
+    pub overloaded_frames: u64,
+    /// Real time dropped (not simulated) since startup
+    pub dropped: Duration,
+    /// Consecutive frames that kept up while `active`
+    pub calm_frames: u32,
+}
+
+impl SimOverloaded {
+    /// Feed one frame's real delta against the catch-up budget.
+    ///
+    /// Returns the new `active` state if it changed this frame.
+    pub fn observe(&mut self, real_delta: Duration, budget: Duration) -> Option<bool> {
+        if real_delta > budget {
+            self.overloaded_frames += 1;
+            self.dropped += real_delta - budget;
+            self.calm_frames = 0;
+            if !self.active {
+                self.active = true;
+                return Some(true);
+            }
+        } else if self.active {
+            self.calm_frames += 1;
+            if self.calm_frames >= self.recovery_frames {
+                self.active = false;
+                self.calm_frames = 0;
+                return Some(false);
+            }
+        }
+        None
+    }
+}
+
+impl Default for SimOverloaded {
+    fn default() -> Self {
+        Self {
+            active: false,
+            recovery_frames: 30,
+            overloaded_frames: 0,
+            dropped: Duration::ZERO,
+            calm_frames: 0,
+        }
+    }
+}
+
 // ============================================================================
 // Simulation Configuration
 // ============================================================================
@@ -111,12 +621,29 @@ pub struct SimConfig {
     pub tick_rate: f64,
     pub unit_speed: FixedNum,
     pub map_size: MapSize,
+    /// Border behavior (clamped, solid or wrapping)
+    pub map_edges: MapEdges,
     pub unit_radius: FixedNum,
     pub collision_push_strength: FixedNum,
     pub collision_restitution: FixedNum,
     pub collision_drag: FixedNum,
     pub collision_iterations: usize,
     pub collision_search_radius_multiplier: FixedNum,
+    /// Extra distance past the sum of radii at which two colliders already count as touching.
+    ///
+    /// Units closing at speed `v` get `v / tick_rate` nearer each tick, so a pair just
+    /// outside contact can end the next tick deep in overlap. A margin of at least
+    /// [`min_collision_margin`](Self::min_collision_margin) for the fastest expected
+    /// closing speed (about twice `max_velocity` head-on) reports them a tick early instead.
+    /// Higher tick rates need less margin. 0 only reports actual overlap.
+    pub collision_detection_margin: FixedNum,
+    /// Nearest neighbors cached per unit for collision detection (0 = no cache: query the
+    /// spatial hash for every unit every tick). See [`CollisionNeighborCache`].
+    pub collision_neighbor_slots: usize,
+    /// Ticks between collision neighbor cache rebuilds (1 or 0 = every tick). In between,
+    /// units only test the neighbors cached at the last rebuild, so a unit that came within
+    /// range since then is missed until the next one.
+    pub collision_neighbor_refresh_interval: u32,
     pub obstacle_search_range: i32,
     pub epsilon: FixedNum,
     pub obstacle_push_strength: FixedNum,
@@ -126,9 +653,18 @@ pub struct SimConfig {
     pub max_acceleration: FixedNum,
     pub repulsion_force: FixedNum,
     pub repulsion_decay: FixedNum,
+    /// Share of its velocity a unit keeps per tick at `friction_reference_tick_rate`.
+    /// Use [`friction_per_tick`](Self::friction_per_tick) for the factor at the actual tick rate.
     pub friction: FixedNum,
+    /// Tick rate `friction` was tuned at
+    pub friction_reference_tick_rate: f64,
     pub min_velocity: FixedNum,
     pub max_velocity: FixedNum,
+    /// Fastest a unit's [`Facing`](super::components::Facing) turns, in radians per second
+    pub unit_turn_rate: FixedNum,
+    /// Longest move per integration sub-step, as a fraction of the unit's radius (0 = one step).
+    /// Faster units are integrated in several sub-steps that stop short of blocked cells.
+    pub max_move_per_substep: FixedNum,
     pub braking_force: FixedNum,
     pub touch_dist_multiplier: FixedNum,
     pub check_dist_multiplier: FixedNum,
@@ -137,7 +673,11 @@ pub struct SimConfig {
     pub cohesion_weight: FixedNum,
     pub neighbor_radius: FixedNum,
     pub separation_radius: FixedNum,
+    /// Nearest neighbors each unit considers for boids (0 = every neighbor in radius)
     pub boids_max_neighbors: usize,
+    /// Ticks between boids recomputes per unit (1 or 0 = every tick). Units are staggered by
+    /// entity index so about 1/N of them recompute each tick; the rest reuse their last force.
+    pub boids_update_interval: u32,
     pub black_hole_strength: FixedNum,
     pub wind_spot_strength: FixedNum,
     pub force_source_radius: FixedNum,
@@ -145,6 +685,12 @@ pub struct SimConfig {
     // Spatial Hash Optimization
     pub spatial_hash_max_ticks_without_update: u8,
     pub spatial_hash_velocity_estimate_scale: FixedNum,
+    /// Maximum number of dynamic entities the spatial hash is sized for (spawns beyond this are refused)
+    pub max_entity_count: usize,
+    /// Most fixed ticks run per frame to catch up; time beyond that is dropped (slow motion)
+    pub max_catchup_ticks: u32,
+    /// Most path requests resolved per tick; the rest are deferred (see `PendingPathRequests`)
+    pub path_requests_per_tick: usize,
     
     // Parallel Update Configuration
     /// Enable parallel spatial hash updates (requires rayon)
@@ -162,12 +708,16 @@ impl Default for SimConfig {
                 top_left: FixedVec2::new(FixedNum::from_num(-1024.0), FixedNum::from_num(-1024.0)),
                 bottom_right: FixedVec2::new(FixedNum::from_num(1024.0), FixedNum::from_num(1024.0)),
             },
+            map_edges: MapEdges::Open,
             unit_radius: FixedNum::from_num(0.5),
             collision_push_strength: FixedNum::from_num(1.0),
             collision_restitution: FixedNum::from_num(0.5),
             collision_drag: FixedNum::from_num(0.1),
             collision_iterations: 4,
             collision_search_radius_multiplier: FixedNum::from_num(4.0),
+            collision_detection_margin: FixedNum::ZERO,
+            collision_neighbor_slots: 0,
+            collision_neighbor_refresh_interval: 1,
             obstacle_search_range: 1,
             epsilon: FixedNum::from_num(0.0001),
             obstacle_push_strength: FixedNum::from_num(1.0),
@@ -178,8 +728,11 @@ impl Default for SimConfig {
             repulsion_force: FixedNum::from_num(20.0),
             repulsion_decay: FixedNum::from_num(2.0),
             friction: FixedNum::from_num(0.9),
+            friction_reference_tick_rate: 30.0,
             min_velocity: FixedNum::from_num(0.01),
             max_velocity: FixedNum::from_num(50.0),
+            unit_turn_rate: FixedNum::from_num(12.0),
+            max_move_per_substep: FixedNum::from_num(0.5),
             braking_force: FixedNum::from_num(5.0),
             touch_dist_multiplier: FixedNum::from_num(2.1),
             check_dist_multiplier: FixedNum::from_num(4.0),
@@ -189,17 +742,221 @@ impl Default for SimConfig {
             neighbor_radius: FixedNum::from_num(5.0),
             separation_radius: FixedNum::from_num(1.5),
             boids_max_neighbors: 8,
+            boids_update_interval: 1,
             black_hole_strength: FixedNum::from_num(50.0),
             wind_spot_strength: FixedNum::from_num(-50.0),
             force_source_radius: FixedNum::from_num(10.0),
             spatial_hash_max_ticks_without_update: 8,
             spatial_hash_velocity_estimate_scale: FixedNum::from_num(1.0),
+            max_entity_count: 100_000,
+            max_catchup_ticks: 4,
+            path_requests_per_tick: 1000,
             spatial_hash_parallel_updates: true,  // Enable by default for performance
             spatial_hash_regions_per_axis: 10,    // 10×10 = 100 parallel chunks
         }
     }
 }
 
+impl SimConfig {
+    /// Seconds per tick
+    pub fn fixed_delta(&self) -> FixedNum {
+        FixedNum::ONE / FixedNum::from_num(self.tick_rate)
+    }
+
+    /// Smallest `collision_detection_margin` that sees a pair closing at `closing_speed`
+    /// one tick before it overlaps: the distance it closes in one tick
+    pub fn min_collision_margin(&self, closing_speed: FixedNum) -> FixedNum {
+        closing_speed * self.fixed_delta()
+    }
+
+    /// Velocity multiplier for one tick, so a unit loses the same share of its speed per
+    /// second at any tick rate: `friction ^ (friction_reference_tick_rate / tick_rate)`.
+    ///
+    /// The power is taken in fixed point (see [`fixed_math::pow`](crate::game::fixed_math::pow)),
+    /// so every client gets the same bits.
+    pub fn friction_per_tick(&self) -> FixedNum {
+        if self.tick_rate <= 0.0 || self.tick_rate == self.friction_reference_tick_rate {
+            return self.friction;
+        }
+        let exponent = FixedNum::from_num(self.friction_reference_tick_rate) / FixedNum::from_num(self.tick_rate);
+        crate::game::fixed_math::pow(self.friction, exponent)
+    }
+}
+
+/// Most collision iterations per tick a [`SimConfig`] may ask for
+pub const MAX_COLLISION_ITERATIONS: usize = 16;
+
+/// Why [`SimConfig::validate`] (and so [`SimConfigBuilder::build`]) rejected a config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimConfigError {
+    /// `tick_rate` is zero, negative or not finite (the tick length divides by it)
+    InvalidTickRate(f64),
+    /// The map has no area
+    EmptyMap { width: FixedNum, height: FixedNum },
+    /// `unit_radius` is zero or negative
+    NonPositiveUnitRadius(FixedNum),
+    /// `collision_iterations` is outside `1..=MAX_COLLISION_ITERATIONS`
+    CollisionIterations(usize),
+    /// `collision_search_radius_multiplier` is zero or negative, so detection finds nothing
+    NonPositiveSearchRadius(FixedNum),
+    /// `collision_detection_margin` is negative
+    NegativeDetectionMargin(FixedNum),
+    /// `max_entity_count` is zero, so the spatial hash refuses every spawn
+    ZeroMaxEntityCount,
+    /// `max_catchup_ticks` is zero, so no frame could ever run a tick
+    ZeroMaxCatchupTicks,
+    /// `path_requests_per_tick` is zero, so every path request stays deferred forever
+    ZeroPathRequestsPerTick,
+}
+
+impl std::fmt::Display for SimConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTickRate(rate) => write!(f, "tick rate must be positive, got {}", rate),
+            Self::EmptyMap { width, height } => write!(f, "map must have a positive size, got {}x{}", width, height),
+            Self::NonPositiveUnitRadius(radius) => write!(f, "unit radius must be positive, got {}", radius),
+            Self::CollisionIterations(count) => write!(
+                f, "collision iterations must be between 1 and {}, got {}", MAX_COLLISION_ITERATIONS, count,
+            ),
+            Self::NonPositiveSearchRadius(multiplier) => write!(
+                f, "collision search radius multiplier must be positive, got {}", multiplier,
+            ),
+            Self::NegativeDetectionMargin(margin) => write!(f, "collision detection margin can't be negative, got {}", margin),
+            Self::ZeroMaxEntityCount => write!(f, "max entity count must be at least 1"),
+            Self::ZeroMaxCatchupTicks => write!(f, "max catch-up ticks must be at least 1"),
+            Self::ZeroPathRequestsPerTick => write!(f, "path requests per tick must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for SimConfigError {}
+
+impl SimConfig {
+    /// Builder starting from the defaults, checked on [`build`](SimConfigBuilder::build)
+    pub fn builder() -> SimConfigBuilder {
+        SimConfigBuilder::default()
+    }
+
+    /// Check the values other systems divide by or size buffers from
+    pub fn validate(&self) -> Result<(), SimConfigError> {
+        if !(self.tick_rate.is_finite() && self.tick_rate > 0.0) {
+            return Err(SimConfigError::InvalidTickRate(self.tick_rate));
+        }
+        let (width, height) = (self.map_size.get_width(), self.map_size.get_height());
+        if width <= FixedNum::ZERO || height <= FixedNum::ZERO {
+            return Err(SimConfigError::EmptyMap { width, height });
+        }
+        if self.unit_radius <= FixedNum::ZERO {
+            return Err(SimConfigError::NonPositiveUnitRadius(self.unit_radius));
+        }
+        if !(1..=MAX_COLLISION_ITERATIONS).contains(&self.collision_iterations) {
+            return Err(SimConfigError::CollisionIterations(self.collision_iterations));
+        }
+        if self.collision_search_radius_multiplier <= FixedNum::ZERO {
+            return Err(SimConfigError::NonPositiveSearchRadius(self.collision_search_radius_multiplier));
+        }
+        if self.collision_detection_margin < FixedNum::ZERO {
+            return Err(SimConfigError::NegativeDetectionMargin(self.collision_detection_margin));
+        }
+        if self.max_entity_count == 0 {
+            return Err(SimConfigError::ZeroMaxEntityCount);
+        }
+        if self.max_catchup_ticks == 0 {
+            return Err(SimConfigError::ZeroMaxCatchupTicks);
+        }
+        if self.path_requests_per_tick == 0 {
+            return Err(SimConfigError::ZeroPathRequestsPerTick);
+        }
+        Ok(())
+    }
+}
+
+/// Builds a [`SimConfig`] from the defaults, rejecting invalid combinations on `build`.
+///
+/// ```
+/// # use peregrine::game::simulation::SimConfig;
+/// let config = SimConfig::builder().tick_rate(60.0).collision_iterations(2).build().unwrap();
+/// assert_eq!(config.tick_rate, 60.0);
+/// assert!(SimConfig::builder().tick_rate(0.0).build().is_err());
+/// ```
+#[derive(Default)]
+pub struct SimConfigBuilder {
+    config: SimConfig,
+}
+
+impl SimConfigBuilder {
+    pub fn tick_rate(mut self, tick_rate: f64) -> Self {
+        self.config.tick_rate = tick_rate;
+        self
+    }
+
+    pub fn map_size(mut self, map_size: MapSize) -> Self {
+        self.config.map_size = map_size;
+        self
+    }
+
+    pub fn map_edges(mut self, map_edges: MapEdges) -> Self {
+        self.config.map_edges = map_edges;
+        self
+    }
+
+    pub fn unit_speed(mut self, unit_speed: FixedNum) -> Self {
+        self.config.unit_speed = unit_speed;
+        self
+    }
+
+    pub fn unit_radius(mut self, unit_radius: FixedNum) -> Self {
+        self.config.unit_radius = unit_radius;
+        self
+    }
+
+    pub fn collision_iterations(mut self, collision_iterations: usize) -> Self {
+        self.config.collision_iterations = collision_iterations;
+        self
+    }
+
+    pub fn collision_search_radius_multiplier(mut self, multiplier: FixedNum) -> Self {
+        self.config.collision_search_radius_multiplier = multiplier;
+        self
+    }
+
+    pub fn collision_detection_margin(mut self, margin: FixedNum) -> Self {
+        self.config.collision_detection_margin = margin;
+        self
+    }
+
+    pub fn collision_neighbor_slots(mut self, slots: usize) -> Self {
+        self.config.collision_neighbor_slots = slots;
+        self
+    }
+
+    pub fn collision_neighbor_refresh_interval(mut self, interval: u32) -> Self {
+        self.config.collision_neighbor_refresh_interval = interval;
+        self
+    }
+
+    pub fn max_entity_count(mut self, max_entity_count: usize) -> Self {
+        self.config.max_entity_count = max_entity_count;
+        self
+    }
+
+    pub fn max_catchup_ticks(mut self, max_catchup_ticks: u32) -> Self {
+        self.config.max_catchup_ticks = max_catchup_ticks;
+        self
+    }
+
+    pub fn path_requests_per_tick(mut self, path_requests_per_tick: usize) -> Self {
+        self.config.path_requests_per_tick = path_requests_per_tick;
+        self
+    }
+
+    /// The config, if [`SimConfig::validate`] accepts it
+    pub fn build(self) -> Result<SimConfig, SimConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
 // ============================================================================
 // Debug Configuration
 // ============================================================================
@@ -209,6 +966,15 @@ impl Default for SimConfig {
 pub struct DebugConfig {
     pub show_pathfinding_graph: bool,
     pub show_paths: bool,
+    /// Draw the spatial hash cells of `spatial_hash_size_class`, colored by occupancy
+    pub show_spatial_hash: bool,
+    pub spatial_hash_size_class: u8,
+    /// Tint Grid A and Grid B cells differently
+    pub spatial_hash_split_grids: bool,
+    /// FPS / TPS / unit count overlay in the HUD
+    pub show_perf_overlay: bool,
+    /// Draw each colliding pair and the push resolution gives it
+    pub show_collisions: bool,
 }
 
 impl Default for DebugConfig {
@@ -216,6 +982,11 @@ impl Default for DebugConfig {
         Self { 
             show_pathfinding_graph: false,
             show_paths: false,
+            show_spatial_hash: false,
+            spatial_hash_size_class: 0,
+            spatial_hash_split_grids: false,
+            show_perf_overlay: false,
+            show_collisions: false,
         }
     }
 }
@@ -0,0 +1,62 @@
+//! The simulation tick: every `FixedUpdate` system in its one fixed order.
+//!
+//! `SimulationPlugin` and `HeadlessSimPlugin` both register [`tick_systems`], so the game
+//! and headless runs step through the same systems in the same order. A system that takes
+//! part in the tick (including pathfinding and unit systems) belongs in this list, not in
+//! its own plugin.
+//!
+//! The systems are chained end to end and grouped into the [`SimSet`]s, which the game
+//! gates on `GameState`. The bookkeeping before and after the sets runs regardless.
+
+use bevy::prelude::*;
+use bevy::ecs::schedule::ScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
+use crate::game::pathfinding::{follow_path, invalidate_integration_field_cache, process_path_requests, sweep_inactive_paths};
+use crate::game::unit::{acquire_targets, apply_boids_steering};
+use super::{collision, digest, physics, systems, EntityCellIndex, SimSet};
+
+/// All simulation systems of one tick, chained in execution order
+pub fn tick_systems() -> ScheduleConfigs<ScheduleSystem> {
+    (
+        // Pre-simulation
+        (
+            systems::increment_sim_tick,
+            systems::update_sim_time,
+            systems::tick_cooldowns,
+            systems::sim_start,
+        ).chain(),
+        (
+            physics::cache_previous_state,
+            systems::scrub_despawned_entities,
+            systems::process_input,
+            systems::wake_units,
+        ).chain().in_set(SimSet::Input),
+        // Pathfinding, then boids on top of the path, then forces
+        (
+            invalidate_integration_field_cache,
+            process_path_requests,
+            follow_path,
+            sweep_inactive_paths,
+            apply_boids_steering,
+            physics::apply_friction,
+            physics::apply_forces,
+        ).chain().in_set(SimSet::Steering),
+        (physics::apply_velocity, physics::update_facing).chain().in_set(SimSet::Integration),
+        (
+            systems::update_spatial_hash,
+            systems::rebuild_spatial_hash_on_overflow,
+            collision::detect_collisions,
+            collision::resolve_collisions,
+            collision::resolve_obstacle_collisions,
+        ).chain().in_set(SimSet::Physics),
+        // Post-simulation: targets are picked from the tick's final positions
+        (
+            acquire_targets,
+            systems::sweep_idle_units,
+            systems::adapt_spatial_hash_capacity,
+            systems::sync_entity_cell_index.run_if(resource_exists::<EntityCellIndex>),
+            systems::sim_end,
+            digest::record_tick_digest,
+        ).chain(),
+    ).chain().into_configs()
+}
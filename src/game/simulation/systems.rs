@@ -3,6 +3,7 @@
 /// This module contains systems for:
 /// - Tick management
 /// - Input processing (commands → pathfinding requests)
+/// - Activity tracking (idle/active unit partition)
 /// - Performance tracking
 ///
 /// Note: Path following has been moved to pathfinding::navigation module
@@ -14,7 +15,10 @@ mod systems_config;
 
 use bevy::prelude::*;
 use crate::game::fixed_math::FixedVec2;
-use crate::game::pathfinding::{Path, PathRequest};
+use crate::game::collections::InclusionIndex;
+use crate::game::pathfinding::{ActivePathSet, Path, PathRequest, PendingPathRequests};
+use crate::game::spatial_hash::SpatialHash;
+use crate::game::unit::{UnitBundle, spawn_unit};
 use peregrine_macros::profile;
 
 use super::components::*;
@@ -22,7 +26,7 @@ use super::resources::*;
 use super::events::*;
 
 // Re-export systems from submodules
-pub use systems_spatial::{update_spatial_hash, init_flow_field, apply_obstacle_to_flow_field, apply_new_obstacles, PendingVecIdxUpdates};
+pub use systems_spatial::{update_spatial_hash, rebuild_spatial_hash_on_overflow, rebuild_spatial_hash_from_world, adapt_spatial_hash_capacity, init_flow_field, apply_obstacle_to_flow_field, remove_obstacle_from_flow_field, apply_new_obstacles, relocate_moved_entities, sync_entity_cell_index, spatial_hash_read_barrier, PendingVecIdxUpdates, SpatialHashNotReady};
 pub use systems_config::{init_sim_config_from_initial, update_sim_from_runtime_config, SpatialHashRebuilt};
 
 // ============================================================================
@@ -38,81 +42,288 @@ pub fn increment_sim_tick(mut tick: ResMut<SimTick>) {
     tick.increment();
 }
 
+/// Mirror the new tick and the configured tick rate into [`SimTime`]
+pub fn update_sim_time(tick: Res<SimTick>, sim_config: Res<SimConfig>, mut sim_time: ResMut<SimTime>) {
+    sim_time.set_if_neq(SimTime::new(tick.get(), sim_config.tick_rate));
+}
+
+/// Count every running [`Cooldown`] down by one tick
+///
+/// Ready cooldowns aren't touched, so `Changed<Cooldown>` only fires while one is running.
+pub fn tick_cooldowns(mut cooldowns: Query<&mut Cooldown>) {
+    for mut cooldown in cooldowns.iter_mut() {
+        if cooldown.remaining_ticks > 0 {
+            cooldown.remaining_ticks -= 1;
+        }
+    }
+}
+
 // ============================================================================
 // Input Processing
 // ============================================================================
 
 /// Process player input commands deterministically
+///
+/// A queued move for a unit that still has orders (a path, a path request in flight, or
+/// earlier queued moves) is appended to its [`WaypointQueue`]; otherwise it is an ordinary
+/// move. Units with nothing left to follow are then sent to their next queued waypoint.
 pub fn process_input(
     mut commands: Commands,
     mut move_events: MessageReader<UnitMoveCommand>,
     mut stop_events: MessageReader<UnitStopCommand>,
     mut spawn_events: MessageReader<SpawnUnitCommand>,
     mut path_requests: MessageWriter<PathRequest>,
-    mut query: Query<(&SimPosition, &mut Path)>,
+    mut query: Query<(Entity, &mut Path, Option<&mut WaypointQueue>), With<SimPosition>>,
+    mut motion: Query<(&mut SimVelocity, &mut SimAcceleration)>,
+    spatial_entities: Query<(), (With<Collider>, Without<StaticObstacle>)>,
+    sim_config: Res<SimConfig>,
+    mut active_units: Option<ResMut<ActiveUnitSet>>,
+    mut spatial_hash: Option<ResMut<SpatialHash>>,
+    mut pending_paths: Option<ResMut<PendingPathRequests>>,
 ) {
     
     
     // Deterministic Input Processing:
     // 1. Collect all events
-    // 2. Sort by Player ID (and potentially sequence number if we had one)
-    // 3. Execute in order
+    // 2. Sort into canonical order (player, entity, then command contents), so writer
+    //    scheduling within the tick can't change the outcome
+    // 3. Execute in order: stops, then moves, then queued waypoints, then spawns
     
     // Handle Stop Commands
-    let mut stops: Vec<&UnitStopCommand> = stop_events.read().collect();
-    stops.sort_by_key(|e| e.player_id);
+    let stops = canonical_order(stop_events.read());
 
     for event in stops {
-        // Set path to inactive instead of removing component
-        if let Ok((_, mut path)) = query.get_mut(event.entity) {
+        // Set path to inactive instead of removing component, and drop queued waypoints.
+        // Rally points belong to structures and are left alone.
+        if let Ok((_, mut path, waypoints)) = query.get_mut(event.entity) {
             *path = Path::Inactive;
+            if let Some(mut waypoints) = waypoints {
+                waypoints.0.clear();
+            }
+        }
+        // A move deferred by the path request budget (or not yet read) must not restart the unit later
+        if let Some(pending_paths) = pending_paths.as_mut() {
+            pending_paths.cancel(event.entity);
+        }
+        // Halt immediately: zero velocity and any accumulated steering so the unit doesn't drift
+        if let Ok((mut velocity, mut acceleration)) = motion.get_mut(event.entity) {
+            velocity.0 = FixedVec2::ZERO;
+            acceleration.0 = FixedVec2::ZERO;
         }
-        // Also reset velocity
-        // MEMORY_OK: ECS component insert, not collection growth
-        commands.entity(event.entity).insert(SimVelocity(FixedVec2::ZERO));
     }
 
     // Handle Move Commands
-    let mut moves: Vec<&UnitMoveCommand> = move_events.read().collect();
-    moves.sort_by_key(|e| e.player_id);
+    let moves = canonical_order(move_events.read());
+    // Units sent somewhere this tick (their path request isn't pending yet)
+    let mut ordered = std::collections::HashSet::new();
     
     for event in moves {
-        if let Ok((_pos, mut path)) = query.get_mut(event.entity) {
+        if let Ok((entity, mut path, waypoints)) = query.get_mut(event.entity) {
+            if event.queued {
+                if let Some(mut waypoints) = waypoints {
+                    let has_orders = matches!(*path, Path::Active(_))
+                        || !waypoints.0.is_empty()
+                        || ordered.contains(&entity)
+                        || pending_paths.as_ref().is_some_and(|pending| pending.contains(entity));
+                    if has_orders {
+                        waypoints.0.push_back(event.target);
+                        continue;
+                    }
+                }
+            } else if let Some(mut waypoints) = waypoints {
+                // A plain move replaces everything queued
+                waypoints.0.clear();
+            }
+
             // Set path to inactive (don't remove component!)
             *path = Path::Inactive;
+
+            if let Some(active_units) = active_units.as_mut() {
+                active_units.wake(entity);
+            }
+            // A move after a stop in the same tick wins
+            if let Some(pending_paths) = pending_paths.as_mut() {
+                pending_paths.resume(entity);
+            }
             
             // Send Path Request - process_path_requests will set it to Active
-            path_requests.write(PathRequest {
-                entity: event.entity,
-                goal: event.target,
-            });
+            path_requests.write(PathRequest::player(entity, event.target));
+            ordered.insert(entity);
+        }
+    }
+
+    // Send units that ran out of path (arrived, or their request failed) to their next waypoint
+    for (entity, mut path, waypoints) in query.iter_mut() {
+        let Some(mut waypoints) = waypoints else { continue };
+        if waypoints.0.is_empty()
+            || matches!(*path, Path::Active(_))
+            || ordered.contains(&entity)
+            || pending_paths.as_ref().is_some_and(|pending| pending.contains(entity))
+        {
+            continue;
         }
+        let Some(target) = waypoints.0.pop_front() else { continue };
+        *path = Path::Inactive;
+        if let Some(active_units) = active_units.as_mut() {
+            active_units.wake(entity);
+        }
+        path_requests.write(PathRequest::player(entity, target));
     }
 
     // Handle Spawn Commands
-    let mut spawns: Vec<&SpawnUnitCommand> = spawn_events.read().collect();
-    spawns.sort_by_key(|e| e.player_id);
+    let spawns = canonical_order(spawn_events.read());
+
+    // Validate against the spatial hash capacity and map bounds before spawning
+    let mut entity_count = spatial_entities.iter().count();
+    let mut rejected = 0;
 
     for event in spawns {
+        if entity_count >= sim_config.max_entity_count {
+            rejected += 1;
+            continue;
+        }
+        entity_count += 1;
+
+        let position = if sim_config.map_size.contains(event.position) {
+            event.position
+        } else {
+            let clamped = sim_config.map_size.clamp_point(event.position, sim_config.unit_radius);
+            warn!("[SPAWN] Spawn at {:?} is outside the map - clamped to {:?}", event.position, clamped);
+            clamped
+        };
+
+
         // Note: In a real game, we'd need a way to deterministically assign Entity IDs 
         // or use a reservation system. For now, we let Bevy spawn.
         // To be strictly deterministic across clients, we would need to reserve Entity IDs 
         // or use a deterministic ID generator.
-        commands.spawn((
-            crate::game::GameEntity,
-            crate::game::unit::Unit,
-            crate::game::unit::Health { current: 100.0, max: 100.0 },
-            SimPosition(event.position),
-            SimPositionPrev(event.position),
-            SimVelocity(FixedVec2::ZERO),
-            SimAcceleration(FixedVec2::ZERO),
-            Collider::default(),
-            CollisionState::default(),
-            crate::game::collections::InclusionIndex::default(),  // For ActivePathSet tracking
-            crate::game::pathfinding::Path::Inactive,  // All units have Path component (starts inactive)
-            crate::game::pathfinding::GoalNavCell::default(),  // Cached navigation cell (updated on path request)
-            // OccupiedCell added by update_spatial_hash on first frame
-        ));
+        let radius = event.radius.unwrap_or(Collider::default().radius);
+        let entity = match spatial_hash.as_deref_mut() {
+            Some(spatial_hash) => spawn_unit(&mut commands, spatial_hash, position, radius, event.player_id),
+            // No hash (e.g. command-only tests): OccupiedCell added by update_spatial_hash on first frame
+            None => commands.spawn(UnitBundle::new(position, radius, event.player_id)).id(),
+        };
+
+        if let Some(active_units) = active_units.as_mut() {
+            active_units.wake(entity);
+        }
+    }
+
+    if rejected > 0 {
+        warn!("[SPAWN] Rejected {} spawn(s): entity capacity of {} reached", rejected, sim_config.max_entity_count);
+    }
+}
+
+// ============================================================================
+// Activity Tracking
+// ============================================================================
+
+/// Wake units whose motion state was written since the last tick.
+///
+/// Catches everything that doesn't wake units explicitly: collision pushes and force
+/// sources (acceleration), externally set velocities, paths assigned by pathfinding, and
+/// teleports (positions set from outside), so the spatial hash picks up the new cell.
+/// Runs after `process_input` so the woken units get steered and integrated this tick.
+pub fn wake_units(
+    mut active_units: ResMut<ActiveUnitSet>,
+    changed: Query<
+        Entity,
+        (
+            Without<StaticObstacle>,
+            Or<(Changed<SimPosition>, Changed<SimVelocity>, Changed<SimAcceleration>, Changed<Path>)>,
+        ),
+    >,
+) {
+    for entity in changed.iter() {
+        if !active_units.wake(entity) {
+            warn!("[ACTIVITY] Active unit set at capacity - {:?} stays idle", entity);
+        }
+    }
+}
+
+/// Drop units that came to rest this tick from the active set.
+///
+/// Runs after physics so a unit pushed by a collision this tick (non-zero acceleration)
+/// stays active for the next integration step.
+pub fn sweep_idle_units(
+    mut active_units: ResMut<ActiveUnitSet>,
+    motion: Query<(&SimVelocity, &SimAcceleration, Option<&Path>)>,
+) {
+    active_units.retain(|entity| {
+        let Ok((velocity, acceleration, path)) = motion.get(entity) else {
+            return false; // Despawned
+        };
+        velocity.0 != FixedVec2::ZERO
+            || acceleration.0 != FixedVec2::ZERO
+            || matches!(path, Some(Path::Active(_)))
+    });
+}
+
+// ============================================================================
+// Despawn Cleanup
+// ============================================================================
+
+/// Observer: announce a despawned collider entity with its spatial hash cell and path slot
+pub fn record_despawned_entity(
+    event: On<Despawn, Collider>,
+    entities: Query<(Option<&OccupiedCell>, Option<&InclusionIndex>), Without<StaticObstacle>>,
+    mut despawned: MessageWriter<EntityDespawned>,
+) {
+    let Ok((occupied, path_index)) = entities.get(event.entity) else {
+        return; // Static obstacles aren't tracked anywhere
+    };
+    despawned.write(EntityDespawned { entity: event.entity, occupied: occupied.copied(), path_index: path_index.copied() });
+}
+
+/// Remove entities despawned since the last tick from every store that holds their id,
+/// in a fixed order: spatial hash, pending path requests, active paths, active units.
+///
+/// Runs before input processing. Selection needs no scrubbing: `Selected` lives on the
+/// entity itself and goes with it. The spatial hash is only touched in incremental mode
+/// (a full rebuild repopulates it anyway) and not right after a resize, when every stored
+/// `OccupiedCell` refers to the old layout. Removal swaps the cell's last entry into the
+/// vacated slot, as `relocate_moved_entities` does, so no tombstones build up; the swapped
+/// entities' new cells go through [`PendingVecIdxUpdates`].
+pub fn scrub_despawned_entities(
+    mut commands: Commands,
+    mut despawned: MessageReader<EntityDespawned>,
+    mut spatial_hash: Option<ResMut<SpatialHash>>,
+    rebuilt: Option<Res<SpatialHashRebuilt>>,
+    mut pending_vec_idx_updates: Option<ResMut<PendingVecIdxUpdates>>,
+    mut pending_paths: Option<ResMut<PendingPathRequests>>,
+    mut active_paths: Option<ResMut<ActivePathSet>>,
+    mut active_units: Option<ResMut<ActiveUnitSet>>,
+) {
+    let despawned: Vec<EntityDespawned> = despawned.read().copied().collect();
+    if despawned.is_empty() {
+        return;
+    }
+
+    if let Some(spatial_hash) = spatial_hash.as_mut().filter(|hash| hash.uses_incremental_updates() && rebuilt.is_none()) {
+        let mut local_updates = PendingVecIdxUpdates::default();
+        let pending = pending_vec_idx_updates.as_deref_mut().unwrap_or(&mut local_updates);
+        for event in &despawned {
+            let Some(component) = event.occupied else { continue };
+            let occupied = pending.current(event.entity, &component);
+            if let Some(Some(swapped_entity)) = spatial_hash.remove_swap(event.entity, &occupied) {
+                pending.record(swapped_entity, occupied);
+            }
+        }
+        pending.forget(|entity| despawned.iter().any(|event| event.entity == entity));
+        pending.apply(&mut commands);
+    }
+    if let Some(pending_paths) = pending_paths.as_mut() {
+        for event in &despawned {
+            pending_paths.cancel(event.entity);
+        }
+    }
+    if let Some(active_paths) = active_paths.as_mut() {
+        for event in &despawned {
+            active_paths.exclude(event.entity, event.path_index);
+        }
+    }
+    if let Some(active_units) = active_units.as_mut() {
+        active_units.retain(|entity| despawned.iter().all(|event| event.entity != entity));
     }
 }
 
@@ -120,15 +331,16 @@ pub fn process_input(
 // Performance Tracking
 // ============================================================================
 
-/// Log simulation status periodically
+/// Log simulation status periodically, and stamp the tick onto logs until `sim_end`
 pub fn sim_start(
     #[allow(unused_variables)] stats: Res<SimPerformance>,
-    #[allow(unused_variables)] tick: Res<SimTick>,
+    tick: Res<SimTick>,
     #[allow(unused_variables)] units_query: Query<Entity, With<crate::game::unit::Unit>>,
     #[allow(unused_variables)] paths_query: Query<&Path>,
 ) {
     use crate::profile_log;
     
+    crate::game::logging::set_sim_tick(tick.0);
     profile_log!(tick, "[SIM STATUS] Tick: {} | Units: {} | Active Paths: {} | Last sim duration: {:?}", 
           tick.0, units_query.iter().len(), paths_query.iter().len(), stats.last_duration);
 }
@@ -142,6 +354,38 @@ pub fn sim_end(mut stats: ResMut<SimPerformance>, time: Res<Time<Fixed>>) {
     // Store the actual fixed timestep duration for status reporting
     // This represents the configured tick duration, not the wall-clock time
     stats.last_duration = time.delta();
+    crate::game::logging::clear_sim_tick();
+}
+
+/// Cap how many fixed ticks a single frame may run to catch up.
+///
+/// Runs before the fixed main loop. Bevy clamps each frame's virtual time to
+/// `Time<Virtual>::max_delta`, so setting it to `max_catchup_ticks` timesteps bounds the
+/// catch-up loop: a frame that took too long is only partly simulated and the game slows
+/// down, instead of every slow frame queueing even more ticks for the next one.
+///
+/// The clamp seen this frame (before updating `max_delta`) feeds [`SimOverloaded`].
+pub fn limit_sim_catchup(
+    sim_config: Res<SimConfig>,
+    fixed_time: Res<Time<Fixed>>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut overloaded: ResMut<SimOverloaded>,
+    mut changed: MessageWriter<SimOverloadChanged>,
+) {
+    if let Some(now_overloaded) = overloaded.observe(real_time.delta(), virtual_time.max_delta()) {
+        if now_overloaded {
+            warn!("[SIM] Falling behind - capping catch-up at {} ticks per frame (slow motion)", sim_config.max_catchup_ticks);
+        } else {
+            info!("[SIM] Caught up with real time ({:?} dropped so far)", overloaded.dropped);
+        }
+        changed.write(SimOverloadChanged { overloaded: now_overloaded });
+    }
+
+    let budget = fixed_time.timestep() * sim_config.max_catchup_ticks.max(1);
+    if virtual_time.max_delta() != budget {
+        virtual_time.set_max_delta(budget);
+    }
 }
 
 // ... Additional loading/setup systems will be added later if needed
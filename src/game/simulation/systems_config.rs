@@ -6,8 +6,7 @@
 
 use bevy::prelude::*;
 use crate::game::config::{GameConfig, GameConfigHandle, InitialConfig};
-use crate::game::fixed_math::{FixedNum, FixedVec2};
-use crate::game::map::MapSize;
+use crate::game::fixed_math::FixedNum;
 use crate::game::spatial_hash::SpatialHash;
 
 use crate::game::simulation::resources::*;
@@ -20,6 +19,7 @@ pub struct SpatialHashRebuilt;
 pub fn init_sim_config_from_initial(
     mut fixed_time: ResMut<Time<Fixed>>,
     mut sim_config: ResMut<SimConfig>,
+    mut map_dimensions: ResMut<MapDimensions>,
     mut spatial_hash: ResMut<SpatialHash>,
     initial_config: Option<Res<InitialConfig>>,
     mut commands: Commands,
@@ -39,25 +39,31 @@ pub fn init_sim_config_from_initial(
     
     // Copy all values from InitialConfig to SimConfig
     sim_config.tick_rate = config.tick_rate;
+    sim_config.max_catchup_ticks = config.max_catchup_ticks;
+    sim_config.path_requests_per_tick = config.path_requests_per_tick;
     sim_config.unit_speed = FixedNum::from_num(config.unit_speed);
-    let half_width = FixedNum::from_num(config.map_width) / FixedNum::from_num(2.0);
-    let half_height = FixedNum::from_num(config.map_height) / FixedNum::from_num(2.0);
-    sim_config.map_size = MapSize {
-        top_left: FixedVec2::new(-half_width, -half_height),
-        bottom_right: FixedVec2::new(half_width, half_height),
-    };
+    // The flow field stays empty until a map is generated or loaded (MapDimensions::apply)
+    *map_dimensions = MapDimensions::from_f32(config.map_width, config.map_height);
+    sim_config.map_size = map_dimensions.map_size();
+    sim_config.map_edges = config.map_edges;
     sim_config.unit_radius = FixedNum::from_num(config.unit_radius);
     sim_config.collision_push_strength = FixedNum::from_num(config.collision_push_strength);
     sim_config.collision_restitution = FixedNum::from_num(config.collision_restitution);
     sim_config.collision_drag = FixedNum::from_num(config.collision_drag);
     sim_config.collision_iterations = config.collision_iterations;
     sim_config.collision_search_radius_multiplier = FixedNum::from_num(config.collision_search_radius_multiplier);
+    sim_config.collision_detection_margin = FixedNum::from_num(config.collision_detection_margin);
+    sim_config.collision_neighbor_slots = config.collision_neighbor_slots;
+    sim_config.collision_neighbor_refresh_interval = config.collision_neighbor_refresh_interval;
     sim_config.obstacle_search_range = config.obstacle_search_range;
     sim_config.epsilon = FixedNum::from_num(config.epsilon);
     sim_config.obstacle_push_strength = FixedNum::from_num(config.obstacle_push_strength);
     sim_config.friction = FixedNum::from_num(config.friction);
+    sim_config.friction_reference_tick_rate = config.friction_reference_tick_rate;
     sim_config.min_velocity = FixedNum::from_num(config.min_velocity);
     sim_config.max_velocity = FixedNum::from_num(config.max_velocity);
+    sim_config.unit_turn_rate = FixedNum::from_num(config.unit_turn_rate);
+    sim_config.max_move_per_substep = FixedNum::from_num(config.max_move_per_substep);
     sim_config.braking_force = FixedNum::from_num(config.braking_force);
     sim_config.touch_dist_multiplier = FixedNum::from_num(config.touch_dist_multiplier);
     sim_config.check_dist_multiplier = FixedNum::from_num(config.check_dist_multiplier);
@@ -73,6 +79,7 @@ pub fn init_sim_config_from_initial(
     sim_config.neighbor_radius = FixedNum::from_num(config.neighbor_radius);
     sim_config.separation_radius = FixedNum::from_num(config.separation_radius);
     sim_config.boids_max_neighbors = config.boids_max_neighbors;
+    sim_config.boids_update_interval = config.boids_update_interval;
     sim_config.black_hole_strength = FixedNum::from_num(config.black_hole_strength);
     sim_config.wind_spot_strength = FixedNum::from_num(config.wind_spot_strength);
     sim_config.force_source_radius = FixedNum::from_num(config.force_source_radius);
@@ -80,6 +87,10 @@ pub fn init_sim_config_from_initial(
     // Spatial hash parallel updates
     sim_config.spatial_hash_parallel_updates = config.spatial_hash_parallel_updates;
     sim_config.spatial_hash_regions_per_axis = config.spatial_hash_regions_per_axis;
+    sim_config.max_entity_count = config.spatial_hash_max_entity_count;
+    if let Err(err) = sim_config.validate() {
+        error!("Invalid simulation settings in InitialConfig: {}", err);
+    }
     
     // Initialize spatial hash with proper configuration
     spatial_hash.resize(
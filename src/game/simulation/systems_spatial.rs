@@ -6,10 +6,12 @@
 /// - Applying obstacles to flow fields dynamically
 
 use bevy::prelude::*;
+use std::collections::HashMap;
 use crate::game::fixed_math::{FixedVec2, FixedNum};
+use crate::game::map::MapEdges;
 use crate::game::pathfinding::{HierarchicalGraph, CLUSTER_SIZE};
 use crate::game::spatial_hash::SpatialHash;
-use crate::game::structures::{FlowField, CELL_SIZE};
+use crate::game::structures::FlowField;
 
 use crate::game::simulation::components::*;
 use crate::game::simulation::resources::*;
@@ -17,9 +19,148 @@ use super::systems_config::SpatialHashRebuilt;
 
 /// Batched vec_idx updates to apply after spatial hash arena updates complete
 /// This separates arena manipulation from ECS component updates for efficiency
+///
+/// Holds the latest cell of every entity the batch has touched: movers, and entities the
+/// arena swapped into a mover's old slot. Until [`apply`](Self::apply) runs, an entity's
+/// `OccupiedCell` component may be stale, so reads go through [`current`](Self::current).
 #[derive(Resource, Default)]
 pub struct PendingVecIdxUpdates {
+    /// (entity, latest cell), in the order entities were first touched
     pub updates: Vec<(Entity, OccupiedCell)>,
+    /// Entity -> index into `updates`
+    slots: HashMap<Entity, usize>,
+}
+
+impl PendingVecIdxUpdates {
+    pub fn clear(&mut self) {
+        self.updates.clear();
+        self.slots.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Record `entity`'s new cell, replacing any earlier fixup for it this batch
+    pub fn record(&mut self, entity: Entity, cell: OccupiedCell) {
+        match self.slots.get(&entity) {
+            Some(&slot) => self.updates[slot].1 = cell,
+            None => {
+                self.slots.insert(entity, self.updates.len());
+                self.updates.push((entity, cell));
+            }
+        }
+    }
+
+    /// `entity`'s cell as the arena has it now: the pending fixup if there is one,
+    /// otherwise its (up to date) `component`
+    pub fn current(&self, entity: Entity, component: &OccupiedCell) -> OccupiedCell {
+        self.slots.get(&entity).map_or(*component, |&slot| self.updates[slot].1)
+    }
+
+    /// Drop the fixups of entities that `gone` says no longer exist
+    pub fn forget(&mut self, gone: impl Fn(Entity) -> bool) {
+        self.updates.retain(|&(entity, _)| !gone(entity));
+        self.slots = self.updates.iter().enumerate().map(|(slot, &(entity, _))| (entity, slot)).collect();
+    }
+
+    /// `Ok` once every fixup of the batch has been handed to [`apply`](Self::apply)
+    pub fn ensure_applied(&self) -> Result<(), SpatialHashNotReady> {
+        match self.updates.len() {
+            0 => Ok(()),
+            pending => Err(SpatialHashNotReady::PendingFixups(pending)),
+        }
+    }
+
+    /// Write every fixup to its entity's `OccupiedCell` (deferred) and empty the batch
+    pub fn apply(&mut self, commands: &mut Commands) {
+        for &(entity, cell) in &self.updates {
+            commands.entity(entity).insert(cell);
+        }
+        self.clear();
+    }
+}
+
+/// Why [`spatial_hash_read_barrier`] refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialHashNotReady {
+    /// This many entities' `OccupiedCell` components don't match the arena yet
+    PendingFixups(usize),
+    /// A cell overflow left entities out of the hash; the rebuild hasn't run yet
+    RebuildPending,
+}
+
+impl std::fmt::Display for SpatialHashNotReady {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PendingFixups(count) => write!(f, "{} OccupiedCell fixups not applied yet", count),
+            Self::RebuildPending => write!(f, "spatial hash is missing overflowed entities until it is rebuilt"),
+        }
+    }
+}
+
+impl std::error::Error for SpatialHashNotReady {}
+
+/// Read barrier for code that reads the spatial hash and `OccupiedCell` components together
+/// outside the normal tick order (tools, tests, mid-batch checks).
+///
+/// Plain queries are always consistent (see [`SpatialHash`]'s query consistency notes);
+/// this reports whether the components match the arena and every entity is in it. Fixups
+/// handed to [`PendingVecIdxUpdates::apply`] count as applied: they land at the next
+/// command flush, before any later system runs.
+pub fn spatial_hash_read_barrier(
+    pending: &PendingVecIdxUpdates,
+    overflow: &SpatialHashOverflow,
+) -> Result<(), SpatialHashNotReady> {
+    pending.ensure_applied()?;
+    if overflow.rebuild_pending {
+        return Err(SpatialHashNotReady::RebuildPending);
+    }
+    Ok(())
+}
+
+/// Move every entity in `moved` that changed cells within the arena, recording the resulting
+/// cells (its own, and that of any entity swapped into its old slot) in `pending`.
+///
+/// Entities the arena shuffles are read back through `pending`, so a unit swapped by an
+/// earlier move and then moving itself in the same batch is removed from the right slot.
+/// Returns (entities that changed cells, entities that could not be placed).
+pub fn relocate_moved_entities<'a>(
+    spatial_hash: &mut SpatialHash,
+    moved: impl IntoIterator<Item = (Entity, FixedVec2, &'a OccupiedCell)>,
+    pending: &mut PendingVecIdxUpdates,
+) -> (usize, usize) {
+    let (mut moved_count, mut rejected) = (0, 0);
+    for (entity, pos, component) in moved {
+        let occupied = pending.current(entity, component);
+        let Some((new_grid_offset, new_col, new_row)) = spatial_hash.should_update(pos, &occupied) else {
+            // Same cell: only the cached position changes
+            spatial_hash.refresh_position(entity, &occupied, pos);
+            continue;
+        };
+        // Entity changed cells - perform arena update (swap, update counts)
+        match spatial_hash.update_incremental(entity, &occupied, new_grid_offset, new_col, new_row, pos) {
+            Ok((new_vec_idx, swapped_entity_opt)) => {
+                moved_count += 1;
+                pending.record(entity, OccupiedCell {
+                    grid_offset: new_grid_offset,
+                    col: new_col,
+                    row: new_row,
+                    vec_idx: new_vec_idx,
+                    ..occupied
+                });
+                // The arena moved another entity into the vacated slot
+                if let Some(swapped_entity) = swapped_entity_opt {
+                    pending.record(swapped_entity, occupied);
+                }
+            }
+            Err(_) => {
+                // Entity was removed from its old cell but not placed in the new one
+                rejected += 1;
+            }
+        }
+    }
+    (moved_count, rejected)
 }
 
 // ============================================================================
@@ -34,20 +175,37 @@ pub struct PendingVecIdxUpdates {
 /// 
 /// The mode is auto-detected based on overcapacity_ratio configured in initial_config.ron.
 /// See SPATIAL_PARTITIONING.md Section 2.8 for performance analysis.
+///
+/// In incremental mode only units in [`ActiveUnitSet`] are checked for cell changes, plus
+/// any other entity whose `SimPosition` was written since the last update (a unit moved
+/// after it went idle, or left out of a full active set), so every moved position reaches
+/// the hash's cached positions. Full rebuild mode always needs every entity.
+///
+/// A cell overflow (or nearly exhausted headroom) in incremental mode sets
+/// [`SpatialHashOverflow::rebuild_pending`]; `rebuild_spatial_hash_on_overflow` then rebuilds
+/// before collision detection. Without that resource the rebuild happens inline.
 pub fn update_spatial_hash(
     mut spatial_hash: ResMut<SpatialHash>,
     query: Query<(Entity, &SimPosition, &Collider, &OccupiedCell), Without<StaticObstacle>>,
     query_new: Query<(Entity, &SimPosition, &Collider), (Without<StaticObstacle>, Without<OccupiedCell>)>,
+    moved: Query<Entity, (Changed<SimPosition>, With<OccupiedCell>, Without<StaticObstacle>)>,
     mut commands: Commands,
-    _sim_config: Res<SimConfig>,
+    sim_config: Res<SimConfig>,
     rebuilt: Option<Res<SpatialHashRebuilt>>,
     mut pending_vec_idx_updates: ResMut<PendingVecIdxUpdates>,
+    active_units: Option<Res<ActiveUnitSet>>,
+    mut overflow: Option<ResMut<SpatialHashOverflow>>,
 ) {
     // If spatial hash was rebuilt (e.g., map resize), just clear the marker
     if rebuilt.is_some() {
         commands.remove_resource::<SpatialHashRebuilt>();
         info!("Spatial hash rebuilt - will repopulate this frame");
     }
+
+    let wraps = sim_config.map_edges == MapEdges::Wrap;
+    if spatial_hash.wraps() != wraps {
+        spatial_hash.set_wraps(wraps);
+    }
     
     // Determine if we're using incremental updates
     // This is auto-detected: overcapacity_ratio > 1.1 means incremental mode
@@ -55,55 +213,39 @@ pub fn update_spatial_hash(
     
     if use_incremental {
         // INCREMENTAL MODE: Only update entities that changed cells
-        let mut moved_count = 0;
-        let mut rebuild_needed = false;
-        
-        pending_vec_idx_updates.updates.clear();
+        pending_vec_idx_updates.clear();
         
         // Update arena and collect component updates to defer via Commands
-        for (entity, pos, _collider, occupied) in query.iter() {
-            if let Some((new_grid_offset, new_col, new_row)) = spatial_hash.should_update(pos.0, &occupied) {
-                // Entity changed cells - perform arena update (swap, update counts)
-                match spatial_hash.update_incremental(entity, &occupied, new_grid_offset, new_col, new_row) {
-                    Ok((new_vec_idx, swapped_entity_opt)) => {
-                        moved_count += 1;
-                        
-                        // Queue component update for moved entity (will apply after system completes)
-                        pending_vec_idx_updates.updates.push((entity, OccupiedCell {
-                            size_class: occupied.size_class,
-                            grid_offset: new_grid_offset,
-                            col: new_col,
-                            row: new_row,
-                            vec_idx: new_vec_idx,
-                        }));
-                        
-                        // If arena swapped an entity, queue its component update too
-                        if let Some(swapped_entity) = swapped_entity_opt {
-                            pending_vec_idx_updates.updates.push((swapped_entity, OccupiedCell {
-                                size_class: occupied.size_class,
-                                grid_offset: occupied.grid_offset,
-                                col: occupied.col,
-                                row: occupied.row,
-                                vec_idx: occupied.vec_idx,
-                            }));
-                        }
-                    }
-                    Err(_) => {
-                        rebuild_needed = true;
-                    }
-                }
-            }
-        }
+        let candidates: Box<dyn Iterator<Item = _>> = match &active_units {
+            Some(active_units) => Box::new(
+                active_units.iter()
+                    .chain(moved.iter().filter(|&entity| !active_units.contains(entity)))
+                    .filter_map(|entity| query.get(entity).ok()),
+            ),
+            None => Box::new(query.iter()),
+        };
+        let (moved_count, mut rejected) = relocate_moved_entities(
+            &mut spatial_hash,
+            candidates.map(|(entity, pos, _collider, occupied)| (entity, pos.0, occupied)),
+            &mut pending_vec_idx_updates,
+        );
+        let mut rebuild_needed = rejected > 0;
         
         // Apply all component updates via Commands (deferred until system completes)
-        for (entity, new_occupied) in &pending_vec_idx_updates.updates {
-            commands.entity(*entity).insert(*new_occupied);
-        }
+        pending_vec_idx_updates.apply(&mut commands);
         
         // 2. Insert new entities (don't have OccupiedCell yet)
         for (entity, pos, collider) in query_new.iter() {
-            let occupied = spatial_hash.insert(entity, pos.0, collider.radius);
-            commands.entity(entity).insert(occupied);
+            match spatial_hash.insert(entity, pos.0, collider.radius) {
+                Ok(occupied) => {
+                    commands.entity(entity).insert(occupied);
+                }
+                Err(_) => {
+                    // Leave it without OccupiedCell; the rebuild places it
+                    rejected += 1;
+                    rebuild_needed = true;
+                }
+            }
         }
         
         // 3. Check if rebuild is needed (any cell exceeded headroom capacity)
@@ -111,22 +253,20 @@ pub fn update_spatial_hash(
             rebuild_needed = true;
         }
         
-        // 4. Perform full rebuild if needed
+        // 4. Signal (or perform) a full rebuild
         if rebuild_needed {
-            debug!("Spatial hash overflow detected - rebuilding with headroom redistribution");
-            
-            spatial_hash.rebuild_all_with_headroom(|| {
-                query.iter()
-                    .map(|(entity, pos, collider, occupied)| {
-                        (entity, pos.0, collider.radius, occupied.clone())
-                    })
-                    .collect()
-            });
-            
-            // Update all OccupiedCell components after rebuild
-            for (entity, pos, collider, _old_occupied) in query.iter() {
-                let new_occupied = spatial_hash.insert(entity, pos.0, collider.radius);
-                commands.entity(entity).insert(new_occupied);
+            match overflow.as_deref_mut() {
+                Some(overflow) => {
+                    overflow.rejected_insertions += rejected;
+                    overflow.request_rebuild();
+                }
+                None => {
+                    let all_entities: Vec<(Entity, FixedVec2, FixedNum)> = query.iter()
+                        .map(|(entity, pos, collider, _occupied)| (entity, pos.0, collider.radius))
+                        .chain(query_new.iter().map(|(entity, pos, collider)| (entity, pos.0, collider.radius)))
+                        .collect();
+                    rebuild_and_reassign_cells(&mut spatial_hash, &all_entities, &mut commands);
+                }
             }
         }
         
@@ -157,6 +297,122 @@ pub fn update_spatial_hash(
     }
 }
 
+/// Rebuild the spatial hash from current positions after an overflow in incremental mode.
+///
+/// Runs after `update_spatial_hash` and before collision detection, so a unit whose cell
+/// overflowed is back in the hash (and queryable) the same tick.
+pub fn rebuild_spatial_hash_on_overflow(
+    mut overflow: ResMut<SpatialHashOverflow>,
+    mut spatial_hash: ResMut<SpatialHash>,
+    query: Query<(Entity, &SimPosition, &Collider), Without<StaticObstacle>>,
+    mut commands: Commands,
+    sim_tick: Option<Res<SimTick>>,
+) {
+    if !overflow.rebuild_pending {
+        return;
+    }
+
+    debug!(
+        "Spatial hash overflow ({} rejected insertions so far) - rebuilding with headroom redistribution",
+        overflow.rejected_insertions
+    );
+
+    let all_entities: Vec<(Entity, FixedVec2, FixedNum)> = query.iter()
+        .map(|(entity, pos, collider)| (entity, pos.0, collider.radius))
+        .collect();
+    rebuild_and_reassign_cells(&mut spatial_hash, &all_entities, &mut commands);
+
+    overflow.rebuild_pending = false;
+    overflow.total_rebuilds += 1;
+    overflow.last_rebuild_tick = sim_tick.map(|tick| tick.get());
+}
+
+/// Grow or shrink the spatial hash arena according to [`SpatialHashGrowth`].
+///
+/// Runs at the end of the tick. Ticks that already paid for an overflow rebuild are not
+/// counted, so the resize waits for a quieter tick instead of stacking two O(N) passes.
+pub fn adapt_spatial_hash_capacity(
+    mut spatial_hash: ResMut<SpatialHash>,
+    mut growth: ResMut<SpatialHashGrowth>,
+    overflow: Option<Res<SpatialHashOverflow>>,
+    sim_tick: Option<Res<SimTick>>,
+) {
+    let now = sim_tick.map(|tick| tick.get());
+    if overflow.is_some_and(|overflow| now.is_some() && overflow.last_rebuild_tick == now) {
+        return;
+    }
+
+    let capacity = spatial_hash.storage_capacity();
+    let entries = spatial_hash.max_grid_entries();
+    if let Some(new_capacity) = growth.observe(entries, capacity) {
+        debug!("Spatial hash arena resize: {} -> {} (fullest grid holds {})", capacity, new_capacity, entries);
+        spatial_hash.set_storage_capacity(new_capacity);
+    }
+}
+
+/// Bring [`EntityCellIndex`] in line with this tick's `OccupiedCell` changes.
+///
+/// Runs at the end of the tick, after every system that moves entities in the hash has had
+/// its commands applied. Only scheduled while the resource exists. In full rebuild mode the
+/// hash keeps no `OccupiedCell`s, so the index stays empty.
+pub fn sync_entity_cell_index(
+    mut index: ResMut<EntityCellIndex>,
+    spatial_hash: Res<SpatialHash>,
+    changed: Query<(Entity, &OccupiedCell), Changed<OccupiedCell>>,
+    present: Query<(), With<OccupiedCell>>,
+    mut removed: RemovedComponents<OccupiedCell>,
+) {
+    if !spatial_hash.uses_incremental_updates() {
+        removed.clear();
+        if !index.is_empty() {
+            index.clear();
+        }
+        return;
+    }
+    for entity in removed.read() {
+        if !present.contains(entity) {
+            index.forget(entity);
+        }
+    }
+    for (entity, occupied) in changed.iter() {
+        index.set(entity, *occupied);
+    }
+}
+
+/// Rebuild from `entities` and replace every entity's `OccupiedCell` with its new slot
+fn rebuild_and_reassign_cells(
+    spatial_hash: &mut SpatialHash,
+    entities: &[(Entity, FixedVec2, FixedNum)],
+    commands: &mut Commands,
+) {
+    let mut cells = Vec::with_capacity(entities.len());
+    spatial_hash.rebuild_from_entity_list_tracked(entities, &mut cells);
+    for (&(entity, _, _), occupied) in entities.iter().zip(cells) {
+        commands.entity(entity).insert(occupied);
+    }
+}
+
+/// Rebuild the spatial hash from scratch out of every collidable entity in the world and
+/// give each a fresh `OccupiedCell`.
+///
+/// The supported way for tools and tests to (re)populate the hash without running the tick.
+/// Units and obstacle-layer colliders go in; `StaticObstacle`s live in the flow field
+/// instead, so they are skipped like in [`update_spatial_hash`]. Entities are inserted in
+/// index order, so the same world always gives the same arena layout.
+pub fn rebuild_spatial_hash_from_world(world: &mut World) {
+    let mut query = world.query_filtered::<(Entity, &SimPosition, &Collider), Without<StaticObstacle>>();
+    let mut entities: Vec<(Entity, FixedVec2, FixedNum)> = query.iter(world)
+        .map(|(entity, pos, collider)| (entity, pos.0, collider.radius))
+        .collect();
+    entities.sort_unstable_by_key(|&(entity, _, _)| (entity.index(), entity));
+
+    let mut cells = Vec::with_capacity(entities.len());
+    world.resource_mut::<SpatialHash>().rebuild_from_entity_list_tracked(&entities, &mut cells);
+    for (&(entity, _, _), occupied) in entities.iter().zip(cells) {
+        world.entity_mut(entity).insert(occupied);
+    }
+}
+
 // ============================================================================
 // Spatial Hash Compaction
 // ============================================================================
@@ -169,24 +425,16 @@ pub fn update_spatial_hash(
 // Flow Field Management
 // ============================================================================
 
-/// Initialize flow field at startup
+/// Initialize flow field at startup, sized from [`MapDimensions`] like every other resize
 pub fn init_flow_field(
     mut map_flow_field: ResMut<MapFlowField>,
-    sim_config: Res<SimConfig>,
+    map_dimensions: Res<MapDimensions>,
 ) {
-    let width = (sim_config.map_size.get_width() / FixedNum::from_num(CELL_SIZE)).ceil().to_num::<usize>();
-    let height = (sim_config.map_size.get_height() / FixedNum::from_num(CELL_SIZE)).ceil().to_num::<usize>();
-    let cell_size = FixedNum::from_num(CELL_SIZE);
-    let origin = FixedVec2::new(
-        -sim_config.map_size.get_width() / FixedNum::from_num(2.0),
-        -sim_config.map_size.get_height() / FixedNum::from_num(2.0),
-    );
-
-    map_flow_field.0 = FlowField::new(width, height, cell_size, origin);
+    map_flow_field.0 = map_dimensions.flow_field();
 }
 
-/// Apply an obstacle to the flow field cost map
-pub fn apply_obstacle_to_flow_field(flow_field: &mut FlowField, pos: FixedVec2, radius: FixedNum) {
+/// Grid cells covered by a circular obstacle
+fn obstacle_cells(flow_field: &FlowField, pos: FixedVec2, radius: FixedNum) -> impl Iterator<Item = (usize, usize)> + '_ {
     // Rasterize circle
     // Even if center is outside, part of it might be inside.
     // But world_to_grid returns None if outside.
@@ -202,25 +450,46 @@ pub fn apply_obstacle_to_flow_field(flow_field: &mut FlowField, pos: FixedVec2,
     let min_local = min_world - origin;
     let max_local = max_world - origin;
     
-    let min_x = (min_local.x / cell_size).floor().to_num::<i32>();
-    let min_y = (min_local.y / cell_size).floor().to_num::<i32>();
-    let max_x = (max_local.x / cell_size).ceil().to_num::<i32>();
-    let max_y = (max_local.y / cell_size).ceil().to_num::<i32>();
+    let min_x = (min_local.x / cell_size).floor().to_num::<i32>().max(0);
+    let min_y = (min_local.y / cell_size).floor().to_num::<i32>().max(0);
+    let max_x = (max_local.x / cell_size).ceil().to_num::<i32>().min(flow_field.width as i32);
+    let max_y = (max_local.y / cell_size).ceil().to_num::<i32>().min(flow_field.height as i32);
     
-    for y in min_y..max_y {
-        for x in min_x..max_x {
-            if x >= 0 && x < flow_field.width as i32 && y >= 0 && y < flow_field.height as i32 {
-                let cell_center = flow_field.grid_to_world(x as usize, y as usize);
-                
-                // Block cells whose center is within the obstacle radius
-                // This matches the actual collision radius used by physics
-                let dist_sq = (cell_center - pos).length_squared();
-                let threshold = radius;
-                
-                if dist_sq < threshold * threshold {
-                    flow_field.set_obstacle(x as usize, y as usize);
-                }
-            }
+    (min_y..max_y).flat_map(move |y| (min_x..max_x).map(move |x| (x as usize, y as usize)))
+        .filter(move |&(x, y)| {
+            // Block cells whose center is within the obstacle radius
+            // This matches the actual collision radius used by physics
+            let cell_center = flow_field.grid_to_world(x, y);
+            (cell_center - pos).length_squared() < radius * radius
+        })
+}
+
+/// Apply an obstacle to the flow field cost map
+pub fn apply_obstacle_to_flow_field(flow_field: &mut FlowField, pos: FixedVec2, radius: FixedNum) {
+    let cells: Vec<_> = obstacle_cells(flow_field, pos, radius).collect();
+    for (x, y) in cells {
+        flow_field.set_obstacle(x, y);
+    }
+}
+
+/// Inverse of [`apply_obstacle_to_flow_field`]: mark the cells the obstacle at `pos` covered
+/// walkable again, except those still covered by one of the `remaining` obstacles
+/// (position, radius). Only the removed obstacle's bounding box is touched, so this is
+/// cheap enough to call per despawn instead of re-finalizing the whole map.
+pub fn remove_obstacle_from_flow_field(
+    flow_field: &mut FlowField,
+    pos: FixedVec2,
+    radius: FixedNum,
+    remaining: &[(FixedVec2, FixedNum)],
+) {
+    let cells: Vec<_> = obstacle_cells(flow_field, pos, radius).collect();
+    for (x, y) in cells {
+        let cell_center = flow_field.grid_to_world(x, y);
+        let still_covered = remaining.iter().any(|&(other_pos, other_radius)| {
+            (cell_center - other_pos).length_squared() < other_radius * other_radius
+        });
+        if !still_covered {
+            flow_field.clear_obstacle(x, y);
         }
     }
 }
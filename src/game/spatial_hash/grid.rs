@@ -48,6 +48,10 @@ pub struct StaggeredGrid {
     /// ARENA: One big pre-allocated Vec for all entities in this grid
     entity_storage: Vec<Entity>,
     
+    /// Position of each `entity_storage` slot as of its last insert/refresh, index-for-index.
+    /// Lets neighbor queries return positions without an ECS lookup per candidate.
+    position_storage: Vec<FixedVec2>,
+    
     /// METADATA: Each cell tracks which range of entity_storage it owns
     pub cell_ranges: Vec<CellRange>,
     
@@ -99,6 +103,7 @@ impl StaggeredGrid {
         Self {
             // Pre-allocate entity storage to actual capacity (zero allocation guarantee)
             entity_storage: Vec::with_capacity(actual_capacity),
+            position_storage: Vec::with_capacity(actual_capacity),
             
             // One range per cell - will be initialized during first rebuild
             cell_ranges: vec![CellRange::new(); num_cells],
@@ -138,13 +143,13 @@ impl StaggeredGrid {
         FixedVec2::new(center_x - self.half_map_width, center_y - self.half_map_height)
     }
     
-    /// Insert entity into cell (appends to entity_storage)
+    /// Insert entity into cell (appends to entity_storage), caching `pos` alongside it
     /// Returns the index in entity_storage where entity was placed
     /// 
     /// Behavior depends on update strategy:
     /// - Full rebuild: ranges are contiguous, just extend count
     /// - Incremental: check headroom, use direct assignment to preallocated slots
-    pub fn insert_entity(&mut self, col: usize, row: usize, entity: Entity) -> usize {
+    pub fn insert_entity(&mut self, col: usize, row: usize, entity: Entity, pos: FixedVec2) -> usize {
         let cell_idx = row * self.cols + col;
         
         // Bounds check to prevent crashes
@@ -163,13 +168,8 @@ impl StaggeredGrid {
             // Check per-cell headroom
             let next_write_pos = range.start_index + range.current_count;
             if next_write_pos >= range.max_index {
-                #[cfg(debug_assertions)]
-                panic!(
-                    "Cell overflow in incremental mode! Cell ({},{}) next_write_pos {} >= max_index {}", 
-                    col, row, next_write_pos, range.max_index
-                );
-                
-                #[cfg(not(debug_assertions))]
+                // Recoverable: callers flag `SpatialHashOverflow` and the hash is rebuilt
+                // with redistributed headroom the same tick, so don't panic in debug builds.
                 {
                     // Clone cell info BEFORE logging to avoid borrow conflicts
                     let this_cell_start = range.start_index;
@@ -192,7 +192,7 @@ impl StaggeredGrid {
                             cell_counts.push((cell_col, cell_row, cell_range.current_count, cell_range.start_index, cell_range.max_index));
                         }
                     }
-                    cell_counts.sort_by(|a, b| b.2.cmp(&a.2));
+                    cell_counts.sort_by_key(|c| std::cmp::Reverse(c.2));
                     
                     warn!("==================== CELL OVERFLOW DIAGNOSTICS ====================");
                     warn!("Cell overflow at ({},{})", col, row);
@@ -217,6 +217,7 @@ impl StaggeredGrid {
             
             // Direct assignment to preallocated slot
             self.entity_storage[next_write_pos] = entity;
+            self.position_storage[next_write_pos] = pos;
             let entity_idx = range.current_count;  // Index within this cell's range
             range.current_count += 1;
             self.entity_count += 1;
@@ -244,6 +245,7 @@ impl StaggeredGrid {
             
             let storage_idx = self.entity_storage.len();
             self.entity_storage.push(entity);
+            self.position_storage.push(pos);
             self.entity_count += 1;
             
             // Update cell range
@@ -337,6 +339,7 @@ impl StaggeredGrid {
             // Swap with last element in this cell
             let last_entity = self.entity_storage[last_idx];
             self.entity_storage[absolute_idx] = last_entity;
+            self.position_storage[absolute_idx] = self.position_storage[last_idx];
             Some(last_entity)
         } else {
             // Entity was already last, no swap needed
@@ -350,11 +353,11 @@ impl StaggeredGrid {
         (true, swapped_entity)
     }
     
-    /// Update entity that moved from old_cell to new_cell using incremental approach
+    /// Update entity that moved from old_cell to the cell containing `pos` using incremental approach
     /// Total cost: O(1) amortized with swap-based removal
     /// 
     /// Returns Ok((new_vec_idx, swapped_entity_option)) where:
-    /// - new_vec_idx: index of entity in new cell
+    /// - new_vec_idx: index of entity in new cell (`pos_to_cell(pos)`)
     /// - swapped_entity_option: entity that was swapped in old cell (needs vec_idx update)
     pub fn update_entity_incremental(
         &mut self,
@@ -362,9 +365,10 @@ impl StaggeredGrid {
         old_col: usize,
         old_row: usize,
         old_vec_idx: usize,
-        new_col: usize,
-        new_row: usize,
+        pos: FixedVec2,
     ) -> Result<(usize, Option<Entity>), &'static str> {
+        let (new_col, new_row) = self.pos_to_cell(pos);
+        
         // Remove from old cell (swap-based, no fragmentation!)
         let (success, swapped_entity) = self.remove_entity_swap(old_col, old_row, old_vec_idx);
         if !success {
@@ -372,7 +376,7 @@ impl StaggeredGrid {
         }
         
         // Insert into new cell (uses headroom)
-        let new_vec_idx = self.insert_entity(new_col, new_row, entity);
+        let new_vec_idx = self.insert_entity(new_col, new_row, entity, pos);
         if new_vec_idx == usize::MAX {
             return Err("Cell overflow - rebuild needed");
         }
@@ -412,7 +416,7 @@ impl StaggeredGrid {
     /// Behavior depends on overcapacity_ratio:
     /// - ratio ~= 1.0: Full rebuild mode (minimal headroom, rebuild every frame)
     /// - ratio > 1.1: Incremental mode (distribute extra capacity equally across cells)
-    pub fn rebuild_with_headroom(&mut self, entities_by_cell: &[Vec<Entity>]) {
+    pub fn rebuild_with_headroom(&mut self, entities_by_cell: &[Vec<(Entity, FixedVec2)>]) {
         let total_used: usize = entities_by_cell.iter().map(|v| v.len()).sum();
         let capacity = self.entity_storage.capacity();
         
@@ -439,18 +443,20 @@ impl StaggeredGrid {
         
         // CRITICAL: Different storage strategy based on mode
         self.entity_storage.clear();
+        self.position_storage.clear();
         
         if use_incremental {
             // INCREMENTAL MODE: Pre-fill entity_storage with placeholders to full capacity
             // Direct indexing (entity_storage[idx] = entity) requires Vec to have actual elements!
             self.entity_storage.resize(capacity, Entity::PLACEHOLDER);
+            self.position_storage.resize(capacity, FixedVec2::ZERO);
         }
         // FULL REBUILD MODE: Leave storage empty, will use push() during rebuild
         
         let mut write_pos = 0;
         
         // Empty vec for cells beyond entities_by_cell.len()
-        let empty_vec: Vec<Entity> = Vec::new();
+        let empty_vec: Vec<(Entity, FixedVec2)> = Vec::new();
         
         // Process ALL cells (not just entities_by_cell)
         for cell_idx in 0..num_cells {
@@ -468,14 +474,16 @@ impl StaggeredGrid {
             // Write entities based on mode
             if use_incremental {
                 // INCREMENTAL: Direct assignment to pre-allocated slots
-                for (i, &entity) in entities.iter().enumerate() {
+                for (i, &(entity, pos)) in entities.iter().enumerate() {
                     self.entity_storage[write_pos + i] = entity;
+                    self.position_storage[write_pos + i] = pos;
                 }
                 // Leave remaining slots as PLACEHOLDER (headroom for future insertions)
             } else {
                 // FULL REBUILD: Push to storage (contiguous, no gaps)
-                for &entity in entities {
+                for &(entity, pos) in entities {
                     self.entity_storage.push(entity);
+                    self.position_storage.push(pos);
                 }
             }
             
@@ -495,7 +503,24 @@ impl StaggeredGrid {
             }
         }
         
-        self.entity_count = total_used;
+        // Tombstones passed back in (see `cell_contents`) take up slots but aren't entities
+        self.entity_count = entities_by_cell.iter().flatten()
+            .filter(|&&(entity, _)| entity != Entity::PLACEHOLDER)
+            .count();
+    }
+
+    /// Every cell's current slots with their cached positions, tombstones included, in the
+    /// shape `rebuild_with_headroom` takes. Rebuilding from this (plus appended entities)
+    /// keeps every stored entity at its cell index.
+    pub fn cell_contents(&self) -> Vec<Vec<(Entity, FixedVec2)>> {
+        (0..self.cell_ranges.len())
+            .map(|cell_idx| {
+                let (col, row) = (cell_idx % self.cols, cell_idx / self.cols);
+                let entities = self.get_cell_entities(col, row);
+                let positions = self.get_cell_positions(col, row);
+                entities.iter().copied().zip(positions.iter().copied()).collect()
+            })
+            .collect()
     }
     
     /// Get all entities in a cell (returns slice of entity_storage)
@@ -524,6 +549,37 @@ impl StaggeredGrid {
         }
     }
     
+    /// Cached positions of a cell's entities, index-for-index with [`get_cell_entities`](Self::get_cell_entities)
+    /// (tombstone slots hold stale positions)
+    pub fn get_cell_positions(&self, col: usize, row: usize) -> &[FixedVec2] {
+        let cell_idx = row * self.cols + col;
+        let Some(range) = self.cell_ranges.get(cell_idx) else { return &[] };
+        let end = range.start_index + range.current_count;
+        if range.current_count > 0 && end <= self.position_storage.len() {
+            &self.position_storage[range.start_index..end]
+        } else {
+            &[]
+        }
+    }
+    
+    /// Overwrite the cached position of `entity`, expected at `vec_idx` of the cell.
+    ///
+    /// `vec_idx` may be stale when the entity was swapped within the cell earlier in the
+    /// same tick (its `OccupiedCell` update is still deferred), so the cell is searched
+    /// if the slot holds someone else. Returns false if the entity isn't in the cell.
+    pub fn set_cached_position(&mut self, col: usize, row: usize, vec_idx: usize, entity: Entity, pos: FixedVec2) -> bool {
+        let entities = self.get_cell_entities(col, row);
+        let Some(idx) = (entities.get(vec_idx) == Some(&entity))
+            .then_some(vec_idx)
+            .or_else(|| entities.iter().position(|&e| e == entity))
+        else {
+            return false;
+        };
+        let absolute_idx = self.cell_ranges[row * self.cols + col].start_index + idx;
+        self.position_storage[absolute_idx] = pos;
+        true
+    }
+    
     /// Get all cells within radius of position
     /// ZERO-ALLOCATION: Uses preallocated out_cells buffer
     /// Clears out_cells before populating
@@ -536,10 +592,19 @@ impl StaggeredGrid {
         let min_y = pos.y - radius + self.half_map_height - self.offset.y;
         let max_y = pos.y + radius + self.half_map_height - self.offset.y;
         
-        let min_col = (min_x / self.cell_size).floor().to_num::<isize>().max(0).min((self.cols - 1) as isize) as usize;
-        let max_col = (max_x / self.cell_size).floor().to_num::<isize>().min((self.cols - 1) as isize) as usize;
-        let min_row = (min_y / self.cell_size).floor().to_num::<isize>().max(0) as usize;
-        let max_row = (max_y / self.cell_size).floor().to_num::<isize>().min((self.rows - 1) as isize) as usize;
+        let min_col = (min_x / self.cell_size).floor().to_num::<isize>();
+        let max_col = (max_x / self.cell_size).floor().to_num::<isize>();
+        let min_row = (min_y / self.cell_size).floor().to_num::<isize>();
+        let max_row = (max_y / self.cell_size).floor().to_num::<isize>();
+
+        // Entirely off the grid: nothing to visit (clamping alone would wrap a negative max)
+        if max_col < 0 || max_row < 0 || min_col >= self.cols as isize || min_row >= self.rows as isize {
+            return;
+        }
+        let min_col = min_col.max(0) as usize;
+        let max_col = max_col.min((self.cols - 1) as isize) as usize;
+        let min_row = min_row.max(0) as usize;
+        let max_row = max_row.min((self.rows - 1) as isize) as usize;
         
         let capacity = out_cells.capacity();
         for row in min_row..=max_row {
@@ -561,9 +626,11 @@ impl StaggeredGrid {
         }
     }
     
-    pub fn clear(&mut self) {
+    /// Empty the grid, keeping the arena allocation for the next rebuild
+    pub fn clear_retaining_capacity(&mut self) {
         // Clear entity storage (doesn't deallocate - keeps capacity)
         self.entity_storage.clear();
+        self.position_storage.clear();
         self.entity_count = 0;
         
         // Reset all cell ranges (including max_index to force full rebuild mode)
@@ -591,13 +658,48 @@ impl StaggeredGrid {
         tombstones as f32 / self.entity_storage.len() as f32
     }
     
-    /// Get storage usage ratio (current size / capacity)
+    /// Get storage usage ratio (stored entities / capacity)
+    ///
+    /// Counts entities rather than `entity_storage.len()`: in incremental mode the storage
+    /// is padded with placeholders to full capacity, so its length is always the capacity.
     pub fn storage_usage_ratio(&self) -> f32 {
         let capacity = self.entity_storage.capacity();
         if capacity == 0 {
             return 0.0;
         }
-        self.entity_storage.len() as f32 / capacity as f32
+        self.entity_count as f32 / capacity as f32
+    }
+
+    /// Arena capacity (entities this grid can hold)
+    pub fn storage_capacity(&self) -> usize {
+        self.entity_storage.capacity()
+    }
+
+    pub fn overcapacity_ratio(&self) -> f32 {
+        self.overcapacity_ratio
+    }
+
+    /// Grow the arena so it holds at least `max_entities` (scaled by the overcapacity
+    /// ratio, as in `with_capacity`). Never shrinks; entries stay in place.
+    pub fn reserve(&mut self, max_entities: usize) {
+        let capacity = (max_entities as f32 * self.overcapacity_ratio) as usize;
+        if capacity > self.storage_capacity() {
+            self.set_storage_capacity(capacity);
+        }
+    }
+
+    /// Reallocate the arena to `capacity` (never below the slots currently in use).
+    ///
+    /// Cells keep their slots in the same order, tombstones included, so every `vec_idx`
+    /// stays valid; headroom is redistributed over the new capacity as in
+    /// `rebuild_with_headroom`. Use `compact` to reclaim tombstoned slots.
+    pub fn set_storage_capacity(&mut self, capacity: usize) {
+        let entities_by_cell = self.cell_contents();
+        let slots_in_use = entities_by_cell.iter().map(Vec::len).sum::<usize>();
+
+        self.entity_storage = Vec::with_capacity(capacity.max(slots_in_use));
+        self.position_storage = Vec::with_capacity(capacity.max(slots_in_use));
+        self.rebuild_with_headroom(&entities_by_cell);
     }
     
     /// Compact the entity storage by removing tombstones
@@ -609,6 +711,7 @@ impl StaggeredGrid {
         
         // Build new compacted storage
         let mut new_storage = Vec::with_capacity(self.entity_count);
+        let mut new_positions = Vec::with_capacity(self.entity_count);
         let mut new_ranges = vec![CellRange::new(); self.cell_ranges.len()];
         
         // Rebuild storage and ranges without tombstones
@@ -628,6 +731,7 @@ impl StaggeredGrid {
                     let entity = self.entity_storage[i];
                     if entity != Entity::PLACEHOLDER {
                         new_storage.push(entity);
+                        new_positions.push(self.position_storage[i]);
                         new_count += 1;
                     }
                 }
@@ -642,6 +746,7 @@ impl StaggeredGrid {
         
         // Replace old storage with compacted version
         self.entity_storage = new_storage;
+        self.position_storage = new_positions;
         self.cell_ranges = new_ranges;
     }
 }
@@ -691,9 +796,9 @@ impl SizeClass {
         }
     }
     
-    pub fn clear(&mut self) {
-        self.grid_a.clear();
-        self.grid_b.clear();
+    pub fn clear_retaining_capacity(&mut self) {
+        self.grid_a.clear_retaining_capacity();
+        self.grid_b.clear_retaining_capacity();
         self.entity_count = 0;
     }
     
@@ -0,0 +1,66 @@
+//! Read-only iteration over the spatial hash contents.
+//!
+//! For debug overlays, stats and tests. Both iterators walk every cell of every grid,
+//! so they are O(cells) regardless of how many entities are stored.
+
+use bevy::prelude::*;
+use crate::game::simulation::components::OccupiedCell;
+use super::{SpatialHash, StaggeredGrid};
+
+/// Entity count of one non-empty cell, yielded by [`SpatialHash::iter_cell_occupancies`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellOccupancy {
+    pub size_class: u8,
+    /// 0 = Grid A, 1 = Grid B
+    pub grid_offset: u8,
+    pub col: usize,
+    pub row: usize,
+    /// Live entities in the cell (tombstones not counted)
+    pub count: usize,
+}
+
+impl SpatialHash {
+    /// Every (size class, grid offset, grid) triple
+    fn grids(&self) -> impl Iterator<Item = (u8, u8, &StaggeredGrid)> {
+        self.size_classes.iter().enumerate().flat_map(|(class_idx, size_class)| {
+            [(0u8, &size_class.grid_a), (1u8, &size_class.grid_b)]
+                .into_iter()
+                .map(move |(offset, grid)| (class_idx as u8, offset, grid))
+        })
+    }
+
+    /// Every stored entity with the cell it lives in, skipping tombstones.
+    ///
+    /// The yielded `OccupiedCell` is exactly what the entity's component should hold,
+    /// so `debug_verify`-style checks can compare the two directly.
+    pub fn iter_entities(&self) -> impl Iterator<Item = (Entity, OccupiedCell)> + '_ {
+        self.grids().flat_map(|(size_class, grid_offset, grid)| {
+            (0..grid.rows).flat_map(move |row| {
+                (0..grid.cols).flat_map(move |col| {
+                    grid.get_cell_entities(col, row)
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &entity)| entity != Entity::PLACEHOLDER)
+                        .map(move |(vec_idx, &entity)| {
+                            (entity, OccupiedCell { size_class, grid_offset, col, row, vec_idx })
+                        })
+                })
+            })
+        })
+    }
+
+    /// Live entity count of every cell holding at least one entity
+    pub fn iter_cell_occupancies(&self) -> impl Iterator<Item = CellOccupancy> + '_ {
+        self.grids().flat_map(|(size_class, grid_offset, grid)| {
+            (0..grid.rows).flat_map(move |row| {
+                (0..grid.cols).filter_map(move |col| {
+                    let count = grid.get_cell_entities(col, row)
+                        .iter()
+                        .filter(|&&entity| entity != Entity::PLACEHOLDER)
+                        .count();
+                    (count > 0).then_some(CellOccupancy { size_class, grid_offset, col, row, count })
+                })
+            })
+        })
+    }
+}
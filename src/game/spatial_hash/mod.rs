@@ -3,25 +3,43 @@ use std::collections::HashSet;
 use crate::game::fixed_math::FixedNum;
 
 mod grid;
+mod iter;
 mod query;
+mod verify;
 #[cfg(test)]
 mod tests;
 
 pub use grid::{StaggeredGrid, SizeClass, CellRange};
+pub use iter::CellOccupancy;
+pub use verify::DebugReport;
 use crate::game::fixed_math::FixedVec2;
 use crate::game::simulation::components::OccupiedCell;
 
+/// Per-cell (entity, position) lists for one grid, indexed `row * cols + col`
+type CellLists = Vec<Vec<(Entity, FixedVec2)>>;
+
 /// Staggered Multi-Resolution Spatial Hash for efficient proximity queries.
 ///
 /// **NEW DESIGN (January 2026):**
 /// - Multiple cell sizes for different entity size ranges
 /// - Each cell size has TWO offset grids (Grid A and Grid B) staggered by half_cell
 /// - Entities are ALWAYS single-cell (inserted into whichever grid they're closest to center of)
-/// - Memory: 25 bytes per entity (vs 96 bytes for old multi-cell approach)
+/// - Memory: 25 bytes per entity (vs 96 bytes for old multi-cell approach), plus 16 bytes
+///   per arena slot for the cached positions returned by `query_radius_with_positions`
 /// - Update threshold: ~half_cell distance (much rarer than multi-cell updates)
 ///
 /// See SPATIAL_PARTITIONING.md Section 2.2 for detailed explanation.
 ///
+/// # Query consistency
+///
+/// Every mutating method leaves the arena whole, so queries between any two calls (say,
+/// halfway through a batch of `update_incremental` moves) see each stored entity exactly
+/// once, in the cell and at the position it was last written with. What a half-applied
+/// batch does leave stale is outside the hash: the `OccupiedCell` components of entities
+/// it moved or swapped, until their fixups are applied, and entities whose move was
+/// rejected, which are missing until the next rebuild. Code that needs those (removal,
+/// `debug_verify`) should check `spatial_hash_read_barrier` first.
+///
 /// # Example
 ///
 /// ```rust
@@ -49,8 +67,32 @@ pub struct SpatialHash {
     
     map_width: FixedNum,
     map_height: FixedNum,
+
+    /// Whether queries reach across the map edges ([`MapEdges::Wrap`](crate::game::map::MapEdges::Wrap))
+    wraps: bool,
+}
+
+/// Why [`SpatialHash::insert`] refused an entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialHashError {
+    /// The target cell has no headroom left (incremental mode) or the grid's arena is full
+    /// (full rebuild mode). The entity was not stored; rebuild or grow the hash and retry.
+    CapacityExhausted { size_class: u8, grid_offset: u8, col: usize, row: usize },
 }
 
+impl std::fmt::Display for SpatialHashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CapacityExhausted { size_class, grid_offset, col, row } => write!(
+                f, "spatial hash capacity exhausted at cell ({}, {}) of size class {} grid {}",
+                col, row, size_class, grid_offset,
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpatialHashError {}
+
 /// Preallocated scratch buffers for zero-allocation queries
 /// 
 /// Per the design document (Section 2.3), all queries must use preallocated buffers
@@ -89,6 +131,17 @@ pub struct SpatialHashScratch {
     /// Example: radius=60, cell_size=10 → (60*2/10+1)^2 = 13^2 = 169 cells
     /// Conservatively allocated to 256 to handle edge cases
     pub cell_coords: Vec<(usize, usize)>,
+
+    /// Squared distances matching `query_results` index-for-index
+    /// (only filled by `query_radius_sorted`)
+    pub query_distances_sq: Vec<FixedNum>,
+
+    /// Preallocated (distance², entity) pairs used while sorting in `query_radius_sorted`
+    pub sort_buffer: Vec<(FixedNum, Entity)>,
+
+    /// Positions matching `query_results` index-for-index, on the querier's side of any
+    /// seam (filled by `query_radius_with_positions` and `query_radius_sorted`)
+    pub query_positions: Vec<FixedVec2>,
 }
 
 impl SpatialHashScratch {
@@ -107,6 +160,9 @@ impl SpatialHashScratch {
             query_results_secondary: Vec::with_capacity(query_capacity),
             seen_entities: HashSet::with_capacity(query_capacity),
             cell_coords: Vec::with_capacity(4096),  // Worst case: large radius on fine grid (e.g., r=60, cell=2 → 61²=3721)
+            query_distances_sq: Vec::with_capacity(query_capacity),
+            sort_buffer: Vec::with_capacity(query_capacity),
+            query_positions: Vec::with_capacity(query_capacity),
         }
     }
     
@@ -176,16 +232,51 @@ impl SpatialHash {
             radius_to_class,
             map_width,
             map_height,
+            wraps: false,
         }
     }
     
     pub fn resize(&mut self, map_width: FixedNum, map_height: FixedNum, entity_radii: &[f32], radius_to_cell_ratio: f32, max_entity_count: usize, overcapacity_ratio: f32) {
+        let wraps = self.wraps;
         *self = Self::new(map_width, map_height, entity_radii, radius_to_cell_ratio, max_entity_count, overcapacity_ratio);
+        self.wraps = wraps;
     }
 
+    /// Re-lay the grids out for a new map size, keeping the size classes, arena capacities
+    /// and update mode. Like `resize`, this empties the hash: every `OccupiedCell` is stale.
+    pub fn resize_map(&mut self, map_width: FixedNum, map_height: FixedNum) {
+        for size_class in &mut self.size_classes {
+            let capacity_a = size_class.grid_a.storage_capacity();
+            let capacity_b = size_class.grid_b.storage_capacity();
+            let overcapacity_ratio = size_class.grid_a.overcapacity_ratio();
+            *size_class = SizeClass::with_capacity(map_width, map_height, size_class.cell_size, 0, overcapacity_ratio);
+            size_class.grid_a.set_storage_capacity(capacity_a);
+            size_class.grid_b.set_storage_capacity(capacity_b);
+        }
+        self.map_width = map_width;
+        self.map_height = map_height;
+    }
+
+    /// Empty every grid. Same as [`clear_retaining_capacity`](Self::clear_retaining_capacity).
     pub fn clear(&mut self) {
+        self.clear_retaining_capacity();
+    }
+
+    /// Empty every grid but keep the arena allocations, so refilling up to the previous
+    /// peak doesn't reallocate. Every `OccupiedCell` is stale afterwards.
+    pub fn clear_retaining_capacity(&mut self) {
         for size_class in &mut self.size_classes {
-            size_class.clear();
+            size_class.clear_retaining_capacity();
+        }
+    }
+
+    /// Pre-grow the arenas: `per_class[i]` is the number of entities size class `i` should
+    /// hold without reallocating (either grid of the class may end up holding all of them).
+    /// Never shrinks; extra entries are ignored and missing ones leave the class as is.
+    pub fn reserve(&mut self, per_class: &[usize]) {
+        for (size_class, &max_entities) in self.size_classes.iter_mut().zip(per_class) {
+            size_class.grid_a.reserve(max_entities);
+            size_class.grid_b.reserve(max_entities);
         }
     }
 
@@ -206,6 +297,20 @@ impl SpatialHash {
     // Getters
     pub fn map_width(&self) -> FixedNum { self.map_width }
     pub fn map_height(&self) -> FixedNum { self.map_height }
+
+    /// Make radius queries near an edge also search the opposite edge (toroidal map)
+    pub fn set_wraps(&mut self, wraps: bool) { self.wraps = wraps; }
+    pub fn wraps(&self) -> bool { self.wraps }
+
+    /// `to - from` as queries see it: on a wrapping map the shortest offset, which may
+    /// cross a seam (see [`MapSize::wrapped_delta`](crate::game::map::MapSize::wrapped_delta))
+    pub fn delta(&self, from: FixedVec2, to: FixedVec2) -> FixedVec2 {
+        if !self.wraps {
+            return to - from;
+        }
+        let half_size = FixedVec2::new(self.map_width, self.map_height) / FixedNum::from_num(2);
+        crate::game::map::MapSize { top_left: -half_size, bottom_right: half_size }.wrapped_delta(from, to)
+    }
     
     // For compatibility with old API (returns cell size of first size class)
     pub fn cell_size(&self) -> FixedNum {
@@ -229,6 +334,12 @@ impl SpatialHash {
     // Entity Management (Insert, Remove, Update)
     // ============================================================================
     
+    /// Size class an entity of `radius` is stored in (for
+    /// [`query_radius_in_class`](Self::query_radius_in_class))
+    pub fn size_class_for_radius(&self, radius: FixedNum) -> u8 {
+        self.classify_entity(radius)
+    }
+
     /// Classify entity by radius to determine which size class it belongs to
     fn classify_entity(&self, radius: FixedNum) -> u8 {
         for &(max_radius, class_idx) in &self.radius_to_class {
@@ -242,7 +353,10 @@ impl SpatialHash {
     
     /// Insert entity into spatial hash
     /// Returns OccupiedCell component to attach to the entity
-    pub fn insert(&mut self, entity: Entity, pos: FixedVec2, radius: FixedNum) -> OccupiedCell {
+    ///
+    /// If the target cell has no headroom left (incremental mode) or the arena is full,
+    /// the entity is NOT stored and [`SpatialHashError::CapacityExhausted`] is returned.
+    pub fn insert(&mut self, entity: Entity, pos: FixedVec2, radius: FixedNum) -> Result<OccupiedCell, SpatialHashError> {
         let size_class_idx = self.classify_entity(radius);
         let size_class = &mut self.size_classes[size_class_idx as usize];
         
@@ -258,22 +372,26 @@ impl SpatialHash {
         
         // Insert into whichever grid is closer
         let (grid_offset, col, row, storage_idx) = if dist_a_sq < dist_b_sq {
-            let idx = size_class.grid_a.insert_entity(col_a, row_a, entity);
+            let idx = size_class.grid_a.insert_entity(col_a, row_a, entity, pos);
             (0, col_a, row_a, idx)
         } else {
-            let idx = size_class.grid_b.insert_entity(col_b, row_b, entity);
+            let idx = size_class.grid_b.insert_entity(col_b, row_b, entity, pos);
             (1, col_b, row_b, idx)
         };
         
+        // usize::MAX = rejected (cell overflow); caller must request a rebuild
+        if storage_idx == usize::MAX {
+            return Err(SpatialHashError::CapacityExhausted { size_class: size_class_idx, grid_offset, col, row });
+        }
         size_class.entity_count += 1;
         
-        OccupiedCell {
+        Ok(OccupiedCell {
             size_class: size_class_idx,
             grid_offset,
             col,
             row,
             vec_idx: storage_idx,  // Now stores index into entity_storage arena
-        }
+        })
     }
     
     /// Remove entity from spatial hash
@@ -296,6 +414,32 @@ impl SpatialHash {
         removed
     }
     
+    /// Remove an entity by moving the last entry of its cell into its slot, leaving no
+    /// tombstone (incremental mode).
+    ///
+    /// Returns the entity moved into `occupied.vec_idx`, whose `OccupiedCell` must be
+    /// updated, or `None` if `entity` isn't in that slot.
+    pub fn remove_swap(&mut self, entity: Entity, occupied: &OccupiedCell) -> Option<Option<Entity>> {
+        let size_class = self.size_classes.get_mut(occupied.size_class as usize)?;
+        let grid = if occupied.grid_offset == 0 {
+            &mut size_class.grid_a
+        } else {
+            &mut size_class.grid_b
+        };
+        if occupied.col >= grid.cols || occupied.row >= grid.rows
+            || grid.get_cell_entities(occupied.col, occupied.row).get(occupied.vec_idx) != Some(&entity)
+        {
+            return None;
+        }
+
+        let (removed, swapped_entity) = grid.remove_entity_swap(occupied.col, occupied.row, occupied.vec_idx);
+        if !removed {
+            return None;
+        }
+        size_class.entity_count -= 1;
+        Some(swapped_entity)
+    }
+    
     /// Check if entity should update its cell (moved closer to opposite grid)
     /// Returns Some(new_occupied_cell) if entity should be re-inserted
     pub fn should_update(&self, pos: FixedVec2, occupied: &OccupiedCell) -> Option<(u8, usize, usize)> {
@@ -350,7 +494,7 @@ impl SpatialHash {
                 &mut size_class.grid_b
             };
             
-            let storage_idx = grid.insert_entity(new_col, new_row, entity);
+            let storage_idx = grid.insert_entity(new_col, new_row, entity, pos);
             size_class.entity_count += 1;
             
             Some(OccupiedCell {
@@ -385,7 +529,7 @@ impl SpatialHash {
                 &mut size_class.grid_b
             };
             
-            let storage_idx = grid.insert_entity(new_col, new_row, entity);
+            let storage_idx = grid.insert_entity(new_col, new_row, entity, pos);
             size_class.entity_count += 1;
             
             Some((
@@ -405,7 +549,7 @@ impl SpatialHash {
     
     /// Insert entity into new cell (used by parallel updates)
     /// Returns the storage_idx where the entity was inserted
-    pub fn insert_into_cell(&mut self, entity: Entity, new_occupied: &OccupiedCell) -> usize {
+    pub fn insert_into_cell(&mut self, entity: Entity, pos: FixedVec2, new_occupied: &OccupiedCell) -> usize {
         let size_class = &mut self.size_classes[new_occupied.size_class as usize];
         let grid = if new_occupied.grid_offset == 0 {
             &mut size_class.grid_a
@@ -413,11 +557,27 @@ impl SpatialHash {
             &mut size_class.grid_b
         };
         
-        let storage_idx = grid.insert_entity(new_occupied.col, new_occupied.row, entity);
+        let storage_idx = grid.insert_entity(new_occupied.col, new_occupied.row, entity, pos);
         size_class.entity_count += 1;
         storage_idx
     }
     
+    /// Refresh the cached position of an entity that moved within its cell.
+    ///
+    /// Entities changing cells get their position on insert; everything else that moved
+    /// needs this, or [`query_radius_with_positions`](Self::query_radius_with_positions)
+    /// returns where it was when it entered the cell. Returns false if `entity` isn't
+    /// stored in `occupied`'s cell.
+    pub fn refresh_position(&mut self, entity: Entity, occupied: &OccupiedCell, pos: FixedVec2) -> bool {
+        let Some(size_class) = self.size_classes.get_mut(occupied.size_class as usize) else { return false };
+        let grid = if occupied.grid_offset == 0 {
+            &mut size_class.grid_a
+        } else {
+            &mut size_class.grid_b
+        };
+        grid.set_cached_position(occupied.col, occupied.row, occupied.vec_idx, entity, pos)
+    }
+    
     /// Compact all grids in all size classes if fragmentation exceeds threshold
     /// Returns true if any compaction was performed
     pub fn compact_if_fragmented(&mut self, fragmentation_threshold: f32) -> bool {
@@ -466,6 +626,40 @@ impl SpatialHash {
         
         max_usage
     }
+
+    /// Largest arena capacity across all grids
+    pub fn storage_capacity(&self) -> usize {
+        self.size_classes.iter()
+            .flat_map(|sc| [sc.grid_a.storage_capacity(), sc.grid_b.storage_capacity()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Summed arena capacity of all grids (entity slots currently allocated)
+    pub fn total_storage_capacity(&self) -> usize {
+        self.size_classes.iter()
+            .map(|sc| sc.grid_a.storage_capacity() + sc.grid_b.storage_capacity())
+            .sum()
+    }
+
+    /// Largest number of entities stored in any single grid
+    pub fn max_grid_entries(&self) -> usize {
+        self.size_classes.iter()
+            .flat_map(|sc| [sc.grid_a.total_entries(), sc.grid_b.total_entries()])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Resize every grid's arena to `capacity` entities, keeping all entries in place.
+    ///
+    /// `OccupiedCell` components stay valid: tombstones keep their slots. Grids using more
+    /// than `capacity` slots are only shrunk down to the slots in use.
+    pub fn set_storage_capacity(&mut self, capacity: usize) {
+        for size_class in &mut self.size_classes {
+            size_class.grid_a.set_storage_capacity(capacity);
+            size_class.grid_b.set_storage_capacity(capacity);
+        }
+    }
     
     // ============================================================================
     // Incremental Update Support (Arena Over-Provisioning Strategy)
@@ -499,6 +693,7 @@ impl SpatialHash {
         new_grid_offset: u8,
         new_col: usize,
         new_row: usize,
+        pos: FixedVec2,
     ) -> Result<(usize, Option<Entity>), &'static str> {
         let size_class = &mut self.size_classes[occupied.size_class as usize];
         
@@ -526,7 +721,7 @@ impl SpatialHash {
             &mut size_class.grid_b
         };
         
-        let new_vec_idx = new_grid.insert_entity(new_col, new_row, entity);
+        let new_vec_idx = new_grid.insert_entity(new_col, new_row, entity, pos);
         if new_vec_idx == usize::MAX {
             return Err("Cell overflow - rebuild needed");
         }
@@ -566,17 +761,17 @@ impl SpatialHash {
             let grid_b_cols = size_class.grid_b.cols;
             let grid_b_rows = size_class.grid_b.rows;
             
-            let mut grid_a_cells: Vec<Vec<Entity>> = vec![Vec::new(); grid_a_cols * grid_a_rows];
-            let mut grid_b_cells: Vec<Vec<Entity>> = vec![Vec::new(); grid_b_cols * grid_b_rows];
+            let mut grid_a_cells: Vec<Vec<(Entity, FixedVec2)>> = vec![Vec::new(); grid_a_cols * grid_a_rows];
+            let mut grid_b_cells: Vec<Vec<(Entity, FixedVec2)>> = vec![Vec::new(); grid_b_cols * grid_b_rows];
             
             // Distribute entities into cells
-            for &(entity, _pos, ref occupied) in entities {
+            for &(entity, pos, ref occupied) in entities {
                 if occupied.grid_offset == 0 {
                     let cell_idx = occupied.row * grid_a_cols + occupied.col;
-                    grid_a_cells[cell_idx].push(entity);
+                    grid_a_cells[cell_idx].push((entity, pos));
                 } else {
                     let cell_idx = occupied.row * grid_b_cols + occupied.col;
-                    grid_b_cells[cell_idx].push(entity);
+                    grid_b_cells[cell_idx].push((entity, pos));
                 }
             }
             
@@ -592,13 +787,35 @@ impl SpatialHash {
     /// This properly distributes entities into cells and calls rebuild_with_headroom
     /// to maintain correct arena structure.
     pub fn rebuild_from_entity_list(&mut self, entities: &[(Entity, FixedVec2, FixedNum)]) {
+        self.rebuild_from_entity_list_impl(entities, None);
+    }
+
+    /// Same as [`rebuild_from_entity_list`](Self::rebuild_from_entity_list), but also reports
+    /// where each entity ended up: `out_cells[i]` is the `OccupiedCell` for `entities[i]`.
+    ///
+    /// Used when recovering from a cell overflow in incremental mode, where every entity's
+    /// `OccupiedCell` component has to be replaced after the rebuild.
+    pub fn rebuild_from_entity_list_tracked(
+        &mut self,
+        entities: &[(Entity, FixedVec2, FixedNum)],
+        out_cells: &mut Vec<OccupiedCell>,
+    ) {
+        out_cells.clear();
+        self.rebuild_from_entity_list_impl(entities, Some(out_cells));
+    }
+
+    fn rebuild_from_entity_list_impl(
+        &mut self,
+        entities: &[(Entity, FixedVec2, FixedNum)],
+        out_cells: Option<&mut Vec<OccupiedCell>>,
+    ) {
         // Group entities by size class and cell
         for size_class in &mut self.size_classes {
             size_class.entity_count = 0;
         }
         
         // Create cell collections for each size class
-        let mut size_class_cells: Vec<(Vec<Vec<Entity>>, Vec<Vec<Entity>>)> = self.size_classes.iter()
+        let size_class_cells: Vec<(CellLists, CellLists)> = self.size_classes.iter()
             .map(|sc| {
                 let grid_a_cells = vec![Vec::new(); sc.grid_a.cols * sc.grid_a.rows];
                 let grid_b_cells = vec![Vec::new(); sc.grid_b.cols * sc.grid_b.rows];
@@ -606,6 +823,34 @@ impl SpatialHash {
             })
             .collect();
         
+        self.fill_cells(entities, size_class_cells, out_cells);
+    }
+
+    /// Insert many entities at once, e.g. when populating a map.
+    ///
+    /// The insert-side analog of [`rebuild_from_entity_list`](Self::rebuild_from_entity_list):
+    /// every entity is classified and grouped by cell first, then each grid's arena is laid
+    /// out once with headroom sized for the final counts, instead of one `insert` (and one
+    /// headroom check) per entity. Entities already stored keep their cells and indices.
+    ///
+    /// Returns the `OccupiedCell` for each entity, in input order; they match what inserting
+    /// the same entities one by one would give.
+    pub fn bulk_insert(&mut self, entities: &[(Entity, FixedVec2, FixedNum)]) -> Vec<OccupiedCell> {
+        let size_class_cells = self.size_classes.iter()
+            .map(|sc| (sc.grid_a.cell_contents(), sc.grid_b.cell_contents()))
+            .collect();
+        let mut out_cells = Vec::with_capacity(entities.len());
+        self.fill_cells(entities, size_class_cells, Some(&mut out_cells));
+        out_cells
+    }
+
+    /// Append `entities` to the per-cell lists and rebuild every grid from them
+    fn fill_cells(
+        &mut self,
+        entities: &[(Entity, FixedVec2, FixedNum)],
+        mut size_class_cells: Vec<(CellLists, CellLists)>,
+        mut out_cells: Option<&mut Vec<OccupiedCell>>,
+    ) {
         // Classify and distribute entities
         for &(entity, pos, radius) in entities {
             let size_class_idx = self.classify_entity(radius) as usize;
@@ -623,13 +868,23 @@ impl SpatialHash {
             
             // Insert into whichever grid is closer
             let (grid_a_cells, grid_b_cells) = &mut size_class_cells[size_class_idx];
-            if dist_a_sq < dist_b_sq {
-                let cell_idx = row_a * size_class.grid_a.cols + col_a;
-                grid_a_cells[cell_idx].push(entity);
+            let (grid_offset, col, row, cell) = if dist_a_sq < dist_b_sq {
+                (0, col_a, row_a, &mut grid_a_cells[row_a * size_class.grid_a.cols + col_a])
             } else {
-                let cell_idx = row_b * size_class.grid_b.cols + col_b;
-                grid_b_cells[cell_idx].push(entity);
+                (1, col_b, row_b, &mut grid_b_cells[row_b * size_class.grid_b.cols + col_b])
+            };
+
+            // Rebuilt cells are packed in push order, so the index within the cell is known now
+            if let Some(out_cells) = out_cells.as_deref_mut() {
+                out_cells.push(OccupiedCell {
+                    size_class: size_class_idx as u8,
+                    grid_offset,
+                    col,
+                    row,
+                    vec_idx: cell.len(),
+                });
             }
+            cell.push((entity, pos));
         }
         
         // Rebuild each size class with proper headroom distribution
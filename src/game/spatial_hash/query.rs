@@ -78,65 +78,235 @@ impl SpatialHash {
     /// CRITICAL: Checks capacity before push to prevent reallocation.
     /// If buffer overflows, logs warning and truncates results (no panic in release).
     /// 
-    /// NOTE: Only returns Entity IDs. Callers must query SimPosition component for positions.
+    /// NOTE: Only returns Entity IDs. Use [`query_radius_with_positions`](Self::query_radius_with_positions)
+    /// to get positions along with them.
+    ///
+    /// On a wrapping map (see [`set_wraps`](Self::set_wraps)) entities across the seam are
+    /// included; measure distances to them with [`delta`](Self::delta).
     pub fn query_radius(&self, pos: FixedVec2, radius: FixedNum, exclude_entity: Option<Entity>, scratch: &mut SpatialHashScratch) {
         scratch.seen_entities.clear();  // O(1), keeps capacity
         scratch.query_results.clear();
         
         let capacity = scratch.query_results.capacity();
         
-        // Query each size class (both Grid A and Grid B)
-        for size_class in &self.size_classes {
-            if size_class.entity_count == 0 {
-                continue;  // Skip empty size classes
+        // On a wrapping map, also search the circle's copies across the seams
+        for shift in self.wrap_shifts(pos, radius) {
+            let origin = pos + shift;
+            // Query each size class (both Grid A and Grid B)
+            for size_class in &self.size_classes {
+                if size_class.entity_count == 0 {
+                    continue;  // Skip empty size classes
+                }
+                
+                // Query Grid A
+                size_class.grid_a.cells_in_radius(origin, radius, &mut scratch.cell_coords);
+                let num_cells = scratch.cell_coords.len();
+                for i in 0..num_cells {
+                    let (col, row) = scratch.cell_coords[i];
+                    let entities = size_class.grid_a.get_cell_entities(col, row);
+                    for &entity in entities {
+                        // Filter out tombstones and excluded entity, use scratch.seen_entities
+                        if entity != Entity::PLACEHOLDER && Some(entity) != exclude_entity && scratch.seen_entities.insert(entity) {
+                            // CRITICAL: Check capacity before push to prevent reallocation
+                            if scratch.query_results.len() < capacity {
+                                scratch.query_results.push(entity);
+                            } else {
+                                #[cfg(debug_assertions)]
+                                panic!("Query result buffer overflow! Need more capacity than {}", capacity);
+                                
+                                #[cfg(not(debug_assertions))]
+                                {
+                                    warn!("Query result buffer overflow - results truncated at {} entities", capacity);
+                                    return;  // Truncate results
+                                }
+                            }
+                        }
+                    }
+                }
+                
+                // Query Grid B
+                size_class.grid_b.cells_in_radius(origin, radius, &mut scratch.cell_coords);
+                let num_cells = scratch.cell_coords.len();
+                for i in 0..num_cells {
+                    let (col, row) = scratch.cell_coords[i];
+                    let entities = size_class.grid_b.get_cell_entities(col, row);
+                    for &entity in entities {
+                        // Filter out tombstones and excluded entity, use scratch.seen_entities
+                        if entity != Entity::PLACEHOLDER && Some(entity) != exclude_entity && scratch.seen_entities.insert(entity) {
+                            // CRITICAL: Check capacity before push to prevent reallocation
+                            if scratch.query_results.len() < capacity {
+                                scratch.query_results.push(entity);
+                            } else {
+                                #[cfg(debug_assertions)]
+                                panic!("Query result buffer overflow! Need more capacity than {}", capacity);
+                                
+                                #[cfg(not(debug_assertions))]
+                                {
+                                    warn!("Query result buffer overflow - results truncated at {} entities", capacity);
+                                    return;  // Truncate results
+                                }
+                            }
+                        }
+                    }
+                }
             }
-            
-            // Query Grid A
-            size_class.grid_a.cells_in_radius(pos, radius, &mut scratch.cell_coords);
-            let num_cells = scratch.cell_coords.len();
-            for i in 0..num_cells {
-                let (col, row) = scratch.cell_coords[i];
-                let entities = size_class.grid_a.get_cell_entities(col, row);
-                for &entity in entities {
-                    // Filter out tombstones and excluded entity, use scratch.seen_entities
-                    if entity != Entity::PLACEHOLDER && Some(entity) != exclude_entity && scratch.seen_entities.insert(entity) {
-                        // CRITICAL: Check capacity before push to prevent reallocation
-                        if scratch.query_results.len() < capacity {
-                            scratch.query_results.push(entity);
-                        } else {
-                            #[cfg(debug_assertions)]
-                            panic!("Query result buffer overflow! Need more capacity than {}", capacity);
-                            
-                            #[cfg(not(debug_assertions))]
-                            {
-                                warn!("Query result buffer overflow - results truncated at {} entities", capacity);
-                                return;  // Truncate results
+        }
+    }
+}
+
+impl SpatialHash {
+    /// Offsets to add to a query position: zero, plus on a wrapping map one per seam the
+    /// query circle reaches across (up to four near a corner). Queries are only shifted by
+    /// one map size, so radii over half the map may miss or repeat entities.
+    fn wrap_shifts(&self, pos: FixedVec2, radius: FixedNum) -> impl Iterator<Item = FixedVec2> {
+        let seam_shift = |coord: FixedNum, size: FixedNum| {
+            let half = size / FixedNum::from_num(2);
+            if !self.wraps {
+                None
+            } else if coord - radius < -half {
+                Some(size)
+            } else if coord + radius > half {
+                Some(-size)
+            } else {
+                None
+            }
+        };
+        let xs = [Some(FixedNum::ZERO), seam_shift(pos.x, self.map_width)];
+        let ys = [Some(FixedNum::ZERO), seam_shift(pos.y, self.map_height)];
+        xs.into_iter().flatten().flat_map(move |dx| ys.into_iter().flatten().map(move |dy| FixedVec2::new(dx, dy)))
+    }
+
+    /// First entity within radius of position that satisfies `pred`, or `None`.
+    ///
+    /// For existence checks ("any enemy in range?"): stops at the first match instead of
+    /// collecting every neighbor. Visits the same candidates as [`query_radius`](Self::query_radius)
+    /// (cells clamped to the map, `exclude_entity` skipped) in a fixed order - size class, then
+    /// Grid A before Grid B, then cell - so the result is deterministic.
+    ///
+    /// Candidates come from whole cells, so `pred` should do the exact distance check.
+    ///
+    /// ZERO-ALLOCATION: Only uses `scratch.cell_coords`; `scratch.query_results` is untouched.
+    pub fn any_in_radius(
+        &self,
+        pos: FixedVec2,
+        radius: FixedNum,
+        exclude_entity: Option<Entity>,
+        scratch: &mut SpatialHashScratch,
+        mut pred: impl FnMut(Entity) -> bool,
+    ) -> Option<Entity> {
+        for shift in self.wrap_shifts(pos, radius) {
+            for size_class in &self.size_classes {
+                if size_class.entity_count == 0 {
+                    continue;
+                }
+
+                for grid in [&size_class.grid_a, &size_class.grid_b] {
+                    grid.cells_in_radius(pos + shift, radius, &mut scratch.cell_coords);
+                    for &(col, row) in &scratch.cell_coords {
+                        // An entity is stored in exactly one grid, so no dedup set is needed
+                        let found = grid.get_cell_entities(col, row).iter().copied().find(|&entity| {
+                            entity != Entity::PLACEHOLDER && Some(entity) != exclude_entity && pred(entity)
+                        });
+                        if found.is_some() {
+                            return found;
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Query all entities within radius of position, with their cached positions.
+    ///
+    /// Same candidates as [`query_radius`](Self::query_radius); `scratch.query_positions[i]`
+    /// is the position of `scratch.query_results[i]` as of the last spatial hash update, so
+    /// callers needing only positions can skip the `SimPosition` lookup per neighbor.
+    /// Cached positions are current after `update_spatial_hash` has run for the tick.
+    ///
+    /// An entity is stored in exactly one grid, so unlike `query_radius` there is no dedup set.
+    ///
+    /// On a wrapping map, positions of entities found across the seam are shifted by the map
+    /// size to the query's side, so `pos - query_positions[i]` is the shortest offset.
+    ///
+    /// ZERO-ALLOCATION: Same capacity handling as `query_radius`.
+    pub fn query_radius_with_positions(&self, pos: FixedVec2, radius: FixedNum, exclude_entity: Option<Entity>, scratch: &mut SpatialHashScratch) {
+        scratch.query_results.clear();
+        scratch.query_positions.clear();
+
+        let capacity = scratch.query_results.capacity().min(scratch.query_positions.capacity());
+        for shift in self.wrap_shifts(pos, radius) {
+            for size_class in &self.size_classes {
+                if size_class.entity_count == 0 {
+                    continue;
+                }
+
+                for grid in [&size_class.grid_a, &size_class.grid_b] {
+                    grid.cells_in_radius(pos + shift, radius, &mut scratch.cell_coords);
+                    for &(col, row) in &scratch.cell_coords {
+                        let entities = grid.get_cell_entities(col, row);
+                        let positions = grid.get_cell_positions(col, row);
+                        for (&entity, &entity_pos) in entities.iter().zip(positions) {
+                            if entity == Entity::PLACEHOLDER || Some(entity) == exclude_entity {
+                                continue;
+                            }
+                            if scratch.query_results.len() < capacity {
+                                scratch.query_results.push(entity);
+                                // Seen from `pos`'s side of the seam
+                                scratch.query_positions.push(entity_pos - shift);
+                            } else {
+                                #[cfg(debug_assertions)]
+                                panic!("Query result buffer overflow! Need more capacity than {}", capacity);
+
+                                #[cfg(not(debug_assertions))]
+                                {
+                                    warn!("Query result buffer overflow - results truncated at {} entities", capacity);
+                                    return;
+                                }
                             }
                         }
                     }
                 }
             }
-            
-            // Query Grid B
-            size_class.grid_b.cells_in_radius(pos, radius, &mut scratch.cell_coords);
-            let num_cells = scratch.cell_coords.len();
-            for i in 0..num_cells {
-                let (col, row) = scratch.cell_coords[i];
-                let entities = size_class.grid_b.get_cell_entities(col, row);
-                for &entity in entities {
-                    // Filter out tombstones and excluded entity, use scratch.seen_entities
-                    if entity != Entity::PLACEHOLDER && Some(entity) != exclude_entity && scratch.seen_entities.insert(entity) {
-                        // CRITICAL: Check capacity before push to prevent reallocation
+        }
+    }
+
+    /// Query entities of one size class within radius of position.
+    ///
+    /// Same as [`query_radius`](Self::query_radius) restricted to the grids of size class
+    /// `class_idx` (see [`size_class_for_radius`](Self::size_class_for_radius)), so a search
+    /// for large entities doesn't scan the fine grids. An out-of-range class yields nothing.
+    ///
+    /// An entity is stored in exactly one grid, so there is no dedup set.
+    ///
+    /// ZERO-ALLOCATION: Same capacity handling as `query_radius`.
+    pub fn query_radius_in_class(&self, pos: FixedVec2, radius: FixedNum, class_idx: u8, exclude_entity: Option<Entity>, scratch: &mut SpatialHashScratch) {
+        scratch.query_results.clear();
+
+        let Some(size_class) = self.size_classes.get(class_idx as usize) else { return };
+        if size_class.entity_count == 0 {
+            return;
+        }
+
+        let capacity = scratch.query_results.capacity();
+        for shift in self.wrap_shifts(pos, radius) {
+            for grid in [&size_class.grid_a, &size_class.grid_b] {
+                grid.cells_in_radius(pos + shift, radius, &mut scratch.cell_coords);
+                for &(col, row) in &scratch.cell_coords {
+                    for &entity in grid.get_cell_entities(col, row) {
+                        if entity == Entity::PLACEHOLDER || Some(entity) == exclude_entity {
+                            continue;
+                        }
                         if scratch.query_results.len() < capacity {
                             scratch.query_results.push(entity);
                         } else {
                             #[cfg(debug_assertions)]
                             panic!("Query result buffer overflow! Need more capacity than {}", capacity);
-                            
+
                             #[cfg(not(debug_assertions))]
                             {
                                 warn!("Query result buffer overflow - results truncated at {} entities", capacity);
-                                return;  // Truncate results
+                                return;
                             }
                         }
                     }
@@ -144,4 +314,51 @@ impl SpatialHash {
             }
         }
     }
+
+    /// Query all entities within radius of position, nearest first.
+    ///
+    /// Same candidates as [`query_radius`](Self::query_radius), but `scratch.query_results`
+    /// is ordered by ascending squared distance to `pos`, and `scratch.query_distances_sq[i]`
+    /// holds the squared distance of `scratch.query_results[i]` (computed once, during
+    /// collection - callers don't need to recompute it).
+    ///
+    /// `position_of` supplies positions (typically a `SimPosition` query lookup), so the
+    /// order reflects positions at call time rather than the hash's cached ones.
+    /// Entities it returns `None` for are dropped.
+    /// Ties are broken by entity ID, so the order is deterministic.
+    ///
+    /// Distances are measured with [`delta`](Self::delta), so on a wrapping map they take the
+    /// short way across the seam, and `scratch.query_positions[i]` is where
+    /// `scratch.query_results[i]` is as seen from `pos`'s side of it.
+    ///
+    /// ZERO-ALLOCATION: Sorts in `scratch.sort_buffer`.
+    pub fn query_radius_sorted(
+        &self,
+        pos: FixedVec2,
+        radius: FixedNum,
+        exclude_entity: Option<Entity>,
+        scratch: &mut SpatialHashScratch,
+        position_of: impl Fn(Entity) -> Option<FixedVec2>,
+    ) {
+        self.query_radius(pos, radius, exclude_entity, scratch);
+
+        scratch.sort_buffer.clear();
+        for &entity in &scratch.query_results {
+            if let Some(other_pos) = position_of(entity) {
+                scratch.sort_buffer.push((self.delta(pos, other_pos).length_squared(), entity));
+            }
+        }
+        scratch.sort_buffer.sort_unstable();
+
+        scratch.query_results.clear();
+        scratch.query_distances_sq.clear();
+        scratch.query_positions.clear();
+        for &(dist_sq, entity) in &scratch.sort_buffer {
+            scratch.query_results.push(entity);
+            scratch.query_distances_sq.push(dist_sq);
+            // Looked up again rather than carried through the sort; present since it was above
+            let other_pos = position_of(entity).unwrap_or(pos);
+            scratch.query_positions.push(pos + self.delta(pos, other_pos));
+        }
+    }
 }
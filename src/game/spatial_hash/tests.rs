@@ -26,9 +26,9 @@ fn test_query_radius_finds_entities_within_range() {
     let pos_b = FixedVec2::new(FixedNum::from_num(5.0), FixedNum::from_num(0.0)); // 5 units away
     let pos_c = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(5.0)); // 5 units away
 
-    hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-    hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
-    hash.insert(entity_c, pos_c, FixedNum::from_num(0.5));
+    hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_c, pos_c, FixedNum::from_num(0.5)).unwrap();
 
     // Query from entity_a with radius 10 (should find B and C, but not self)
     let mut scratch = SpatialHashScratch::new(100);
@@ -59,9 +59,9 @@ fn test_query_radius_excludes_entities_outside_range() {
     let pos_b = FixedVec2::new(FixedNum::from_num(3.0), FixedNum::from_num(0.0)); // 3 units away
     let pos_c = FixedVec2::new(FixedNum::from_num(20.0), FixedNum::from_num(0.0)); // 20 units away
 
-    hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-    hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
-    hash.insert(entity_c, pos_c, FixedNum::from_num(0.5));
+    hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_c, pos_c, FixedNum::from_num(0.5)).unwrap();
 
     // Query with radius 5 (should find B but not C)
     let mut scratch = SpatialHashScratch::new(100);
@@ -89,7 +89,7 @@ fn test_spatial_hash_excludes_self() {
     let entity = Entity::from_bits(1);
     let pos = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
 
-    hash.insert(entity, pos, FixedNum::from_num(0.5));
+    hash.insert(entity, pos, FixedNum::from_num(0.5)).unwrap();
 
     // Query from same entity - should NOT include self
     let mut scratch = SpatialHashScratch::new(100);
@@ -115,8 +115,8 @@ fn test_spatial_hash_query_finds_neighbors() {
     let pos_a = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
     let pos_b = FixedVec2::new(FixedNum::from_num(2.0), FixedNum::from_num(2.0));
 
-    let occupied_a = hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-    let occupied_b = hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
+    let occupied_a = hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+    let occupied_b = hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
     
     println!("Entity A: {:?} at {:?}, occupied: {:?}", entity_a, pos_a, occupied_a);
     println!("Entity B: {:?} at {:?}, occupied: {:?}", entity_b, pos_b, occupied_b);
@@ -179,9 +179,9 @@ fn test_spatial_hash_boundary_cases() {
     let pos_center = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
     let pos_edge = FixedVec2::new(FixedNum::from_num(49.0), FixedNum::from_num(0.0));
 
-    hash.insert(entity_corner, pos_corner, FixedNum::from_num(0.5));
-    hash.insert(entity_center, pos_center, FixedNum::from_num(0.5));
-    hash.insert(entity_edge, pos_edge, FixedNum::from_num(0.5));
+    hash.insert(entity_corner, pos_corner, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_center, pos_center, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity_edge, pos_edge, FixedNum::from_num(0.5)).unwrap();
 
     // Query from corner with small radius - should not find center or edge
     let mut scratch = SpatialHashScratch::new(100);
@@ -217,7 +217,7 @@ fn test_query_radius_returns_same_results_as_brute_force() {
 
     // Insert into spatial hash
     for (entity, pos) in &entities {
-        hash.insert(*entity, *pos, FixedNum::from_num(0.5));
+        hash.insert(*entity, *pos, FixedNum::from_num(0.5)).unwrap();
     }
 
     let query_entity = entities[0].0;
@@ -274,7 +274,7 @@ fn test_query_radius_excludes_self() {
     let entity = Entity::from_bits(1);
     let pos = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
 
-    hash.insert(entity, pos, FixedNum::from_num(0.5));
+    hash.insert(entity, pos, FixedNum::from_num(0.5)).unwrap();
 
     // Query with exclude_entity set - should NOT include self
     let mut scratch = SpatialHashScratch::new(100);
@@ -298,8 +298,8 @@ fn test_query_radius_includes_all_without_exclusion() {
     let entity2 = test_entity(2);
     let pos = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
 
-    hash.insert(entity1, pos, FixedNum::from_num(0.5));
-    hash.insert(entity2, pos, FixedNum::from_num(0.5));
+    hash.insert(entity1, pos, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity2, pos, FixedNum::from_num(0.5)).unwrap();
 
     // Query with None - should include all entities
     let mut scratch = SpatialHashScratch::new(100);
@@ -324,8 +324,8 @@ fn test_query_radius_finds_neighbors_excludes_self() {
     let pos1 = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
     let pos2 = FixedVec2::new(FixedNum::from_num(5.0), FixedNum::from_num(0.0));
 
-    hash.insert(entity1, pos1, FixedNum::from_num(0.5));
-    hash.insert(entity2, pos2, FixedNum::from_num(0.5));
+    hash.insert(entity1, pos1, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(entity2, pos2, FixedNum::from_num(0.5)).unwrap();
 
     // Query from entity1 with exclusion - should find entity2 but not entity1
     let mut scratch = SpatialHashScratch::new(100);
@@ -352,7 +352,7 @@ fn test_compaction_removes_tombstones() {
 
     // Insert 10 entities
     let occupied_cells: Vec<_> = entities.iter()
-        .map(|&e| hash.insert(e, pos, FixedNum::from_num(0.5)))
+        .map(|&e| hash.insert(e, pos, FixedNum::from_num(0.5)).unwrap())
         .collect();
 
     println!("Inserted {} entities", entities.len());
@@ -388,3 +388,453 @@ fn test_compaction_removes_tombstones() {
     assert_eq!(scratch.query_results.len(), 5, "Should still find the 5 non-removed entities");
 }
 
+#[test]
+fn test_debug_verify_consistent_hash() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0, 25.0],
+        4.0,
+        10_000,
+        1.0
+    );
+
+    let mut tracked = Vec::new();
+    for i in 0..20 {
+        let entity = test_entity(i + 1);
+        let pos = FixedVec2::new(FixedNum::from_num((i % 5) as f32 * 3.0), FixedNum::from_num((i / 5) as f32 * 3.0));
+        let radius = if i % 4 == 0 { FixedNum::from_num(10.0) } else { FixedNum::from_num(0.5) };
+        tracked.push((entity, hash.insert(entity, pos, radius).unwrap()));
+    }
+
+    let report = hash.debug_verify(tracked.iter().map(|(e, oc)| (*e, oc)));
+
+    assert!(report.is_consistent(), "Fresh hash should be consistent: {:?}", report);
+    assert_eq!(report.entities_checked, 20);
+    assert_eq!(report.total_entries, 20);
+    assert!(report.occupied_cells > 0);
+    assert!(report.max_cell_occupancy >= 1);
+}
+
+#[test]
+fn test_debug_verify_detects_corrupted_occupied_cell() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0, 25.0],
+        4.0,
+        10_000,
+        1.0
+    );
+
+    let a = test_entity(1);
+    let b = test_entity(2);
+    let pos = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
+    let cell_a = hash.insert(a, pos, FixedNum::from_num(0.5)).unwrap();
+    let cell_b = hash.insert(b, FixedVec2::new(FixedNum::from_num(30.0), FixedNum::from_num(30.0)), FixedNum::from_num(0.5)).unwrap();
+
+    // Point A's component at B's cell: slot holds B, not A
+    let corrupted = OccupiedCell { col: cell_b.col, row: cell_b.row, grid_offset: cell_b.grid_offset, ..cell_a };
+    let report = hash.debug_verify([(a, &corrupted), (b, &cell_b)].into_iter());
+    assert_eq!(report.mismatches, vec![a]);
+    assert!(!report.is_consistent());
+
+    // Out-of-range vec_idx and size class are mismatches, not panics
+    let bad_idx = OccupiedCell { vec_idx: 99, ..cell_a };
+    let bad_class = OccupiedCell { size_class: 42, ..cell_a };
+    let report = hash.debug_verify([(a, &bad_idx), (b, &bad_class)].into_iter());
+    assert_eq!(report.mismatches, vec![a, b]);
+
+    // Entity missing from the checked set shows up as untracked
+    let report = hash.debug_verify([(a, &cell_a)].into_iter());
+    assert_eq!(report.untracked_entries, 1);
+    assert!(report.mismatches.is_empty());
+}
+
+#[test]
+fn test_debug_verify_detects_duplicates() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0, 25.0],
+        4.0,
+        10_000,
+        1.0
+    );
+
+    let entity = test_entity(7);
+    let first = hash.insert(entity, FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0)), FixedNum::from_num(0.5)).unwrap();
+    // Inserting again without removing leaves a stale copy in the arena
+    hash.insert(entity, FixedVec2::new(FixedNum::from_num(40.0), FixedNum::from_num(-40.0)), FixedNum::from_num(0.5)).unwrap();
+
+    let report = hash.debug_verify([(entity, &first)].into_iter());
+    assert_eq!(report.duplicate_entries, 1);
+    assert_eq!(report.total_entries, 2);
+    assert!(!report.is_consistent());
+}
+
+#[test]
+fn test_query_radius_sorted_nearest_first() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0, 25.0],
+        4.0,
+        10_000,
+        1.0
+    );
+    let mut scratch = SpatialHashScratch::new(100);
+
+    let origin = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
+    let querier = test_entity(1);
+    // Inserted out of distance order, mixed size classes
+    let placed = [
+        (test_entity(2), FixedVec2::new(FixedNum::from_num(4.0), FixedNum::from_num(0.0)), FixedNum::from_num(0.5)),
+        (test_entity(3), FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(1.0)), FixedNum::from_num(0.5)),
+        (test_entity(4), FixedVec2::new(FixedNum::from_num(-3.0), FixedNum::from_num(-3.0)), FixedNum::from_num(10.0)),
+        (test_entity(5), FixedVec2::new(FixedNum::from_num(2.0), FixedNum::from_num(0.0)), FixedNum::from_num(0.5)),
+    ];
+    hash.insert(querier, origin, FixedNum::from_num(0.5)).unwrap();
+    for &(entity, pos, radius) in &placed {
+        hash.insert(entity, pos, radius).unwrap();
+    }
+    let position_of = |entity: Entity| placed.iter().find(|(e, _, _)| *e == entity).map(|(_, p, _)| *p);
+
+    hash.query_radius_sorted(origin, FixedNum::from_num(6.0), Some(querier), &mut scratch, position_of);
+
+    assert_eq!(scratch.query_results, vec![test_entity(3), test_entity(5), test_entity(2), test_entity(4)]);
+    assert_eq!(scratch.query_distances_sq.len(), scratch.query_results.len());
+    assert_eq!(scratch.query_distances_sq[0], FixedNum::from_num(1.0), "Nearest should be first");
+    assert!(scratch.query_distances_sq.windows(2).all(|w| w[0] <= w[1]), "Distances must be ascending");
+    for (i, &entity) in scratch.query_results.iter().enumerate() {
+        assert_eq!(scratch.query_distances_sq[i], (position_of(entity).unwrap() - origin).length_squared());
+    }
+}
+
+#[test]
+fn test_any_in_radius_stops_at_first_match() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0],
+        4.0,
+        10_000,
+        1.0
+    );
+    let mut scratch = SpatialHashScratch::new(100);
+
+    let origin = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
+    let querier = test_entity(1);
+    hash.insert(querier, origin, FixedNum::from_num(0.5)).unwrap();
+    // Allies (even ids) and one enemy (id 7), all in range
+    let team_of = |entity: Entity| if entity == test_entity(7) { 1 } else { 0 };
+    for id in 2..=12 {
+        let pos = FixedVec2::new(FixedNum::from_num(id as f32 * 0.3), FixedNum::from_num(0.0));
+        hash.insert(test_entity(id), pos, FixedNum::from_num(0.5)).unwrap();
+    }
+
+    // Everyone matches: the very first candidate is returned
+    let mut calls = 0;
+    let found = hash.any_in_radius(origin, FixedNum::from_num(5.0), Some(querier), &mut scratch, |_| {
+        calls += 1;
+        true
+    });
+    assert!(found.is_some_and(|e| e != querier));
+    assert_eq!(calls, 1, "Should stop after the first match");
+
+    // Only the enemy matches
+    let mut calls = 0;
+    let found = hash.any_in_radius(origin, FixedNum::from_num(5.0), Some(querier), &mut scratch, |e| {
+        calls += 1;
+        team_of(e) == 1
+    });
+    assert_eq!(found, Some(test_entity(7)));
+    assert!(calls <= 11);
+
+    // No match: None, after visiting every candidate except self
+    let mut calls = 0;
+    let found = hash.any_in_radius(origin, FixedNum::from_num(5.0), Some(querier), &mut scratch, |_| {
+        calls += 1;
+        false
+    });
+    assert_eq!(found, None);
+    assert_eq!(calls, 11);
+
+    // Query centered off the map edge is clamped, and the excluded self never reaches pred
+    let edge = FixedVec2::new(FixedNum::from_num(-60.0), FixedNum::from_num(0.0));
+    let found = hash.any_in_radius(edge, FixedNum::from_num(2.0), None, &mut scratch, |_| true);
+    assert_eq!(found, None);
+    let found = hash.any_in_radius(origin, FixedNum::from_num(0.1), Some(querier), &mut scratch, |e| e == querier);
+    assert_eq!(found, None);
+}
+
+#[test]
+fn test_query_radius_in_class_is_restricted_subset() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0],
+        4.0,
+        10_000,
+        1.0
+    );
+    let mut scratch = SpatialHashScratch::new(100);
+
+    let origin = FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0));
+    let small = FixedNum::from_num(0.5);
+    let large = FixedNum::from_num(8.0);
+    let small_class = hash.size_class_for_radius(small);
+    let large_class = hash.size_class_for_radius(large);
+    assert_ne!(small_class, large_class);
+
+    let mut radius_of = std::collections::HashMap::new();
+    for id in 1..=12 {
+        let radius = if id % 3 == 0 { large } else { small };
+        let pos = FixedVec2::new(FixedNum::from_num(id as f32 - 6.0), FixedNum::from_num(id as f32 * 0.5));
+        hash.insert(test_entity(id), pos, radius).unwrap();
+        radius_of.insert(test_entity(id), radius);
+    }
+
+    hash.query_radius(origin, FixedNum::from_num(8.0), None, &mut scratch);
+    let all: std::collections::HashSet<Entity> = scratch.query_results.iter().copied().collect();
+
+    for class in [small_class, large_class] {
+        hash.query_radius_in_class(origin, FixedNum::from_num(8.0), class, None, &mut scratch);
+        assert!(!scratch.query_results.is_empty());
+        for entity in &scratch.query_results {
+            assert!(all.contains(entity), "{:?} missing from the all-class query", entity);
+            assert_eq!(hash.size_class_for_radius(radius_of[entity]), class, "{:?} is in another class", entity);
+        }
+        // Nothing of this class is lost
+        let in_class = all.iter().filter(|entity| hash.size_class_for_radius(radius_of[*entity]) == class).count();
+        assert_eq!(scratch.query_results.len(), in_class);
+    }
+
+    // Exclusion works, and a class that doesn't exist is empty
+    hash.query_radius_in_class(origin, FixedNum::from_num(8.0), large_class, Some(test_entity(3)), &mut scratch);
+    assert!(!scratch.query_results.contains(&test_entity(3)));
+    hash.query_radius_in_class(origin, FixedNum::from_num(8.0), 7, None, &mut scratch);
+    assert!(scratch.query_results.is_empty());
+}
+
+fn cell_key(cell: &OccupiedCell) -> (u8, u8, usize, usize, usize) {
+    (cell.size_class, cell.grid_offset, cell.col, cell.row, cell.vec_idx)
+}
+
+#[test]
+fn test_iter_entities_matches_inserts_updates_and_removals() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(100.0),
+        FixedNum::from_num(100.0),
+        &[0.5, 10.0, 25.0],
+        4.0,
+        10_000,
+        2.0  // Headroom for the updates below
+    );
+
+    let mut expected = std::collections::HashMap::new();
+    for i in 0..30 {
+        let entity = test_entity(i + 1);
+        let pos = FixedVec2::from_f32((i % 6) as f32 * 7.0 - 20.0, (i / 6) as f32 * 7.0 - 20.0);
+        let radius = if i % 5 == 0 { FixedNum::from_num(10.0) } else { FixedNum::from_num(0.5) };
+        expected.insert(entity, hash.insert(entity, pos, radius).unwrap());
+    }
+
+    // Move a third of them across the map (changes cells), then drop a few
+    for i in 0..10 {
+        let entity = test_entity(i + 1);
+        let moved = FixedVec2::from_f32(30.0 - i as f32, 35.0);
+        if let Some(new_cell) = hash.update(entity, moved, &expected[&entity]) {
+            expected.insert(entity, new_cell);
+        }
+    }
+    for i in 25..30 {
+        let entity = test_entity(i + 1);
+        hash.remove(entity, &expected.remove(&entity).unwrap());
+    }
+
+    let mut yielded: Vec<_> = hash.iter_entities().map(|(e, cell)| (e, cell_key(&cell))).collect();
+    yielded.sort_unstable();
+    let mut want: Vec<_> = expected.iter().map(|(&e, cell)| (e, cell_key(cell))).collect();
+    want.sort_unstable();
+    assert_eq!(yielded, want, "iter_entities should yield each live entity once, at its current cell");
+
+    let occupancies: Vec<_> = hash.iter_cell_occupancies().collect();
+    assert_eq!(occupancies.iter().map(|c| c.count).sum::<usize>(), 25);
+    assert!(occupancies.iter().all(|c| c.count > 0), "Only non-empty cells are yielded");
+    let report = hash.debug_verify(expected.iter().map(|(e, cell)| (*e, cell)));
+    assert_eq!(occupancies.len(), report.occupied_cells);
+    assert_eq!(occupancies.iter().map(|c| c.count).max(), Some(report.max_cell_occupancy));
+}
+
+#[test]
+fn test_clear_retaining_capacity_keeps_arenas() {
+    for overcapacity_ratio in [1.0, 2.0] {
+        let mut hash = SpatialHash::new(
+            FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 500, overcapacity_ratio,
+        );
+        let entities: Vec<_> = (0..300)
+            .map(|i| (test_entity(i + 1), FixedVec2::from_f32((i % 20) as f32 * 4.0 - 40.0, (i / 20) as f32 * 4.0 - 30.0), FixedNum::from_num(0.5)))
+            .collect();
+        hash.rebuild_from_entity_list(&entities);
+        let capacity = hash.total_storage_capacity();
+
+        hash.clear_retaining_capacity();
+        assert_eq!(hash.total_entries(), 0);
+        assert_eq!(hash.total_storage_capacity(), capacity, "ratio {}: arenas should keep their capacity", overcapacity_ratio);
+        for size_class in hash.size_classes() {
+            assert!(size_class.grid_a.cell_ranges.iter().all(|r| r.is_empty()));
+            assert!(size_class.grid_b.cell_ranges.iter().all(|r| r.is_empty()));
+        }
+    }
+}
+
+#[test]
+fn test_reserve_grows_per_class_and_never_shrinks() {
+    let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 10, 1.0);
+    hash.reserve(&[1000]);
+    let classes = hash.size_classes();
+    assert_eq!(classes[0].grid_a.storage_capacity(), 1000);
+    assert_eq!(classes[0].grid_b.storage_capacity(), 1000);
+    assert_eq!(classes[1].grid_a.storage_capacity(), 10, "Classes without an entry are left alone");
+
+    hash.reserve(&[50, 50]);
+    assert_eq!(hash.size_classes()[0].grid_a.storage_capacity(), 1000);
+    assert_eq!(hash.size_classes()[1].grid_a.storage_capacity(), 50);
+}
+
+#[test]
+fn test_resizing_keeps_cells_valid_around_tombstones() {
+    // Incremental mode, so removals leave tombstones in the cell
+    let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 64, 1.5);
+    let pos = FixedVec2::from_f32(1.0, 1.0);
+    let mut tracked: Vec<_> = (0..6)
+        .map(|i| {
+            let entity = test_entity(i + 1);
+            (entity, hash.insert(entity, pos, FixedNum::from_num(0.5)).unwrap())
+        })
+        .collect();
+    for removed in [tracked.remove(3), tracked.remove(1)] {
+        hash.remove(removed.0, &removed.1).unwrap();
+    }
+    assert!(hash.fragmentation_ratio() > 0.0);
+
+    for capacity in [512, 16] {
+        hash.set_storage_capacity(capacity);
+        let report = hash.debug_verify(tracked.iter().map(|(entity, cell)| (*entity, cell)));
+        assert!(report.is_consistent(), "capacity {}: {:?}", capacity, report);
+        assert_eq!(report.total_entries, 4);
+    }
+
+    // The cells point at the right slots, so removing by them still removes the right entity
+    let (removed, cell) = tracked.pop().unwrap();
+    hash.remove(removed, &cell).unwrap();
+    let report = hash.debug_verify(tracked.iter().map(|(entity, cell)| (*entity, cell)));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.total_entries, 3);
+}
+
+#[test]
+fn test_repeated_clear_and_rebuild_stays_within_peak_allocation() {
+    let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 16, 1.0);
+    let unit_at = |i: u32| {
+        let radius = if i % 7 == 0 { FixedNum::from_num(10.0) } else { FixedNum::from_num(0.5) };
+        (test_entity(i + 1), FixedVec2::from_f32((i % 25) as f32 * 3.5 - 45.0, (i / 25) as f32 * 3.5 - 45.0), radius)
+    };
+    let largest: Vec<_> = (0..600).map(unit_at).collect();
+    hash.rebuild_from_entity_list(&largest);
+    let peak = hash.total_storage_capacity();
+
+    // Full rebuild every tick with a fluctuating population
+    for cycle in 0..20u32 {
+        let count = 100 + (cycle * 131) % 500;
+        let entities: Vec<_> = (0..count).map(unit_at).collect();
+        hash.clear_retaining_capacity();
+        hash.rebuild_from_entity_list(&entities);
+        assert_eq!(hash.total_entries(), count as usize);
+        assert!(hash.total_storage_capacity() <= peak, "cycle {}: {} > peak {}", cycle, hash.total_storage_capacity(), peak);
+    }
+}
+
+#[test]
+fn test_insert_beyond_cell_capacity_returns_error() {
+    // Incremental mode: the first rebuild spreads ~27 slots of headroom over each cell
+    let mut hash = SpatialHash::new(FixedNum::from_num(20.0), FixedNum::from_num(20.0), &[0.5], 4.0, 2000, 2.0);
+    hash.rebuild_from_entity_list(&[]);
+
+    let pos = FixedVec2::from_f32(1.0, 1.0);
+    let mut stored = Vec::new();
+    let error = (1..=1000u32)
+        .find_map(|id| match hash.insert(test_entity(id), pos, FixedNum::from_num(0.5)) {
+            Ok(occupied) => {
+                stored.push(occupied);
+                None
+            }
+            Err(error) => Some((id, error)),
+        })
+        .expect("A single cell can't take 1000 entities");
+
+    let (rejected_id, SpatialHashError::CapacityExhausted { size_class, grid_offset, col, row }) = error;
+    let last = stored.last().expect("The cell should take at least one entity");
+    assert_eq!((size_class, grid_offset, col, row), (last.size_class, last.grid_offset, last.col, last.row));
+    assert_eq!(hash.total_entries(), stored.len(), "Rejected entity must not be counted");
+
+    let mut scratch = SpatialHashScratch::new(64);
+    hash.query_radius(pos, FixedNum::from_num(1.0), None, &mut scratch);
+    assert_eq!(scratch.query_results.len(), stored.len());
+    assert!(!scratch.query_results.contains(&test_entity(rejected_id)), "No phantom entry for the rejected entity");
+}
+
+#[test]
+fn test_bulk_insert_matches_sequential_inserts() {
+    let new_hash = || {
+        // Incremental mode, with headroom laid out by an empty rebuild (as at map load)
+        let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 20_000, 2.0);
+        hash.rebuild_from_entity_list(&[]);
+        hash
+    };
+    let unit_at = |i: u32| {
+        let radius = if i % 9 == 0 { FixedNum::from_num(8.0) } else { FixedNum::from_num(0.5) };
+        (test_entity(i + 1), FixedVec2::from_f32((i % 20) as f32 * 4.5 - 45.0, (i / 20) as f32 * 4.5 - 45.0), radius)
+    };
+    let existing: Vec<_> = (0..40).map(unit_at).collect();
+    let added: Vec<_> = (40..400).map(unit_at).collect();
+
+    let mut sequential = new_hash();
+    let mut bulk = new_hash();
+    let mut sequential_cells = Vec::new();
+    for &(entity, pos, radius) in &existing {
+        sequential_cells.push(sequential.insert(entity, pos, radius).unwrap());
+        bulk.insert(entity, pos, radius).unwrap();
+    }
+    // One tombstone in both, so bulk insert has to keep slot indices around it
+    let (removed, _, _) = existing[3];
+    sequential.remove(removed, &sequential_cells[3]).unwrap();
+    bulk.remove(removed, &sequential_cells[3]).unwrap();
+
+    let mut added_sequential = Vec::new();
+    for &(entity, pos, radius) in &added {
+        added_sequential.push(sequential.insert(entity, pos, radius).unwrap());
+    }
+    let added_bulk = bulk.bulk_insert(&added);
+    let keys = |cells: &[OccupiedCell]| cells.iter().map(cell_key).collect::<Vec<_>>();
+    assert_eq!(keys(&added_bulk), keys(&added_sequential));
+    assert_eq!(bulk.total_entries(), sequential.total_entries());
+    assert_eq!(bulk.total_entries(), existing.len() + added.len() - 1);
+
+    // Entities inserted before the bulk insert are still where their OccupiedCells say
+    let tracked: Vec<(Entity, OccupiedCell)> = existing.iter().zip(&sequential_cells)
+        .filter(|((entity, _, _), _)| *entity != removed)
+        .map(|(&(entity, _, _), &cell)| (entity, cell))
+        .chain(added.iter().zip(&added_bulk).map(|(&(entity, _, _), &cell)| (entity, cell)))
+        .collect();
+    let report = bulk.debug_verify(tracked.iter().map(|(entity, cell)| (*entity, cell)));
+    assert!(report.is_consistent(), "{:?}", report);
+
+    let members = |hash: &SpatialHash| {
+        let mut members: Vec<_> = hash.iter_entities().map(|(entity, cell)| (entity, cell_key(&cell))).collect();
+        members.sort_unstable();
+        members
+    };
+    assert_eq!(members(&bulk), members(&sequential));
+}
@@ -0,0 +1,115 @@
+//! Debug consistency checks for the spatial hash.
+//!
+//! Cross-checks the arena contents against the `OccupiedCell` components the ECS holds.
+//! This allocates and walks every cell, so it is meant for tests and `debug_assertions`
+//! builds - never call it from a per-tick system in release.
+
+use bevy::prelude::*;
+use crate::game::simulation::components::OccupiedCell;
+use super::SpatialHash;
+
+/// Result of [`SpatialHash::debug_verify`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DebugReport {
+    /// Number of (entity, OccupiedCell) pairs checked
+    pub entities_checked: usize,
+    /// Entities whose OccupiedCell does not point at a slot holding that entity
+    /// (invalid size class / cell / vec_idx, or the slot holds someone else)
+    pub mismatches: Vec<Entity>,
+    /// Number of extra arena slots holding an entity that is already stored elsewhere
+    pub duplicate_entries: usize,
+    /// Arena entries (non-tombstone) whose entity was not in the checked set
+    pub untracked_entries: usize,
+    /// Total non-tombstone entries across all grids
+    pub total_entries: usize,
+    /// Number of cells holding at least one entity
+    pub occupied_cells: usize,
+    /// Largest number of entities found in a single cell
+    pub max_cell_occupancy: usize,
+}
+
+impl DebugReport {
+    /// True if every entity matches its cell and the arena holds no duplicates or strays
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty() && self.duplicate_entries == 0 && self.untracked_entries == 0
+    }
+
+    /// Mean entities per occupied cell (0 if the hash is empty)
+    pub fn mean_cell_occupancy(&self) -> f32 {
+        if self.occupied_cells == 0 {
+            0.0
+        } else {
+            self.total_entries as f32 / self.occupied_cells as f32
+        }
+    }
+}
+
+impl SpatialHash {
+    /// Verify that `entities` (typically every `(Entity, &OccupiedCell)` in the world) agree
+    /// with what is actually stored in the arena.
+    ///
+    /// Usage:
+    /// ```rust,ignore
+    /// let report = spatial_hash.debug_verify(query.iter());
+    /// debug_assert!(report.is_consistent(), "{:?}", report);
+    /// ```
+    pub fn debug_verify<'a>(&self, entities: impl Iterator<Item = (Entity, &'a OccupiedCell)>) -> DebugReport {
+        let mut report = DebugReport::default();
+
+        // 1. Each OccupiedCell must point at a slot that holds its entity
+        let mut tracked: Vec<Entity> = Vec::new();
+        for (entity, occupied) in entities {
+            report.entities_checked += 1;
+            tracked.push(entity);
+
+            let slot = self.size_classes.get(occupied.size_class as usize).and_then(|size_class| {
+                let grid = match occupied.grid_offset {
+                    0 => &size_class.grid_a,
+                    1 => &size_class.grid_b,
+                    _ => return None,
+                };
+                if occupied.col >= grid.cols || occupied.row >= grid.rows {
+                    return None;
+                }
+                grid.get_cell_entities(occupied.col, occupied.row).get(occupied.vec_idx).copied()
+            });
+
+            if slot != Some(entity) {
+                report.mismatches.push(entity);
+            }
+        }
+        tracked.sort_unstable();
+
+        // 2. Walk every cell: occupancy stats, duplicates, and entries nobody claims
+        let mut stored: Vec<Entity> = Vec::new();
+        for size_class in &self.size_classes {
+            for grid in [&size_class.grid_a, &size_class.grid_b] {
+                for row in 0..grid.rows {
+                    for col in 0..grid.cols {
+                        let before = stored.len();
+                        stored.extend(
+                            grid.get_cell_entities(col, row).iter().copied().filter(|&e| e != Entity::PLACEHOLDER),
+                        );
+                        let in_cell = stored.len() - before;
+                        if in_cell > 0 {
+                            report.occupied_cells += 1;
+                            report.max_cell_occupancy = report.max_cell_occupancy.max(in_cell);
+                        }
+                    }
+                }
+            }
+        }
+        report.total_entries = stored.len();
+
+        stored.sort_unstable();
+        for (i, &entity) in stored.iter().enumerate() {
+            if i > 0 && stored[i - 1] == entity {
+                report.duplicate_entries += 1;
+            } else if tracked.binary_search(&entity).is_err() {
+                report.untracked_entries += 1;
+            }
+        }
+
+        report
+    }
+}
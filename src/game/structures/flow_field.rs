@@ -1,11 +1,20 @@
 use crate::game::fixed_math::{FixedNum, FixedVec2};
+use crate::game::collections::GridLayout;
 use bevy::prelude::*;
-use std::collections::VecDeque;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 use serde::{Serialize, Deserialize};
 
 /// Fixed cell size for the flow field grid (1 world unit per cell).
 pub const CELL_SIZE: f32 = 1.0;
 
+/// Cost of an impassable cell. Anything below is walkable (1 = normal, 2-254 = slow terrain).
+pub const OBSTACLE_COST: u8 = 255;
+
+/// Integration value for cells that can't reach the goal (obstacles, enclosed areas).
+/// Reachable distances saturate one below this.
+pub const UNREACHABLE: u16 = u16::MAX;
+
 /// Flow field navigation grid using Dijkstra-based integration and vector fields.
 ///
 /// A flow field guides units to a target by precomputing the optimal direction
@@ -69,6 +78,10 @@ pub struct FlowField {
     pub cell_size: FixedNum,
     pub origin: FixedVec2, // Bottom-left corner of the grid in world space
     pub cost_field: Vec<u8>, // 1 = walkable, 255 = obstacle
+    /// Terrain cost underneath obstacle cells, recorded by `set_obstacle` so `clear_obstacle`
+    /// can put it back. Empty until the first obstacle is set.
+    #[serde(default)]
+    pub base_cost_field: Vec<u8>,
     pub integration_field: Vec<u32>, // Distance to target
     pub vector_field: Vec<FixedVec2>, // Direction to move
     pub target_cell: Option<(usize, usize)>,
@@ -84,42 +97,154 @@ impl FlowField {
             cell_size,
             origin,
             cost_field: vec![1; size],
+            base_cost_field: Vec::new(),
             integration_field: vec![u32::MAX; size],
             vector_field: vec![FixedVec2::ZERO; size],
             target_cell: None,
         }
     }
 
-    pub fn world_to_grid(&self, world_pos: FixedVec2) -> Option<(usize, usize)> {
-        let local_pos = world_pos - self.origin;
-        
-        // Convert to grid coordinates (can be negative if position is left/below origin)
-        let grid_x = (local_pos.x / self.cell_size).to_num::<i32>();
-        let grid_y = (local_pos.y / self.cell_size).to_num::<i32>();
-
-        // Check bounds - reject if outside the grid
-        if grid_x < 0 || grid_y < 0 || grid_x >= self.width as i32 || grid_y >= self.height as i32 {
-            return None;
-        }
+    /// Grid geometry shared by all three fields (indexing and world↔grid conversion)
+    #[inline]
+    pub fn layout(&self) -> GridLayout {
+        GridLayout::new(self.width, self.height, self.cell_size, self.origin)
+    }
 
-        Some((grid_x as usize, grid_y as usize))
+    pub fn world_to_grid(&self, world_pos: FixedVec2) -> Option<(usize, usize)> {
+        self.layout().world_to_grid(world_pos)
     }
 
     pub fn grid_to_world(&self, x: usize, y: usize) -> FixedVec2 {
-        let offset = self.cell_size / FixedNum::from_num(2.0);
-        self.origin + FixedVec2::new(
-            FixedNum::from_num(x) * self.cell_size + offset,
-            FixedNum::from_num(y) * self.cell_size + offset,
-        )
+        self.layout().grid_to_world(x, y)
     }
 
     pub fn get_index(&self, x: usize, y: usize) -> usize {
-        y * self.width + x
+        self.layout().index(x, y)
     }
 
     pub fn set_obstacle(&mut self, x: usize, y: usize) {
         let idx = self.get_index(x, y);
-        self.cost_field[idx] = 255;
+        let cost = self.cost_field[idx];
+        if cost != OBSTACLE_COST {
+            self.set_base_cost(idx, cost);
+        }
+        self.cost_field[idx] = OBSTACLE_COST;
+    }
+
+    /// Inverse of [`set_obstacle`](Self::set_obstacle): put back the terrain cost (x, y) had
+    /// before it was blocked, or 1 if it was never blocked through `set_obstacle`
+    pub fn clear_obstacle(&mut self, x: usize, y: usize) {
+        let idx = self.get_index(x, y);
+        self.cost_field[idx] = self.base_cost_field.get(idx).copied().unwrap_or(1);
+    }
+
+    /// Set the terrain cost of (x, y). An obstacle cell stays blocked and takes the cost once
+    /// it is cleared.
+    pub fn set_terrain_cost(&mut self, x: usize, y: usize, cost: u8) {
+        let idx = self.get_index(x, y);
+        if self.cost_field[idx] == OBSTACLE_COST {
+            self.set_base_cost(idx, cost);
+        } else {
+            self.cost_field[idx] = cost;
+        }
+    }
+
+    fn set_base_cost(&mut self, idx: usize, cost: u8) {
+        if self.base_cost_field.len() != self.cost_field.len() {
+            self.base_cost_field = vec![1; self.cost_field.len()];
+        }
+        self.base_cost_field[idx] = cost;
+    }
+
+    /// Cost of cell (x, y), or `None` outside the grid
+    #[inline]
+    pub fn cost(&self, x: usize, y: usize) -> Option<u8> {
+        if x < self.width && y < self.height {
+            self.cost_field.get(self.get_index(x, y)).copied()
+        } else {
+            None
+        }
+    }
+
+    /// True if (x, y) is inside the grid and not an obstacle
+    #[inline]
+    pub fn is_walkable(&self, x: usize, y: usize) -> bool {
+        self.cost(x, y).is_some_and(|cost| cost != OBSTACLE_COST)
+    }
+
+    /// [`is_walkable`](Self::is_walkable) for the cell containing a world position
+    #[inline]
+    pub fn is_walkable_world(&self, pos: FixedVec2) -> bool {
+        self.world_to_grid(pos).is_some_and(|(x, y)| self.is_walkable(x, y))
+    }
+
+    /// True if the straight segment from `from` to `to` crosses no obstacle cell.
+    ///
+    /// Walks every cell the segment touches (grid DDA in fixed point, so the answer is the same
+    /// on every machine). A segment passing exactly through a cell corner counts as touching
+    /// both side cells, so sight can't slip diagonally between two obstacles. Endpoints off the
+    /// grid have no line of sight.
+    pub fn has_line_of_sight(&self, from: FixedVec2, to: FixedVec2) -> bool {
+        self.line_of_sight(from, to, false)
+    }
+
+    /// [`has_line_of_sight`](Self::has_line_of_sight) on a wrapping map.
+    ///
+    /// `to` may lie past an edge, where it is seen across the seam from `from` (as in the
+    /// spatial hash's `query_positions`); cells past the edge are read from the opposite side.
+    pub fn has_line_of_sight_wrapped(&self, from: FixedVec2, to: FixedVec2) -> bool {
+        self.line_of_sight(from, to, true)
+    }
+
+    fn line_of_sight(&self, from: FixedVec2, to: FixedVec2, wraps: bool) -> bool {
+        let Some((start_x, start_y)) = self.world_to_grid(from) else { return false };
+        let end = if wraps {
+            let local = (to - self.origin) / self.cell_size;
+            (local.x.floor().to_num::<i64>(), local.y.floor().to_num::<i64>())
+        } else {
+            let Some((end_x, end_y)) = self.world_to_grid(to) else { return false };
+            (end_x as i64, end_y as i64)
+        };
+        let (mut x, mut y) = (start_x as i64, start_y as i64);
+        let walkable = |x: i64, y: i64| {
+            let (x, y) = if wraps { (x.rem_euclid(self.width as i64), y.rem_euclid(self.height as i64)) } else { (x, y) };
+            x >= 0 && y >= 0 && self.is_walkable(x as usize, y as usize)
+        };
+        // Segment in grid units, relative to the start cell's corner
+        let start = (from - self.origin) / self.cell_size - FixedVec2::new(FixedNum::from_num(x), FixedNum::from_num(y));
+        let delta = (to - from) / self.cell_size;
+        let (dx, dy) = (delta.x.abs(), delta.y.abs());
+        // Distance along each axis to the next cell boundary the segment crosses
+        let mut next_x = if delta.x >= FixedNum::ZERO { FixedNum::ONE - start.x } else { start.x };
+        let mut next_y = if delta.y >= FixedNum::ZERO { FixedNum::ONE - start.y } else { start.y };
+
+        let step = |v: i64, negative: bool| if negative { v - 1 } else { v + 1 };
+        for _ in 0..=(x.abs_diff(end.0) + y.abs_diff(end.1)) {
+            if !walkable(x, y) {
+                return false;
+            }
+            if (x, y) == end {
+                return true;
+            }
+            // Cross-multiplied comparison of the segment parameter at each boundary
+            let (cross_x, cross_y) = (next_x * dy, next_y * dx);
+            if cross_x < cross_y || (cross_x == cross_y && dy == FixedNum::ZERO) {
+                x = step(x, delta.x < FixedNum::ZERO);
+                next_x += FixedNum::ONE;
+            } else if cross_y < cross_x || dx == FixedNum::ZERO {
+                y = step(y, delta.y < FixedNum::ZERO);
+                next_y += FixedNum::ONE;
+            } else {
+                let (nx, ny) = (step(x, delta.x < FixedNum::ZERO), step(y, delta.y < FixedNum::ZERO));
+                if !walkable(nx, y) || !walkable(x, ny) {
+                    return false;
+                }
+                (x, y) = (nx, ny);
+                next_x += FixedNum::ONE;
+                next_y += FixedNum::ONE;
+            }
+        }
+        false
     }
 
     pub fn generate_integration_field(&mut self, target_x: usize, target_y: usize) {
@@ -152,7 +277,7 @@ impl FlowField {
                 let n_idx = self.get_index(nx, ny);
                 let cost = self.cost_field[n_idx];
 
-                if cost == 255 {
+                if cost == OBSTACLE_COST {
                     continue; // Obstacle
                 }
 
@@ -170,7 +295,7 @@ impl FlowField {
             for x in 0..self.width {
                 let idx = self.get_index(x, y);
                 
-                if self.cost_field[idx] == 255 {
+                if self.cost_field[idx] == OBSTACLE_COST {
                     self.vector_field[idx] = FixedVec2::ZERO;
                     continue;
                 }
@@ -222,4 +347,223 @@ impl FlowField {
             }
         }
     }
+
+    /// Dijkstra integration field from `goal`: per-cell path cost to the goal cell.
+    ///
+    /// Unlike [`generate_integration_field`](Self::generate_integration_field) this leaves
+    /// the struct untouched and returns a compact `u16` field, so callers can keep one per
+    /// shared destination (e.g. a rally point for thousands of units) without cloning the
+    /// cost field. 4-connected, weighted by `cost_field`; obstacles, unreachable cells and
+    /// everything (if `goal` is off the grid or blocked) are [`UNREACHABLE`].
+    pub fn compute_integration_field(&self, goal: (usize, usize)) -> Vec<u16> {
+        let layout = self.layout();
+        let mut integration = vec![UNREACHABLE; layout.len()];
+        let Some(goal_idx) = layout.checked_index(goal.0, goal.1) else {
+            return integration;
+        };
+        if self.cost_field[goal_idx] == OBSTACLE_COST {
+            return integration;
+        }
+
+        integration[goal_idx] = 0;
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((0u16, goal_idx)));
+
+        while let Some(Reverse((cost, idx))) = open.pop() {
+            if cost > integration[idx] {
+                continue; // Stale entry
+            }
+            let (x, y) = (idx % self.width, idx / self.width);
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbors {
+                let Some(n_idx) = layout.checked_index(nx, ny) else {
+                    continue;
+                };
+                let step = self.cost_field[n_idx];
+                if step == OBSTACLE_COST {
+                    continue;
+                }
+                let new_cost = cost.saturating_add(step as u16).min(UNREACHABLE - 1);
+                if new_cost < integration[n_idx] {
+                    integration[n_idx] = new_cost;
+                    open.push(Reverse((new_cost, n_idx)));
+                }
+            }
+        }
+
+        integration
+    }
+
+    /// Unit direction from cell (x, y) towards its lowest-cost neighbor in `integration`.
+    ///
+    /// Considers all 8 neighbors, but diagonals only when both adjacent cardinals are
+    /// walkable so units don't cut obstacle corners. Zero at the goal, on obstacles and
+    /// on unreachable cells.
+    pub fn flow_direction(&self, integration: &[u16], x: usize, y: usize) -> FixedVec2 {
+        let layout = self.layout();
+        let Some(idx) = layout.checked_index(x, y) else {
+            return FixedVec2::ZERO;
+        };
+        let mut best_cost = integration[idx];
+        if best_cost == UNREACHABLE || best_cost == 0 {
+            return FixedVec2::ZERO;
+        }
+
+        let walkable = |dx: isize, dy: isize| {
+            layout.checked_index(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy))
+                .is_some_and(|n_idx| integration[n_idx] != UNREACHABLE)
+        };
+
+        let mut best_dir = (0, 0);
+        for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)] {
+            if !walkable(dx, dy) || (dx != 0 && dy != 0 && !(walkable(dx, 0) && walkable(0, dy))) {
+                continue;
+            }
+            let n_idx = layout.index(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy));
+            if integration[n_idx] < best_cost {
+                best_cost = integration[n_idx];
+                best_dir = (dx, dy);
+            }
+        }
+
+        if best_dir == (0, 0) {
+            FixedVec2::ZERO
+        } else {
+            FixedVec2::new(FixedNum::from_num(best_dir.0), FixedNum::from_num(best_dir.1)).normalize()
+        }
+    }
+
+    /// [`flow_direction`](Self::flow_direction) for every cell, row-major
+    pub fn compute_flow_directions(&self, integration: &[u16]) -> Vec<FixedVec2> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| self.flow_direction(integration, x, y))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_field(width: usize, height: usize) -> FlowField {
+        FlowField::new(width, height, FixedNum::from_num(CELL_SIZE), FixedVec2::ZERO)
+    }
+
+    #[test]
+    fn test_cost_and_walkability_lookups() {
+        let mut field = FlowField::new(6, 4, FixedNum::from_num(CELL_SIZE), FixedVec2::from_f32(-3.0, -2.0));
+        field.set_obstacle(2, 1);
+        let slow = field.get_index(4, 3);
+        field.cost_field[slow] = 7;
+
+        assert_eq!(field.cost(0, 0), Some(1));
+        assert_eq!(field.cost(2, 1), Some(OBSTACLE_COST));
+        assert_eq!(field.cost(4, 3), Some(7));
+        assert!(field.is_walkable(0, 0) && field.is_walkable(4, 3), "Slow terrain is still walkable");
+        assert!(!field.is_walkable(2, 1));
+
+        // World lookups go through the origin offset: cell (2, 1) spans [-1, 0) x [-1, 0)
+        assert!(!field.is_walkable_world(FixedVec2::from_f32(-0.5, -0.5)));
+        assert!(field.is_walkable_world(FixedVec2::from_f32(0.5, -0.5)));
+    }
+
+    #[test]
+    fn test_out_of_bounds_lookups_are_not_walkable() {
+        let field = open_field(6, 4);
+        // x = 6 on row 0 would alias (0, 1) with naive index math
+        for (x, y) in [(6, 0), (0, 4), (6, 4), (usize::MAX, 0), (0, usize::MAX)] {
+            assert_eq!(field.cost(x, y), None, "({}, {})", x, y);
+            assert!(!field.is_walkable(x, y), "({}, {})", x, y);
+        }
+        for pos in [(-0.5, 1.0), (1.0, -0.5), (6.0, 1.0), (1.0, 4.0), (1000.0, 1000.0)] {
+            assert!(!field.is_walkable_world(FixedVec2::from_f32(pos.0, pos.1)), "{:?}", pos);
+        }
+        assert!(!FlowField::default().is_walkable(0, 0), "Empty field");
+    }
+
+    #[test]
+    fn test_line_of_sight_stops_at_obstacles() {
+        let mut field = open_field(10, 10);
+        for y in 2..8usize {
+            field.set_obstacle(5, y);
+        }
+        let at = |x: f32, y: f32| FixedVec2::from_f32(x, y);
+
+        assert!(!field.has_line_of_sight(at(1.5, 4.5), at(8.5, 4.5)), "Through the wall");
+        assert!(!field.has_line_of_sight(at(8.5, 5.2), at(1.5, 3.7)), "Shallow slope through the wall");
+        assert!(field.has_line_of_sight(at(1.5, 0.5), at(8.5, 1.5)), "Below the wall");
+        assert!(field.has_line_of_sight(at(4.5, 9.5), at(4.5, 0.5)), "Along the wall");
+        assert!(field.has_line_of_sight(at(3.25, 3.25), at(3.25, 3.25)));
+
+        // Diagonal gap between two obstacles touching at a corner
+        let mut corner = open_field(4, 4);
+        corner.set_obstacle(1, 2);
+        corner.set_obstacle(2, 1);
+        assert!(!corner.has_line_of_sight(at(0.5, 0.5), at(3.5, 3.5)));
+        assert!(open_field(4, 4).has_line_of_sight(at(0.5, 0.5), at(3.5, 3.5)));
+
+        assert!(!field.has_line_of_sight(at(1.5, 1.5), at(12.0, 1.5)), "Off the grid");
+    }
+
+    #[test]
+    fn test_integration_field_increases_with_distance_from_goal() {
+        let field = open_field(8, 8);
+        let integration = field.compute_integration_field((2, 3));
+
+        assert_eq!(integration[field.get_index(2, 3)], 0);
+        for y in 0..8usize {
+            for x in 0..8usize {
+                let manhattan = x.abs_diff(2) + y.abs_diff(3);
+                assert_eq!(integration[field.get_index(x, y)] as usize, manhattan, "cell ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flow_vectors_route_around_obstacle() {
+        // Wall at x = 5 for y in 0..8, open above; goal on the far side
+        let mut field = open_field(10, 10);
+        for y in 0..8usize {
+            field.set_obstacle(5, y);
+        }
+        let goal = (8, 2);
+        let integration = field.compute_integration_field(goal);
+        let directions = field.compute_flow_directions(&integration);
+
+        assert_eq!(integration[field.get_index(5, 2)], UNREACHABLE);
+        // Detour over the top of the wall costs more than the straight-line distance
+        assert!(integration[field.get_index(2, 2)] as usize > 6);
+
+        // Following the vectors from behind the wall reaches the goal without entering it
+        let (mut x, mut y) = (2usize, 2usize);
+        for _ in 0..100 {
+            if (x, y) == goal {
+                break;
+            }
+            let dir = directions[field.get_index(x, y)];
+            assert_ne!(dir, FixedVec2::ZERO, "stuck at ({}, {})", x, y);
+            x = (x as i32 + dir.x.to_num::<f32>().round() as i32) as usize;
+            y = (y as i32 + dir.y.to_num::<f32>().round() as i32) as usize;
+            assert!(field.is_walkable(x, y), "walked into the wall at ({}, {})", x, y);
+        }
+        assert_eq!((x, y), goal);
+
+        // Next to the wall the flow points away from it (up, around the end)
+        assert_eq!(directions[field.get_index(4, 2)], FixedVec2::new(FixedNum::ZERO, FixedNum::ONE));
+        assert_eq!(directions[field.get_index(goal.0, goal.1)], FixedVec2::ZERO);
+    }
+
+    #[test]
+    fn test_blocked_or_off_grid_goal_is_unreachable_everywhere() {
+        let mut field = open_field(4, 4);
+        field.set_obstacle(1, 1);
+        assert!(field.compute_integration_field((1, 1)).iter().all(|&c| c == UNREACHABLE));
+        assert!(field.compute_integration_field((9, 0)).iter().all(|&c| c == UNREACHABLE));
+    }
 }
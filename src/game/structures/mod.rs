@@ -5,4 +5,4 @@
 
 mod flow_field;
 
-pub use flow_field::{FlowField, CELL_SIZE};
+pub use flow_field::{FlowField, CELL_SIZE, OBSTACLE_COST, UNREACHABLE};
@@ -58,8 +58,8 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
+            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
         }
         
         // Add the boids system
@@ -114,7 +114,7 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity, FixedVec2::ZERO, FixedNum::from_num(0.5));
+            hash.insert(entity, FixedVec2::ZERO, FixedNum::from_num(0.5)).unwrap();
         }
         
         app.add_systems(Update, apply_boids_steering);
@@ -174,8 +174,8 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
+            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
         }
         
         // Manually populate boids cache for entity_a with entity_b as neighbor
@@ -243,8 +243,8 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
+            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
         }
         
         // Manually populate boids cache for entity_a with entity_b as neighbor
@@ -323,9 +323,9 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
-            hash.insert(entity_c, pos_c, FixedNum::from_num(0.5));
+            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_c, pos_c, FixedNum::from_num(0.5)).unwrap();
         }
         
         // Manually populate boids cache for entity_a with B and C as neighbors
@@ -401,9 +401,9 @@ mod tests {
         {
             let mut hash = app.world_mut().resource_mut::<SpatialHash>();
             hash.clear();
-            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5));
-            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5));
-            hash.insert(entity_c, pos_c, FixedNum::from_num(0.5));
+            hash.insert(entity_a, pos_a, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_b, pos_b, FixedNum::from_num(0.5)).unwrap();
+            hash.insert(entity_c, pos_c, FixedNum::from_num(0.5)).unwrap();
         }
         
         app.add_systems(Update, apply_boids_steering);
@@ -1,9 +1,55 @@
 use bevy::prelude::*;
+use crate::game::fixed_math::{FixedNum, FixedVec2};
 
 /// Marks an entity as a unit in the game
 #[derive(Component)]
 pub struct Unit;
 
+/// The kind of unit, used for grouping (e.g. the HUD selection summary).
+///
+/// Units spawned without an explicit type default to `Soldier`.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnitType {
+    #[default]
+    Soldier,
+    Tank,
+}
+
+impl UnitType {
+    /// Display name for a group of `count` units of this type ("1 soldier", "40 soldiers")
+    pub fn display_name(&self, count: usize) -> &'static str {
+        match (self, count == 1) {
+            (UnitType::Soldier, true) => "soldier",
+            (UnitType::Soldier, false) => "soldiers",
+            (UnitType::Tank, true) => "tank",
+            (UnitType::Tank, false) => "tanks",
+        }
+    }
+}
+
+/// Owning player / team of a unit (matches `player_id` in commands)
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Team(pub u8);
+
+/// Team colors, cycling for team ids past the end
+const TEAM_COLORS: [Color; 8] = [
+    Color::srgb(0.2, 0.45, 1.0),
+    Color::srgb(0.95, 0.2, 0.15),
+    Color::srgb(0.25, 0.8, 0.3),
+    Color::srgb(1.0, 0.8, 0.1),
+    Color::srgb(0.7, 0.3, 0.95),
+    Color::srgb(1.0, 0.5, 0.1),
+    Color::srgb(0.1, 0.85, 0.85),
+    Color::srgb(1.0, 0.45, 0.75),
+];
+
+impl Team {
+    /// Color the team is drawn in (minimap dots)
+    pub fn color(self) -> Color {
+        TEAM_COLORS[self.0 as usize % TEAM_COLORS.len()]
+    }
+}
+
 /// Health component for units
 #[derive(Component)]
 pub struct Health {
@@ -11,14 +57,43 @@ pub struct Health {
     pub max: f32,
 }
 
+/// Makes a unit pick its own targets: the nearest living enemy within `sight_range` that it
+/// has line of sight to through the cost field (see `acquire_targets`)
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetAcquisition {
+    pub sight_range: FixedNum,
+}
+
+impl TargetAcquisition {
+    pub fn new(sight_range: FixedNum) -> Self {
+        Self { sight_range }
+    }
+}
+
+/// Enemy a unit has acquired; removed when none is in sight
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackTarget(pub Entity);
+
+/// Last boids steering force computed for a unit, reapplied on the ticks between
+/// recomputes when `SimConfig::boids_update_interval` is above 1
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoidsSteering {
+    pub force: FixedVec2,
+    /// `SimTick` the force was computed on
+    pub computed_tick: u64,
+}
+
+/// Marks an entity the player can select (click, box or paint select).
+///
+/// Part of [`UnitBundle`](super::UnitBundle); obstacles and projectiles leave it out so a
+/// box drag over terrain doesn't pick them up.
+#[derive(Component, Default)]
+pub struct Selectable;
+
 /// Marks a unit as currently selected by the player
 #[derive(Component)]
 pub struct Selected;
 
-/// Marks the child entity that renders the selection circle
-#[derive(Component)]
-pub struct SelectionCircle;
-
 /// Marks the child entity that renders the health bar
 #[derive(Component)]
 pub struct HealthBar;
@@ -2,20 +2,23 @@ mod components;
 mod resources;
 mod visuals;
 mod boids;
+mod spawn;
+mod targeting;
 
 use bevy::prelude::*;
 use crate::game::GameState;
-use crate::game::simulation::SimSet;
-use crate::game::pathfinding::follow_path;
 
 // Re-export public types
-pub use components::{Unit, Health, Selected, SelectionCircle, HealthBar};
-pub use resources::{HealthBarSettings, UnitMesh, UnitMaterials};
+pub use components::{Unit, UnitType, Team, Health, Selectable, Selected, HealthBar, BoidsSteering, TargetAcquisition, AttackTarget};
+pub use resources::{HealthBarSettings, HealthBarMode, UnitMesh, UnitMaterials, BoidsStats, SelectionRing, SelectionRings};
 pub use boids::apply_boids_steering;
+pub use spawn::{UnitBundle, TeamAssignment, TeamAssignments, DEFAULT_STARTING_UNITS, spawn_unit, spawn_unit_in_world, spawn_starting_units};
+pub use targeting::acquire_targets;
+pub use visuals::update_selection_rings;
 
 use resources::setup_unit_resources;
-use visuals::{spawn_unit_visuals, sync_visuals, update_selection_visuals, 
-              update_selection_circle_visibility, update_unit_lod,
+use visuals::{spawn_unit_visuals, sync_visuals, update_selection_visuals,
+              draw_selection_rings, update_unit_lod,
               toggle_health_bars, update_health_bars};
 
 /// Plugin that manages unit entities, their visuals, and behaviors
@@ -24,20 +27,16 @@ pub struct UnitPlugin;
 impl Plugin for UnitPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<HealthBarSettings>()
+           .init_resource::<SelectionRings>()
            .add_systems(Startup, setup_unit_resources)
-           // Boids steering runs in FixedUpdate after pathfinding
-           .add_systems(FixedUpdate, 
-               apply_boids_steering
-                   .in_set(SimSet::Steering)
-                   .after(follow_path))
+           // Boids steering and targeting are part of the simulation tick (simulation::schedule)
            // Visual systems run in Update for smooth rendering
            .add_systems(Update, (
                spawn_unit_visuals,
                update_selection_visuals,
-               update_selection_circle_visibility,
                update_health_bars,
                toggle_health_bars,
-               sync_visuals,
+               (sync_visuals, update_selection_rings, draw_selection_rings).chain(),
                update_unit_lod,
            ).run_if(in_state(GameState::InGame).or(in_state(GameState::Editor))));
     }
@@ -1,16 +1,75 @@
 use bevy::prelude::*;
+use super::components::Health;
+
+/// Counters from the last `apply_boids_steering` run (optional resource)
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoidsStats {
+    /// Neighbor entries evaluated across all steered units (bounded by
+    /// `SimConfig::boids_max_neighbors` per unit)
+    pub neighbors_processed: usize,
+}
+
+/// When unit health bars are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HealthBarMode {
+    /// Every unit
+    Always,
+    /// Only units below max health
+    WhenDamaged,
+    /// Only selected units
+    WhenSelected,
+    /// Hidden
+    #[default]
+    Never,
+}
+
+impl HealthBarMode {
+    /// Next mode in the toggle-key cycle: Never → Always → WhenDamaged → WhenSelected → Never
+    pub fn next(self) -> Self {
+        match self {
+            HealthBarMode::Never => HealthBarMode::Always,
+            HealthBarMode::Always => HealthBarMode::WhenDamaged,
+            HealthBarMode::WhenDamaged => HealthBarMode::WhenSelected,
+            HealthBarMode::WhenSelected => HealthBarMode::Never,
+        }
+    }
+
+    /// Whether a unit's health bar should be visible in this mode
+    pub fn is_visible(self, health: &Health, selected: bool) -> bool {
+        match self {
+            HealthBarMode::Always => true,
+            HealthBarMode::WhenDamaged => health.current < health.max,
+            HealthBarMode::WhenSelected => selected,
+            HealthBarMode::Never => false,
+        }
+    }
+}
 
 /// Settings for health bar display
 #[derive(Resource, Default)]
 pub struct HealthBarSettings {
-    pub show: bool,
+    pub mode: HealthBarMode,
+}
+
+/// One selection ring in the batch drawn by `draw_selection_rings`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SelectionRing {
+    /// Ground point under the unit
+    pub center: Vec3,
+    /// Multiplier on the base ring radii (1.0 for a 0.5-radius unit)
+    pub scale: f32,
+}
+
+/// Draw data for every selected unit's ring, rebuilt each frame from the `Selected` set
+#[derive(Resource, Debug, Default)]
+pub struct SelectionRings {
+    pub rings: Vec<SelectionRing>,
 }
 
 /// Shared mesh handles for unit rendering
 #[derive(Resource)]
 pub struct UnitMesh {
     pub unit: Handle<Mesh>,
-    pub circle: Handle<Mesh>,
     pub quad: Handle<Mesh>,
 }
 
@@ -19,7 +78,6 @@ pub struct UnitMesh {
 pub struct UnitMaterials {
     pub normal: Handle<StandardMaterial>,
     pub colliding: Handle<StandardMaterial>,
-    pub selection_circle: Handle<StandardMaterial>,
     pub health_bar: Handle<StandardMaterial>,
 }
 
@@ -30,22 +88,15 @@ pub(super) fn setup_unit_resources(
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     let mesh = meshes.add(Capsule3d::default());
-    let circle_mesh = meshes.add(Annulus::new(0.6, 0.7)); // Inner radius 0.6, Outer 0.7
     let quad_mesh = meshes.add(Rectangle::new(1.0, 0.15));
 
     commands.insert_resource(UnitMesh {
         unit: mesh,
-        circle: circle_mesh,
         quad: quad_mesh,
     });
 
     let normal_mat = materials.add(Color::srgb(0.8, 0.7, 0.6));
     let colliding_mat = materials.add(Color::srgb(0.8, 0.2, 0.2));
-    let circle_mat = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.2, 1.0, 0.2),
-        unlit: true,
-        ..default()
-    });
     let health_mat = materials.add(StandardMaterial {
         base_color: Color::srgb(0.0, 1.0, 0.0),
         unlit: true,
@@ -56,7 +107,6 @@ pub(super) fn setup_unit_resources(
     commands.insert_resource(UnitMaterials {
         normal: normal_mat,
         colliding: colliding_mat,
-        selection_circle: circle_mat,
         health_bar: health_mat,
     });
 }
@@ -0,0 +1,203 @@
+//! Spawning simulated units.
+//!
+//! A unit only takes part in the simulation if it has the full component set: stepping,
+//! collision, spatial hashing and pathfinding each filter on different components, and a
+//! missing one silently excludes the unit from that system. [`UnitBundle`] is that set;
+//! [`spawn_unit`] (from a system) and [`spawn_unit_in_world`] (scenario/test setup)
+//! additionally place the unit in the spatial hash, so it shows up in proximity queries -
+//! and first-tick boids and collision - before the next `update_spatial_hash`.
+//! [`spawn_starting_units`] does the same for every player's units at map start.
+
+use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+use crate::game::GameEntity;
+use crate::game::collections::InclusionIndex;
+use crate::game::fixed_math::{FixedNum, FixedVec2};
+use crate::game::map::MapData;
+use crate::game::pathfinding::{snap_to_walkable, Path, GoalNavCell, GOAL_SNAP_RADIUS};
+use crate::game::simulation::bundles::ColliderBundle;
+use crate::game::simulation::components::{SimVelocity, SimAcceleration, Facing, Collider, OccupiedCell, WaypointQueue};
+use crate::game::spatial_hash::SpatialHash;
+use crate::game::structures::FlowField;
+use super::components::{Unit, UnitType, Health, Team, Selectable, BoidsSteering};
+
+/// Every component a simulated unit needs (no `OccupiedCell`; see [`spawn_unit`])
+#[derive(Bundle)]
+pub struct UnitBundle {
+    pub game_entity: GameEntity,
+    pub unit: Unit,
+    pub selectable: Selectable,
+    pub unit_type: UnitType,
+    pub team: Team,
+    pub health: Health,
+    pub collision: ColliderBundle,
+    pub velocity: SimVelocity,
+    pub acceleration: SimAcceleration,
+    pub facing: Facing,
+    /// For ActivePathSet tracking
+    pub path_index: InclusionIndex,
+    /// All units have a Path (starts inactive)
+    pub path: Path,
+    /// Cached navigation cell (updated on path request)
+    pub goal_nav_cell: GoalNavCell,
+    /// Moves queued behind the current one (starts empty)
+    pub waypoints: WaypointQueue,
+    pub boids_steering: BoidsSteering,
+}
+
+impl UnitBundle {
+    /// Unit at rest at `position`
+    pub fn new(position: FixedVec2, radius: FixedNum, team: u8) -> Self {
+        Self {
+            game_entity: GameEntity,
+            unit: Unit,
+            selectable: Selectable,
+            unit_type: UnitType::default(),
+            team: Team(team),
+            health: Health { current: 100.0, max: 100.0 },
+            collision: ColliderBundle::new(position, Collider { radius, ..Default::default() }),
+            velocity: SimVelocity(FixedVec2::ZERO),
+            acceleration: SimAcceleration(FixedVec2::ZERO),
+            facing: Facing::default(),
+            path_index: InclusionIndex::default(),
+            path: Path::Inactive,
+            goal_nav_cell: GoalNavCell::default(),
+            waypoints: WaypointQueue::default(),
+            boids_steering: BoidsSteering::default(),
+        }
+    }
+}
+
+/// Spawn a fully simulated unit and insert it into the spatial hash right away.
+///
+/// The returned entity is found by spatial queries immediately. If its cell has no
+/// headroom left, it is left without `OccupiedCell` and `update_spatial_hash` places it
+/// on the next tick instead.
+pub fn spawn_unit(
+    commands: &mut Commands,
+    spatial_hash: &mut SpatialHash,
+    position: FixedVec2,
+    radius: FixedNum,
+    team: u8,
+) -> Entity {
+    let entity = commands.spawn(UnitBundle::new(position, radius, team)).id();
+    if let Some(occupied) = insert_into_hash(spatial_hash, entity, position, radius) {
+        commands.entity(entity).insert(occupied);
+    }
+    entity
+}
+
+/// [`spawn_unit`] for exclusive access (scenario setup, tests).
+///
+/// Uses the world's `SpatialHash` if there is one; otherwise the unit is spawned without
+/// `OccupiedCell` and `update_spatial_hash` places it on the first tick.
+pub fn spawn_unit_in_world(world: &mut World, position: FixedVec2, radius: FixedNum, team: u8) -> Entity {
+    let entity = world.spawn(UnitBundle::new(position, radius, team)).id();
+    let occupied = world.get_resource_mut::<SpatialHash>()
+        .and_then(|mut spatial_hash| insert_into_hash(&mut spatial_hash, entity, position, radius));
+    if let Some(occupied) = occupied {
+        world.entity_mut(entity).insert(occupied);
+    }
+    entity
+}
+
+/// Which team a map's player slot plays on, and how many units it starts with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeamAssignment {
+    /// `StartLocation::player_id` of the slot
+    pub player_id: u8,
+    pub team: u8,
+    pub unit_count: usize,
+}
+
+/// Units each player starts with when map loading has no [`TeamAssignments`]
+pub const DEFAULT_STARTING_UNITS: usize = 5;
+
+/// Who plays which start location, read by map loading when it spawns the starting units
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TeamAssignments(pub Vec<TeamAssignment>);
+
+impl TeamAssignment {
+    /// Every start location's player on a team of their own (team = player id), each with
+    /// `unit_count` units
+    pub fn one_team_per_player(map: &MapData, unit_count: usize) -> Vec<Self> {
+        map.start_locations.iter()
+            .map(|start| Self { player_id: start.player_id, team: start.player_id, unit_count })
+            .collect()
+    }
+}
+
+/// Spawn each assigned player's starting units around their map start location.
+///
+/// Units stand in a square grid centred on the start location, one default collider
+/// diameter apart, and each is moved to the nearest walkable spot (see
+/// [`snap_to_walkable`]). Every unit gets a flow field cell of its own: slots that snap to a
+/// cell already taken (several slots inside one large obstacle) move on to the next free
+/// walkable cell, so no two units spawn stacked. Start locations without an assignment stay
+/// empty; units with no free walkable ground within `GOAL_SNAP_RADIUS` are skipped. Returns
+/// the spawned entities in start location order.
+pub fn spawn_starting_units(
+    commands: &mut Commands,
+    spatial_hash: &mut SpatialHash,
+    map: &MapData,
+    flow_field: &FlowField,
+    team_assignments: &[TeamAssignment],
+) -> Vec<Entity> {
+    let radius = Collider::default().radius;
+    let spacing = radius * FixedNum::from_num(3);
+    let mut spawned = Vec::new();
+    let mut taken = HashSet::new();
+    for start in &map.start_locations {
+        let Some(assignment) = team_assignments.iter().find(|assignment| assignment.player_id == start.player_id) else {
+            continue;
+        };
+        let columns = (1..).find(|&columns| columns * columns >= assignment.unit_count).unwrap_or(1);
+        let rows = assignment.unit_count.div_ceil(columns);
+        let centre = |count: usize, i: usize| (FixedNum::from_num(i) - FixedNum::from_num(count - 1) / FixedNum::from_num(2)) * spacing;
+        for i in 0..assignment.unit_count {
+            let slot = start.position + FixedVec2::new(centre(columns, i % columns), centre(rows, i / columns));
+            let Some(position) = snap_to_walkable(slot, flow_field, GOAL_SNAP_RADIUS)
+                .and_then(|position| free_position(flow_field, position, &mut taken))
+            else {
+                warn!("[SPAWN] No walkable ground near start location of player {} - unit skipped", start.player_id);
+                continue;
+            };
+            spawned.push(spawn_unit(commands, spatial_hash, position, radius, assignment.team));
+        }
+    }
+    spawned
+}
+
+/// `position` if its cell is still free, else the centre of the nearest free walkable cell
+/// (4-connected flood over walkable cells, within `GOAL_SNAP_RADIUS`). The cell used is
+/// marked taken.
+fn free_position(flow_field: &FlowField, position: FixedVec2, taken: &mut HashSet<(usize, usize)>) -> Option<FixedVec2> {
+    let start = flow_field.world_to_grid(position)?;
+    if taken.insert(start) {
+        return Some(position);
+    }
+    let max_steps = (FixedNum::from_num(GOAL_SNAP_RADIUS) / flow_field.cell_size).to_num::<usize>();
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some(((x, y), steps)) = queue.pop_front() {
+        if !taken.contains(&(x, y)) {
+            taken.insert((x, y));
+            return Some(flow_field.grid_to_world(x, y));
+        }
+        if steps == max_steps {
+            continue;
+        }
+        let neighbors = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+        for next in neighbors {
+            if flow_field.is_walkable(next.0, next.1) && visited.insert(next) {
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Insert into the hash, or `None` if the target cell had no headroom left
+fn insert_into_hash(spatial_hash: &mut SpatialHash, entity: Entity, position: FixedVec2, radius: FixedNum) -> Option<OccupiedCell> {
+    spatial_hash.insert(entity, position, radius).ok()
+}
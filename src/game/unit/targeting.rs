@@ -0,0 +1,57 @@
+//! Sight-based target acquisition.
+//!
+//! Units with a [`TargetAcquisition`] look for enemies every tick: a radius query on the
+//! spatial hash gives the candidates nearest first, and the first one that is on another
+//! team, alive and not hidden behind obstacles in the cost field becomes the unit's
+//! [`AttackTarget`]. Everything is fixed point, so every client picks the same target.
+
+use bevy::prelude::*;
+use crate::game::simulation::{MapFlowField, SimPosition};
+use crate::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+
+use super::components::{AttackTarget, Health, Team, TargetAcquisition, Unit};
+
+/// Point each [`TargetAcquisition`] unit at the nearest visible enemy, or clear its target.
+///
+/// Runs after physics, on the tick's final positions. Ties in distance go to the lower
+/// entity ID. Units without `Health` count as alive.
+pub fn acquire_targets(
+    mut commands: Commands,
+    spatial_hash: Res<SpatialHash>,
+    mut scratch: ResMut<SpatialHashScratch>,
+    flow_field: Res<MapFlowField>,
+    hunters: Query<(Entity, &SimPosition, &Team, &TargetAcquisition, Option<&AttackTarget>)>,
+    candidates: Query<(&SimPosition, &Team, Option<&Health>), With<Unit>>,
+) {
+    for (entity, pos, team, acquisition, current) in hunters.iter() {
+        let range = acquisition.sight_range;
+        spatial_hash.query_radius_sorted(pos.0, range, Some(entity), &mut scratch, |other| {
+            candidates.get(other).ok().map(|(other_pos, _, _)| other_pos.0)
+        });
+        let range_sq = range * range;
+        let target = scratch.query_results.iter().zip(&scratch.query_distances_sq).zip(&scratch.query_positions)
+            .take_while(|&((_, &dist_sq), _)| dist_sq <= range_sq)
+            .find(|&((&other, _), &other_pos)| {
+                let Ok((_, other_team, health)) = candidates.get(other) else { return false };
+                other_team != team
+                    && health.is_none_or(|health| health.current > 0.0)
+                    && if spatial_hash.wraps() {
+                        // Sight runs the short way, across the seam if that's where the enemy is
+                        flow_field.0.has_line_of_sight_wrapped(pos.0, other_pos)
+                    } else {
+                        flow_field.0.has_line_of_sight(pos.0, other_pos)
+                    }
+            })
+            .map(|((&other, _), _)| other);
+
+        match target {
+            Some(target) if current != Some(&AttackTarget(target)) => {
+                commands.entity(entity).insert(AttackTarget(target));
+            }
+            None if current.is_some() => {
+                commands.entity(entity).remove::<AttackTarget>();
+            }
+            _ => {}
+        }
+    }
+}
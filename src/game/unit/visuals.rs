@@ -1,22 +1,32 @@
 use bevy::prelude::*;
 use crate::game::camera::RtsCamera;
 use crate::game::config::{GameConfig, GameConfigHandle};
-use crate::game::simulation::{SimPosition, SimPositionPrev, CollisionState};
+use crate::game::simulation::{SimPosition, SimPositionPrev, Facing, CollisionState, Collider};
 
-use super::components::{Unit, Selected, SelectionCircle, HealthBar, Health};
-use super::resources::{UnitMesh, UnitMaterials, HealthBarSettings};
+use super::components::{Unit, Selected, HealthBar, Health};
+use super::resources::{UnitMesh, UnitMaterials, HealthBarSettings, SelectionRing, SelectionRings};
+
+/// Selection ring radii for a unit of `SELECTION_RING_BASE_UNIT_RADIUS`
+const SELECTION_RING_INNER_RADIUS: f32 = 0.6;
+const SELECTION_RING_OUTER_RADIUS: f32 = 0.7;
+/// Collider radius the base ring radii are sized for
+const SELECTION_RING_BASE_UNIT_RADIUS: f32 = 0.5;
+/// Height above the ground the rings are drawn at
+const SELECTION_RING_HEIGHT: f32 = 0.05;
+const SELECTION_RING_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
 
 /// Spawns visual representations for newly created units
 /// 
 /// Note: Only runs on Added<Unit> - NOT a hot path (only processes new spawns)
 pub(super) fn spawn_unit_visuals(
     mut commands: Commands,
-    query: Query<(Entity, &SimPosition), Added<Unit>>,
+    query: Query<(Entity, &SimPosition, Option<&Health>, Has<Selected>), Added<Unit>>,
     unit_mesh: Res<UnitMesh>,
     unit_materials: Res<UnitMaterials>,
     settings: Res<HealthBarSettings>,
 ) {
-    for (entity, pos) in query.iter() {
+    for (entity, pos, health, selected) in query.iter() {
+        let show_health_bar = health.is_some_and(|health| settings.mode.is_visible(health, selected));
         let p = pos.0.to_vec2();
         commands.entity(entity).insert((
             // NOLINT: Handle::clone() is cheap (Arc-based ref count)
@@ -25,16 +35,6 @@ pub(super) fn spawn_unit_visuals(
             MeshMaterial3d(unit_materials.normal.clone()),
             Transform::from_xyz(p.x, 1.0, p.y),
         )).with_children(|parent| {
-            parent.spawn((
-                // NOLINT: Handle::clone() is cheap (Arc-based ref count)
-                Mesh3d(unit_mesh.circle.clone()),
-                // NOLINT: Handle::clone() is cheap (Arc-based ref count)
-                MeshMaterial3d(unit_materials.selection_circle.clone()),
-                Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
-                    .with_translation(Vec3::new(0.0, -0.95, 0.0)),
-                Visibility::Hidden,
-                SelectionCircle,
-            ));
             // Health Bar
             parent.spawn((
                 // NOLINT: Handle::clone() is cheap (Arc-based ref count)
@@ -42,7 +42,7 @@ pub(super) fn spawn_unit_visuals(
                 // NOLINT: Handle::clone() is cheap (Arc-based ref count)
                 MeshMaterial3d(unit_materials.health_bar.clone()),
                 Transform::from_xyz(0.0, 1.5, 0.0),
-                if settings.show { Visibility::Visible } else { Visibility::Hidden },
+                if show_health_bar { Visibility::Visible } else { Visibility::Hidden },
                 HealthBar,
             ));
         });
@@ -50,17 +50,22 @@ pub(super) fn spawn_unit_visuals(
 }
 
 /// Synchronizes visual transforms with simulation positions (with interpolation)
+/// and turns entities with a [`Facing`] to face along it
 pub(super) fn sync_visuals(
-    mut query: Query<(&mut Transform, &SimPosition, &SimPositionPrev)>,
+    mut query: Query<(&mut Transform, &SimPosition, &SimPositionPrev, Option<&Facing>)>,
     fixed_time: Res<Time<Fixed>>,
 ) {
     let alpha = fixed_time.overstep_fraction();
-    for (mut transform, pos, prev_pos) in query.iter_mut() {
+    for (mut transform, pos, prev_pos, facing) in query.iter_mut() {
         let prev = prev_pos.0.to_vec2();
         let curr = pos.0.to_vec2();
         let interpolated = prev.lerp(curr, alpha);
         transform.translation.x = interpolated.x;
         transform.translation.z = interpolated.y;
+        if let Some(facing) = facing {
+            // Sim y is world z, so counterclockwise in the sim is clockwise about world +y
+            transform.rotation = Quat::from_rotation_y(-facing.0.to_num::<f32>());
+        }
     }
 }
 
@@ -82,35 +87,35 @@ pub(super) fn update_selection_visuals(
     }
 }
 
-/// Shows/hides selection circles based on Selected component
-pub(super) fn update_selection_circle_visibility(
-    q_added: Query<&Children, (With<Unit>, Added<Selected>)>,
-    q_children_lookup: Query<&Children>,
-    q_selected: Query<Entity, With<Selected>>,
-    mut q_vis: Query<&mut Visibility, With<SelectionCircle>>,
-    mut removed_selected: RemovedComponents<Selected>,
+/// Rebuilds the selection ring batch from the currently selected units.
+///
+/// Rings are plain draw data rather than an entity per unit, so selecting thousands
+/// of units costs one buffer refill here and one gizmo batch in `draw_selection_rings`.
+/// Runs after `sync_visuals` so rings sit under the interpolated unit positions.
+pub fn update_selection_rings(
+    q_selected: Query<(&Transform, Option<&Collider>), With<Selected>>,
+    mut selection_rings: ResMut<SelectionRings>,
 ) {
-    // Handle Added Selected
-    for children in q_added.iter() {
-        for child in children.iter() {
-            if let Ok(mut vis) = q_vis.get_mut(child) {
-                *vis = Visibility::Visible;
-            }
+    selection_rings.rings.clear();
+    selection_rings.rings.extend(q_selected.iter().map(|(transform, collider)| {
+        let radius = collider.map_or(SELECTION_RING_BASE_UNIT_RADIUS, |collider| collider.radius.to_num::<f32>());
+        SelectionRing {
+            center: Vec3::new(transform.translation.x, SELECTION_RING_HEIGHT, transform.translation.z),
+            scale: radius / SELECTION_RING_BASE_UNIT_RADIUS,
         }
-    }
+    }));
+}
 
-    // Handle Removed Selected
-    for entity in removed_selected.read() {
-        if q_selected.contains(entity) {
-            continue;
-        }
-        if let Ok(children) = q_children_lookup.get(entity) {
-            for child in children.iter() {
-                if let Ok(mut vis) = q_vis.get_mut(child) {
-                    *vis = Visibility::Hidden;
-                }
-            }
-        }
+/// Draws every selection ring in one gizmo batch (inner and outer edge of each ring)
+pub(super) fn draw_selection_rings(
+    mut gizmos: Gizmos,
+    selection_rings: Res<SelectionRings>,
+) {
+    let rotation = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    for ring in &selection_rings.rings {
+        let isometry = Isometry3d::new(ring.center, rotation);
+        gizmos.circle(isometry, SELECTION_RING_INNER_RADIUS * ring.scale, SELECTION_RING_COLOR);
+        gizmos.circle(isometry, SELECTION_RING_OUTER_RADIUS * ring.scale, SELECTION_RING_COLOR);
     }
 }
 
@@ -151,34 +156,39 @@ pub(super) fn update_unit_lod(
     }
 }
 
-/// Toggles health bar visibility when user presses configured key
+/// Cycles the health bar mode when user presses configured key
+/// (visibility is applied by `update_health_bars`)
 pub(super) fn toggle_health_bars(
     keys: Res<ButtonInput<KeyCode>>,
     config_handle: Res<GameConfigHandle>,
     game_configs: Res<Assets<GameConfig>>,
     mut settings: ResMut<HealthBarSettings>,
-    mut q_bars: Query<&mut Visibility, With<HealthBar>>,
 ) {
     let Some(config) = game_configs.get(&config_handle.0) else { return };
 
     if keys.just_pressed(config.key_toggle_health_bars) {
-        settings.show = !settings.show;
-        let vis = if settings.show { Visibility::Visible } else { Visibility::Hidden };
-        for mut visibility in q_bars.iter_mut() {
-            *visibility = vis;
-        }
+        settings.mode = settings.mode.next();
+        info!("Health bars: {:?}", settings.mode);
     }
 }
 
-/// Updates health bar visuals based on current health
+/// Updates health bar fill and visibility (per `HealthBarSettings::mode`)
+///
+/// Only touches units whose health, selection or visuals changed, or all units when
+/// the mode changes.
 pub(super) fn update_health_bars(
-    q_units: Query<(&Children, &Health), Changed<Health>>,
-    mut q_bars: Query<&mut Transform, With<HealthBar>>,
+    settings: Res<HealthBarSettings>,
+    q_units: Query<(Entity, &Children, &Health, Has<Selected>), With<Unit>>,
+    q_changed: Query<Entity, (With<Unit>, Or<(Changed<Health>, Added<Selected>, Changed<Children>)>)>,
+    mut removed_selected: RemovedComponents<Selected>,
+    mut q_bars: Query<(&mut Transform, &mut Visibility), With<HealthBar>>,
 ) {
-    for (children, health) in q_units.iter() {
+    let mut refresh = |entity: Entity| {
+        let Ok((_, children, health, selected)) = q_units.get(entity) else { return };
         let pct = (health.current / health.max).clamp(0.0, 1.0);
+        let visibility = if settings.mode.is_visible(health, selected) { Visibility::Visible } else { Visibility::Hidden };
         for child in children.iter() {
-            if let Ok(mut transform) = q_bars.get_mut(child) {
+            if let Ok((mut transform, mut bar_visibility)) = q_bars.get_mut(child) {
                 transform.scale.x = pct;
                 // Center is 0.0. Width is 1.0.
                 // If scale is 1.0, left is -0.5, right is 0.5.
@@ -186,7 +196,120 @@ pub(super) fn update_health_bars(
                 // We want left to stay at -0.5.
                 // New center = -0.5 + (width * scale / 2.0) = -0.5 + (1.0 * pct / 2.0)
                 transform.translation.x = -0.5 + (pct * 0.5);
+                bar_visibility.set_if_neq(visibility);
             }
         }
+    };
+
+    if settings.is_changed() {
+        removed_selected.clear();
+        for (entity, ..) in q_units.iter() {
+            refresh(entity);
+        }
+        return;
+    }
+
+    for entity in q_changed.iter().chain(removed_selected.read()) {
+        refresh(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::fixed_math::FixedNum;
+    use crate::game::unit::resources::HealthBarMode;
+
+    fn spawn_unit_with_bar(world: &mut World, current: f32, selected: bool) -> (Entity, Entity) {
+        let bar = world.spawn((Transform::default(), Visibility::Hidden, HealthBar)).id();
+        let mut unit = world.spawn((Unit, Health { current, max: 100.0 }));
+        if selected {
+            unit.insert(Selected);
+        }
+        unit.add_child(bar);
+        (unit.id(), bar)
+    }
+
+    fn setup_health_bar_app(mode: HealthBarMode) -> App {
+        let mut app = App::new();
+        app.insert_resource(HealthBarSettings { mode });
+        app.add_systems(Update, update_health_bars);
+        app
+    }
+
+    #[test]
+    fn test_when_damaged_shows_only_damaged_units() {
+        let mut app = setup_health_bar_app(HealthBarMode::WhenDamaged);
+        let (_, full_bar) = spawn_unit_with_bar(app.world_mut(), 100.0, true);
+        let (damaged, damaged_bar) = spawn_unit_with_bar(app.world_mut(), 40.0, false);
+
+        app.update();
+
+        assert_eq!(app.world().get::<Visibility>(full_bar), Some(&Visibility::Hidden));
+        assert_eq!(app.world().get::<Visibility>(damaged_bar), Some(&Visibility::Visible));
+        assert_eq!(app.world().get::<Transform>(damaged_bar).unwrap().scale.x, 0.4);
+
+        // Healing back to full hides the bar again
+        app.world_mut().get_mut::<Health>(damaged).unwrap().current = 100.0;
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(damaged_bar), Some(&Visibility::Hidden));
+    }
+
+    #[test]
+    fn test_when_selected_follows_selection() {
+        let mut app = setup_health_bar_app(HealthBarMode::WhenSelected);
+        let (unit, bar) = spawn_unit_with_bar(app.world_mut(), 40.0, false);
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(bar), Some(&Visibility::Hidden));
+
+        app.world_mut().entity_mut(unit).insert(Selected);
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(bar), Some(&Visibility::Visible));
+
+        app.world_mut().entity_mut(unit).remove::<Selected>();
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(bar), Some(&Visibility::Hidden));
+    }
+
+    #[test]
+    fn test_mode_change_refreshes_all_bars() {
+        let mut app = setup_health_bar_app(HealthBarMode::Never);
+        let (_, bar) = spawn_unit_with_bar(app.world_mut(), 100.0, false);
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(bar), Some(&Visibility::Hidden));
+
+        app.world_mut().resource_mut::<HealthBarSettings>().mode = HealthBarMode::Always;
+        app.update();
+        assert_eq!(app.world().get::<Visibility>(bar), Some(&Visibility::Visible));
+    }
+
+    #[test]
+    fn test_selection_rings_batch_one_ring_per_selected_unit() {
+        let mut app = App::new();
+        app.init_resource::<SelectionRings>();
+        app.add_systems(Update, update_selection_rings);
+        let small = app.world_mut().spawn((
+            Unit, Selected, Transform::from_xyz(3.0, 1.0, -2.0), Collider::ground_unit(FixedNum::from_num(0.5)),
+        )).id();
+        app.world_mut().spawn((
+            Unit, Selected, Transform::from_xyz(-8.0, 1.0, 6.0), Collider::ground_unit(FixedNum::from_num(1.5)),
+        ));
+        app.world_mut().spawn((Unit, Transform::from_xyz(0.0, 1.0, 0.0), Collider::ground_unit(FixedNum::from_num(0.5))));
+
+        app.update();
+        let mut rings = app.world().resource::<SelectionRings>().rings.clone();
+        rings.sort_by(|a, b| a.center.x.total_cmp(&b.center.x));
+        assert_eq!(rings, vec![
+            SelectionRing { center: Vec3::new(-8.0, SELECTION_RING_HEIGHT, 6.0), scale: 3.0 },
+            SelectionRing { center: Vec3::new(3.0, SELECTION_RING_HEIGHT, -2.0), scale: 1.0 },
+        ]);
+
+        // Rings follow the unit, and deselecting drops its ring
+        app.world_mut().get_mut::<Transform>(small).unwrap().translation.x = 4.0;
+        app.update();
+        assert!(app.world().resource::<SelectionRings>().rings.iter().any(|ring| ring.center.x == 4.0));
+        app.world_mut().entity_mut(small).remove::<Selected>();
+        app.update();
+        assert_eq!(app.world().resource::<SelectionRings>().rings.len(), 1);
     }
 }
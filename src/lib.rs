@@ -1,4 +1,5 @@
 ﻿pub mod game;
+pub mod prelude;
 
 // ============================================================================
 // Profiling Macros
@@ -3,12 +3,15 @@ use bevy::prelude::*;
 use bevy::window::WindowResolution;
 
 use peregrine::game::GamePlugin;
+use peregrine::game::launch::{load_startup_map, start_with_map, LaunchOptions};
+use peregrine::game::logging::{cleanup_old_logs, flush_logs, flush_logs_on_panic, keep_log_guard, LogRetention, SimTickFormat};
 
 use bevy::log::LogPlugin;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use std::fs;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 fn setup_file_logging() -> String {
     // Create logs directory if it doesn't exist
@@ -17,8 +20,8 @@ fn setup_file_logging() -> String {
         fs::create_dir_all(&log_dir).expect("Failed to create logs directory");
     }
 
-    // Clean up old log files, keeping only the last 25
-    cleanup_old_logs(&log_dir, 25);
+    // Clean up old log files (last 25 by default, see PEREGRINE_LOG_KEEP / PEREGRINE_LOG_KEEP_BYTES)
+    cleanup_old_logs(&log_dir, LogRetention::from_env());
 
     // Generate timestamped filename
     let now = chrono::Local::now();
@@ -33,15 +36,21 @@ fn setup_file_logging() -> String {
         &log_filename
     );
 
-    // Create a formatting layer for the file
+    // Buffer lines for a worker thread; flushed on exit or panic (see logging::flush_logs)
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+    keep_log_guard(guard);
+    flush_logs_on_panic();
+
+    // Create a formatting layer for the file (lines logged during a sim tick get `tick=N`)
     let file_layer = fmt::layer()
-        .with_writer(file_appender)
+        .event_format(SimTickFormat(fmt::format()))
+        .with_writer(file_writer)
         .with_ansi(false); // No ANSI colors in file
 
     // Create a formatting layer for stdout (minimal)
     let stdout_layer = fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_target(false);
+        .event_format(SimTickFormat(fmt::format().with_target(false)))
+        .with_writer(std::io::stdout);
 
     // Set up the subscriber with both layers
     let filter = EnvFilter::try_from_default_env()
@@ -58,32 +67,23 @@ fn setup_file_logging() -> String {
     log_path_str
 }
 
-fn cleanup_old_logs(log_dir: &PathBuf, keep_count: usize) {
-    if let Ok(entries) = fs::read_dir(log_dir) {
-        let mut log_files: Vec<_> = entries
-            .filter_map(|e| e.ok())
-            .filter(|e| {
-                e.path()
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .map(|s| s.starts_with("peregrine") && s.ends_with(".log"))
-                    .unwrap_or(false)
-            })
-            .collect();
-
-        // Sort by modified time (oldest first)
-        log_files.sort_by_key(|e| e.metadata().ok().and_then(|m| m.modified().ok()));
-
-        // Delete oldest files if we exceed keep_count
-        if log_files.len() > keep_count {
-            for file in log_files.iter().take(log_files.len() - keep_count) {
-                let _ = fs::remove_file(file.path());
-            }
+fn main() -> ExitCode {
+    // Parse arguments and read the startup map (if any) before opening a window
+    let options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
         }
-    }
-}
+    };
+    let startup_map = match options.map.as_deref().map(load_startup_map).transpose() {
+        Ok(map) => map,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
 
-fn main() {
     // Set up file logging and get the log file path
     let log_file = setup_file_logging();
     
@@ -93,7 +93,8 @@ fn main() {
     println!("║  Log file: {:<42} ║", log_file);
     println!("╚══════════════════════════════════════════════════════════╝");
 
-    App::new()
+    let mut app = App::new();
+    app
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Peregrine RTS".into(),
@@ -103,7 +104,15 @@ fn main() {
             }),
             ..default()
         }).build().disable::<LogPlugin>()) // Disable Bevy's default logging since we set up our own
-        .add_plugins(GamePlugin)
-        .run();
+        .add_plugins(GamePlugin);
+
+    if let Some(map_data) = startup_map {
+        start_with_map(&mut app, map_data);
+    }
+
+    app.run();
+    // In case the loop ended without an AppExit message reaching flush_logs_on_exit
+    flush_logs();
+    ExitCode::SUCCESS
 }
 
@@ -0,0 +1,33 @@
+//! Commonly used types in one import.
+//!
+//! ```
+//! use peregrine::prelude::*;
+//!
+//! let mut world = World::new();
+//! let unit = spawn_unit_in_world(&mut world, FixedVec2::from_f32(3.0, 4.0), FixedNum::from_num(0.5), 0);
+//!
+//! let mut units = world.query::<(Entity, &Unit, &SimPosition, &SimVelocity, &Collider, &Health)>();
+//! let (entity, _, position, velocity, collider, health) = units.single(&world).unwrap();
+//! assert_eq!(entity, unit);
+//! assert_eq!(position.0, FixedVec2::from_f32(3.0, 4.0));
+//! assert_eq!(velocity.0, FixedVec2::ZERO);
+//! assert_eq!(collider.radius, FixedNum::from_num(0.5));
+//! assert_eq!(health.current, health.max);
+//! ```
+//!
+//! Only the handful of Bevy types needed to drive a world are included; add
+//! `bevy::prelude::*` for the rest.
+
+pub use bevy::prelude::{App, Entity, World};
+
+pub use crate::game::{GamePlugin, GameState};
+pub use crate::game::config::{GameConfigPlugin, InitialConfig};
+pub use crate::game::fixed_math::{FixedNum, FixedVec2};
+pub use crate::game::headless::{HeadlessSim, HeadlessSimPlugin};
+pub use crate::game::map::{MapEdges, MapSize};
+pub use crate::game::pathfinding::{Path, PathRequest, PathfindingPlugin};
+pub use crate::game::simulation::{
+    layers, Collider, MapDimensions, SimAcceleration, SimConfig, SimPosition, SimTick, SimVelocity, SimulationPlugin,
+};
+pub use crate::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+pub use crate::game::unit::{spawn_unit, spawn_unit_in_world, Health, Team, Unit, UnitBundle, UnitPlugin};
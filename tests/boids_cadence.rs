@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{SimConfig, SimTick, SimVelocity};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::{apply_boids_steering, spawn_unit_in_world, BoidsSteering};
+
+/// Separation-only boids on a pair of close units, recomputing every `interval` ticks
+fn setup_pair_app(interval: u32) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.insert_resource(SpatialHashScratch::new(64));
+    app.insert_resource(SimConfig {
+        separation_weight: FixedNum::ONE,
+        alignment_weight: FixedNum::ZERO,
+        cohesion_weight: FixedNum::ZERO,
+        separation_radius: FixedNum::from_num(2.0),
+        boids_update_interval: interval,
+        ..Default::default()
+    });
+    app.insert_resource(SpatialHash::new(
+        FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.0,
+    ));
+    app.add_systems(FixedUpdate, apply_boids_steering);
+
+    let radius = FixedNum::from_num(0.5);
+    let probe = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.0, 0.0), radius, 0);
+    spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(1.0, 0.0), radius, 0);
+    (app, probe)
+}
+
+/// Advance the tick counter and run boids once
+fn step(app: &mut App) -> u64 {
+    app.world_mut().resource_mut::<SimTick>().0 += 1;
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world().resource::<SimTick>().0
+}
+
+#[test]
+fn test_unit_recomputes_exactly_every_interval_and_reuses_force_between() {
+    let interval = 4;
+    let (mut app, probe) = setup_pair_app(interval);
+    let delta = app.world().resource::<SimConfig>().fixed_delta();
+    let stagger = u64::from(probe.index());
+
+    let mut recomputed_on = Vec::new();
+    let mut last = *app.world().get::<BoidsSteering>(probe).unwrap();
+    for _ in 0..12 {
+        let velocity_before = app.world().get::<SimVelocity>(probe).unwrap().0;
+        let tick = step(&mut app);
+        let steering = *app.world().get::<BoidsSteering>(probe).unwrap();
+        let velocity = app.world().get::<SimVelocity>(probe).unwrap().0;
+
+        if (tick + stagger).is_multiple_of(u64::from(interval)) {
+            assert_eq!(steering.computed_tick, tick);
+            recomputed_on.push(tick);
+        } else {
+            assert_eq!(steering, last, "Tick {} is off-cadence and should keep the cached force", tick);
+        }
+        // Recomputed or reused, this tick's force is the cached one
+        if steering.computed_tick > 0 {
+            assert_eq!(velocity, velocity_before + steering.force * delta, "tick {}", tick);
+        }
+        last = steering;
+    }
+
+    assert_eq!(recomputed_on.len(), 3, "{:?}", recomputed_on);
+    assert!(recomputed_on.windows(2).all(|pair| pair[1] - pair[0] == u64::from(interval)), "{:?}", recomputed_on);
+    assert!(last.force.x < FixedNum::ZERO, "Separation pushes the probe away from its neighbor");
+}
+
+#[test]
+fn test_interval_of_one_recomputes_every_tick() {
+    let (mut app, probe) = setup_pair_app(1);
+    for _ in 0..5 {
+        let tick = step(&mut app);
+        assert_eq!(app.world().get::<BoidsSteering>(probe).unwrap().computed_tick, tick);
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{SimConfig, SimTick, SimVelocity};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::{apply_boids_steering, spawn_unit_in_world, BoidsStats};
+
+/// Separation-only boids on a dense 9x9 block, probing a unit on its left edge
+fn setup_crowd_app(max_neighbors: usize) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.init_resource::<BoidsStats>();
+    app.insert_resource(SpatialHashScratch::new(256));
+    app.insert_resource(SimConfig {
+        separation_weight: FixedNum::ONE,
+        alignment_weight: FixedNum::ZERO,
+        cohesion_weight: FixedNum::ZERO,
+        neighbor_radius: FixedNum::from_num(5.0),
+        separation_radius: FixedNum::from_num(5.0),
+        boids_max_neighbors: max_neighbors,
+        ..Default::default()
+    });
+    app.insert_resource(SpatialHash::new(
+        FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 1000, 1.0,
+    ));
+    app.add_systems(FixedUpdate, apply_boids_steering);
+
+    let radius = FixedNum::from_num(0.5);
+    let mut probe = Entity::PLACEHOLDER;
+    for row in -4i32..=4 {
+        for col in -4i32..=4 {
+            let entity = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(col as f32, row as f32), radius, 0);
+            if (col, row) == (-4, -1) {
+                probe = entity;
+            }
+        }
+    }
+    (app, probe)
+}
+
+/// Velocity the probe picks up in one tick (from rest, so this is the steering direction)
+fn run_tick(max_neighbors: usize) -> (FixedVec2, usize) {
+    let (mut app, probe) = setup_crowd_app(max_neighbors);
+    app.world_mut().run_schedule(FixedUpdate);
+    let velocity = app.world().get::<SimVelocity>(probe).unwrap().0;
+    (velocity, app.world().resource::<BoidsStats>().neighbors_processed)
+}
+
+fn cos_angle(a: FixedVec2, b: FixedVec2) -> f32 {
+    let (a, b) = (a.to_vec2(), b.to_vec2());
+    a.dot(b) / (a.length() * b.length())
+}
+
+#[test]
+fn test_neighbor_cap_bounds_work_and_keeps_separation_direction() {
+    let (uncapped, uncapped_processed) = run_tick(0);
+    let (capped, capped_processed) = run_tick(12);
+
+    assert!(capped_processed <= 81 * 12, "At most 12 neighbors per unit, got {}", capped_processed);
+    assert!(capped_processed < uncapped_processed / 2, "{} vs {}", capped_processed, uncapped_processed);
+
+    // On the edge: pushed out of the block either way
+    assert!(uncapped.x < FixedNum::ZERO && capped.x < FixedNum::ZERO, "{:?} / {:?}", uncapped, capped);
+    let cos = cos_angle(capped, uncapped);
+    assert!(cos > 0.9, "Capped separation should point roughly the same way: cos = {}", cos);
+}
+
+#[test]
+fn test_larger_cap_converges_to_uncapped() {
+    let (uncapped, _) = run_tick(0);
+    let (wide, _) = run_tick(200);
+    assert_eq!(wide, uncapped, "A cap above the neighbor count changes nothing");
+
+    for cap in [8, 24, 48] {
+        let cos = cos_angle(run_tick(cap).0, uncapped);
+        assert!(cos > 0.95, "cap {}: cos = {}", cap, cos);
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy::state::app::StatesPlugin;
 use peregrine::game::fixed_math::{FixedVec2, FixedNum};
-use peregrine::game::simulation::{SimulationPlugin, SimConfig, SimPosition, SimVelocity};
+use peregrine::game::simulation::{SimulationPlugin, SimConfig, SimPosition, SimVelocity, Collider, rebuild_spatial_hash_from_world};
 use peregrine::game::unit::Unit;
 use peregrine::game::config::GameConfigPlugin;
 use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
@@ -46,24 +46,13 @@ fn test_10k_units_boids_tick_under_16ms() {
                 Unit,
                 SimPosition(FixedVec2::new(FixedNum::from_num(pos_x), FixedNum::from_num(pos_y))),
                 SimVelocity(FixedVec2::ZERO),
+                Collider::ground_unit(FixedNum::from_num(0.5)),  // Default unit radius
             ));
         }
     }
     
     // Update spatial hash with all units
-    let positions: Vec<_> = {
-        let mut query = app.world_mut().query_filtered::<(Entity, &SimPosition), With<Unit>>();
-        query.iter(app.world()).map(|(e, p)| (e, p.0)).collect()
-    };
-    
-    {
-        let mut hash = app.world_mut().resource_mut::<SpatialHash>();
-        hash.clear();
-        
-        for (entity, pos) in positions {
-            hash.insert(entity, pos, FixedNum::from_num(0.5));  // Default unit radius
-        }
-    }
+    rebuild_spatial_hash_from_world(app.world_mut());
     
     // Warm up (first tick might be slower)
     app.world_mut().run_schedule(FixedUpdate);
@@ -106,34 +95,25 @@ fn test_spatial_query_correctness() {
         Unit,
         SimPosition(FixedVec2::new(FixedNum::from_num(0.0), FixedNum::from_num(0.0))),
         SimVelocity(FixedVec2::ZERO),
+        Collider::ground_unit(FixedNum::from_num(0.5)),  // Default unit radius
     )).id();
     
     let entity_near = app.world_mut().spawn((
         Unit,
         SimPosition(FixedVec2::new(FixedNum::from_num(5.0), FixedNum::from_num(0.0))),
         SimVelocity(FixedVec2::ZERO),
+        Collider::ground_unit(FixedNum::from_num(0.5)),  // Default unit radius
     )).id();
     
     let _entity_far = app.world_mut().spawn((
         Unit,
         SimPosition(FixedVec2::new(FixedNum::from_num(50.0), FixedNum::from_num(0.0))),
         SimVelocity(FixedVec2::ZERO),
+        Collider::ground_unit(FixedNum::from_num(0.5)),  // Default unit radius
     )).id();
     
     // Update spatial hash
-    let positions: Vec<_> = {
-        let mut query = app.world_mut().query_filtered::<(Entity, &SimPosition), With<Unit>>();
-        query.iter(app.world()).map(|(e, p)| (e, p.0)).collect()
-    };
-    
-    {
-        let mut hash = app.world_mut().resource_mut::<SpatialHash>();
-        hash.clear();
-        
-        for (entity, pos) in positions {
-            hash.insert(entity, pos, FixedNum::from_num(0.5));  // Default unit radius
-        }
-    }
+    rebuild_spatial_hash_from_world(app.world_mut());
     
     // Query with radius 10 from center
     let hash = app.world().resource::<SpatialHash>();
@@ -201,24 +181,13 @@ fn test_boids_with_spatial_hash_vs_brute_force() {
                 Unit,
                 SimPosition(FixedVec2::new(FixedNum::from_num(pos_x), FixedNum::from_num(pos_y))),
                 SimVelocity(FixedVec2::ZERO),
+                Collider::ground_unit(FixedNum::from_num(0.5)),  // Default unit radius
             ));
         }
     }
     
     // Update spatial hash
-    let positions: Vec<_> = {
-        let mut query = app.world_mut().query_filtered::<(Entity, &SimPosition), With<Unit>>();
-        query.iter(app.world()).map(|(e, p)| (e, p.0)).collect()
-    };
-    
-    {
-        let mut hash = app.world_mut().resource_mut::<SpatialHash>();
-        hash.clear();
-        
-        for (entity, pos) in positions {
-            hash.insert(entity, pos, FixedNum::from_num(0.5));  // Default unit radius
-        }
-    }
+    rebuild_spatial_hash_from_world(app.world_mut());
     
     // Measure spatial hash performance
     let start = Instant::now();
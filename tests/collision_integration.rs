@@ -119,8 +119,7 @@ fn test_collision_unit_wall() {
         let wall_grid_x = 30;
         let wall_grid_y = 25; // y=0 world
         
-        let idx = map.0.get_index(wall_grid_x, wall_grid_y);
-        map.0.cost_field[idx] = 255;
+        map.0.set_obstacle(wall_grid_x, wall_grid_y);
         
         // Verify world pos of wall
         let wall_pos = map.0.grid_to_world(wall_grid_x, wall_grid_y);
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{SimConfig, SimTick, SimPosition, SimVelocity, SimAcceleration, CollisionState, SpatialHashOverflow};
+use peregrine::game::simulation::collision::{detect_collisions, resolve_collisions, CollisionEvent};
+use peregrine::game::simulation::physics::apply_velocity;
+use peregrine::game::simulation::systems::{update_spatial_hash, PendingVecIdxUpdates};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::spawn_unit_in_world;
+
+/// Integration, spatial hash and detection only (no friction or push-back)
+fn setup_detection_app(margin: FixedNum) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let mut sim_config = SimConfig::default();
+    sim_config.collision_detection_margin = margin;
+    app.insert_resource(sim_config);
+    app.init_resource::<SimTick>();
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.add_message::<CollisionEvent>();
+    app.add_systems(FixedUpdate, (apply_velocity, update_spatial_hash, detect_collisions).chain());
+    app
+}
+
+/// First tick two units rushing at each other at `speed` register a collision
+fn first_collision_tick(margin: FixedNum, speed: f32) -> u32 {
+    let mut app = setup_detection_app(margin);
+    let left = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-2.0, 0.0), FixedNum::from_num(0.5), 0);
+    let right = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(2.0, 0.0), FixedNum::from_num(0.5), 1);
+    app.world_mut().get_mut::<SimVelocity>(left).unwrap().0 = FixedVec2::from_f32(speed, 0.0);
+    app.world_mut().get_mut::<SimVelocity>(right).unwrap().0 = FixedVec2::from_f32(-speed, 0.0);
+
+    for tick in 1..=30 {
+        app.world_mut().run_schedule(FixedUpdate);
+        if app.world().get::<CollisionState>(left).unwrap().is_colliding {
+            assert!(app.world().get::<CollisionState>(right).unwrap().is_colliding);
+            return tick;
+        }
+    }
+    panic!("Units never collided");
+}
+
+#[test]
+fn test_detection_margin_catches_fast_approach_a_tick_early() {
+    // Closing at 12 units/s is 0.4 per tick at 30 TPS: the 3.0 gap turns into overlap on
+    // tick 8, but is already within one tick's travel on tick 7
+    let speed = 6.0;
+    let margin = SimConfig::default().min_collision_margin(FixedNum::from_num(2.0 * speed));
+    let exact = first_collision_tick(FixedNum::ZERO, speed);
+    assert_eq!(exact, 8);
+    assert_eq!(first_collision_tick(margin, speed), exact - 1);
+}
+
+#[test]
+fn test_margin_pairs_without_pushing_until_touching() {
+    let mut app = setup_detection_app(FixedNum::from_num(0.25));
+    app.add_systems(FixedUpdate, resolve_collisions.after(detect_collisions));
+    let left = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.0, 0.0), FixedNum::from_num(0.5), 0);
+    let right = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(1.1, 0.0), FixedNum::from_num(0.5), 1);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    let events: Vec<CollisionEvent> = app.world_mut().resource_mut::<Messages<CollisionEvent>>().drain().collect();
+    assert_eq!(events.len(), 1, "A gap inside the margin should count as contact");
+    assert_eq!(events[0].overlap, FixedNum::ZERO, "0.1 apart isn't overlapping");
+    for unit in [left, right] {
+        assert_eq!(app.world().get::<SimAcceleration>(unit).unwrap().0, FixedVec2::ZERO, "Nothing to push apart yet");
+    }
+
+    // Once they touch, overlap is against the radii alone
+    app.world_mut().get_mut::<SimPosition>(right).unwrap().0 = FixedVec2::from_f32(0.9, 0.0);
+    app.world_mut().run_schedule(FixedUpdate);
+    let events: Vec<CollisionEvent> = app.world_mut().resource_mut::<Messages<CollisionEvent>>().drain().collect();
+    assert_eq!(events.len(), 1);
+    assert!((events[0].overlap - FixedNum::from_num(0.1)).abs() < FixedNum::from_num(0.001), "{}", events[0].overlap);
+    assert!(app.world().get::<SimAcceleration>(left).unwrap().0.x < FixedNum::ZERO);
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::map::{MapEdges, MapSize};
+use peregrine::game::simulation::{SimConfig, SimTick, SimPosition, CollisionNeighborCache, SpatialHashOverflow};
+use peregrine::game::simulation::collision::{detect_collisions, CollisionEvent};
+use peregrine::game::simulation::systems::{update_spatial_hash, PendingVecIdxUpdates};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::spawn_unit_in_world;
+
+/// Spatial hash and detection only, with the neighbor cache on
+fn setup_cached_detection_app(slots: usize, refresh_interval: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let sim_config = SimConfig::builder()
+        .collision_neighbor_slots(slots)
+        .collision_neighbor_refresh_interval(refresh_interval)
+        .build()
+        .unwrap();
+    app.insert_resource(sim_config);
+    app.init_resource::<SimTick>();
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.init_resource::<CollisionNeighborCache>();
+    app.add_message::<CollisionEvent>();
+    app.add_systems(FixedUpdate, (update_spatial_hash, detect_collisions).chain());
+    app
+}
+
+/// Unordered pairs reported on the last tick
+fn collision_pairs(app: &App) -> Vec<(Entity, Entity)> {
+    let mut pairs: Vec<(Entity, Entity)> = app.world().resource::<Messages<CollisionEvent>>()
+        .iter_current_update_messages()
+        .map(|event| (event.entity1.min(event.entity2), event.entity1.max(event.entity2)))
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+fn pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    (a.min(b), a.max(b))
+}
+
+#[test]
+fn test_cache_rebuilds_every_n_ticks_and_skips_stale_neighbors() {
+    let mut app = setup_cached_detection_app(4, 3);
+    let radius = FixedNum::from_num(0.5);
+    let a = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.0, 0.0), radius, 0);
+    let b = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.8, 0.0), radius, 0);
+    let c = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(10.0, 0.0), radius, 0);
+
+    let mut rebuilds = Vec::new();
+    for tick in 1..=7 {
+        match tick {
+            // Between rebuilds: `c` comes within range of `a` and `b` goes away
+            2 => app.world_mut().get_mut::<SimPosition>(c).unwrap().0 = FixedVec2::from_f32(-0.8, 0.0),
+            5 => {
+                app.world_mut().despawn(b);
+            }
+            _ => {}
+        }
+        app.world_mut().resource_mut::<Messages<CollisionEvent>>().clear();
+        app.world_mut().run_schedule(FixedUpdate);
+        let cache = app.world().resource::<CollisionNeighborCache>();
+        rebuilds.push(cache.rebuilds());
+
+        match tick {
+            1 => assert_eq!(collision_pairs(&app), vec![pair(a, b)]),
+            // `c` isn't in anyone's cache until the rebuild on tick 4
+            2 | 3 => assert_eq!(collision_pairs(&app), vec![pair(a, b)], "Tick {}", tick),
+            4 => {
+                let mut expected = vec![pair(a, b), pair(a, c)];
+                expected.sort();
+                assert_eq!(collision_pairs(&app), expected);
+            }
+            5 | 6 => {
+                assert!(cache.neighbors(a).unwrap().contains(&b), "Cache still holds the despawned unit");
+                assert_eq!(collision_pairs(&app), vec![pair(a, c)], "Tick {}", tick);
+            }
+            7 => {
+                assert!(!cache.neighbors(a).unwrap().contains(&b), "Rebuild drops the despawned unit");
+                assert_eq!(collision_pairs(&app), vec![pair(a, c)]);
+            }
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(rebuilds, vec![1, 1, 1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn test_slots_cap_cached_neighbors_to_the_nearest() {
+    let mut app = setup_cached_detection_app(1, 1);
+    let radius = FixedNum::from_num(0.5);
+    let center = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.0, 0.0), radius, 0);
+    let near = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.6, 0.0), radius, 0);
+    let far = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(0.0, -0.9), radius, 0);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    let cache = app.world().resource::<CollisionNeighborCache>();
+    assert_eq!(cache.neighbors(center), Some(&[near][..]));
+    // `far` still finds `center` through its own nearest neighbor
+    assert_eq!(cache.neighbors(far), Some(&[center][..]));
+    let mut expected = vec![pair(center, near), pair(center, far)];
+    expected.sort();
+    assert_eq!(collision_pairs(&app), expected);
+}
+
+#[test]
+fn test_cached_neighbors_collide_across_the_seam() {
+    let mut app = setup_cached_detection_app(4, 3);
+    // 20x20 wrapping map, hash sized to match so the seam lines up
+    let mut sim_config = app.world_mut().resource_mut::<SimConfig>();
+    sim_config.map_size = MapSize {
+        top_left: FixedVec2::from_f32(-10.0, -10.0),
+        bottom_right: FixedVec2::from_f32(10.0, 10.0),
+    };
+    sim_config.map_edges = MapEdges::Wrap;
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(20.0), FixedNum::from_num(20.0), &[0.5], 4.0, 100, 1.5));
+
+    let radius = FixedNum::from_num(0.5);
+    let east = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(9.6, 0.0), radius, 0);
+    let west = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-9.6, 0.0), radius, 0);
+
+    for tick in 1..=3 {
+        if tick == 2 {
+            // Between rebuilds: the pair closes in across the seam
+            app.world_mut().get_mut::<SimPosition>(west).unwrap().0 = FixedVec2::from_f32(-9.8, 0.0);
+        }
+        app.world_mut().resource_mut::<Messages<CollisionEvent>>().clear();
+        app.world_mut().run_schedule(FixedUpdate);
+        assert_eq!(app.world().resource::<CollisionNeighborCache>().rebuilds(), 1, "Tick {}", tick);
+        assert_eq!(collision_pairs(&app), vec![pair(east, west)], "Tick {}", tick);
+
+        // Pushed apart the short way, back from the seam
+        let event = app.world().resource::<Messages<CollisionEvent>>().iter_current_update_messages().next().unwrap();
+        let east_normal = if event.entity1 == east { event.normal } else { -event.normal };
+        assert!(east_normal.x < FixedNum::ZERO, "Tick {}: {:?}", tick, east_normal);
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use peregrine::game::headless::sim_checksum;
+use peregrine::game::simulation::{Cooldown, SimTick};
+use peregrine::game::simulation::systems::{increment_sim_tick, tick_cooldowns};
+
+/// Minimal app counting ticks and cooldowns
+fn setup_cooldown_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.add_systems(FixedUpdate, (increment_sim_tick, tick_cooldowns).chain());
+    app
+}
+
+fn remaining(app: &App, entity: Entity) -> Cooldown {
+    *app.world().get::<Cooldown>(entity).unwrap()
+}
+
+#[test]
+fn test_cooldown_decrements_once_per_tick_and_is_ready_at_zero() {
+    let mut app = setup_cooldown_app();
+    let entity = app.world_mut().spawn(Cooldown::new(3)).id();
+    assert!(!remaining(&app, entity).ready());
+
+    for expected in [2, 1] {
+        app.world_mut().run_schedule(FixedUpdate);
+        assert_eq!(remaining(&app, entity).remaining_ticks, expected);
+        assert!(!remaining(&app, entity).ready(), "Not ready with {} ticks left", expected);
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(remaining(&app, entity), Cooldown::new(0));
+    assert!(remaining(&app, entity).ready(), "Ready on exactly the third tick");
+
+    // Stays at zero instead of wrapping
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(remaining(&app, entity).remaining_ticks, 0);
+}
+
+#[test]
+fn test_try_trigger_restarts_only_when_ready() {
+    let mut cooldown = Cooldown::default();
+    assert!(cooldown.try_trigger(5));
+    assert_eq!(cooldown.remaining_ticks, 5);
+    assert!(!cooldown.try_trigger(5), "Still cooling down");
+    assert_eq!(cooldown.remaining_ticks, 5, "A refused trigger doesn't restart the cooldown");
+}
+
+#[test]
+fn test_cooldowns_are_part_of_the_checksum() {
+    let mut world = World::new();
+    world.init_resource::<SimTick>();
+    let unit = world.spawn(Cooldown::new(4)).id();
+    let before = sim_checksum(&world, &[unit]);
+
+    world.get_mut::<Cooldown>(unit).unwrap().remaining_ticks = 3;
+    assert_ne!(sim_checksum(&world, &[unit]), before);
+}
@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use peregrine::game::collections::{IncludeResult, InclusionIndex};
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{ActiveUnitSet, EntityDespawned, OccupiedCell};
+use peregrine::game::simulation::systems::{record_despawned_entity, scrub_despawned_entities};
+use peregrine::game::pathfinding::{ActivePathSet, Path, PathRequest, PathState, PendingPathRequests};
+use peregrine::game::spatial_hash::SpatialHash;
+use peregrine::game::unit::{spawn_unit_in_world, Selected};
+
+/// Despawn observer plus the cleanup pass, over an incremental-mode spatial hash
+fn setup_cleanup_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(SpatialHash::new(
+        FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 1000, 2.0,
+    ));
+    app.init_resource::<PendingPathRequests>();
+    app.init_resource::<ActivePathSet>();
+    app.init_resource::<ActiveUnitSet>();
+    app.add_message::<EntityDespawned>();
+    app.add_observer(record_despawned_entity);
+    app.add_systems(FixedUpdate, scrub_despawned_entities);
+    app
+}
+
+/// Unit stored in the hash, awake, with an active path and a queued repath
+fn spawn_pathing_unit(app: &mut App, x: f32) -> Entity {
+    let world = app.world_mut();
+    let entity = spawn_unit_in_world(world, FixedVec2::from_f32(x, 0.0), FixedNum::from_num(0.5), 0);
+    let goal = FixedVec2::from_f32(x, 20.0);
+
+    if let IncludeResult::Inserted(Some(index)) = world.resource_mut::<ActivePathSet>().include(entity) {
+        *world.get_mut::<InclusionIndex>(entity).unwrap() = index;
+    }
+    *world.get_mut::<Path>(entity).unwrap() = Path::Active(PathState::Direct(goal));
+    world.resource_mut::<PendingPathRequests>().push(&PathRequest::background(entity, goal));
+    world.resource_mut::<ActiveUnitSet>().wake(entity);
+    world.entity_mut(entity).insert(Selected);
+    entity
+}
+
+#[test]
+fn test_despawned_unit_leaves_no_dangling_references() {
+    let mut app = setup_cleanup_app();
+    let doomed = spawn_pathing_unit(&mut app, 0.0);
+    let survivor = spawn_pathing_unit(&mut app, 5.0);
+
+    app.world_mut().despawn(doomed);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let world = app.world();
+    let in_hash: Vec<Entity> = world.resource::<SpatialHash>().iter_entities().map(|(entity, _)| entity).collect();
+    assert_eq!(in_hash, vec![survivor], "Only the survivor should be left in the spatial hash");
+    assert!(!world.resource::<PendingPathRequests>().contains(doomed));
+    assert!(world.resource::<ActivePathSet>().iter().all(|entity| entity != doomed));
+    assert!(!world.resource::<ActiveUnitSet>().contains(doomed));
+
+    // The survivor keeps everything
+    assert!(world.resource::<PendingPathRequests>().contains(survivor));
+    assert!(world.resource::<ActivePathSet>().iter().any(|entity| entity == survivor));
+    assert!(world.resource::<ActiveUnitSet>().contains(survivor));
+    assert!(world.get::<Selected>(survivor).is_some());
+    let mut selected = app.world_mut().query_filtered::<Entity, With<Selected>>();
+    assert_eq!(selected.iter(app.world()).collect::<Vec<_>>(), vec![survivor]);
+}
+
+#[test]
+fn test_despawns_swap_the_cell_tail_into_place_without_tombstones() {
+    let mut app = setup_cleanup_app();
+    // Five units sharing one cell; despawn the first two so others have to move into their slots
+    let units: Vec<Entity> = (0..5).map(|i| spawn_pathing_unit(&mut app, 0.1 * i as f32)).collect();
+    app.world_mut().despawn(units[0]);
+    app.world_mut().despawn(units[1]);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let mut occupied = app.world_mut().query::<(Entity, &OccupiedCell)>();
+    let tracked: Vec<(Entity, OccupiedCell)> = occupied.iter(app.world()).map(|(entity, cell)| (entity, *cell)).collect();
+    let hash = app.world().resource::<SpatialHash>();
+    for (_, cell) in &tracked {
+        let size_class = &hash.size_classes()[cell.size_class as usize];
+        let grid = if cell.grid_offset == 0 { &size_class.grid_a } else { &size_class.grid_b };
+        assert!(grid.get_cell_entities(cell.col, cell.row).iter().all(|&entity| entity != Entity::PLACEHOLDER),
+            "Removal shouldn't leave tombstones");
+    }
+    let report = hash.debug_verify(tracked.iter().map(|(entity, cell)| (*entity, cell)));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.total_entries, 3);
+}
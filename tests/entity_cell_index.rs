@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::simulation::{EntityCellIndex, OccupiedCell, SimConfig, SimPosition, SpatialHashOverflow};
+use peregrine::game::simulation::systems::{
+    update_spatial_hash, rebuild_spatial_hash_on_overflow, sync_entity_cell_index, PendingVecIdxUpdates,
+};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::spawn_unit_in_world;
+
+/// Incremental spatial hash updates with the cell index on
+fn setup_indexed_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimConfig>();
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5));
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.init_resource::<EntityCellIndex>();
+    app.add_systems(FixedUpdate, (
+        update_spatial_hash,
+        rebuild_spatial_hash_on_overflow,
+        sync_entity_cell_index.run_if(resource_exists::<EntityCellIndex>),
+    ).chain());
+    app
+}
+
+/// A crowd sharing a few cells, then half of it moving elsewhere (so the arena swaps slots)
+fn populated_app() -> (App, Vec<Entity>) {
+    let mut app = setup_indexed_app();
+    let radius = FixedNum::from_num(0.5);
+    let units: Vec<Entity> = (0..12)
+        .map(|i| spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(1.0 + (i % 4) as f32 * 0.3, 1.0 + (i / 4) as f32 * 0.3), radius, 0))
+        .collect();
+    app.world_mut().run_schedule(FixedUpdate);
+    for (i, &unit) in units.iter().enumerate().step_by(2) {
+        app.world_mut().get_mut::<SimPosition>(unit).unwrap().0 = FixedVec2::from_f32(20.0 + i as f32, 30.0);
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+    (app, units)
+}
+
+/// Every entity a whole-map query finds, sorted
+fn stored_entities(app: &App) -> Vec<Entity> {
+    let mut scratch = SpatialHashScratch::new(64);
+    app.world().resource::<SpatialHash>()
+        .query_radius(FixedVec2::from_f32(50.0, 50.0), FixedNum::from_num(80.0), None, &mut scratch);
+    let mut found = scratch.query_results.clone();
+    found.sort();
+    found
+}
+
+#[test]
+fn test_index_tracks_components_and_removal_matches_component_removal() {
+    let (mut by_component, units) = populated_app();
+    let (mut by_index, units_b) = populated_app();
+    assert_eq!(units, units_b);
+
+    // The index mirrors every (possibly swapped) component
+    for app in [&mut by_component, &mut by_index] {
+        let index = app.world().resource::<EntityCellIndex>();
+        assert_eq!(index.len(), units.len());
+        for &unit in &units {
+            let (indexed, component) = (index.get(unit).unwrap(), app.world().get::<OccupiedCell>(unit).unwrap());
+            assert_eq!(
+                (indexed.size_class, indexed.grid_offset, indexed.col, indexed.row, indexed.vec_idx),
+                (component.size_class, component.grid_offset, component.col, component.row, component.vec_idx),
+                "Index out of step for {:?}", unit,
+            );
+        }
+        let report = app.world().resource::<SpatialHash>()
+            .debug_verify(units.iter().map(|&unit| (unit, index.get(unit).unwrap())));
+        assert!(report.is_consistent());
+    }
+
+    for &unit in &units[..5] {
+        let occupied = *by_component.world().get::<OccupiedCell>(unit).unwrap();
+        let via_component = by_component.world_mut().resource_mut::<SpatialHash>().remove(unit, &occupied);
+
+        let world = by_index.world_mut();
+        let via_index = world.resource_scope(|world, mut index: Mut<EntityCellIndex>| {
+            index.remove(&mut world.resource_mut::<SpatialHash>(), unit)
+        });
+        assert_eq!(via_index, via_component);
+        assert_eq!(via_index, Some(true));
+        assert!(by_index.world().resource::<EntityCellIndex>().get(unit).is_none());
+    }
+
+    assert_eq!(stored_entities(&by_index), stored_entities(&by_component));
+    assert_eq!(stored_entities(&by_index).len(), units.len() - 5);
+    let total = |app: &App| app.world().resource::<SpatialHash>().total_entries();
+    assert_eq!(total(&by_index), total(&by_component));
+    // Unknown entities are a no-op
+    let world = by_index.world_mut();
+    let missing = world.resource_scope(|world, mut index: Mut<EntityCellIndex>| {
+        index.remove(&mut world.resource_mut::<SpatialHash>(), units[0])
+    });
+    assert_eq!(missing, None);
+}
+
+#[test]
+fn test_despawned_units_leave_the_index() {
+    let (mut app, units) = populated_app();
+    app.world_mut().despawn(units[3]);
+    app.world_mut().run_schedule(FixedUpdate);
+    let index = app.world().resource::<EntityCellIndex>();
+    assert_eq!(index.len(), units.len() - 1);
+    assert!(index.get(units[3]).is_none());
+}
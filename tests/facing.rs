@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{atan2, FixedNum, FixedVec2};
+use peregrine::game::headless::sim_checksum;
+use peregrine::game::simulation::{Facing, SimConfig, SimTick, SimVelocity};
+use peregrine::game::simulation::physics::update_facing;
+
+/// Facing updates only
+fn setup_facing_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimConfig>();
+    app.init_resource::<SimTick>();
+    app.add_systems(FixedUpdate, update_facing);
+    app
+}
+
+#[test]
+fn test_unit_moving_east_turns_to_east_without_overshoot() {
+    let mut app = setup_facing_app();
+    let max_turn = {
+        let config = app.world().resource::<SimConfig>();
+        config.unit_turn_rate * config.fixed_delta()
+    };
+    let north = FixedNum::FRAC_PI_2;
+    let unit = app.world_mut().spawn((
+        SimVelocity(FixedVec2::from_f32(5.0, 0.0)),
+        Facing(north),
+    )).id();
+
+    let ticks_needed = (north / max_turn).ceil().to_num::<u32>();
+    let mut previous = north;
+    for tick in 1..=ticks_needed + 5 {
+        app.world_mut().run_schedule(FixedUpdate);
+        let facing = app.world().get::<Facing>(unit).unwrap().0;
+        assert!(facing >= FixedNum::ZERO, "Overshot east on tick {}: {}", tick, facing);
+        assert!(previous - facing <= max_turn, "Turned faster than the turn rate on tick {}", tick);
+        if tick < ticks_needed {
+            assert_eq!(facing, north - max_turn * FixedNum::from_num(tick), "Turns a full step per tick");
+        } else {
+            assert_eq!(facing, FixedNum::ZERO, "Settles exactly on east");
+        }
+        previous = facing;
+    }
+}
+
+#[test]
+fn test_facing_turns_the_short_way_and_holds_at_rest() {
+    let mut app = setup_facing_app();
+    // Just above west, heading just below west: the short way crosses ±π
+    let start = FixedNum::PI - FixedNum::from_num(0.05);
+    let velocity = FixedVec2::from_f32(-5.0, -0.25);
+    let unit = app.world_mut().spawn((
+        SimVelocity(velocity),
+        Facing(start),
+    )).id();
+
+    app.world_mut().run_schedule(FixedUpdate);
+    let facing = app.world().get::<Facing>(unit).unwrap().0;
+    // Only ~0.1 rad away through west (the long way is ~6.2), well inside one tick's turn
+    assert_eq!(facing, atan2(velocity.y, velocity.x), "Should turn through west");
+    assert!(facing < FixedNum::ZERO);
+
+    // Stopped units keep their last facing
+    app.world_mut().get_mut::<SimVelocity>(unit).unwrap().0 = FixedVec2::ZERO;
+    for _ in 0..3 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+    assert_eq!(app.world().get::<Facing>(unit).unwrap().0, facing);
+}
+
+#[test]
+fn test_facing_is_part_of_the_checksum() {
+    let mut world = World::new();
+    world.init_resource::<SimTick>();
+    let unit = world.spawn(Facing(FixedNum::ZERO)).id();
+    let before = sim_checksum(&world, &[unit]);
+
+    world.get_mut::<Facing>(unit).unwrap().0 = FixedNum::from_num(0.5);
+    assert_ne!(sim_checksum(&world, &[unit]), before);
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{ForceSource, ForceKind, SimTick, SimPosition, SimAcceleration};
+use peregrine::game::simulation::physics::apply_forces;
+
+/// Minimal app running only `apply_forces`
+fn setup_force_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.add_systems(FixedUpdate, apply_forces);
+    app
+}
+
+fn spawn_unit_at(app: &mut App, x: f32, y: f32) -> Entity {
+    app.world_mut().spawn((SimPosition(FixedVec2::from_f32(x, y)), SimAcceleration::default())).id()
+}
+
+fn acceleration(app: &App, unit: Entity) -> FixedVec2 {
+    app.world().get::<SimAcceleration>(unit).unwrap().0
+}
+
+#[test]
+fn test_repel_pushes_nearby_units_away() {
+    let mut app = setup_force_app();
+    let strength = FixedNum::from_num(8);
+    app.world_mut().spawn(ForceSource::repel(FixedVec2::ZERO, strength, FixedNum::from_num(10)));
+
+    let east = spawn_unit_at(&mut app, 3.0, 0.0);
+    let south = spawn_unit_at(&mut app, 0.0, -4.0);
+    let outside = spawn_unit_at(&mut app, 20.0, 0.0);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    // Exact in fixed point: axis-aligned offsets normalize to unit vectors
+    assert_eq!(acceleration(&app, east), FixedVec2::new(strength, FixedNum::ZERO));
+    assert_eq!(acceleration(&app, south), FixedVec2::new(FixedNum::ZERO, -strength));
+    assert_eq!(acceleration(&app, outside), FixedVec2::ZERO, "Units outside the radius are unaffected");
+}
+
+#[test]
+fn test_attract_pulls_nearby_units_in() {
+    let mut app = setup_force_app();
+    let center = FixedVec2::from_f32(10.0, 10.0);
+    app.world_mut().spawn(ForceSource::attract(center, FixedNum::from_num(5), FixedNum::from_num(6)));
+
+    let unit = spawn_unit_at(&mut app, 13.0, 14.0);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    // (3, 4) away at distance 5: pulled along (-3, -4) / 5 with magnitude 5
+    let acc = acceleration(&app, unit);
+    let to_center = center - FixedVec2::from_f32(13.0, 14.0);
+    assert!(acc.dot(to_center) > FixedNum::ZERO, "Attract should point towards the source, got {:?}", acc);
+    let tolerance = FixedNum::from_num(0.01);
+    assert!((acc.x - FixedNum::from_num(-3)).abs() < tolerance && (acc.y - FixedNum::from_num(-4)).abs() < tolerance, "{:?}", acc);
+}
+
+#[test]
+fn test_overlapping_sources_sum_and_cancel() {
+    let mut app = setup_force_app();
+    let strength = FixedNum::from_num(2);
+    let radius = FixedNum::from_num(10);
+    app.world_mut().spawn(ForceSource::attract(FixedVec2::from_f32(-5.0, 0.0), strength, radius));
+    app.world_mut().spawn(ForceSource::repel(FixedVec2::from_f32(5.0, 0.0), strength, radius));
+    app.world_mut().spawn(ForceSource { position: FixedVec2::from_f32(0.0, 5.0), strength, radius, kind: ForceKind::Attract });
+
+    let unit = spawn_unit_at(&mut app, 0.0, 0.0);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    // Both x sources push towards -x; the third pulls towards +y
+    assert_eq!(acceleration(&app, unit), FixedVec2::new(-strength * 2, strength));
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use peregrine::game::GameState;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::headless::{insert_map, HeadlessSimPlugin};
+use peregrine::game::pathfinding::{Path, PathState, PathfindingPlugin};
+use peregrine::game::simulation::{MapDimensions, SimPosition, SimTick, SimulationPlugin, SpawnUnitCommand, UnitMoveCommand, WaypointQueue};
+use peregrine::game::unit::Unit;
+
+#[test]
+fn test_headless_plugin_ticks_without_render_resources() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    insert_map(&mut app, MapDimensions::from_f32(64.0, 64.0), &[]);
+    app.add_plugins(HeadlessSimPlugin);
+
+    app.world_mut().write_message(SpawnUnitCommand { player_id: 0, position: FixedVec2::from_f32(-10.0, 0.0), radius: None });
+    app.world_mut().run_schedule(FixedUpdate);
+    let mut units = app.world_mut().query_filtered::<Entity, With<Unit>>();
+    let unit = units.single(app.world()).expect("Spawn command should create one unit");
+
+    let target = FixedVec2::from_f32(10.0, 0.0);
+    app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target, queued: false });
+    for _ in 0..30 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+
+    let mut windows = app.world_mut().query_filtered::<(), With<Window>>();
+    assert_eq!(windows.iter(app.world()).count(), 0);
+    let world = app.world();
+    assert_eq!(world.resource::<SimTick>().0, 31);
+    let position = world.get::<SimPosition>(unit).unwrap().0;
+    assert!(position.x > FixedVec2::from_f32(-10.0, 0.0).x, "Unit should walk towards its target, at {:?}", position);
+
+    // Nothing from the renderer, asset server or window was needed
+    assert!(!world.contains_resource::<AssetServer>());
+    assert!(!world.contains_resource::<Assets<Mesh>>());
+    assert!(!world.contains_resource::<Assets<StandardMaterial>>());
+}
+
+#[test]
+fn test_queued_move_runs_after_the_first_arrives() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    insert_map(&mut app, MapDimensions::from_f32(64.0, 64.0), &[]);
+    app.add_plugins(HeadlessSimPlugin);
+
+    app.world_mut().write_message(SpawnUnitCommand { player_id: 0, position: FixedVec2::from_f32(-24.0, -24.0), radius: None });
+    app.world_mut().run_schedule(FixedUpdate);
+    let mut units = app.world_mut().query_filtered::<Entity, With<Unit>>();
+    let unit = units.single(app.world()).expect("Spawn command should create one unit");
+
+    // Both targets lie in the spawn's cluster
+    let (first, second) = (FixedVec2::from_f32(-12.0, -24.0), FixedVec2::from_f32(-12.0, -12.0));
+    app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target: first, queued: false });
+    app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target: second, queued: true });
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(app.world().get::<WaypointQueue>(unit).unwrap().0, [second], "Second move should wait its turn");
+
+    let heading_for = |app: &App, target: FixedVec2| matches!(
+        app.world().get::<Path>(unit).unwrap(),
+        Path::Active(PathState::Hierarchical { goal, .. }) if *goal == target
+    );
+    let position = |app: &App| app.world().get::<SimPosition>(unit).unwrap().0;
+    let distance = |app: &App, target: FixedVec2| (position(app) - target).length();
+
+    // The queued move only starts once the first one has arrived
+    let mut ticks = 0;
+    while !heading_for(&app, second) && ticks < 600 {
+        app.world_mut().run_schedule(FixedUpdate);
+        ticks += 1;
+    }
+    assert!(heading_for(&app, second), "Unit should go on to the queued target");
+    assert!(distance(&app, first) < FixedNum::from_num(1.5), "Unit left for the queued target early, at {:?}", position(&app));
+    assert!(app.world().get::<WaypointQueue>(unit).unwrap().0.is_empty());
+
+    for _ in 0..600 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+    assert!(distance(&app, second) < FixedNum::from_num(1.5), "Unit should reach the queued target, at {:?}", position(&app));
+}
+
+#[test]
+fn test_game_plugins_run_the_shared_tick() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin));
+    app.insert_state(GameState::InGame);
+    app.add_plugins((SimulationPlugin, PathfindingPlugin));
+    app.world_mut().run_schedule(Startup);
+    app.world_mut().run_schedule(StateTransition);
+
+    app.world_mut().write_message(SpawnUnitCommand { player_id: 0, position: FixedVec2::from_f32(-10.0, 0.0), radius: None });
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let mut units = app.world_mut().query_filtered::<(), With<Unit>>();
+    assert_eq!(units.iter(app.world()).count(), 1);
+    assert_eq!(app.world().resource::<SimTick>().0, 2);
+}
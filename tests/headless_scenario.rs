@@ -0,0 +1,85 @@
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::headless::{run_scenario, HeadlessSim, Scenario};
+use peregrine::game::simulation::SimPosition;
+use peregrine::game::simulation::digest::{diff_logs, TickDigestLog};
+
+/// Two squads crossing past an obstacle, a late reinforcement, and a stop order
+const SMOKE_SCENARIO: &str = r#"(
+    map_width: 64.0,
+    map_height: 64.0,
+    ticks: 200,
+    units: [
+        (x: -12.0, y: -2.0),
+        (x: -12.0, y: 0.0),
+        (x: -12.0, y: 2.0),
+        (x: 12.0, y: -1.0, team: 1),
+        (x: 12.0, y: 1.0, team: 1),
+    ],
+    obstacles: [(x: 0.0, y: 0.0, radius: 2.0)],
+    commands: [
+        (tick: 1, action: Move(units: [0, 1, 2], x: 14.0, y: 0.0)),
+        (tick: 1, action: Move(units: [3, 4], x: -14.0, y: 0.0)),
+        (tick: 50, action: Spawn(x: 0.0, y: 20.0)),
+        (tick: 60, action: Move(units: [5], x: 0.0, y: -20.0)),
+        (tick: 150, action: Stop(units: [3])),
+    ],
+)"#;
+
+/// Golden checksum for `SMOKE_SCENARIO` with the default `fixed_i48f16` precision.
+///
+/// If a change to the simulation is intended, update this with the value the test prints.
+const SMOKE_CHECKSUM: u64 = 0x73ba_94d0_8008_714e;
+
+#[test]
+fn test_smoke_scenario_matches_golden_checksum() {
+    let scenario = Scenario::from_ron_str(SMOKE_SCENARIO).unwrap();
+    let result = run_scenario(&scenario);
+
+    assert_eq!(result.ticks, 200);
+    assert_eq!(result.unit_count, 6, "Spawned reinforcement should be tracked");
+    assert_eq!(result.checksum, SMOKE_CHECKSUM, "Checksum changed: got {:#018x}", result.checksum);
+}
+
+#[test]
+fn test_scenario_runs_are_reproducible_and_move_units() {
+    let scenario = Scenario::from_ron_str(SMOKE_SCENARIO).unwrap();
+
+    let mut sim = HeadlessSim::new(&scenario);
+    sim.run(100);
+    let start = FixedVec2::from_f32(-12.0, 0.0);
+    let moved = sim.app.world().get::<SimPosition>(sim.units[1]).unwrap().0;
+    assert!(moved.x > start.x, "Move command should have advanced the unit, still at {:?}", moved);
+
+    assert_eq!(run_scenario(&scenario), run_scenario(&scenario));
+}
+
+/// `SMOKE_SCENARIO` for 80 ticks with digests on; `nudge_before` shifts unit 3 slightly
+/// just before that tick runs
+fn digest_run(nudge_before: Option<u64>) -> (HeadlessSim, TickDigestLog) {
+    let scenario = Scenario::from_ron_str(SMOKE_SCENARIO).unwrap();
+    let mut sim = HeadlessSim::new(&scenario);
+    sim.app.insert_resource(TickDigestLog::new(100));
+    for _ in 0..80 {
+        if nudge_before == Some(sim.tick() + 1) {
+            let unit = sim.units[3];
+            sim.app.world_mut().get_mut::<SimPosition>(unit).unwrap().0.y += FixedNum::DELTA;
+        }
+        sim.step();
+    }
+    let log = sim.app.world_mut().remove_resource::<TickDigestLog>().unwrap();
+    (sim, log)
+}
+
+#[test]
+fn test_digest_diff_finds_first_divergent_tick() {
+    let (_, baseline) = digest_run(None);
+    let (_, repeat) = digest_run(None);
+    assert_eq!(baseline.len(), 80);
+    assert_eq!(diff_logs(&baseline, &repeat), None, "Identical runs should not diverge");
+
+    let (sim, nudged) = digest_run(Some(41));
+    let divergence = diff_logs(&baseline, &nudged).expect("Nudged run should diverge");
+    assert_eq!(divergence.tick, 41);
+    assert!(divergence.entities.contains(&sim.units[3]), "{:?}", divergence.entities);
+    assert!(!divergence.entities.contains(&sim.units[0]), "Unit 0 is far from the nudge: {:?}", divergence.entities);
+}
@@ -14,8 +14,13 @@ use bevy::ecs::system::RunSystemOnce;
 use peregrine::game::simulation::components::{
     SimPosition, SimVelocity, SimAcceleration, Collider, OccupiedCell, layers,
 };
-use peregrine::game::simulation::resources::SimConfig;
-use peregrine::game::spatial_hash::SpatialHash;
+use peregrine::game::simulation::resources::{ActiveUnitSet, SimConfig, SpatialHashOverflow, SpatialHashGrowth};
+use peregrine::game::simulation::systems::{
+    update_spatial_hash, rebuild_spatial_hash_on_overflow, spatial_hash_read_barrier, SpatialHashNotReady, rebuild_spatial_hash_from_world, adapt_spatial_hash_capacity,
+    relocate_moved_entities, PendingVecIdxUpdates,
+};
+use peregrine::game::simulation::ObstacleBundle;
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
 use peregrine::game::fixed_math::{FixedNum, FixedVec2};
 use std::time::Instant;
 
@@ -31,14 +36,14 @@ fn simple_update_spatial_hash(
     if use_incremental {
         // Insert new entities
         for (entity, pos, collider) in query_new.iter() {
-            let occupied = spatial_hash.insert(entity, pos.0, collider.radius);
+            let occupied = spatial_hash.insert(entity, pos.0, collider.radius).unwrap();
             commands.entity(entity).insert(occupied);
         }
         
         // Update moved entities
         for (entity, pos, _collider, occupied) in query.iter() {
             if let Some((new_grid_offset, new_col, new_row)) = spatial_hash.should_update(pos.0, occupied) {
-                match spatial_hash.update_incremental(entity, occupied, new_grid_offset, new_col, new_row) {
+                match spatial_hash.update_incremental(entity, occupied, new_grid_offset, new_col, new_row, pos.0) {
                     Ok((new_vec_idx, _swapped_entity_opt)) => {
                         commands.entity(entity).insert(OccupiedCell {
                             size_class: occupied.size_class,
@@ -58,7 +63,7 @@ fn simple_update_spatial_hash(
         for (entity, pos, collider) in query.iter().map(|(e, p, c, _)| (e, p, c))
             .chain(query_new.iter())
         {
-            spatial_hash.insert(entity, pos.0, collider.radius);
+            spatial_hash.insert(entity, pos.0, collider.radius).unwrap();
         }
     }
 }
@@ -237,3 +242,380 @@ fn test_incremental_infrastructure() {
     
     println!("✓ Incremental update infrastructure works correctly");
 }
+
+/// Live update, overflow recovery and capacity adaptation on a 64x64 map (2.0 cells, 1.5x overcapacity)
+fn create_live_app(max_entity_count: usize, growth: SpatialHashGrowth) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(SpatialHash::new(
+        FixedNum::from_num(64.0),
+        FixedNum::from_num(64.0),
+        &[0.5],
+        4.0,
+        max_entity_count,
+        1.5,
+    ));
+    app.insert_resource(SimConfig::default());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.insert_resource(growth);
+    app.add_systems(Update, (update_spatial_hash, rebuild_spatial_hash_on_overflow, adapt_spatial_hash_capacity).chain());
+    app
+}
+
+/// Every cell has ~1 slot of headroom, and growth never kicks in
+fn create_overflow_app() -> App {
+    create_live_app(1000, SpatialHashGrowth { sustain_ticks: u32::MAX, ..default() })
+}
+
+fn spawn_unit(app: &mut App, pos: FixedVec2) -> Entity {
+    app.world_mut().spawn((
+        SimPosition(pos),
+        Collider { radius: FixedNum::from_num(0.5), layer: layers::UNIT, mask: layers::UNIT | layers::OBSTACLE },
+    )).id()
+}
+
+/// Spawn `count` units, one per Grid A cell center (odd coordinates on the 64x64 map)
+fn spawn_in_grid_a(app: &mut App, count: usize) -> Vec<Entity> {
+    (0..count)
+        .map(|i| {
+            let x = -29.0 + 2.0 * (i % 30) as f32;
+            let y = -29.0 + 2.0 * (i / 30) as f32;
+            spawn_unit(app, FixedVec2::from_f32(x, y))
+        })
+        .collect()
+}
+
+fn despawn_and_rebuild(app: &mut App, units: &[Entity]) {
+    for &unit in units {
+        app.world_mut().despawn(unit);
+    }
+    app.world_mut().resource_mut::<SpatialHashOverflow>().request_rebuild();
+}
+
+fn assert_hash_consistent(app: &mut App) {
+    let mut cells = app.world_mut().query::<(Entity, &OccupiedCell)>();
+    let report = app.world().resource::<SpatialHash>().debug_verify(cells.iter(app.world()));
+    assert!(report.is_consistent(), "{:?}", report);
+}
+
+#[test]
+fn test_cell_overflow_triggers_rebuild_and_keeps_entity_queryable() {
+    let mut app = create_overflow_app();
+
+    let units: Vec<Entity> = [(-20.0, -20.0), (20.0, -20.0), (-20.0, 20.0), (20.0, 20.0)]
+        .into_iter()
+        .map(|(x, y)| spawn_unit(&mut app, FixedVec2::from_f32(x, y)))
+        .collect();
+
+    // First tick appends without headroom; force a rebuild so cells get their headroom ranges
+    app.update();
+    app.world_mut().resource_mut::<SpatialHashOverflow>().request_rebuild();
+    app.update();
+    assert_eq!(app.world().resource::<SpatialHashOverflow>().total_rebuilds, 1);
+
+    // Crowd three units into one cell that only has headroom for one more
+    let crowded = FixedVec2::from_f32(11.0, 11.0);
+    for &unit in &units[..3] {
+        app.world_mut().get_mut::<SimPosition>(unit).unwrap().0 = crowded;
+    }
+    app.update();
+
+    let overflow = app.world().resource::<SpatialHashOverflow>();
+    assert!(overflow.rejected_insertions >= 1, "Crowding a cell should overflow it");
+    assert!(!overflow.rebuild_pending, "Rebuild should run the same tick");
+    assert_eq!(overflow.total_rebuilds, 2);
+
+    // Every crowded unit is still found where it actually is
+    let mut scratch = SpatialHashScratch::new(64);
+    app.world().resource::<SpatialHash>().query_radius(crowded, FixedNum::from_num(1.0), None, &mut scratch);
+    for &unit in &units[..3] {
+        assert!(scratch.query_results.contains(&unit), "{:?} dropped out of the hash after overflow", unit);
+    }
+
+    assert_hash_consistent(&mut app);
+}
+
+fn capacity(app: &App) -> usize {
+    app.world().resource::<SpatialHash>().storage_capacity()
+}
+
+#[test]
+fn test_sustained_usage_grows_then_shrinks_arena() {
+    // 100 entities x 1.5 overcapacity = 150 slots per grid
+    let mut app = create_live_app(100, SpatialHashGrowth { sustain_ticks: 5, ..default() });
+    let units = spawn_in_grid_a(&mut app, 140);  // 140 / 150 = 93% usage in Grid A
+
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(capacity(&app), 150, "Must not grow before usage has been high for sustain_ticks");
+
+    app.update();
+    assert_eq!(capacity(&app), 234, "Should grow so 140 entries sit at 60% usage");
+    assert_hash_consistent(&mut app);
+
+    // Landed between the water marks: stays put
+    for _ in 0..30 {
+        app.update();
+    }
+    assert_eq!(capacity(&app), 234);
+
+    // Battle's over: 20 / 234 = 8.5% usage
+    despawn_and_rebuild(&mut app, &units[20..]);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(capacity(&app), 150, "Should shrink back, but not below the startup capacity");
+    assert_hash_consistent(&mut app);
+
+    // Still below the low-water mark, but already at the floor: no further resizes
+    for _ in 0..30 {
+        app.update();
+    }
+    let growth = app.world().resource::<SpatialHashGrowth>();
+    assert_eq!((growth.grow_count, growth.shrink_count), (1, 1));
+    assert_eq!(capacity(&app), 150);
+}
+
+#[test]
+fn test_usage_flickering_at_high_water_does_not_resize() {
+    let mut app = create_live_app(100, SpatialHashGrowth { sustain_ticks: 5, ..default() });
+    spawn_in_grid_a(&mut app, 135);  // Exactly 90%: not above the mark
+    app.update();
+
+    // One unit comes and goes every tick: 90.7%, 90%, 90.7%, ...
+    for _ in 0..20 {
+        let extra = spawn_unit(&mut app, FixedVec2::from_f32(29.0, 29.0));
+        app.update();
+        despawn_and_rebuild(&mut app, &[extra]);
+        app.update();
+    }
+
+    let growth = app.world().resource::<SpatialHashGrowth>();
+    assert_eq!((growth.grow_count, growth.shrink_count), (0, 0));
+    assert_eq!(capacity(&app), 150);
+}
+
+/// Every stored unit with the position the hash has cached for it
+fn cached_positions(app: &App) -> std::collections::HashMap<Entity, FixedVec2> {
+    let mut scratch = SpatialHashScratch::new(256);
+    app.world().resource::<SpatialHash>().query_radius_with_positions(FixedVec2::ZERO, FixedNum::from_num(64.0), None, &mut scratch);
+    scratch.query_results.iter().copied().zip(scratch.query_positions.iter().copied()).collect()
+}
+
+fn assert_cached_positions_current(app: &mut App) {
+    let cached = cached_positions(app);
+    let mut positions = app.world_mut().query::<(Entity, &SimPosition)>();
+    let positions: Vec<(Entity, FixedVec2)> = positions.iter(app.world()).map(|(entity, pos)| (entity, pos.0)).collect();
+    assert_eq!(cached.len(), positions.len());
+    for (entity, pos) in positions {
+        assert_eq!(cached.get(&entity), Some(&pos), "Stale cached position for {:?}", entity);
+    }
+}
+
+#[test]
+fn test_query_with_positions_matches_sim_position() {
+    let mut app = create_live_app(1000, SpatialHashGrowth { sustain_ticks: u32::MAX, ..default() });
+    let units = spawn_in_grid_a(&mut app, 60);
+    app.update();
+    app.world_mut().resource_mut::<SpatialHashOverflow>().request_rebuild();
+    app.update();
+    assert_cached_positions_current(&mut app);
+
+    // Mix of nudges within a cell and jumps across cells (swapping cell neighbors around)
+    for step in 1..=6 {
+        for (i, &unit) in units.iter().enumerate() {
+            let offset = if i % 3 == 0 { 0.9 * step as f32 } else { 0.05 * step as f32 };
+            let mut pos = app.world_mut().get_mut::<SimPosition>(unit).unwrap();
+            pos.0 = pos.0 + FixedVec2::from_f32(offset, -offset / 2.0);
+        }
+        app.update();
+        assert_cached_positions_current(&mut app);
+    }
+
+    // A full rebuild carries the positions over too
+    app.world_mut().resource_mut::<SpatialHashOverflow>().request_rebuild();
+    app.update();
+    assert_cached_positions_current(&mut app);
+    assert_hash_consistent(&mut app);
+}
+
+#[test]
+fn test_positions_moved_while_idle_reach_the_cache() {
+    let mut app = create_live_app(1000, SpatialHashGrowth { sustain_ticks: u32::MAX, ..default() });
+    // No unit is ever woken, so none are in the active set
+    app.init_resource::<ActiveUnitSet>();
+    let units = spawn_in_grid_a(&mut app, 30);
+    app.update();
+    assert_cached_positions_current(&mut app);
+
+    // Nudges within a cell and jumps across cells
+    for (i, &unit) in units.iter().enumerate() {
+        let offset = if i % 2 == 0 { 0.1 } else { 2.0 };
+        let mut pos = app.world_mut().get_mut::<SimPosition>(unit).unwrap();
+        pos.0 = pos.0 + FixedVec2::from_f32(offset, offset);
+    }
+    app.update();
+    assert!(app.world().resource::<ActiveUnitSet>().iter().next().is_none());
+    assert_cached_positions_current(&mut app);
+    assert_hash_consistent(&mut app);
+}
+
+#[test]
+fn test_rebuild_from_world_gives_every_collider_a_valid_cell() {
+    let mut world = World::new();
+    world.insert_resource(SpatialHash::new(
+        FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 1000, 1.5,
+    ));
+    let mut colliders: Vec<Entity> = (0..40)
+        .map(|i| world.spawn((
+            SimPosition(FixedVec2::from_f32((i % 8) as f32 * 6.0 - 24.0, (i / 8) as f32 * 6.0 - 15.0)),
+            Collider::ground_unit(FixedNum::from_num(0.5)),
+        )).id())
+        .collect();
+    // A large obstacle-layer collider lands in the bigger size class
+    colliders.push(world.spawn((SimPosition(FixedVec2::from_f32(30.0, 30.0)), Collider::obstacle(FixedNum::from_num(8.0)))).id());
+    let static_obstacle = world.spawn(ObstacleBundle::new(FixedVec2::from_f32(-30.0, 30.0), FixedNum::from_num(3.0))).id();
+    // Stale cell from before the rebuild must be replaced
+    world.entity_mut(colliders[0]).insert(OccupiedCell { vec_idx: 999, ..OccupiedCell::default() });
+
+    rebuild_spatial_hash_from_world(&mut world);
+
+    assert!(world.get::<OccupiedCell>(static_obstacle).is_none(), "Static obstacles stay out of the hash");
+    let mut query = world.query::<(Entity, &OccupiedCell)>();
+    let report = world.resource::<SpatialHash>().debug_verify(query.iter(&world));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.entities_checked, colliders.len());
+    assert_eq!(report.total_entries, colliders.len());
+    assert_eq!(world.get::<OccupiedCell>(colliders[40]).unwrap().size_class, 1);
+
+    // Every collider is found by a query around it
+    let mut scratch = SpatialHashScratch::new(100);
+    for &entity in &colliders {
+        let pos = world.get::<SimPosition>(entity).unwrap().0;
+        world.resource::<SpatialHash>().query_radius(pos, FixedNum::from_num(1.0), None, &mut scratch);
+        assert!(scratch.query_results.contains(&entity), "{:?} missing from a query at its own position", entity);
+    }
+
+    // Rebuilding again is idempotent
+    let before: Vec<OccupiedCell> = colliders.iter().map(|&entity| *world.get::<OccupiedCell>(entity).unwrap()).collect();
+    rebuild_spatial_hash_from_world(&mut world);
+    let after: Vec<OccupiedCell> = colliders.iter().map(|&entity| *world.get::<OccupiedCell>(entity).unwrap()).collect();
+    assert_eq!(format!("{:?}", before), format!("{:?}", after));
+}
+
+#[test]
+fn test_batched_moves_with_swaps_keep_every_vec_idx_current() {
+    // Enough headroom (~5 slots a cell) that no move fills a cell and forces a rebuild
+    let mut app = create_live_app(4000, SpatialHashGrowth { sustain_ticks: u32::MAX, ..default() });
+    // Four units sharing the Grid A cell centered on (1, 1), plus a spread-out crowd
+    let offsets = [(-0.3, -0.3), (0.3, -0.3), (-0.3, 0.3), (0.3, 0.3)];
+    let shared: Vec<Entity> = offsets.iter()
+        .map(|&(dx, dy)| spawn_unit(&mut app, FixedVec2::from_f32(1.0 + dx, 1.0 + dy)))
+        .collect();
+    let crowd = spawn_in_grid_a(&mut app, 40);
+    app.update();
+    app.world_mut().resource_mut::<SpatialHashOverflow>().request_rebuild();
+    app.update();
+    assert_hash_consistent(&mut app);
+    let rebuilds = app.world().resource::<SpatialHashOverflow>().total_rebuilds;
+    let slot = |app: &App, entity: Entity| app.world().get::<OccupiedCell>(entity).unwrap().vec_idx;
+    let mut by_slot = shared.clone();
+    by_slot.sort_by_key(|&entity| slot(&app, entity));
+
+    // The first two slots leave the cell: each removal swaps the cell's last unit into the
+    // hole, and the last unit leaves too, from the slot it was swapped into
+    let moves = [(by_slot[0], (5.0, 1.0)), (by_slot[1], (1.0, 5.0)), (by_slot[3], (-3.0, 1.0))];
+    for &(entity, (x, y)) in &moves {
+        app.world_mut().get_mut::<SimPosition>(entity).unwrap().0 = FixedVec2::from_f32(x, y);
+    }
+    app.update();
+
+    assert_eq!(app.world().resource::<SpatialHashOverflow>().total_rebuilds, rebuilds, "Moves should be applied incrementally");
+    assert_hash_consistent(&mut app);
+    assert_eq!(slot(&app, by_slot[2]), 0, "The one unit left in the cell is packed into its first slot");
+
+    // Several ticks of mixed moves across the crowd stay consistent without rebuilding
+    for step in 1..=5 {
+        for (i, &unit) in crowd.iter().chain(&shared).enumerate() {
+            if (i + step) % 3 == 0 {
+                let mut pos = app.world_mut().get_mut::<SimPosition>(unit).unwrap();
+                pos.0 = pos.0 + FixedVec2::from_f32(2.0, 0.0);
+            }
+        }
+        app.update();
+        assert_hash_consistent(&mut app);
+    }
+    assert_eq!(app.world().resource::<SpatialHashOverflow>().total_rebuilds, rebuilds);
+}
+
+#[test]
+fn test_queries_mid_batch_are_consistent_and_the_barrier_waits_for_fixups() {
+    let mut world = World::new();
+    let mut hash = SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5);
+    assert!(hash.uses_incremental_updates());
+    let radius = FixedNum::from_num(0.5);
+    // Four units sharing the Grid A cell centered on (1, 1)
+    let units: Vec<Entity> = [(-0.3, -0.3), (0.3, -0.3), (-0.3, 0.3), (0.3, 0.3)].iter()
+        .map(|&(dx, dy)| {
+            let entity = world.spawn(SimPosition(FixedVec2::from_f32(1.0 + dx, 1.0 + dy))).id();
+            let occupied = hash.insert(entity, FixedVec2::from_f32(1.0 + dx, 1.0 + dy), radius).unwrap();
+            world.entity_mut(entity).insert(occupied);
+            entity
+        })
+        .collect();
+    let cells = |world: &mut World| -> Vec<(Entity, OccupiedCell)> {
+        world.query::<(Entity, &OccupiedCell)>().iter(world).map(|(entity, cell)| (entity, *cell)).collect()
+    };
+
+    // Half a tick: the first two slots move out (swapping the others around), no fixups yet
+    let before = cells(&mut world);
+    let mut by_slot = before.clone();
+    by_slot.sort_by_key(|(_, cell)| cell.vec_idx);
+    let moves = [(by_slot[0].0, FixedVec2::from_f32(5.0, 1.0)), (by_slot[1].0, FixedVec2::from_f32(1.0, 5.0))];
+    let mut pending = PendingVecIdxUpdates::default();
+    let (moved, rejected) = relocate_moved_entities(
+        &mut hash,
+        moves.iter().map(|&(entity, pos)| (entity, pos, &before.iter().find(|(e, _)| *e == entity).unwrap().1)),
+        &mut pending,
+    );
+    assert_eq!((moved, rejected), (2, 0));
+
+    // The barrier refuses while components are stale...
+    let overflow = SpatialHashOverflow::default();
+    let Err(SpatialHashNotReady::PendingFixups(count)) = spatial_hash_read_barrier(&pending, &overflow) else {
+        panic!("Barrier should report the pending fixups");
+    };
+    assert!(count >= moves.len());
+    assert!(!hash.debug_verify(before.iter().map(|(entity, cell)| (*entity, cell))).is_consistent());
+
+    // ...but queries already see every unit exactly once, movers at their new positions
+    let mut scratch = SpatialHashScratch::new(100);
+    hash.query_radius_with_positions(FixedVec2::from_f32(2.0, 2.0), FixedNum::from_num(10.0), None, &mut scratch);
+    let mut found = scratch.query_results.clone();
+    found.sort();
+    let mut expected = units.clone();
+    expected.sort();
+    assert_eq!(found, expected);
+    for &(entity, pos) in &moves {
+        let i = scratch.query_results.iter().position(|&e| e == entity).unwrap();
+        assert_eq!(scratch.query_positions[i], pos);
+    }
+    hash.query_radius(FixedVec2::from_f32(1.0, 1.0), FixedNum::from_num(0.6), None, &mut scratch);
+    assert!(moves.iter().all(|(entity, _)| !scratch.query_results.contains(entity)), "Movers left their old cell");
+    // Read through the batch, the stale components already agree with the arena
+    let current: Vec<(Entity, OccupiedCell)> = before.iter().map(|&(entity, cell)| (entity, pending.current(entity, &cell))).collect();
+    assert!(hash.debug_verify(current.iter().map(|(entity, cell)| (*entity, cell))).is_consistent());
+
+    // Once the fixups land the barrier opens and the components match
+    let mut commands = world.commands();
+    pending.apply(&mut commands);
+    world.flush();
+    assert_eq!(spatial_hash_read_barrier(&pending, &overflow), Ok(()));
+    let after = cells(&mut world);
+    assert!(hash.debug_verify(after.iter().map(|(entity, cell)| (*entity, cell))).is_consistent());
+
+    let overflowed = SpatialHashOverflow { rebuild_pending: true, ..default() };
+    assert_eq!(spatial_hash_read_barrier(&pending, &overflowed), Err(SpatialHashNotReady::RebuildPending));
+}
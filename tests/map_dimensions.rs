@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{MapDimensions, SimConfig};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::structures::FlowField;
+
+#[test]
+fn test_applying_map_dimensions_resizes_all_dependents_together() {
+    let mut sim_config = SimConfig::default();
+    let mut flow_field = FlowField::default();
+    let mut spatial_hash = SpatialHash::new(
+        FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5, 10.0], 4.0, 1000, 1.0,
+    );
+    let capacities: Vec<usize> = spatial_hash.size_classes().iter().map(|sc| sc.grid_a.storage_capacity()).collect();
+
+    let dimensions = MapDimensions::from_f32(300.0, 120.0);
+    dimensions.apply(&mut sim_config, &mut flow_field, &mut spatial_hash);
+
+    // SimConfig bounds, flow field and spatial hash all describe the same 300x120 map
+    assert_eq!(sim_config.map_size.get_width(), dimensions.width);
+    assert_eq!(sim_config.map_size.get_height(), dimensions.height);
+    assert_eq!((flow_field.width, flow_field.height), (300, 120));
+    assert_eq!(flow_field.origin, sim_config.map_size.top_left);
+    assert_eq!(flow_field.cost_field.len(), 300 * 120);
+    assert_eq!((spatial_hash.map_width(), spatial_hash.map_height()), (dimensions.width, dimensions.height));
+
+    // Size classes and arena capacity survive the resize
+    let resized: Vec<usize> = spatial_hash.size_classes().iter().map(|sc| sc.grid_a.storage_capacity()).collect();
+    assert_eq!(resized, capacities);
+
+    // The far corner of the new map is addressable in both grids
+    let corner = sim_config.map_size.bottom_right - FixedVec2::from_f32(0.5, 0.5);
+    assert_eq!(flow_field.world_to_grid(corner), Some((299, 119)));
+    let entity = World::new().spawn_empty().id();
+    assert!(spatial_hash.insert(entity, corner, FixedNum::from_num(0.5)).is_ok(), "Corner insert should not overflow");
+    let mut scratch = SpatialHashScratch::new(16);
+    spatial_hash.query_radius(corner, FixedNum::from_num(2.0), None, &mut scratch);
+    assert_eq!(scratch.query_results, vec![entity]);
+}
+
+#[test]
+fn test_startup_flow_field_follows_map_dimensions() {
+    use bevy::ecs::system::RunSystemOnce;
+    use peregrine::game::simulation::MapFlowField;
+    use peregrine::game::simulation::systems::init_flow_field;
+
+    let mut world = World::new();
+    world.init_resource::<MapFlowField>();
+    // SimConfig left at its (different) default map size
+    world.init_resource::<SimConfig>();
+    let dimensions = MapDimensions::from_f32(64.0, 32.0);
+    world.insert_resource(dimensions);
+    world.run_system_once(init_flow_field).unwrap();
+
+    let flow_field = &world.resource::<MapFlowField>().0;
+    assert_eq!((flow_field.width, flow_field.height), (64, 32));
+    assert_eq!(flow_field.origin, dimensions.map_size().top_left);
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::map::{MapEdges, MapSize};
+use peregrine::game::simulation::{Collider, SimConfig, SimTick, SimPosition, SimVelocity, SimAcceleration, SpatialHashOverflow};
+use peregrine::game::simulation::collision::{detect_collisions, resolve_collisions, CollisionEvent};
+use peregrine::game::simulation::physics::apply_velocity;
+use peregrine::game::simulation::systems::{update_spatial_hash, PendingVecIdxUpdates};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::{apply_boids_steering, spawn_unit_in_world};
+
+const MAP_SIZE: f32 = 20.0;
+
+/// 20x20 map centered on the origin
+fn map_size() -> MapSize {
+    MapSize {
+        top_left: FixedVec2::from_f32(-MAP_SIZE / 2.0, -MAP_SIZE / 2.0),
+        bottom_right: FixedVec2::from_f32(MAP_SIZE / 2.0, MAP_SIZE / 2.0),
+    }
+}
+
+/// Integration only, on the test map
+fn setup_edges_app(map_edges: MapEdges) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let mut sim_config = SimConfig::default();
+    sim_config.map_size = map_size();
+    sim_config.map_edges = map_edges;
+    app.insert_resource(sim_config);
+    app.init_resource::<SimTick>();
+    app.add_systems(FixedUpdate, apply_velocity);
+    app
+}
+
+fn spawn_mover(app: &mut App, position: FixedVec2, velocity: FixedVec2) -> Entity {
+    app.world_mut().spawn((
+        SimPosition(position),
+        SimVelocity(velocity),
+        SimAcceleration::default(),
+        Collider::ground_unit(FixedNum::from_num(0.5)),
+    )).id()
+}
+
+#[test]
+fn test_walls_keep_units_inside_the_border() {
+    let mut app = setup_edges_app(MapEdges::Walls);
+    let unit = spawn_mover(&mut app, FixedVec2::from_f32(8.0, 0.0), FixedVec2::from_f32(20.0, -20.0));
+
+    for _ in 0..30 {
+        app.world_mut().run_schedule(FixedUpdate);
+        let pos = app.world().get::<SimPosition>(unit).unwrap().0;
+        assert!(pos.x <= FixedNum::from_num(9.5) && pos.y >= FixedNum::from_num(-9.5), "Unit crossed the wall: {:?}", pos);
+    }
+
+    // Pressed into the corner, a radius from both walls, with nothing left pushing outward
+    assert_eq!(app.world().get::<SimPosition>(unit).unwrap().0, FixedVec2::from_f32(9.5, -9.5));
+    assert_eq!(app.world().get::<SimVelocity>(unit).unwrap().0, FixedVec2::ZERO);
+}
+
+#[test]
+fn test_open_edges_clamp_unit_centers_onto_the_border() {
+    let mut app = setup_edges_app(MapEdges::Open);
+    let unit = spawn_mover(&mut app, FixedVec2::from_f32(9.0, 0.0), FixedVec2::from_f32(45.0, 0.0));
+
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(app.world().get::<SimPosition>(unit).unwrap().0, FixedVec2::from_f32(10.0, 0.0));
+    assert_eq!(app.world().get::<SimVelocity>(unit).unwrap().0.x, FixedNum::ZERO);
+}
+
+#[test]
+fn test_wrap_moves_units_through_to_the_opposite_edge() {
+    let mut app = setup_edges_app(MapEdges::Wrap);
+    let velocity = FixedVec2::from_f32(30.0, 0.0);
+    let start = FixedVec2::from_f32(9.5, 3.0);
+    let unit = spawn_mover(&mut app, start, velocity);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    let pos = app.world().get::<SimPosition>(unit).unwrap().0;
+    // About a unit past the right edge comes out about a unit inside the left one
+    let step = velocity * app.world().resource::<SimConfig>().fixed_delta();
+    assert_eq!(pos, start + step - FixedVec2::from_f32(MAP_SIZE, 0.0));
+    assert!(pos.x < FixedNum::from_num(-9.0), "{:?}", pos);
+    assert_eq!(app.world().get::<SimVelocity>(unit).unwrap().0, velocity, "Wrapping keeps the unit's speed");
+}
+
+#[test]
+fn test_wrapped_query_finds_neighbors_across_the_seam() {
+    let mut hash = SpatialHash::new(
+        FixedNum::from_num(MAP_SIZE), FixedNum::from_num(MAP_SIZE), &[0.5], 4.0, 100, 2.0,
+    );
+    let mut world = World::new();
+    let querier = world.spawn_empty().id();
+    let across_x = world.spawn_empty().id();
+    let across_corner = world.spawn_empty().id();
+    let far = world.spawn_empty().id();
+    let near_edge = FixedVec2::from_f32(9.5, 9.5);
+    hash.insert(querier, near_edge, FixedNum::from_num(0.5)).unwrap();
+    hash.insert(across_x, FixedVec2::from_f32(-9.5, 9.0), FixedNum::from_num(0.5)).unwrap();
+    hash.insert(across_corner, FixedVec2::from_f32(-9.8, -9.8), FixedNum::from_num(0.5)).unwrap();
+    hash.insert(far, FixedVec2::from_f32(0.0, 0.0), FixedNum::from_num(0.5)).unwrap();
+
+    let mut scratch = SpatialHashScratch::new(100);
+    let radius = FixedNum::from_num(2.0);
+    hash.query_radius(near_edge, radius, Some(querier), &mut scratch);
+    assert!(scratch.query_results.is_empty(), "Without wrapping the seam is a hard edge");
+
+    hash.set_wraps(true);
+    hash.query_radius(near_edge, radius, Some(querier), &mut scratch);
+    let mut found = scratch.query_results.clone();
+    found.sort();
+    let mut expected = vec![across_x, across_corner];
+    expected.sort();
+    assert_eq!(found, expected);
+
+    // Searching one size class wraps the same way
+    hash.query_radius_in_class(near_edge, radius, 0, Some(querier), &mut scratch);
+    let mut found = scratch.query_results.clone();
+    found.sort();
+    assert_eq!(found, expected);
+
+    // Cached positions come back on the querier's side of the seam
+    hash.query_radius_with_positions(near_edge, radius, Some(querier), &mut scratch);
+    let position_of = |entity| {
+        let i = scratch.query_results.iter().position(|&e| e == entity).unwrap();
+        scratch.query_positions[i]
+    };
+    assert_eq!(position_of(across_x), FixedVec2::from_f32(10.5, 9.0));
+    assert_eq!(position_of(across_corner), FixedVec2::from_f32(10.2, 10.2));
+
+    // Distances take the short way across the seam, matching the shifted positions
+    let map_size = map_size();
+    assert_eq!(map_size.wrapped_delta(near_edge, FixedVec2::from_f32(-9.5, 9.0)), FixedVec2::from_f32(1.0, -0.5));
+    assert_eq!(map_size.wrapped_delta(FixedVec2::from_f32(-9.5, 9.0), near_edge), FixedVec2::from_f32(-1.0, 0.5));
+    assert_eq!(map_size.wrapped_delta(FixedVec2::ZERO, FixedVec2::from_f32(4.0, -3.0)), FixedVec2::from_f32(4.0, -3.0));
+}
+
+#[test]
+fn test_boids_and_collision_push_apart_across_the_seam() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(SimConfig {
+        map_size: map_size(),
+        map_edges: MapEdges::Wrap,
+        separation_weight: FixedNum::ONE,
+        alignment_weight: FixedNum::ZERO,
+        cohesion_weight: FixedNum::ZERO,
+        neighbor_radius: FixedNum::from_num(2.0),
+        separation_radius: FixedNum::from_num(2.0),
+        ..Default::default()
+    });
+    app.init_resource::<SimTick>();
+    app.insert_resource(SpatialHash::new(
+        FixedNum::from_num(MAP_SIZE), FixedNum::from_num(MAP_SIZE), &[0.5], 4.0, 100, 2.0,
+    ));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.add_message::<CollisionEvent>();
+    app.add_systems(FixedUpdate, (update_spatial_hash, apply_boids_steering, detect_collisions, resolve_collisions).chain());
+
+    // 0.8 apart the short way, overlapping across the right/left seam
+    let radius = FixedNum::from_num(0.5);
+    let east = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(9.6, 0.0), radius, 0);
+    let west = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-9.6, 0.0), radius, 1);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let events: Vec<CollisionEvent> = app.world().resource::<Messages<CollisionEvent>>()
+        .iter_current_update_messages().cloned().collect();
+    assert_eq!(events.len(), 1, "The pair collides across the seam");
+
+    // Apart the short way is back from the seam: the long way would close the 18.4 gap
+    let zero = FixedNum::ZERO;
+    assert!(app.world().get::<SimVelocity>(east).unwrap().0.x < zero, "Separation pushes east back from the seam");
+    assert!(app.world().get::<SimVelocity>(west).unwrap().0.x > zero, "Separation pushes west back from the seam");
+    assert!(app.world().get::<SimAcceleration>(east).unwrap().0.x < zero, "Collision pushes east back from the seam");
+    assert!(app.world().get::<SimAcceleration>(west).unwrap().0.x > zero, "Collision pushes west back from the seam");
+}
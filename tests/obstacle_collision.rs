@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::simulation::{
+    SimConfig, SimTick, MapFlowField, MapStatus, SimPosition, SimVelocity, SimAcceleration, Collider, ObstacleBundle, layers,
+};
+use peregrine::game::simulation::collision::resolve_obstacle_collisions;
+use peregrine::game::simulation::physics::{apply_friction, apply_velocity};
+use peregrine::game::structures::FlowField;
+use peregrine::game::unit::UnitBundle;
+
+/// Column of blocked cells covering world x in [2, 3)
+const WALL_COLUMN: usize = 12;
+
+/// 20x20 map of 1.0 cells centered on the origin, with a blocked column at `WALL_COLUMN`
+fn setup_app(terrain_baked: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimConfig>();
+    app.init_resource::<SimTick>();
+    app.insert_resource(MapStatus { loaded: false, terrain_baked });
+
+    let mut flow_field = FlowField::new(20, 20, FixedNum::ONE, FixedVec2::from_f32(-10.0, -10.0));
+    for y in 0..20 {
+        flow_field.set_obstacle(WALL_COLUMN, y);
+    }
+    app.insert_resource(MapFlowField(flow_field));
+
+    app.add_systems(FixedUpdate, (resolve_obstacle_collisions, apply_friction, apply_velocity).chain());
+    app
+}
+
+/// Unit at the origin heading for the wall at 3 units/s; returns the furthest x it reached
+fn run_towards_wall(app: &mut App, ticks: usize) -> FixedNum {
+    run_collider_towards_wall(app, ticks, Collider::default())
+}
+
+fn run_collider_towards_wall(app: &mut App, ticks: usize, collider: Collider) -> FixedNum {
+    let mut unit = UnitBundle::new(FixedVec2::ZERO, collider.radius, 0);
+    unit.collision.collider = collider;
+    unit.velocity = SimVelocity(FixedVec2::from_f32(3.0, 0.0));
+    let unit = app.world_mut().spawn(unit).id();
+
+    let mut max_x = FixedNum::ZERO;
+    for _ in 0..ticks {
+        // Keep pushing like a unit following a path through the wall would
+        app.world_mut().get_mut::<SimAcceleration>(unit).unwrap().0 = FixedVec2::from_f32(5.0, 0.0);
+        app.world_mut().run_schedule(FixedUpdate);
+        max_x = max_x.max(app.world().get::<SimPosition>(unit).unwrap().0.x);
+    }
+    max_x
+}
+
+#[test]
+fn test_baked_cost_field_blocks_unit_without_obstacle_entities() {
+    let mut app = setup_app(true);
+    let max_x = run_towards_wall(&mut app, 120);
+
+    let wall_x = app.world().resource::<MapFlowField>().0.grid_to_world(WALL_COLUMN, 10).x
+        - FixedNum::from_num(0.5);
+    assert!(max_x < wall_x, "Unit entered the blocked cell: reached x = {}, wall starts at {}", max_x, wall_x);
+    assert!(max_x > FixedNum::ONE, "Unit should have moved up to the wall, reached x = {}", max_x);
+}
+
+#[test]
+fn test_unbaked_map_collides_with_obstacle_entities_not_cost_field() {
+    // Stale cost field (not baked): the blocked column is ignored...
+    let mut app = setup_app(false);
+    let max_x = run_towards_wall(&mut app, 120);
+    assert!(max_x > FixedNum::from_num(3.0), "Unbaked map should not collide with the cost field, reached x = {}", max_x);
+
+    // ...and an obstacle entity placed in the editor is what stops the unit
+    let mut app = setup_app(false);
+    app.world_mut().spawn(ObstacleBundle::new(FixedVec2::from_f32(3.0, 0.0), FixedNum::ONE));
+    let max_x = run_towards_wall(&mut app, 120);
+    assert!(max_x < FixedNum::from_num(2.0), "Obstacle entity should stop the unit, reached x = {}", max_x);
+}
+
+/// Unit at the origin flying at the wall at 45 units/s (1.5 per tick, so a whole tick's move
+/// jumps the one-cell wall); returns its x after `ticks` ticks
+fn fire_at_wall(max_move_per_substep: f32, ticks: usize) -> FixedNum {
+    let mut app = setup_app(true);
+    app.world_mut().resource_mut::<SimConfig>().max_move_per_substep = FixedNum::from_num(max_move_per_substep);
+    let mut unit = UnitBundle::new(FixedVec2::ZERO, Collider::default().radius, 0);
+    unit.velocity = SimVelocity(FixedVec2::from_f32(45.0, 0.0));
+    let unit = app.world_mut().spawn(unit).id();
+
+    for _ in 0..ticks {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+    app.world().get::<SimPosition>(unit).unwrap().0.x
+}
+
+#[test]
+fn test_substepping_stops_fast_unit_tunneling_through_thin_wall() {
+    let wall_x = FixedNum::from_num(2.0);
+
+    let without = fire_at_wall(0.0, 10);
+    assert!(without > FixedNum::from_num(3.0), "Single-step integration should jump the wall, ended at x = {}", without);
+
+    let with = fire_at_wall(0.5, 10);
+    assert!(with < wall_x, "Sub-stepped unit should stop at the wall, ended at x = {}", with);
+    assert!(with > FixedNum::ONE, "Sub-stepped unit should still reach the wall, ended at x = {}", with);
+}
+
+#[test]
+fn test_flying_unit_passes_over_obstacles_ground_unit_collides() {
+    let ground = run_towards_wall(&mut setup_app(true), 120);
+    assert!(ground < FixedNum::from_num(2.0), "Ground unit should be stopped by the wall, reached x = {}", ground);
+
+    // Same runs with the collider on the flying layer, over baked terrain and past an
+    // obstacle entity on an unbaked map
+    let flyer = Collider::flying_unit(Collider::default().radius);
+    let flying = run_collider_towards_wall(&mut setup_app(true), 120, flyer);
+    assert!(flying > FixedNum::from_num(3.0), "Flying unit should pass over the wall, reached x = {}", flying);
+
+    let mut app = setup_app(false);
+    app.world_mut().spawn(ObstacleBundle::new(FixedVec2::from_f32(3.0, 0.0), FixedNum::ONE));
+    let flying = run_collider_towards_wall(&mut app, 120, flyer);
+    assert!(flying > FixedNum::from_num(4.0), "Flying unit should pass over the obstacle entity, reached x = {}", flying);
+}
+
+#[test]
+fn test_collider_layer_must_be_a_single_bit() {
+    assert!(Collider::projectile(FixedNum::ONE).is_valid());
+    assert!(!Collider { layer: layers::UNIT | layers::FLYING, ..Default::default() }.is_valid());
+    assert!(!Collider { layer: layers::NONE, ..Default::default() }.is_valid());
+    assert!(std::panic::catch_unwind(|| Collider::new(FixedNum::ONE, layers::ALL, layers::ALL)).is_err());
+}
@@ -0,0 +1,88 @@
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::simulation::{apply_obstacle_to_flow_field, remove_obstacle_from_flow_field};
+use peregrine::game::structures::FlowField;
+
+/// 20x20 map of 1.0 cells centered on the origin
+fn empty_field() -> FlowField {
+    FlowField::new(20, 20, FixedNum::ONE, FixedVec2::from_f32(-10.0, -10.0))
+}
+
+fn blocked_cells(flow_field: &FlowField) -> Vec<(usize, usize)> {
+    (0..flow_field.height)
+        .flat_map(|y| (0..flow_field.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| !flow_field.is_walkable(x, y))
+        .collect()
+}
+
+#[test]
+fn test_removing_lone_obstacle_restores_cost_field() {
+    let mut flow_field = empty_field();
+    let (pos, radius) = (FixedVec2::from_f32(1.5, -2.0), FixedNum::from_num(3));
+
+    apply_obstacle_to_flow_field(&mut flow_field, pos, radius);
+    assert!(!blocked_cells(&flow_field).is_empty());
+
+    remove_obstacle_from_flow_field(&mut flow_field, pos, radius, &[]);
+    assert_eq!(flow_field.cost_field, empty_field().cost_field);
+}
+
+#[test]
+fn test_removing_one_of_two_overlapping_obstacles_keeps_shared_cells() {
+    let radius = FixedNum::from_num(3);
+    let removed = FixedVec2::from_f32(-2.0, 0.0);
+    let kept = FixedVec2::from_f32(2.0, 0.0);
+
+    let mut only_kept = empty_field();
+    apply_obstacle_to_flow_field(&mut only_kept, kept, radius);
+    let mut only_removed = empty_field();
+    apply_obstacle_to_flow_field(&mut only_removed, removed, radius);
+
+    let mut flow_field = empty_field();
+    apply_obstacle_to_flow_field(&mut flow_field, removed, radius);
+    apply_obstacle_to_flow_field(&mut flow_field, kept, radius);
+    let kept_cells = blocked_cells(&only_kept);
+    let shared: Vec<_> = blocked_cells(&only_removed).into_iter().filter(|cell| kept_cells.contains(cell)).collect();
+    assert!(!shared.is_empty(), "Test obstacles should overlap");
+
+    remove_obstacle_from_flow_field(&mut flow_field, removed, radius, &[(kept, radius)]);
+
+    // Shared cells stay blocked, cells only the removed obstacle covered are walkable again
+    assert_eq!(blocked_cells(&flow_field), kept_cells);
+    for &(x, y) in &shared {
+        assert!(!flow_field.is_walkable(x, y), "Shared cell ({}, {}) should stay blocked", x, y);
+    }
+}
+
+#[test]
+fn test_removal_leaves_unrelated_obstacles_alone() {
+    let radius = FixedNum::from_num(2);
+    let mut flow_field = empty_field();
+    flow_field.set_obstacle(0, 0);
+    apply_obstacle_to_flow_field(&mut flow_field, FixedVec2::ZERO, radius);
+
+    // A baked wall outside the obstacle's footprint is not touched
+    remove_obstacle_from_flow_field(&mut flow_field, FixedVec2::ZERO, radius, &[]);
+    assert_eq!(blocked_cells(&flow_field), vec![(0, 0)]);
+}
+
+#[test]
+fn test_removal_restores_terrain_cost_under_obstacle() {
+    let (pos, radius) = (FixedVec2::ZERO, FixedNum::from_num(2));
+    let mut flow_field = empty_field();
+    for y in 0..flow_field.height {
+        for x in 0..flow_field.width {
+            flow_field.set_terrain_cost(x, y, 7);
+        }
+    }
+    let terrain = flow_field.cost_field.clone();
+
+    apply_obstacle_to_flow_field(&mut flow_field, pos, radius);
+    // Terrain repainted while the obstacle stands only shows once it is gone
+    flow_field.set_terrain_cost(10, 10, 20);
+    assert!(!flow_field.is_walkable(10, 10));
+
+    remove_obstacle_from_flow_field(&mut flow_field, pos, radius, &[]);
+    let mut expected = terrain;
+    expected[flow_field.get_index(10, 10)] = 20;
+    assert_eq!(flow_field.cost_field, expected);
+}
@@ -55,8 +55,7 @@ fn test_pathfinding_around_wall() {
         // Map is 50x50.
         let wall_x = 25;
         for y in 0..40 {
-            let idx = map.0.get_index(wall_x, y);
-            map.0.cost_field[idx] = 255;
+            map.0.set_obstacle(wall_x, y);
         }
     }
 
@@ -94,10 +93,7 @@ fn test_pathfinding_around_wall() {
     )).id();
 
     // 4. Send Path Request
-    app.world_mut().write_message(PathRequest {
-        entity: unit_entity,
-        goal: goal_pos,
-    });
+    app.world_mut().write_message(PathRequest::player(unit_entity, goal_pos));
 
     println!("Test started. Unit at {:?}, Goal at {:?}", start_pos, goal_pos);
 
@@ -226,10 +222,7 @@ fn test_pathfinding_close_target_line_of_sight() {
         
     )).id();
 
-    app.world_mut().write_message(PathRequest {
-        entity: unit_entity,
-        goal: goal_pos,
-    });
+    app.world_mut().write_message(PathRequest::player(unit_entity, goal_pos));
 
     // Run updates to process path request
     for _ in 0..2 {
@@ -336,10 +329,7 @@ fn test_pathfinding_close_target_obstacle() {
         
     )).id();
 
-    app.world_mut().write_message(PathRequest {
-        entity: unit_entity,
-        goal: goal_pos,
-    });
+    app.world_mut().write_message(PathRequest::player(unit_entity, goal_pos));
 
     // Run updates to process path request
     for _ in 0..2 {
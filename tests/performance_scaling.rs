@@ -103,10 +103,10 @@
 use bevy::prelude::*;
 use bevy::ecs::system::RunSystemOnce;
 use peregrine::game::simulation::components::{
-    SimPosition, SimPositionPrev, SimVelocity, SimAcceleration, Collider,
-    CollisionState, OccupiedCell, StaticObstacle, layers,
+    SimPosition, SimPositionPrev, SimVelocity, Collider,
+    OccupiedCell, StaticObstacle, layers,
 };
-use peregrine::game::unit::Unit;
+use peregrine::game::unit::UnitBundle;
 use peregrine::game::simulation::resources::{SimConfig, MapFlowField};
 use peregrine::game::simulation::systems::apply_obstacle_to_flow_field;
 use peregrine::game::simulation::collision::CollisionEvent;
@@ -116,7 +116,7 @@ use peregrine::game::simulation::systems;
 use peregrine::game::unit::apply_boids_steering;
 use peregrine::game::spatial_hash::SpatialHash;
 use peregrine::game::pathfinding::{
-    PathRequest, HierarchicalGraph,
+    PathRequest, PathFailed, HierarchicalGraph,
 };
 use peregrine::game::structures::FlowField;
 use peregrine::game::fixed_math::{FixedNum, FixedVec2};
@@ -666,13 +666,10 @@ fn generate_pathfinding_requests(
                     let goal_x = generator.rng.f32() * generator.map_size - half_size;
                     let goal_y = generator.rng.f32() * generator.map_size - half_size;
                     
-                    writer.write(PathRequest {
+                    writer.write(PathRequest::background(
                         entity,
-                        goal: FixedVec2::new(
-                            FixedNum::from_num(goal_x),
-                            FixedNum::from_num(goal_y),
-                        ),
-                    });
+                        FixedVec2::new(FixedNum::from_num(goal_x), FixedNum::from_num(goal_y)),
+                    ));
                 }
             }
         }
@@ -698,10 +695,7 @@ fn generate_pathfinding_requests(
                 
                 for &idx in selected_indices.iter().take(request_count) {
                     if let Some(&entity) = entities.get(idx) {
-                        writer.write(PathRequest {
-                            entity,
-                            goal: common_goal,
-                        });
+                        writer.write(PathRequest::player(entity, common_goal));
                     }
                 }
             }
@@ -786,8 +780,9 @@ fn run_perf_test(config: PerfTestConfig) -> PerfTestResult {
     );
     app.insert_resource(MapFlowField(flow_field));
     
-    // Add pathfinding message
+    // Add pathfinding messages
     app.add_message::<PathRequest>();
+    app.add_message::<PathFailed>();
     
     // Spawn units with ALL necessary components for realistic simulation
     let half_size = map_size / 2.0;
@@ -801,23 +796,13 @@ fn run_perf_test(config: PerfTestConfig) -> PerfTestResult {
         
         let pos = FixedVec2::new(FixedNum::from_num(x), FixedNum::from_num(y));
         
-        app.world_mut().spawn((
-            Unit, // Required for boids steering to run
-            SimPosition(pos),
-            SimPositionPrev(pos),
-            SimVelocity(FixedVec2::new(
-                FixedNum::from_num(vx),
-                FixedNum::from_num(vy),
-            )),
-            SimAcceleration(FixedVec2::ZERO),
-            Collider::default(), // Collision detection enabled
-            CollisionState::default(),
-            OccupiedCell::default(),
-            // Pathfinding components (required for ActivePathSet navigation)
-            peregrine::game::pathfinding::Path::Inactive,
-            peregrine::game::pathfinding::GoalNavCell::default(),
-            peregrine::game::collections::InclusionIndex::default(),
+        // Full simulated component set; OccupiedCell is added by update_spatial_hash on the first tick
+        let mut unit = UnitBundle::new(pos, Collider::default().radius, 0);
+        unit.velocity = SimVelocity(FixedVec2::new(
+            FixedNum::from_num(vx),
+            FixedNum::from_num(vy),
         ));
+        app.world_mut().spawn(unit);
     }
     
     // Add some obstacles to make collision detection actually work
@@ -892,6 +877,8 @@ fn run_perf_test(config: PerfTestConfig) -> PerfTestResult {
     app.insert_resource(nav_lookup);
     app.insert_resource(nav_routing);
     app.insert_resource(peregrine::game::pathfinding::ActivePathSet::default());
+    app.init_resource::<peregrine::game::pathfinding::PendingPathRequests>();
+    app.init_resource::<peregrine::game::pathfinding::PathRequestStats>();
     
     // Add pathfinding request generator (deterministic)
     app.insert_resource(PathRequestGenerator {
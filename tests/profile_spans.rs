@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex};
+use bevy::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::log::tracing::{self, field::{Field, Visit}, span};
+use bevy::log::tracing_subscriber::{layer::{Context, SubscriberExt}, Layer, Registry};
+use peregrine::game::simulation::SimTick;
+use peregrine_macros::profile;
+
+/// A span seen by [`CaptureLayer`]: its name and recorded fields as `(name, debug value)`
+#[derive(Debug, Clone, PartialEq)]
+struct CapturedSpan {
+    name: &'static str,
+    fields: Vec<(String, String)>,
+}
+
+/// Records every new span into a shared list
+#[derive(Clone, Default)]
+struct CaptureLayer {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.push((field.name().to_string(), format!("{:?}", value)));
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, S>) {
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().push(CapturedSpan { name: attrs.metadata().name(), fields });
+    }
+}
+
+#[profile]
+fn profiled_tick_system(tick: Res<SimTick>) {
+    let _ = tick.0;
+}
+
+#[profile]
+fn profiled_helper() -> u32 {
+    7
+}
+
+/// Run `f` with a [`CaptureLayer`] installed as the thread's subscriber
+fn capture_spans(f: impl FnOnce()) -> Vec<CapturedSpan> {
+    let layer = CaptureLayer::default();
+    let spans = layer.spans.clone();
+    tracing::subscriber::with_default(Registry::default().with(layer), f);
+    let captured = spans.lock().unwrap().clone();
+    captured
+}
+
+#[test]
+fn test_profile_span_records_function_name_and_tick() {
+    let mut world = World::new();
+    world.insert_resource(SimTick(42));
+
+    let spans = capture_spans(|| {
+        world.run_system_once(profiled_tick_system).unwrap();
+    });
+    let ours: Vec<_> = spans.iter().filter(|s| s.name == "profiled_tick_system").collect();
+
+    if cfg!(feature = "trace_spans") {
+        assert_eq!(ours.len(), 1, "Expected one span for the profiled system, got {:?}", spans);
+        assert_eq!(ours[0].fields, vec![("tick".to_string(), "42".to_string())]);
+    } else {
+        assert!(ours.is_empty(), "No spans without the trace_spans feature, got {:?}", ours);
+    }
+}
+
+#[test]
+fn test_profile_span_without_tick_has_no_fields() {
+    let mut result = 0;
+    let spans = capture_spans(|| result = profiled_helper());
+    assert_eq!(result, 7, "Profiling must not change the return value");
+
+    if cfg!(feature = "trace_spans") {
+        assert_eq!(spans, vec![CapturedSpan { name: "profiled_helper", fields: Vec::new() }]);
+    } else {
+        assert!(spans.is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::map::{MapEdges, MapSize};
+use peregrine::game::simulation::{SimConfig, SimConfigError, MAX_COLLISION_ITERATIONS};
+
+fn square_map(size: f32) -> MapSize {
+    MapSize {
+        top_left: FixedVec2::from_f32(-size / 2.0, -size / 2.0),
+        bottom_right: FixedVec2::from_f32(size / 2.0, size / 2.0),
+    }
+}
+
+#[test]
+fn test_valid_builder_settings_build() {
+    let config = SimConfig::builder()
+        .tick_rate(20.0)
+        .map_size(square_map(64.0))
+        .map_edges(MapEdges::Wrap)
+        .unit_radius(FixedNum::from_num(0.75))
+        .collision_iterations(MAX_COLLISION_ITERATIONS)
+        .collision_detection_margin(FixedNum::from_num(0.1))
+        .max_entity_count(500)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.tick_rate, 20.0);
+    assert_eq!(config.map_size.get_width(), FixedNum::from_num(64));
+    assert_eq!(config.map_edges, MapEdges::Wrap);
+    assert_eq!(config.unit_radius, FixedNum::from_num(0.75));
+    assert_eq!(config.collision_iterations, MAX_COLLISION_ITERATIONS);
+    assert_eq!(config.max_entity_count, 500);
+    // Untouched fields keep their defaults
+    assert_eq!(config.friction, SimConfig::default().friction);
+
+    assert!(SimConfig::default().validate().is_ok(), "Defaults should be valid");
+}
+
+#[test]
+fn test_invalid_tick_rate_is_rejected() {
+    for rate in [0.0, -30.0, f64::NAN, f64::INFINITY] {
+        let err = SimConfig::builder().tick_rate(rate).build().err();
+        assert!(matches!(err, Some(SimConfigError::InvalidTickRate(_))), "tick rate {}: {:?}", rate, err);
+    }
+}
+
+#[test]
+fn test_empty_map_is_rejected() {
+    let err = SimConfig::builder().map_size(square_map(0.0)).build().err();
+    assert_eq!(err, Some(SimConfigError::EmptyMap { width: FixedNum::ZERO, height: FixedNum::ZERO }));
+
+    // Corners swapped
+    let inverted = MapSize { top_left: FixedVec2::from_f32(5.0, 0.0), bottom_right: FixedVec2::from_f32(-5.0, 10.0) };
+    assert!(matches!(SimConfig::builder().map_size(inverted).build(), Err(SimConfigError::EmptyMap { .. })));
+}
+
+#[test]
+fn test_other_invalid_fields_are_rejected() {
+    assert_eq!(SimConfig::builder().collision_iterations(0).build().err(), Some(SimConfigError::CollisionIterations(0)));
+    assert_eq!(
+        SimConfig::builder().collision_iterations(MAX_COLLISION_ITERATIONS + 1).build().err(),
+        Some(SimConfigError::CollisionIterations(MAX_COLLISION_ITERATIONS + 1)),
+    );
+    assert_eq!(
+        SimConfig::builder().unit_radius(FixedNum::ZERO).build().err(),
+        Some(SimConfigError::NonPositiveUnitRadius(FixedNum::ZERO)),
+    );
+    assert_eq!(
+        SimConfig::builder().collision_search_radius_multiplier(FixedNum::ZERO).build().err(),
+        Some(SimConfigError::NonPositiveSearchRadius(FixedNum::ZERO)),
+    );
+    assert_eq!(
+        SimConfig::builder().collision_detection_margin(FixedNum::from_num(-0.5)).build().err(),
+        Some(SimConfigError::NegativeDetectionMargin(FixedNum::from_num(-0.5))),
+    );
+    let err = SimConfig::builder().max_entity_count(0).build().err().unwrap();
+    assert_eq!(err, SimConfigError::ZeroMaxEntityCount);
+    assert_eq!(err.to_string(), "max entity count must be at least 1");
+    assert_eq!(
+        SimConfig::builder().max_catchup_ticks(0).build().err(),
+        Some(SimConfigError::ZeroMaxCatchupTicks),
+    );
+    assert_eq!(
+        SimConfig::builder().path_requests_per_tick(0).build().err(),
+        Some(SimConfigError::ZeroPathRequestsPerTick),
+    );
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use peregrine::game::simulation::{SimConfig, SimOverloaded, SimOverloadChanged};
+use peregrine::game::simulation::systems::limit_sim_catchup;
+
+#[derive(Resource, Default)]
+struct TickCount(u32);
+
+fn count_tick(mut count: ResMut<TickCount>) {
+    count.0 += 1;
+}
+
+/// Every `SimOverloadChanged` seen, in order
+#[derive(Resource, Default)]
+struct OverloadLog(Vec<bool>);
+
+fn record_overload_changes(mut messages: MessageReader<SimOverloadChanged>, mut log: ResMut<OverloadLog>) {
+    log.0.extend(messages.read().map(|message| message.overloaded));
+}
+
+/// Fixed loop at 30 TPS with the catch-up limiter, where every frame "takes" `frame_time`
+fn setup_app(frame_time: Duration, max_catchup_ticks: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(Time::<Fixed>::from_hz(30.0));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+    app.insert_resource(SimConfig { max_catchup_ticks, ..default() });
+    app.init_resource::<SimOverloaded>();
+    app.init_resource::<TickCount>();
+    app.init_resource::<OverloadLog>();
+    app.add_message::<SimOverloadChanged>();
+    app.add_systems(RunFixedMainLoop, limit_sim_catchup.in_set(RunFixedMainLoopSystems::BeforeFixedMainLoop));
+    app.add_systems(FixedUpdate, count_tick);
+    app.add_systems(Update, record_overload_changes);
+    app
+}
+
+/// Run one frame and return how many fixed ticks it simulated
+fn ticks_in_frame(app: &mut App) -> u32 {
+    app.world_mut().resource_mut::<TickCount>().0 = 0;
+    app.update();
+    app.world().resource::<TickCount>().0
+}
+
+fn overload_messages(app: &App) -> Vec<bool> {
+    app.world().resource::<OverloadLog>().0.clone()
+}
+
+#[test]
+fn test_slow_frames_do_not_cause_unbounded_catch_up() {
+    // Every frame takes 2s of real time: 60 ticks' worth at 30 TPS
+    let mut app = setup_app(Duration::from_secs(2), 3);
+
+    // The first frames apply the limit (and the first real delta is zero)
+    ticks_in_frame(&mut app);
+    ticks_in_frame(&mut app);
+
+    let ticks: Vec<u32> = (0..20).map(|_| ticks_in_frame(&mut app)).collect();
+    assert!(ticks.iter().all(|&n| n <= 3), "Catch-up exceeded max_catchup_ticks: {:?}", ticks);
+    assert!(ticks.iter().all(|&n| n >= 2), "Overloaded frames should still advance the sim: {:?}", ticks);
+
+    let overloaded = app.world().resource::<SimOverloaded>();
+    assert!(overloaded.active);
+    assert!(overloaded.dropped >= Duration::from_secs(30), "Dropped time should accumulate, got {:?}", overloaded.dropped);
+    assert_eq!(overload_messages(&app), vec![true]);
+}
+
+#[test]
+fn test_overload_clears_after_recovery_frames() {
+    let mut app = setup_app(Duration::from_secs(1), 4);
+    for _ in 0..3 {
+        ticks_in_frame(&mut app);
+    }
+    assert!(app.world().resource::<SimOverloaded>().active);
+
+    // Back to real-time frames: one tick each, and the flag clears after recovery_frames
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / 30.0)));
+    let recovery_frames = app.world().resource::<SimOverloaded>().recovery_frames;
+    for _ in 0..recovery_frames + 1 {
+        assert!(ticks_in_frame(&mut app) <= 2);
+    }
+
+    assert!(!app.world().resource::<SimOverloaded>().active);
+    assert_eq!(overload_messages(&app), vec![true, false]);
+}
+
+#[test]
+fn test_low_cap_bounds_a_single_long_frame() {
+    let mut app = setup_app(Duration::from_secs_f64(1.0 / 30.0), 1);
+    for _ in 0..5 {
+        ticks_in_frame(&mut app);
+    }
+
+    // One hitch worth 30 ticks still runs a single one
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)));
+    assert_eq!(ticks_in_frame(&mut app), 1);
+
+    // Raising the cap takes effect from the next frame
+    app.world_mut().resource_mut::<SimConfig>().max_catchup_ticks = 5;
+    ticks_in_frame(&mut app);
+    let ticks = ticks_in_frame(&mut app);
+    assert!((2..=5).contains(&ticks), "Expected catch-up bounded by the new cap, got {}", ticks);
+}
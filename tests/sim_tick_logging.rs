@@ -0,0 +1,73 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use bevy::prelude::*;
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::log::tracing;
+use tracing_subscriber::{fmt, layer::SubscriberExt, Registry};
+use peregrine::game::logging::{current_sim_tick, SimTickFormat};
+use peregrine::game::simulation::{SimPerformance, SimTick};
+use peregrine::game::simulation::systems::{increment_sim_tick, sim_start, sim_end};
+
+/// `MakeWriter` that appends everything to a shared buffer
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> fmt::MakeWriter<'a> for SharedBuffer {
+    type Writer = SharedBuffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn log_inside_tick() {
+    info!("collision pass done");
+}
+
+#[test]
+fn test_logs_inside_fixed_tick_carry_tick_number() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.init_resource::<SimPerformance>();
+    app.init_resource::<Time<Fixed>>();
+    app.add_systems(FixedUpdate, (increment_sim_tick, sim_start, log_inside_tick, sim_end).chain());
+    // Keep every system on this thread so the scoped subscriber below sees their logs
+    app.edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+
+    let buffer = SharedBuffer::default();
+    let subscriber = Registry::default().with(
+        fmt::layer()
+            .event_format(SimTickFormat(fmt::format().with_target(false)))
+            .with_writer(buffer.clone())
+            .with_ansi(false),
+    );
+
+    tracing::subscriber::with_default(subscriber, || {
+        app.world_mut().run_schedule(FixedUpdate);
+        app.world_mut().run_schedule(FixedUpdate);
+        info!("between ticks");
+    });
+
+    assert_eq!(current_sim_tick(), None, "sim_end should clear the tick");
+
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 3, "Unexpected log output:\n{}", output);
+    assert!(lines[0].starts_with("tick=1 ") && lines[0].contains("collision pass done"), "{}", lines[0]);
+    assert!(lines[1].starts_with("tick=2 ") && lines[1].contains("collision pass done"), "{}", lines[1]);
+    assert!(!lines[2].contains("tick=") && lines[2].contains("between ticks"), "{}", lines[2]);
+}
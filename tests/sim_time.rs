@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::simulation::{SimConfig, SimTick, SimTime, SimPosition, SimVelocity, SimAcceleration};
+use peregrine::game::simulation::systems::{increment_sim_tick, update_sim_time};
+use peregrine::game::simulation::physics::{apply_friction, apply_velocity};
+
+#[test]
+fn test_tick_to_seconds_at_common_tick_rates() {
+    for (tick_rate, ticks, seconds) in [(30.0, 30, 1.0), (30.0, 45, 1.5), (20.0, 20, 1.0), (20.0, 5, 0.25), (60.0, 600, 10.0)] {
+        let time = SimTime::new(0, tick_rate);
+        assert_eq!(time.seconds(ticks), FixedNum::from_num(seconds), "{} ticks at {} Hz", ticks, tick_rate);
+        assert_eq!(time.ticks_for(FixedNum::from_num(seconds)), ticks, "{} s at {} Hz", seconds, tick_rate);
+    }
+}
+
+#[test]
+fn test_partial_ticks_round_up() {
+    // 0.1 s is 3 ticks at 30 Hz but 2 ticks at 20 Hz; a non-multiple never rounds down
+    assert_eq!(SimTime::new(0, 30.0).ticks_for(FixedNum::from_num(0.1)), 3);
+    assert_eq!(SimTime::new(0, 20.0).ticks_for(FixedNum::from_num(0.1)), 2);
+    assert_eq!(SimTime::new(0, 20.0).ticks_for(FixedNum::from_num(0.11)), 3);
+    assert_eq!(SimTime::new(0, 30.0).ticks_for(FixedNum::ZERO), 0);
+    assert_eq!(SimTime::new(0, 30.0).ticks_for(FixedNum::from_num(-2.0)), 0);
+}
+
+#[test]
+fn test_elapsed_ticks_and_cooldowns() {
+    let time = SimTime::new(100, 20.0);
+    assert_eq!(time.ticks_since(40), 60);
+    assert_eq!(time.ticks_since(150), 0, "A tick in the future has no elapsed time");
+
+    // A 2 s cooldown started at tick 60 is ready from tick 100 on at 20 Hz
+    assert!(time.has_elapsed(60, FixedNum::from_num(2.0)));
+    assert!(!time.has_elapsed(61, FixedNum::from_num(2.0)));
+}
+
+#[test]
+fn test_sim_time_follows_tick_and_configured_rate() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.init_resource::<SimTime>();
+    app.insert_resource(SimConfig { tick_rate: 20.0, ..Default::default() });
+    app.add_systems(FixedUpdate, (increment_sim_tick, update_sim_time).chain());
+
+    for _ in 0..3 {
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+
+    let time = *app.world().resource::<SimTime>();
+    assert_eq!(time.current_tick(), 3);
+    assert_eq!(time.tick_rate(), FixedNum::from_num(20));
+    assert_eq!(time.seconds(time.current_tick()), FixedNum::from_num(0.15));
+}
+
+/// Seconds until a unit launched at 10 units/s coasts to rest, and the distance it covers
+fn coast_to_rest(tick_rate: f64) -> (f32, f32) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimTick>();
+    app.insert_resource(SimConfig { tick_rate, ..Default::default() });
+    app.add_systems(FixedUpdate, (apply_friction, apply_velocity).chain());
+    let unit = app.world_mut().spawn((
+        SimPosition(FixedVec2::ZERO), SimVelocity(FixedVec2::from_f32(10.0, 0.0)), SimAcceleration::default(),
+    )).id();
+
+    let mut ticks = 0;
+    while app.world().get::<SimVelocity>(unit).unwrap().0 != FixedVec2::ZERO {
+        app.world_mut().run_schedule(FixedUpdate);
+        ticks += 1;
+        assert!(ticks < 100_000, "Unit never came to rest at {} TPS", tick_rate);
+    }
+    let distance = app.world().get::<SimPosition>(unit).unwrap().0.x.to_num::<f32>();
+    (ticks as f32 / tick_rate as f32, distance)
+}
+
+#[test]
+fn test_friction_decelerates_over_same_time_at_any_tick_rate() {
+    let (time_20, distance_20) = coast_to_rest(20.0);
+    let (time_100, distance_100) = coast_to_rest(100.0);
+    assert!((time_20 - time_100).abs() <= 0.05, "Stopped after {}s at 20 TPS but {}s at 100 TPS", time_20, time_100);
+    // Only the integration step differs between the two rates
+    assert!((distance_20 - distance_100).abs() / distance_100 < 0.1, "Coasted {} at 20 TPS but {} at 100 TPS", distance_20, distance_100);
+
+    // Unscaled per-tick friction would stop the unit 5x sooner at 100 TPS
+    let config = SimConfig { tick_rate: 100.0, ..Default::default() };
+    assert!(config.friction_per_tick() > config.friction);
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedNum, FixedVec2};
+use peregrine::game::simulation::MapFlowField;
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::structures::FlowField;
+use peregrine::game::unit::{acquire_targets, spawn_unit_in_world, AttackTarget, Health, TargetAcquisition};
+
+/// Target acquisition only, on an open 40x40 map with a wall from (20, 10) to (20, 18)
+fn setup_targeting_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    let mut flow_field = FlowField::new(40, 40, FixedNum::ONE, FixedVec2::ZERO);
+    for y in 10..=18 {
+        flow_field.set_obstacle(20, y);
+    }
+    app.insert_resource(MapFlowField(flow_field));
+    // The hash is centred on the origin, so it needs twice the map's size to cover (0, 0)..(40, 40)
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(80.0), FixedNum::from_num(80.0), &[0.5], 4.0, 100, 1.5));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.add_systems(FixedUpdate, acquire_targets);
+    app
+}
+
+fn target_of(app: &App, entity: Entity) -> Option<Entity> {
+    app.world().get::<AttackTarget>(entity).map(|target| target.0)
+}
+
+#[test]
+fn test_wall_hides_an_enemy_at_the_same_distance_as_a_visible_one() {
+    let mut app = setup_targeting_app();
+    let radius = FixedNum::from_num(0.5);
+    let hunter = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(15.5, 14.5), radius, 0);
+    app.world_mut().entity_mut(hunter).insert(TargetAcquisition::new(FixedNum::from_num(10.0)));
+    // Both 8 away: one straight through the wall, one straight down in the open
+    let hidden = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(23.5, 14.5), radius, 1);
+    let visible = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(15.5, 6.5), radius, 1);
+    // Closer, but on the hunter's team
+    spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(17.5, 14.5), radius, 0);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), Some(visible));
+
+    // With the visible one dead, the one behind the wall still isn't acquired
+    app.world_mut().get_mut::<Health>(visible).unwrap().current = 0.0;
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), None);
+
+    // Knock a hole in the wall and the hidden one comes into view
+    app.world_mut().resource_mut::<MapFlowField>().0.clear_obstacle(20, 14);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), Some(hidden));
+}
+
+#[test]
+fn test_nearest_visible_enemy_wins_and_range_is_respected() {
+    let mut app = setup_targeting_app();
+    let radius = FixedNum::from_num(0.5);
+    let hunter = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(5.5, 5.5), radius, 0);
+    app.world_mut().entity_mut(hunter).insert(TargetAcquisition::new(FixedNum::from_num(6.0)));
+    let far = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(5.5, 10.5), radius, 2);
+    let near = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(8.5, 5.5), radius, 1);
+    // 7 away, past the sight range
+    spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(12.5, 5.5), radius, 1);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), Some(near));
+
+    app.world_mut().despawn(near);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), Some(far));
+
+    app.world_mut().despawn(far);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), None);
+}
+
+#[test]
+fn test_enemy_across_the_seam_is_seen_the_short_way() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    // 20x20 wrapping map centred on the origin, walled down the middle
+    let mut flow_field = FlowField::new(20, 20, FixedNum::ONE, FixedVec2::from_f32(-10.0, -10.0));
+    for y in 0..20 {
+        flow_field.set_obstacle(10, y);
+    }
+    app.insert_resource(MapFlowField(flow_field));
+    let mut spatial_hash = SpatialHash::new(FixedNum::from_num(20.0), FixedNum::from_num(20.0), &[0.5], 4.0, 100, 1.5);
+    spatial_hash.set_wraps(true);
+    app.insert_resource(spatial_hash);
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.add_systems(FixedUpdate, acquire_targets);
+
+    let radius = FixedNum::from_num(0.5);
+    let hunter = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(9.5, 0.5), radius, 0);
+    app.world_mut().entity_mut(hunter).insert(TargetAcquisition::new(FixedNum::from_num(4.0)));
+    // Two cells away over the seam, the width of the map away through the wall
+    let enemy = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-8.5, 0.5), radius, 1);
+
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), Some(enemy));
+
+    // An obstacle just over the seam blocks that short way
+    app.world_mut().resource_mut::<MapFlowField>().0.set_obstacle(0, 10);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(target_of(&app, hunter), None);
+}
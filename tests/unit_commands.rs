@@ -0,0 +1,395 @@
+use bevy::prelude::*;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{SimConfig, SimTick, SimPosition, SimVelocity, SimAcceleration, ActiveUnitSet, MapFlowField, UnitMoveCommand, UnitStopCommand, SpawnUnitCommand, WaypointQueue};
+use peregrine::game::simulation::systems::{process_input, wake_units, sweep_idle_units, update_spatial_hash, PendingVecIdxUpdates};
+use peregrine::game::simulation::physics::{apply_friction, apply_velocity};
+use peregrine::game::pathfinding::{Path, PathState, PathRequest, PathFailed, PendingPathRequests, ActivePathSet, PathRequestStats, HierarchicalGraph, NavigationLookup, process_path_requests};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::unit::{spawn_unit_in_world, Unit};
+
+/// Minimal app running only the command processing system
+fn setup_command_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimConfig>();
+    app.add_message::<UnitMoveCommand>();
+    app.add_message::<UnitStopCommand>();
+    app.add_message::<SpawnUnitCommand>();
+    app.add_message::<PathRequest>();
+    app.add_message::<PathFailed>();
+    app.add_systems(FixedUpdate, process_input);
+    app
+}
+
+#[test]
+fn test_stop_command_halts_moving_pathing_unit() {
+    let mut app = setup_command_app();
+
+    let target = FixedVec2::new(FixedNum::from_num(50.0), FixedNum::from_num(0.0));
+    let unit = app.world_mut().spawn((
+        Unit,
+        SimPosition(FixedVec2::ZERO),
+        SimVelocity(FixedVec2::new(FixedNum::from_num(5.0), FixedNum::from_num(0.0))),
+        SimAcceleration(FixedVec2::new(FixedNum::from_num(2.0), FixedNum::from_num(1.0))),
+        Path::Active(PathState::Direct(target)),
+    )).id();
+
+    app.world_mut().write_message(UnitStopCommand { player_id: 0, entity: unit });
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let path = app.world().get::<Path>(unit).unwrap();
+    assert!(matches!(path, Path::Inactive), "Stop command should clear the path, got {:?}", path);
+    assert_eq!(app.world().get::<SimVelocity>(unit).unwrap().0, FixedVec2::ZERO);
+    assert_eq!(app.world().get::<SimAcceleration>(unit).unwrap().0, FixedVec2::ZERO);
+}
+
+#[test]
+fn test_stop_command_only_affects_target_unit() {
+    let mut app = setup_command_app();
+
+    let velocity = FixedVec2::new(FixedNum::from_num(1.0), FixedNum::from_num(1.0));
+    let target = FixedVec2::new(FixedNum::from_num(10.0), FixedNum::from_num(10.0));
+    let stopped = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity(velocity), SimAcceleration::default(),
+        Path::Active(PathState::Direct(target)),
+    )).id();
+    let moving = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity(velocity), SimAcceleration::default(),
+        Path::Active(PathState::Direct(target)),
+    )).id();
+
+    app.world_mut().write_message(UnitStopCommand { player_id: 0, entity: stopped });
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(matches!(app.world().get::<Path>(stopped).unwrap(), Path::Inactive));
+    assert!(matches!(app.world().get::<Path>(moving).unwrap(), Path::Active(_)));
+    assert_eq!(app.world().get::<SimVelocity>(moving).unwrap().0, velocity);
+}
+
+#[test]
+fn test_stop_command_cancels_deferred_path_request() {
+    let mut app = setup_command_app();
+    app.init_resource::<PendingPathRequests>();
+
+    let unit = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity::default(), SimAcceleration::default(), Path::Inactive,
+    )).id();
+    let goal = FixedVec2::new(FixedNum::from_num(30.0), FixedNum::from_num(0.0));
+    app.world_mut().resource_mut::<PendingPathRequests>().push(&PathRequest::player(unit, goal));
+
+    app.world_mut().write_message(UnitStopCommand { player_id: 0, entity: unit });
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(!app.world().resource::<PendingPathRequests>().contains(unit), "Stopped unit should not path later");
+}
+
+#[test]
+fn test_stop_command_clears_waypoints_and_in_flight_path_requests() {
+    let mut app = setup_command_app();
+    app.init_resource::<PendingPathRequests>();
+    // Path processing without a map: everything read is left waiting in the pending queue
+    app.init_resource::<MapFlowField>();
+    app.init_resource::<HierarchicalGraph>();
+    app.init_resource::<NavigationLookup>();
+    app.init_resource::<ActivePathSet>();
+    app.init_resource::<PathRequestStats>();
+    app.add_systems(FixedUpdate, process_path_requests.after(process_input));
+
+    let waypoints = [FixedVec2::from_f32(10.0, 0.0), FixedVec2::from_f32(10.0, 10.0)];
+    let unit = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity::default(), SimAcceleration::default(),
+        Path::Active(PathState::Direct(waypoints[0])), WaypointQueue(waypoints.into()),
+    )).id();
+    // One request deferred from an earlier tick, one sent but not read yet
+    app.world_mut().resource_mut::<PendingPathRequests>().push(&PathRequest::player(unit, waypoints[0]));
+    app.world_mut().write_message(PathRequest::background(unit, waypoints[1]));
+
+    app.world_mut().write_message(UnitStopCommand { player_id: 0, entity: unit });
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(matches!(app.world().get::<Path>(unit).unwrap(), Path::Inactive));
+    assert!(app.world().get::<WaypointQueue>(unit).unwrap().0.is_empty(), "Stop should drop queued waypoints");
+    assert!(!app.world().resource::<PendingPathRequests>().contains(unit), "No request should survive the stop");
+
+    // A later order goes through as usual
+    let target = FixedVec2::from_f32(-5.0, 0.0);
+    app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target, queued: false });
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(app.world().resource::<PendingPathRequests>().contains(unit));
+}
+
+fn spawn_at(app: &mut App, x: f32, y: f32) {
+    app.world_mut().write_message(SpawnUnitCommand { player_id: 0, position: FixedVec2::from_f32(x, y), radius: None });
+}
+
+fn unit_positions(app: &mut App) -> Vec<FixedVec2> {
+    let mut query = app.world_mut().query_filtered::<&SimPosition, With<Unit>>();
+    query.iter(app.world()).map(|p| p.0).collect()
+}
+
+#[test]
+fn test_out_of_bounds_spawn_is_clamped_into_map() {
+    let mut app = setup_command_app();
+    let map_size = app.world().resource::<SimConfig>().map_size.clone();
+    let margin = app.world().resource::<SimConfig>().unit_radius;
+
+    spawn_at(&mut app, 5000.0, -5000.0);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let positions = unit_positions(&mut app);
+    assert_eq!(positions.len(), 1);
+    assert!(map_size.contains(positions[0]), "Spawn should be clamped inside the map, got {:?}", positions[0]);
+    assert_eq!(positions[0].x, map_size.bottom_right.x - margin);
+    assert_eq!(positions[0].y, map_size.top_left.y + margin);
+}
+
+#[test]
+fn test_in_bounds_spawn_is_unchanged() {
+    let mut app = setup_command_app();
+
+    spawn_at(&mut app, 12.5, -3.0);
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert_eq!(unit_positions(&mut app), vec![FixedVec2::from_f32(12.5, -3.0)]);
+}
+
+#[test]
+fn test_spawn_past_capacity_is_refused_without_panic() {
+    let mut app = setup_command_app();
+    app.world_mut().resource_mut::<SimConfig>().max_entity_count = 5;
+
+    for i in 0..8 {
+        spawn_at(&mut app, i as f32, 0.0);
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(unit_positions(&mut app).len(), 5);
+
+    // Already full: further spawns are refused as well
+    spawn_at(&mut app, 0.0, 10.0);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(unit_positions(&mut app).len(), 5);
+}
+
+/// Command processing plus the idle/active partition around friction and integration
+fn setup_activity_app() -> App {
+    let mut app = setup_command_app();
+    app.init_resource::<SimTick>();
+    app.init_resource::<ActiveUnitSet>();
+    app.add_systems(FixedUpdate, (
+        wake_units,
+        apply_friction,
+        apply_velocity,
+        sweep_idle_units,
+    ).chain().after(process_input));
+    app
+}
+
+#[test]
+fn test_idle_unit_skipped_by_steering_until_move_command() {
+    let mut app = setup_activity_app();
+
+    let unit = app.world_mut().spawn((
+        Unit,
+        SimPosition(FixedVec2::ZERO),
+        SimVelocity(FixedVec2::ZERO),
+        SimAcceleration::default(),
+        Path::Inactive,
+    )).id();
+
+    // Spawning wakes the unit; at rest it is swept out at the end of the tick
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(!app.world().resource::<ActiveUnitSet>().contains(unit), "Resting unit should go idle");
+
+    // Sneak a velocity in without change detection: an idle unit must not be touched
+    let sneaked = FixedVec2::new(FixedNum::from_num(4.0), FixedNum::from_num(0.0));
+    app.world_mut().get_mut::<SimVelocity>(unit).unwrap().bypass_change_detection().0 = sneaked;
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(app.world().get::<SimVelocity>(unit).unwrap().0, sneaked, "Friction should skip idle units");
+    assert_eq!(app.world().get::<SimPosition>(unit).unwrap().0, FixedVec2::ZERO, "Integration should skip idle units");
+
+    // A move command wakes it up in the same tick
+    let target = FixedVec2::new(FixedNum::from_num(20.0), FixedNum::from_num(0.0));
+    app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target, queued: false });
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(app.world().resource::<ActiveUnitSet>().contains(unit), "Move command should wake the unit");
+    assert!(app.world().get::<SimPosition>(unit).unwrap().0.x > FixedNum::ZERO, "Woken unit should be integrated");
+    assert!(app.world().get::<SimVelocity>(unit).unwrap().0.x < sneaked.x, "Woken unit should get friction");
+}
+
+#[test]
+fn test_idle_unit_wakes_when_pushed() {
+    let mut app = setup_activity_app();
+
+    let unit = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity(FixedVec2::ZERO), SimAcceleration::default(), Path::Inactive,
+    )).id();
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(!app.world().resource::<ActiveUnitSet>().contains(unit));
+
+    // A collision push writes acceleration (as resolve_collisions does at the end of a tick)
+    app.world_mut().get_mut::<SimAcceleration>(unit).unwrap().0 = FixedVec2::new(FixedNum::ZERO, FixedNum::from_num(10.0));
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(app.world().resource::<ActiveUnitSet>().contains(unit), "Pushed unit should reactivate");
+    assert!(app.world().get::<SimPosition>(unit).unwrap().0.y > FixedNum::ZERO, "Pushed unit should move");
+}
+
+#[test]
+fn test_teleported_idle_unit_moves_in_the_spatial_hash() {
+    let mut app = setup_activity_app();
+    // Incremental mode, where only active units are checked for cell changes
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 100, 1.5));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.add_systems(FixedUpdate, update_spatial_hash.after(apply_velocity).before(sweep_idle_units));
+
+    let unit = spawn_unit_in_world(app.world_mut(), FixedVec2::ZERO, FixedNum::from_num(0.5), 0);
+    app.world_mut().run_schedule(FixedUpdate);
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(!app.world().resource::<ActiveUnitSet>().contains(unit));
+
+    let teleported = FixedVec2::new(FixedNum::from_num(30.0), FixedNum::from_num(-20.0));
+    app.world_mut().get_mut::<SimPosition>(unit).unwrap().0 = teleported;
+    app.world_mut().run_schedule(FixedUpdate);
+
+    let world = app.world_mut();
+    world.resource_scope(|world, mut scratch: Mut<SpatialHashScratch>| {
+        let hash = world.resource::<SpatialHash>();
+        hash.query_radius(teleported, FixedNum::ONE, None, &mut scratch);
+        assert_eq!(scratch.query_results, vec![unit], "Teleported unit should be found at its new spot");
+        hash.query_radius(FixedVec2::ZERO, FixedNum::ONE, None, &mut scratch);
+        assert!(scratch.query_results.is_empty(), "Teleported unit should be gone from its old spot");
+    });
+}
+
+
+/// Command processing feeding the path request queue (no map, so requests stay queued)
+fn setup_ordering_app() -> App {
+    let mut app = setup_command_app();
+    app.init_resource::<PendingPathRequests>();
+    app.init_resource::<ActivePathSet>();
+    app.init_resource::<PathRequestStats>();
+    app.init_resource::<MapFlowField>();
+    app.init_resource::<HierarchicalGraph>();
+    app.init_resource::<NavigationLookup>();
+    app.add_systems(FixedUpdate, process_path_requests.after(process_input));
+    app
+}
+
+/// One tick's commands from several writers, in `order`
+fn write_commands(app: &mut App, units: &[Entity], order: &[usize]) {
+    let pos = |x: f32, y: f32| FixedVec2::from_f32(x, y);
+    for &i in order {
+        let world = app.world_mut();
+        match i {
+            0 => { world.write_message(SpawnUnitCommand { player_id: 1, position: pos(4.0, 4.0), radius: None }); }
+            1 => { world.write_message(SpawnUnitCommand { player_id: 0, position: pos(4.0, 4.0), radius: None }); }
+            2 => { world.write_message(SpawnUnitCommand { player_id: 0, position: pos(-6.0, 2.0), radius: Some(FixedNum::from_num(1.0)) }); }
+            3 => { world.write_message(UnitMoveCommand { player_id: 1, entity: units[0], target: pos(20.0, 0.0), queued: false }); }
+            4 => { world.write_message(UnitMoveCommand { player_id: 0, entity: units[0], target: pos(-20.0, 5.0), queued: false }); }
+            5 => { world.write_message(UnitMoveCommand { player_id: 0, entity: units[1], target: pos(0.0, 30.0), queued: false }); }
+            6 => { world.write_message(UnitMoveCommand { player_id: 0, entity: units[2], target: pos(8.0, 8.0), queued: false }); }
+            7 => { world.write_message(UnitStopCommand { player_id: 1, entity: units[1] }); }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Every unit's id, team, position and path, plus the queued path requests in serving order
+fn world_state(app: &mut App) -> (Vec<(Entity, u8, FixedVec2, String)>, Vec<(Entity, FixedVec2)>) {
+    let mut query = app.world_mut().query_filtered::<(Entity, &peregrine::game::unit::Team, &SimPosition, &Path), With<Unit>>();
+    let mut units: Vec<_> = query.iter(app.world())
+        .map(|(entity, team, pos, path)| (entity, team.0, pos.0, format!("{:?}", path)))
+        .collect();
+    units.sort_by_key(|&(entity, ..)| entity);
+    let mut pending = app.world_mut().resource_mut::<PendingPathRequests>();
+    let queued = std::iter::from_fn(|| pending.pop()).map(|request| (request.entity, request.goal)).collect();
+    (units, queued)
+}
+
+#[test]
+fn test_command_writer_order_does_not_change_outcome() {
+    let orders: [&[usize]; 3] = [
+        &[0, 1, 2, 3, 4, 5, 6, 7],
+        &[7, 6, 5, 4, 3, 2, 1, 0],
+        &[4, 1, 7, 2, 6, 0, 5, 3],
+    ];
+    let states: Vec<_> = orders.iter().map(|order| {
+        let mut app = setup_ordering_app();
+        let units: Vec<Entity> = (0..3).map(|i| app.world_mut().spawn((
+            Unit, peregrine::game::unit::Team(0), SimPosition(FixedVec2::from_f32(i as f32, 0.0)),
+            SimVelocity::default(), SimAcceleration::default(), Path::Inactive,
+            peregrine::game::pathfinding::GoalNavCell::default(), peregrine::game::collections::InclusionIndex::default(),
+        )).id()).collect();
+        write_commands(&mut app, &units, order);
+        app.world_mut().run_schedule(FixedUpdate);
+        world_state(&mut app)
+    }).collect();
+
+    let (units, queued) = &states[0];
+    assert_eq!(units.len(), 6, "All three spawns go through");
+    assert_eq!(queued.len(), 3, "One queued request per moved unit");
+    for (order, state) in orders.iter().zip(&states).skip(1) {
+        assert_eq!(state, &states[0], "Writer order {:?} changed the result", order);
+    }
+}
+
+#[test]
+fn test_last_move_issued_in_a_tick_wins() {
+    // Whichever target lies further along x, the later click is the order that stands
+    for (first, last) in [(-10.0, 10.0), (10.0, -10.0)] {
+        let mut app = setup_ordering_app();
+        let unit = app.world_mut().spawn((
+            Unit, SimPosition(FixedVec2::ZERO), SimVelocity::default(), SimAcceleration::default(), Path::Inactive,
+            peregrine::game::pathfinding::GoalNavCell::default(), peregrine::game::collections::InclusionIndex::default(),
+        )).id();
+        for x in [first, last] {
+            app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target: FixedVec2::from_f32(x, 0.0), queued: false });
+        }
+        app.world_mut().run_schedule(FixedUpdate);
+
+        let (_, queued) = world_state(&mut app);
+        assert_eq!(queued, vec![(unit, FixedVec2::from_f32(last, 0.0))]);
+    }
+}
+
+#[test]
+fn test_waypoints_queued_in_one_tick_keep_click_order() {
+    let mut app = setup_ordering_app();
+    let unit = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity::default(), SimAcceleration::default(), Path::Inactive,
+        peregrine::game::pathfinding::GoalNavCell::default(), peregrine::game::collections::InclusionIndex::default(),
+        WaypointQueue::default(),
+    )).id();
+    // Not sorted along either axis
+    let clicks = [FixedVec2::from_f32(5.0, 5.0), FixedVec2::from_f32(20.0, -3.0), FixedVec2::from_f32(-8.0, 12.0), FixedVec2::from_f32(1.0, -9.0)];
+    for target in clicks {
+        app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target, queued: true });
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+
+    // The first click is the order, the rest wait behind it as clicked
+    assert_eq!(app.world().get::<WaypointQueue>(unit).unwrap().0.iter().copied().collect::<Vec<_>>(), clicks[1..]);
+    let (_, queued) = world_state(&mut app);
+    assert_eq!(queued, vec![(unit, clicks[0])]);
+}
+
+#[test]
+fn test_plain_move_after_queued_ones_in_a_tick_replaces_them() {
+    let mut app = setup_ordering_app();
+    let unit = app.world_mut().spawn((
+        Unit, SimPosition(FixedVec2::ZERO), SimVelocity::default(), SimAcceleration::default(), Path::Inactive,
+        peregrine::game::pathfinding::GoalNavCell::default(), peregrine::game::collections::InclusionIndex::default(),
+        WaypointQueue::default(),
+    )).id();
+    let last = FixedVec2::from_f32(-4.0, 7.0);
+    for (target, queued) in [(FixedVec2::from_f32(5.0, 5.0), true), (FixedVec2::from_f32(20.0, -3.0), true), (last, false)] {
+        app.world_mut().write_message(UnitMoveCommand { player_id: 0, entity: unit, target, queued });
+    }
+    app.world_mut().run_schedule(FixedUpdate);
+
+    assert!(app.world().get::<WaypointQueue>(unit).unwrap().0.is_empty());
+    let (_, queued) = world_state(&mut app);
+    assert_eq!(queued, vec![(unit, last)]);
+}
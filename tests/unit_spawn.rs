@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use peregrine::game::fixed_math::{FixedVec2, FixedNum};
+use peregrine::game::simulation::{
+    SimConfig, SimTick, SimPosition, SimPositionPrev, SimVelocity, Collider, CollisionState, OccupiedCell,
+    StaticObstacle, SpatialHashOverflow, SpawnUnitCommand, UnitMoveCommand, UnitStopCommand,
+    ColliderBundle, ObstacleBundle, MapDimensions, layers, apply_obstacle_to_flow_field,
+};
+use peregrine::game::simulation::collision::{detect_collisions, resolve_collisions, CollisionEvent};
+use peregrine::game::simulation::physics::{apply_friction, apply_velocity};
+use peregrine::game::simulation::systems::{process_input, update_spatial_hash, rebuild_spatial_hash_on_overflow, PendingVecIdxUpdates};
+use peregrine::game::spatial_hash::{SpatialHash, SpatialHashScratch};
+use peregrine::game::pathfinding::{HierarchicalGraph, PathRequest, CLUSTER_SIZE};
+use peregrine::game::unit::{spawn_starting_units, spawn_unit, spawn_unit_in_world, Team, TeamAssignment, Unit};
+use peregrine::game::map::{MapData, StartLocation, MAP_VERSION};
+
+/// Input, integration, spatial hash and collision - the systems a unit must be picked up by
+fn setup_sim_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.init_resource::<SimConfig>();
+    app.init_resource::<SimTick>();
+    app.insert_resource(SpatialHash::new(FixedNum::from_num(100.0), FixedNum::from_num(100.0), &[0.5], 4.0, 1000, 1.5));
+    app.insert_resource(SpatialHashScratch::default_capacity());
+    app.init_resource::<PendingVecIdxUpdates>();
+    app.init_resource::<SpatialHashOverflow>();
+    app.add_message::<UnitMoveCommand>();
+    app.add_message::<UnitStopCommand>();
+    app.add_message::<SpawnUnitCommand>();
+    app.add_message::<PathRequest>();
+    app.add_message::<CollisionEvent>();
+    app.add_systems(FixedUpdate, (
+        process_input,
+        apply_friction,
+        apply_velocity,
+        update_spatial_hash,
+        rebuild_spatial_hash_on_overflow,
+        detect_collisions,
+        resolve_collisions,
+    ).chain());
+    app
+}
+
+fn spawn(app: &mut App, x: f32, y: f32, team: u8) -> Entity {
+    app.world_mut()
+        .run_system_once(move |mut commands: Commands, mut spatial_hash: ResMut<SpatialHash>| {
+            spawn_unit(&mut commands, &mut spatial_hash, FixedVec2::from_f32(x, y), FixedNum::from_num(0.5), team)
+        })
+        .unwrap()
+}
+
+fn query_near(app: &App, x: f32, y: f32) -> Vec<Entity> {
+    let mut scratch = SpatialHashScratch::new(64);
+    app.world().resource::<SpatialHash>().query_radius(FixedVec2::from_f32(x, y), FixedNum::from_num(1.0), None, &mut scratch);
+    scratch.query_results
+}
+
+#[test]
+fn test_spawned_unit_is_queryable_and_simulated() {
+    let mut app = setup_sim_app();
+
+    // Two overlapping units
+    let a = spawn(&mut app, 0.0, 0.0, 1);
+    let b = spawn(&mut app, 0.3, 0.0, 2);
+
+    // In the spatial hash before any simulation tick has run
+    let nearby = query_near(&app, 0.0, 0.0);
+    assert!(nearby.contains(&a) && nearby.contains(&b), "Helper should insert into the spatial hash, got {:?}", nearby);
+    assert!(app.world().get::<OccupiedCell>(a).is_some());
+    assert_eq!(app.world().get::<Team>(b), Some(&Team(2)));
+    assert!(app.world().get::<Unit>(a).is_some());
+
+    // One tick: the overlap is detected and resolved into a push
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(app.world().get::<CollisionState>(a).unwrap().is_colliding);
+    assert!(app.world().get::<CollisionState>(b).unwrap().is_colliding);
+
+    // Next tick integrates the push: the units separate
+    app.world_mut().run_schedule(FixedUpdate);
+    assert!(app.world().get::<SimVelocity>(a).unwrap().0.x < FixedNum::ZERO, "a should be pushed left");
+    assert!(app.world().get::<SimPosition>(b).unwrap().0.x > FixedNum::from_num(0.3), "b should be pushed right");
+}
+
+#[test]
+fn test_spawn_command_inserts_into_spatial_hash_same_tick() {
+    let mut app = setup_sim_app();
+    app.world_mut().write_message(SpawnUnitCommand { player_id: 3, position: FixedVec2::from_f32(10.0, 10.0), radius: None });
+
+    // Run only input processing: no spatial hash update has happened yet
+    app.world_mut().run_system_once(process_input).unwrap();
+
+    let nearby = query_near(&app, 10.0, 10.0);
+    assert_eq!(nearby.len(), 1);
+    assert_eq!(app.world().get::<Team>(nearby[0]), Some(&Team(3)));
+}
+
+#[test]
+fn test_unit_spawned_in_world_is_queryable_before_first_tick() {
+    let mut app = setup_sim_app();
+    let a = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-30.0, 30.0), FixedNum::from_num(0.5), 0);
+    let b = spawn_unit_in_world(app.world_mut(), FixedVec2::from_f32(-30.4, 30.0), FixedNum::from_num(0.5), 1);
+
+    // No system has run: both are already in the hash, with matching OccupiedCells
+    let nearby = query_near(&app, -30.0, 30.0);
+    assert!(nearby.contains(&a) && nearby.contains(&b), "Spawned units should be queryable, got {:?}", nearby);
+    let mut cells = app.world_mut().query::<(Entity, &OccupiedCell)>();
+    let report = app.world().resource::<SpatialHash>().debug_verify(cells.iter(app.world()));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.entities_checked, 2);
+
+    // The first tick keeps them in place in the hash (no duplicate insert) and resolves the overlap
+    app.world_mut().run_schedule(FixedUpdate);
+    let mut cells = app.world_mut().query::<(Entity, &OccupiedCell)>();
+    let report = app.world().resource::<SpatialHash>().debug_verify(cells.iter(app.world()));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.total_entries, 2);
+}
+
+#[test]
+fn test_collider_bundle_carries_collision_components() {
+    // Compile-time: the bundle must keep exactly these fields with these types
+    let ColliderBundle { position, position_prev, collider, collision_state } =
+        ColliderBundle::new(FixedVec2::from_f32(1.0, 2.0), Collider::default());
+    let _: (SimPosition, SimPositionPrev, Collider, CollisionState) = (position, position_prev, collider, collision_state);
+    assert_eq!(position_prev.0, position.0, "Previous position starts at the spawn position");
+
+    let ObstacleBundle { obstacle, position, position_prev, collider } =
+        ObstacleBundle::new(FixedVec2::ZERO, FixedNum::from_num(2.0));
+    let _: (StaticObstacle, SimPosition, SimPositionPrev, Collider) = (obstacle, position, position_prev, collider);
+}
+
+#[test]
+fn test_collider_bundle_entity_is_consistent_in_spatial_hash() {
+    let mut app = setup_sim_app();
+    let collider = app.world_mut().spawn(ColliderBundle::new(FixedVec2::from_f32(-5.0, 5.0), Collider::default())).id();
+    let obstacle = app.world_mut().spawn(ObstacleBundle::new(FixedVec2::from_f32(20.0, 20.0), FixedNum::from_num(2.0))).id();
+
+    app.world_mut().run_schedule(FixedUpdate);
+
+    // Picked up by the spatial hash (OccupiedCell added) and consistent with it
+    assert!(app.world().get::<OccupiedCell>(collider).is_some());
+    assert!(query_near(&app, -5.0, 5.0).contains(&collider));
+    let mut cells = app.world_mut().query::<(Entity, &OccupiedCell)>();
+    let report = app.world().resource::<SpatialHash>().debug_verify(cells.iter(app.world()));
+    assert!(report.is_consistent(), "{:?}", report);
+    assert_eq!(report.entities_checked, 1);
+
+    // Obstacles stay out of the hash
+    assert!(app.world().get::<OccupiedCell>(obstacle).is_none());
+    assert!(!query_near(&app, 20.0, 20.0).contains(&obstacle));
+}
+
+#[test]
+fn test_collision_events_carry_both_layers() {
+    let mut app = setup_sim_app();
+    let a = spawn(&mut app, 0.0, 0.0, 0);
+    let b = spawn(&mut app, 0.3, 0.0, 0);
+    let collector = spawn(&mut app, 10.0, 0.0, 0);
+    let pickup = app.world_mut().spawn(ColliderBundle::new(FixedVec2::from_f32(10.3, 0.0), Collider::pickup(FixedNum::from_num(0.5)))).id();
+
+    app.world_mut().run_schedule(FixedUpdate);
+    let events: Vec<CollisionEvent> = app.world().resource::<Messages<CollisionEvent>>()
+        .iter_current_update_messages()
+        .cloned()
+        .collect();
+    assert_eq!(events.len(), 2, "{:?}", events);
+
+    let unit_pairs: Vec<_> = events.iter().filter(|event| event.is_between(layers::UNIT, layers::UNIT)).collect();
+    assert_eq!(unit_pairs.len(), 1);
+    assert!(!unit_pairs[0].is_trigger());
+    let pair = [unit_pairs[0].entity1, unit_pairs[0].entity2];
+    assert!(pair.contains(&a) && pair.contains(&b));
+
+    let pickups: Vec<_> = events.iter().filter(|event| event.is_between(layers::UNIT, layers::PICKUP)).collect();
+    assert_eq!(pickups.len(), 1);
+    assert!(pickups[0].is_trigger());
+    assert_eq!(pickups[0].entity_on(layers::PICKUP), Some(pickup));
+    assert_eq!(pickups[0].entity_on(layers::UNIT), Some(collector));
+
+    // Touching a pickup doesn't push the unit
+    app.world_mut().run_schedule(FixedUpdate);
+    assert_eq!(app.world().get::<SimVelocity>(collector).unwrap().0, FixedVec2::ZERO);
+    assert!(app.world().get::<SimVelocity>(a).unwrap().0.x < FixedNum::ZERO, "Units still push each other apart");
+}
+
+#[test]
+fn test_starting_units_spawn_around_start_locations_with_assigned_teams() {
+    let mut app = setup_sim_app();
+    let dimensions = MapDimensions::from_f32(100.0, 100.0);
+    let mut flow_field = dimensions.flow_field();
+    // Player 2's start location sits on an obstacle, so its units are moved off it
+    let (blocked_x, blocked_y) = flow_field.world_to_grid(FixedVec2::from_f32(30.0, 30.0)).unwrap();
+    flow_field.set_obstacle(blocked_x, blocked_y);
+    let start = |player_id: u8, x: f32, y: f32| StartLocation { player_id, position: FixedVec2::from_f32(x, y) };
+    let map = MapData {
+        version: MAP_VERSION,
+        size: dimensions.map_size(),
+        cell_size: dimensions.cell_size,
+        cluster_size: CLUSTER_SIZE,
+        obstacles: vec![],
+        // Player 3 has no assignment: an empty slot
+        start_locations: vec![start(1, -30.0, -30.0), start(2, 30.0, 30.0), start(3, 0.0, 0.0)],
+        cost_field: flow_field.cost_field.clone(),
+        graph: HierarchicalGraph::default(),
+    };
+    let assignments = [
+        TeamAssignment { player_id: 1, team: 0, unit_count: 4 },
+        TeamAssignment { player_id: 2, team: 1, unit_count: 5 },
+    ];
+
+    let field = flow_field.clone();
+    let spawned = app.world_mut()
+        .run_system_once(move |mut commands: Commands, mut spatial_hash: ResMut<SpatialHash>| {
+            spawn_starting_units(&mut commands, &mut spatial_hash, &map, &field, &assignments)
+        })
+        .unwrap();
+    assert_eq!(spawned.len(), 9);
+
+    let units_near = |app: &App, x: f32, y: f32| -> Vec<(FixedVec2, u8)> {
+        let centre = FixedVec2::from_f32(x, y);
+        spawned.iter()
+            .map(|&entity| (app.world().get::<SimPosition>(entity).unwrap().0, app.world().get::<Team>(entity).unwrap().0))
+            .filter(|(position, _)| (*position - centre).length() < FixedNum::from_num(5.0))
+            .collect()
+    };
+    let first = units_near(&app, -30.0, -30.0);
+    assert_eq!(first.len(), 4);
+    assert!(first.iter().all(|&(_, team)| team == 0));
+    let second = units_near(&app, 30.0, 30.0);
+    assert_eq!(second.len(), 5);
+    assert!(second.iter().all(|&(_, team)| team == 1));
+    assert!(second.iter().all(|&(position, _)| flow_field.is_walkable_world(position)), "Moved off the obstacle");
+    assert!(units_near(&app, 0.0, 0.0).is_empty());
+
+    // Spread out, not stacked
+    for (i, &(a, _)) in first.iter().chain(&second).enumerate() {
+        assert!(first.iter().chain(&second).skip(i + 1).all(|&(b, _)| a != b));
+    }
+}
+
+#[test]
+fn test_starting_units_inside_a_large_obstacle_get_a_cell_each() {
+    let mut app = setup_sim_app();
+    let dimensions = MapDimensions::from_f32(100.0, 100.0);
+    let mut flow_field = dimensions.flow_field();
+    // Every slot of the formation lies inside the obstacle
+    let centre = FixedVec2::from_f32(30.0, 30.0);
+    apply_obstacle_to_flow_field(&mut flow_field, centre, FixedNum::from_num(6.0));
+    let map = MapData {
+        version: MAP_VERSION,
+        size: dimensions.map_size(),
+        cell_size: dimensions.cell_size,
+        cluster_size: CLUSTER_SIZE,
+        obstacles: vec![],
+        start_locations: vec![StartLocation { player_id: 1, position: centre }],
+        cost_field: flow_field.cost_field.clone(),
+        graph: HierarchicalGraph::default(),
+    };
+    let assignments = [TeamAssignment { player_id: 1, team: 0, unit_count: 9 }];
+
+    let field = flow_field.clone();
+    let spawned = app.world_mut()
+        .run_system_once(move |mut commands: Commands, mut spatial_hash: ResMut<SpatialHash>| {
+            spawn_starting_units(&mut commands, &mut spatial_hash, &map, &field, &assignments)
+        })
+        .unwrap();
+    assert_eq!(spawned.len(), 9);
+
+    let mut cells = Vec::new();
+    for &entity in &spawned {
+        let position = app.world().get::<SimPosition>(entity).unwrap().0;
+        assert!(flow_field.is_walkable_world(position), "Unit at {:?} should be moved off the obstacle", position);
+        assert!((position - centre).length() < FixedNum::from_num(10.0), "Unit at {:?} strayed from its start location", position);
+        let cell = flow_field.world_to_grid(position).unwrap();
+        assert!(!cells.contains(&cell), "Two units snapped into cell {:?}", cell);
+        cells.push(cell);
+    }
+}